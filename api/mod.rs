@@ -0,0 +1,272 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::card::{Card, Rank, SimulationResults, check_distinct, chen_score, combo_count, describe_hand, generate_all_starting_hands, generate_canonical_starting_hands, monte_carlo_vs_opponents_with_rng, parse_cards, sklansky_group, try_evaluate_hand};
+use crate::hand_history::card_code;
+use crate::json::JsonValue;
+use crate::range::{PREFLOP_RANKING, Range, hand_notation, monte_carlo_vs_range_with_rng};
+
+// Requests to the simulator as JSON values and the answers it sends back, for
+// programs that call it instead of a person at the command line. Every
+// request is an object of named parameters; a bad one comes back as an error
+// message for the caller to show.
+
+// Deals per request when the caller doesn't ask for a number
+const DEFAULT_TRIALS: usize = 10000;
+
+// Opponents a request can ask for, as at the command line
+const MAX_OPPONENTS: usize = 9;
+
+// The made hand on a board of three to five cards:
+//   {"hand": "AhKh", "board": "QhJhTh2c"}
+// "category" counts up from 1 for high card to 10 for a royal flush, and
+// "high_cards" are the ranks that break ties within it, highest first.
+pub fn evaluate(request: &JsonValue) -> Result<JsonValue, String> {
+    let hand = hole_cards(required_text(request, "hand")?)?;
+    let board = parse_cards(required_text(request, "board")?)?;
+    let evaluation = try_evaluate_hand(&hand, &board)?;
+    let high_cards: String = evaluation.high_cards.iter()
+        .map(|rank| match rank {
+            Rank::Ten => "T".to_string(),
+            rank => rank.to_string(),
+        })
+        .collect();
+    Ok(JsonValue::Object(vec![
+        ("hand".to_string(), JsonValue::String(cards_text(&hand))),
+        ("board".to_string(), JsonValue::String(cards_text(&board))),
+        ("rank".to_string(), JsonValue::String(evaluation.rank.to_string())),
+        ("category".to_string(), JsonValue::Number(evaluation.rank as u8 as f64)),
+        ("high_cards".to_string(), JsonValue::String(high_cards)),
+    ]))
+}
+
+// Hero's equity: POST /equity with
+//   {"hero": "AhKh", "villain": "QQ+,AKs", "board": "Qh7c2d", "trials": 20000}
+// "board" is optional, and "villain" can be left out to play "opponents" random
+// hands instead (one by default). Trials are capped at `max_trials`.
+pub fn equity<R: Rng>(request: &JsonValue, max_trials: usize, rng: &mut R) -> Result<JsonValue, String> {
+    let hand = hole_cards(required_text(request, "hero")?)?;
+    let board = match optional_text(request, "board")? {
+        Some(text) => parse_cards(text)?,
+        None => Vec::new(),
+    };
+    if ![0, 3, 4, 5].contains(&board.len()) {
+        return Err(format!("A board has three to five cards, found {}", board.len()));
+    }
+    check_distinct(hand.iter().chain(board.iter()))?;
+    let trials = trials(request, max_trials)?;
+
+    let mut answer = vec![
+        ("hero".to_string(), JsonValue::String(cards_text(&hand))),
+        ("board".to_string(), JsonValue::String(cards_text(&board))),
+    ];
+    let results = match optional_text(request, "villain")? {
+        Some(notation) => {
+            if request.get("opponents").is_some() {
+                return Err("Give either a villain range or a number of opponents, not both".to_string());
+            }
+            let range = Range::parse(notation)?;
+            let results = monte_carlo_vs_range_with_rng(&hand, &range, &board, trials, rng);
+            if results.total_games == 0 {
+                return Err(format!("No combo of the range '{}' is left once the known cards are removed", notation));
+            }
+            answer.push(("villain".to_string(), JsonValue::String(notation.to_string())));
+            results
+        },
+        None => {
+            let opponents = opponents(request)?;
+            answer.push(("opponents".to_string(), JsonValue::Number(opponents as f64)));
+            monte_carlo_vs_opponents_with_rng(&hand, opponents, &board, trials, rng)
+        },
+    };
+    answer.extend(result_fields(&results));
+    Ok(JsonValue::Object(answer))
+}
+
+// A starting hand's standing: GET /preflop/{hand} with "AKs", "T9o", "QQ" or an
+// exact combo such as "AhKh", and optionally "opponents" and "trials". Gives
+// its place in PREFLOP_RANKING, the share of combos at least as strong, the
+// Sklansky group and Chen score, and its all-in equity against random hands.
+pub fn preflop<R: Rng>(request: &JsonValue, max_trials: usize, rng: &mut R) -> Result<JsonValue, String> {
+    let text = required_text(request, "hand")?;
+    let range = Range::parse(text)?;
+    let hand = match range.combos() {
+        [first, rest @ ..] if rest.iter().all(|combo| hand_notation(combo) == hand_notation(first)) => first.clone(),
+        _ => return Err(format!("'{}' is not one starting hand; name one such as AKs, T9o, QQ or AhKh", text)),
+    };
+    let notation = hand_notation(&hand);
+    let rank = PREFLOP_RANKING.iter().position(|ranked| *ranked == notation).ok_or(format!("'{}' is not a starting hand", text))?;
+    let combos_up_to: usize = PREFLOP_RANKING[..=rank].iter()
+        .map(|ranked| match ranked.chars().nth(2) {
+            Some('s') => 4,
+            Some(_) => 12,
+            None => 6,
+        })
+        .sum();
+    let opponents = opponents(request)?;
+    let trials = trials(request, max_trials)?;
+    let results = monte_carlo_vs_opponents_with_rng(&hand, opponents, &[], trials, rng);
+
+    let mut answer = vec![
+        ("hand".to_string(), JsonValue::String(notation)),
+        ("rank".to_string(), JsonValue::Number((rank + 1) as f64)),
+        ("top_percent".to_string(), JsonValue::Number(combos_up_to as f64 / 1326.0 * 100.0)),
+        ("combos".to_string(), JsonValue::Number(combo_count(&hand) as f64)),
+        ("sklansky_group".to_string(), JsonValue::Number(sklansky_group(&hand) as f64)),
+        ("chen_score".to_string(), JsonValue::Number(chen_score(&hand) as f64)),
+        ("opponents".to_string(), JsonValue::Number(opponents as f64)),
+    ];
+    answer.extend(result_fields(&results));
+    Ok(JsonValue::Object(answer))
+}
+
+// Progress events are sent at most this often; results go out as each hand finishes
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+// The bulk run as a stream of events, for live dashboards. Takes
+//   {"trials": 1000, "opponents": 2, "board": "Qh7c2d", "only": "pairs", "canonical": true}
+// all optional, as the bulk command's -n, --opponents, --board, --only and
+// --canonical. `on_event` gets, in order:
+//   {"event": "start", "total": hands, "trials": per hand, "opponents": n, "board": cards}
+//   {"event": "result", "completed": n, "total": hands, "hand": "AhKh", ...} for every hand
+//   {"event": "progress", "completed": n, "total": hands, "elapsed": seconds, "remaining": seconds}
+//   {"event": "done", "completed": hands, "total": hands, "elapsed": seconds}
+// and returns false to stop the run early, e.g. when the listener went away.
+// Invalid requests fail before the first event.
+pub fn bulk<R, F>(request: &JsonValue, max_trials: usize, rng: &mut R, mut on_event: F) -> Result<(), String>
+where
+    R: Rng,
+    F: FnMut(JsonValue) -> bool,
+{
+    let board = match optional_text(request, "board")? {
+        Some(text) => parse_cards(text)?,
+        None => Vec::new(),
+    };
+    if ![0, 3, 4, 5].contains(&board.len()) {
+        return Err(format!("A board has three to five cards, found {}", board.len()));
+    }
+    check_distinct(board.iter())?;
+    let only = match optional_text(request, "only")? {
+        Some(notation) => Some(Range::parse(notation)?),
+        None => None,
+    };
+    let canonical = match request.get("canonical") {
+        None | Some(JsonValue::Null) => false,
+        Some(JsonValue::Bool(canonical)) => *canonical,
+        Some(_) => return Err("\"canonical\" must be true or false".to_string()),
+    };
+    let opponents = opponents(request)?;
+    let trials = trials(request, max_trials)?;
+
+    let mut hands = if canonical { generate_canonical_starting_hands() } else { generate_all_starting_hands() };
+    hands.retain(|hand| !hand.iter().any(|card| board.contains(card)));
+    if let Some(range) = &only {
+        hands.retain(|hand| range.contains(hand));
+    }
+    if hands.is_empty() {
+        return Err("No starting hands match the \"only\" range".to_string());
+    }
+
+    let total = hands.len();
+    let event = |name: &str, completed: usize| vec![
+        ("event".to_string(), JsonValue::String(name.to_string())),
+        ("completed".to_string(), JsonValue::Number(completed as f64)),
+        ("total".to_string(), JsonValue::Number(total as f64)),
+    ];
+    let mut start = event("start", 0);
+    start.extend([
+        ("trials".to_string(), JsonValue::Number(trials as f64)),
+        ("opponents".to_string(), JsonValue::Number(opponents as f64)),
+        ("board".to_string(), JsonValue::String(cards_text(&board))),
+    ]);
+    if !on_event(JsonValue::Object(start)) {
+        return Ok(());
+    }
+
+    let start_time = Instant::now();
+    let mut last_progress = start_time;
+    for (index, hand) in hands.iter().enumerate() {
+        let results = monte_carlo_vs_opponents_with_rng(hand, opponents, &board, trials, rng);
+        let mut result = event("result", index + 1);
+        result.extend([
+            ("hand".to_string(), JsonValue::String(cards_text(hand))),
+            ("notation".to_string(), JsonValue::String(hand_notation(hand))),
+            ("description".to_string(), JsonValue::String(describe_hand(hand))),
+        ]);
+        result.extend(result_fields(&results));
+        if !on_event(JsonValue::Object(result)) {
+            return Ok(());
+        }
+
+        let elapsed = start_time.elapsed().as_secs_f64();
+        if index + 1 < total && last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let mut progress = event("progress", index + 1);
+            progress.extend([
+                ("elapsed".to_string(), JsonValue::Number(elapsed)),
+                ("remaining".to_string(), JsonValue::Number(elapsed / (index + 1) as f64 * (total - index - 1) as f64)),
+            ]);
+            if !on_event(JsonValue::Object(progress)) {
+                return Ok(());
+            }
+        }
+    }
+    let mut done = event("done", total);
+    done.push(("elapsed".to_string(), JsonValue::Number(start_time.elapsed().as_secs_f64())));
+    on_event(JsonValue::Object(done));
+    Ok(())
+}
+
+// Rates in percent, as in the CSV and JSON exports
+pub fn result_fields(results: &SimulationResults) -> Vec<(String, JsonValue)> {
+    vec![
+        ("trials".to_string(), JsonValue::Number(results.total_games as f64)),
+        ("win_rate".to_string(), JsonValue::Number(results.win_rate)),
+        ("tie_rate".to_string(), JsonValue::Number(results.tie_rate)),
+        ("lose_rate".to_string(), JsonValue::Number(100.0 - results.win_rate - results.tie_rate)),
+        ("equity".to_string(), JsonValue::Number(results.win_rate + results.tie_rate / 2.0)),
+    ]
+}
+
+// Short card codes with no separators, e.g. "AhKh"
+pub fn cards_text(cards: &[Card]) -> String {
+    cards.iter().map(card_code).collect()
+}
+
+fn hole_cards(text: &str) -> Result<[Card; 2], String> {
+    match <[Card; 2]>::try_from(parse_cards(text)?) {
+        Ok(hand) => Ok(hand),
+        Err(cards) => Err(format!("A hand has two hole cards, found {} in '{}'", cards.len(), text)),
+    }
+}
+
+fn optional_text<'a>(request: &'a JsonValue, key: &str) -> Result<Option<&'a str>, String> {
+    match request.get(key) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(value) => value.as_str().map(Some).ok_or(format!("\"{}\" must be a string", key)),
+    }
+}
+
+fn required_text<'a>(request: &'a JsonValue, key: &str) -> Result<&'a str, String> {
+    optional_text(request, key)?.ok_or(format!("Missing \"{}\"", key))
+}
+
+// A whole number no smaller than 1 and no larger than `max`, or `default` when left out
+fn count(request: &JsonValue, key: &str, default: usize, max: usize) -> Result<usize, String> {
+    match request.get(key) {
+        None | Some(JsonValue::Null) => Ok(default),
+        Some(value) => match value.as_f64() {
+            Some(n) if n >= 1.0 && n <= max as f64 && n.fract() == 0.0 => Ok(n as usize),
+            _ => Err(format!("\"{}\" must be a whole number from 1 to {}", key, max)),
+        },
+    }
+}
+
+fn trials(request: &JsonValue, max_trials: usize) -> Result<usize, String> {
+    count(request, "trials", DEFAULT_TRIALS.min(max_trials), max_trials)
+}
+
+fn opponents(request: &JsonValue) -> Result<usize, String> {
+    count(request, "opponents", 1, MAX_OPPONENTS)
+}
@@ -0,0 +1,185 @@
+use rand::Rng;
+
+// Bankroll sizing from a win rate and standard deviation, both in big blinds
+// per 100 hands. Kelly maximizes the long-run growth of the bankroll, which
+// for a game with win rate mu and variance sigma^2 means playing with a
+// bankroll of sigma^2 / mu big blinds; fractional Kelly scales that up for
+// smaller swings at the cost of slower growth.
+
+// Fractions of Kelly the bankroll command compares
+pub const COMMON_KELLY_FRACTIONS: [f64; 4] = [1.0, 0.5, 0.25, 0.1];
+
+#[derive(Debug, Clone, Copy)]
+pub struct KellySizing {
+    pub win_rate: f64,
+    pub std_dev: f64,
+    pub fraction: f64,
+    // Bankroll in big blinds at this fraction of Kelly
+    pub bankroll_bb: f64,
+    // The same bankroll counted in buy-ins of `buy_in_bb`
+    pub buy_ins: f64,
+    // Chance of ever losing the whole bankroll playing these stakes forever
+    pub risk_of_ruin: f64,
+}
+
+fn check_edge(win_rate: f64, std_dev: f64) -> Result<(), String> {
+    if !(win_rate > 0.0 && win_rate.is_finite()) {
+        return Err(format!("Kelly sizing needs a positive win rate, got {} bb/100", win_rate));
+    }
+    if !(std_dev > 0.0 && std_dev.is_finite()) {
+        return Err(format!("Invalid standard deviation {} bb/100", std_dev));
+    }
+    Ok(())
+}
+
+fn check_fraction(fraction: f64) -> Result<(), String> {
+    if !(fraction > 0.0 && fraction <= 1.0) {
+        return Err(format!("Invalid Kelly fraction {}, expected more than 0 and at most 1", fraction));
+    }
+    Ok(())
+}
+
+// The full-Kelly bankroll in big blinds
+pub fn kelly_bankroll(win_rate: f64, std_dev: f64) -> Result<f64, String> {
+    check_edge(win_rate, std_dev)?;
+    Ok(std_dev * std_dev / win_rate)
+}
+
+// Chance of going broke from `bankroll_bb` with a diffusion approximation of
+// the results: exp(-2 * mu * bankroll / sigma^2)
+pub fn risk_of_ruin(win_rate: f64, std_dev: f64, bankroll_bb: f64) -> f64 {
+    if win_rate <= 0.0 {
+        return 1.0;
+    }
+    (-2.0 * win_rate * bankroll_bb / (std_dev * std_dev)).exp()
+}
+
+pub fn kelly_sizing(win_rate: f64, std_dev: f64, fraction: f64, buy_in_bb: f64) -> Result<KellySizing, String> {
+    check_fraction(fraction)?;
+    if !(buy_in_bb > 0.0 && buy_in_bb.is_finite()) {
+        return Err(format!("Invalid buy-in {} bb", buy_in_bb));
+    }
+    let bankroll_bb = kelly_bankroll(win_rate, std_dev)? / fraction;
+    Ok(KellySizing {
+        win_rate,
+        std_dev,
+        fraction,
+        bankroll_bb,
+        buy_ins: bankroll_bb / buy_in_bb,
+        risk_of_ruin: risk_of_ruin(win_rate, std_dev, bankroll_bb),
+    })
+}
+
+// The largest big blind a bankroll of `bankroll` (in money) supports at this fraction of Kelly
+pub fn max_big_blind(bankroll: f64, win_rate: f64, std_dev: f64, fraction: f64) -> Result<f64, String> {
+    check_fraction(fraction)?;
+    if !(bankroll > 0.0 && bankroll.is_finite()) {
+        return Err(format!("Invalid bankroll {}", bankroll));
+    }
+    Ok(bankroll / (kelly_bankroll(win_rate, std_dev)? / fraction))
+}
+
+// Outcome of many simulated careers of `hands` hands each
+#[derive(Debug, Clone)]
+pub struct BankrollSimulation {
+    pub trials: usize,
+    pub hands: u64,
+    // Share of trials that lost the whole bankroll
+    pub risk_of_ruin: f64,
+    // Largest peak-to-trough drop of each trial in big blinds, smallest first
+    pub max_downswings: Vec<f64>,
+    // Hands each trial took to double the bankroll, fastest first; trials
+    // that never doubled are left out
+    pub hands_to_double: Vec<u64>,
+}
+
+impl BankrollSimulation {
+    pub fn mean_max_downswing(&self) -> f64 {
+        self.max_downswings.iter().sum::<f64>() / self.max_downswings.len().max(1) as f64
+    }
+
+    // Share of trials that doubled the bankroll within the horizon
+    pub fn doubled(&self) -> f64 {
+        self.hands_to_double.len() as f64 / self.trials.max(1) as f64
+    }
+}
+
+// The value `fraction` of the way through sorted values (0.5 is the median)
+pub fn percentile<T: Copy>(sorted: &[T], fraction: f64) -> Option<T> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let index = ((sorted.len() - 1) as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+    Some(sorted[index])
+}
+
+// Standard normal sample (Box-Muller)
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+// Plays `trials` careers of `hands` hands from `bankroll_bb`, drawing each
+// block of 100 hands from a normal distribution with the given win rate and
+// standard deviation. A trial stops when the bankroll hits zero.
+pub fn simulate_bankroll(win_rate: f64, std_dev: f64, bankroll_bb: f64, hands: u64, trials: usize) -> Result<BankrollSimulation, String> {
+    simulate_bankroll_with_rng(win_rate, std_dev, bankroll_bb, hands, trials, &mut rand::thread_rng())
+}
+
+pub fn simulate_bankroll_with_rng<R: Rng>(
+    win_rate: f64,
+    std_dev: f64,
+    bankroll_bb: f64,
+    hands: u64,
+    trials: usize,
+    rng: &mut R,
+) -> Result<BankrollSimulation, String> {
+    if !win_rate.is_finite() {
+        return Err(format!("Invalid win rate {} bb/100", win_rate));
+    }
+    if !(std_dev > 0.0 && std_dev.is_finite()) {
+        return Err(format!("Invalid standard deviation {} bb/100", std_dev));
+    }
+    if !(bankroll_bb > 0.0 && bankroll_bb.is_finite()) {
+        return Err(format!("Invalid bankroll {} bb", bankroll_bb));
+    }
+    if hands == 0 || trials == 0 {
+        return Err("Simulate at least one hand and one trial".to_string());
+    }
+
+    let blocks = hands.div_ceil(100);
+    let mut ruined = 0;
+    let mut max_downswings = Vec::with_capacity(trials);
+    let mut hands_to_double = Vec::new();
+    for _ in 0..trials {
+        let mut bankroll = bankroll_bb;
+        let mut peak = bankroll;
+        let mut max_downswing: f64 = 0.0;
+        let mut doubled = false;
+        for block in 1..=blocks {
+            bankroll += win_rate + std_dev * standard_normal(rng);
+            peak = peak.max(bankroll);
+            max_downswing = max_downswing.max(peak - bankroll);
+            if !doubled && bankroll >= 2.0 * bankroll_bb {
+                doubled = true;
+                hands_to_double.push(block * 100);
+            }
+            if bankroll <= 0.0 {
+                ruined += 1;
+                break;
+            }
+        }
+        max_downswings.push(max_downswing);
+    }
+    max_downswings.sort_by(f64::total_cmp);
+    hands_to_double.sort_unstable();
+
+    Ok(BankrollSimulation {
+        trials,
+        hands: blocks * 100,
+        risk_of_ruin: ruined as f64 / trials as f64,
+        max_downswings,
+        hands_to_double,
+    })
+}
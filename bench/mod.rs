@@ -0,0 +1,110 @@
+use crate::card::{Card, Deck, Rank, Suit, evaluate_hand, evaluate_many, monte_carlo_simulation_with_rng, EVALUATOR_BACKEND};
+use crate::rng::RngChoice;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Hands evaluated against each board in the evaluator benchmarks, i.e. a full ring
+const PLAYERS_PER_BOARD: usize = 9;
+
+// One measured configuration: `operations` evaluations or simulated games in `elapsed`
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub benchmark: String,
+    pub configuration: String,
+    pub threads: usize,
+    pub operations: usize,
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    pub fn per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 { self.operations as f64 / seconds } else { 0.0 }
+    }
+}
+
+// 1, 2, 4, ... up to max_threads, always ending on max_threads itself
+pub fn thread_counts(max_threads: usize) -> Vec<usize> {
+    let max_threads = max_threads.max(1);
+    let mut counts: Vec<usize> = std::iter::successors(Some(1), |&n| Some(n * 2))
+        .take_while(|&n| n < max_threads)
+        .collect();
+    counts.push(max_threads);
+    counts
+}
+
+// Times evaluate_hand (one hand at a time) against evaluate_many (the board
+// counted once per table) on the same random deals, roughly `evaluations` each
+pub fn bench_evaluators(evaluations: usize, choice: RngChoice) -> Vec<BenchResult> {
+    let mut rng = choice.build();
+    let mut deck = Deck::new();
+    let deals: Vec<(Vec<Card>, Vec<[Card; 2]>)> = (0..evaluations.div_ceil(PLAYERS_PER_BOARD).max(1))
+        .map(|_| {
+            deck.reset();
+            let cards = deck.deal_n_with(5 + 2 * PLAYERS_PER_BOARD, &mut rng).expect("a full deck covers a full ring");
+            let hands = cards[5..].chunks(2).map(|pair| [pair[0].clone(), pair[1].clone()]).collect();
+            (cards[..5].to_vec(), hands)
+        })
+        .collect();
+    let operations = deals.len() * PLAYERS_PER_BOARD;
+
+    let start = Instant::now();
+    for (board, hands) in &deals {
+        for hand in hands {
+            black_box(evaluate_hand(hand, board));
+        }
+    }
+    let single = start.elapsed();
+
+    let start = Instant::now();
+    for (board, hands) in &deals {
+        black_box(evaluate_many(hands, board));
+    }
+    let batched = start.elapsed();
+
+    vec![
+        BenchResult {
+            benchmark: "evaluation".to_string(),
+            configuration: format!("evaluate_hand ({})", EVALUATOR_BACKEND),
+            threads: 1,
+            operations,
+            elapsed: single,
+        },
+        BenchResult {
+            benchmark: "evaluation".to_string(),
+            configuration: format!("evaluate_many ({})", EVALUATOR_BACKEND),
+            threads: 1,
+            operations,
+            elapsed: batched,
+        },
+    ]
+}
+
+// Heads-up preflop games (as the bulk run plays them) split across `threads`,
+// each thread drawing from its own generator of the chosen kind. A seeded
+// choice gives thread i the seed + i so the threads don't replay one stream.
+pub fn bench_simulations(simulations: usize, choice: RngChoice, threads: usize) -> BenchResult {
+    let threads = threads.max(1);
+    let per_thread = simulations.div_ceil(threads);
+    let hand = [Card::new(Rank::Ace, Suit::Hearts), Card::new(Rank::King, Suit::Hearts)];
+
+    let start = Instant::now();
+    let operations: usize = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|i| {
+                let hand = &hand;
+                let thread_choice = RngChoice { seed: choice.seed.map(|seed| seed.wrapping_add(i as u64)), ..choice };
+                scope.spawn(move || monte_carlo_simulation_with_rng(hand, per_thread, &mut thread_choice.build()).total_games)
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().expect("bench worker panicked")).sum()
+    });
+
+    BenchResult {
+        benchmark: "simulation".to_string(),
+        configuration: format!("{} rng", RngChoice { seed: None, ..choice }),
+        threads,
+        operations,
+        elapsed: start.elapsed(),
+    }
+}
@@ -0,0 +1,287 @@
+use rand::Rng;
+
+use crate::card::{Card, combo_count, exact_river_showdown, generate_canonical_starting_hands, monte_carlo_with_community_with_rng, unseen_combos};
+use crate::postflop::hand_potential_with_rng;
+use crate::range::{Range, hand_notation};
+
+// Hand abstraction: every hand on a street gets a strength between 0 and 1,
+// and hands of similar strength share a bucket. Buckets are numbered from the
+// weakest (0) up and are defined by strength boundaries, so the same strength
+// always lands in the same bucket and the indices can be used as features or
+// as the hand labels of an abstracted game.
+
+// What a hand's strength is measured as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketMetric {
+    // All-in equity against a random hand, running out the board
+    Equity,
+    // Effective hand strength against a random hand: strength now plus the
+    // chance of improving past a better hand minus the chance of being outdrawn
+    Ehs,
+}
+
+impl std::str::FromStr for BucketMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "equity" => Ok(BucketMetric::Equity),
+            "ehs" => Ok(BucketMetric::Ehs),
+            _ => Err(format!("Invalid metric '{}', expected equity or ehs", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for BucketMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BucketMetric::Equity => write!(f, "equity"),
+            BucketMetric::Ehs => write!(f, "ehs"),
+        }
+    }
+}
+
+// How strengths are split into buckets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketMethod {
+    // Equal numbers of combos per bucket
+    Percentile,
+    // One-dimensional k-means: buckets around clusters of similar strength
+    KMeans,
+}
+
+impl std::str::FromStr for BucketMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "percentile" | "quantile" => Ok(BucketMethod::Percentile),
+            "kmeans" | "k-means" => Ok(BucketMethod::KMeans),
+            _ => Err(format!("Invalid bucketing method '{}', expected percentile or kmeans", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for BucketMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BucketMethod::Percentile => write!(f, "percentile"),
+            BucketMethod::KMeans => write!(f, "kmeans"),
+        }
+    }
+}
+
+// One hand's measured strength
+#[derive(Debug, Clone)]
+pub struct HandValue {
+    // Canonical notation preflop ("AKs"), the exact combo after
+    pub hand: String,
+    // Between 0 and 1
+    pub value: f64,
+    // Number of combos the hand stands for
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bucket {
+    pub index: usize,
+    // Strengths above lower and up to upper land here; the weakest bucket
+    // starts at 0 and the strongest ends at 1
+    pub lower: f64,
+    pub upper: f64,
+    // Combo-weighted average strength of the hands that built the bucket
+    pub center: f64,
+    pub combos: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bucketing {
+    pub board: Vec<Card>,
+    pub metric: BucketMetric,
+    pub method: BucketMethod,
+    pub buckets: Vec<Bucket>,
+}
+
+impl Bucketing {
+    // Index of the bucket a strength falls in
+    pub fn bucket_of(&self, value: f64) -> usize {
+        self.buckets.iter()
+            .position(|bucket| value <= bucket.upper)
+            .unwrap_or(self.buckets.len() - 1)
+    }
+}
+
+// Strength of every hand on `board`: the 169 canonical hands preflop, every
+// combo that doesn't use a board card after. Flop and turn strengths use
+// `samples` Monte Carlo deals per hand; river strengths are exact.
+pub fn hand_values(board: &[Card], metric: BucketMetric, samples: usize) -> Result<Vec<HandValue>, String> {
+    hand_values_with_rng(board, metric, samples, &mut rand::thread_rng())
+}
+
+pub fn hand_values_with_rng<R: Rng>(board: &[Card], metric: BucketMetric, samples: usize, rng: &mut R) -> Result<Vec<HandValue>, String> {
+    if ![0, 3, 4, 5].contains(&board.len()) {
+        return Err(format!("Bucket hands preflop or on a flop, turn or river, not a {}-card board", board.len()));
+    }
+    crate::card::check_distinct(board.iter())?;
+    if samples == 0 {
+        return Err("The number of samples must be positive".to_string());
+    }
+
+    if board.is_empty() {
+        // There is no made hand yet to have potential from, so both metrics are equity
+        return Ok(generate_canonical_starting_hands().into_iter()
+            .map(|cards| {
+                let results = monte_carlo_with_community_with_rng(&cards, board, samples, rng);
+                HandValue {
+                    hand: hand_notation(&cards),
+                    value: (results.win_rate + results.tie_rate / 2.0) / 100.0,
+                    weight: combo_count(&cards) as f64,
+                }
+            })
+            .collect());
+    }
+
+    let random = Range::random();
+    unseen_combos(board).into_iter()
+        .map(|cards| {
+            let value = if board.len() == 5 {
+                river_strength(&cards, board)
+            } else if metric == BucketMetric::Ehs {
+                hand_potential_with_rng(&cards, &random, board, samples, rng)?.effective_strength()
+            } else {
+                let results = monte_carlo_with_community_with_rng(&cards, board, samples, rng);
+                (results.win_rate + results.tie_rate / 2.0) / 100.0
+            };
+            Ok(HandValue { hand: format!("{}{}", cards[0], cards[1]), value, weight: 1.0 })
+        })
+        .collect()
+}
+
+// Exact share of showdowns `hand` wins against every other combo on a full board
+pub fn river_strength(hand: &[Card; 2], board: &[Card]) -> f64 {
+    let known: Vec<Card> = hand.iter().chain(board).cloned().collect();
+    let opponents = unseen_combos(&known);
+    let results = exact_river_showdown(hand, board, opponents.iter().map(|combo| (combo, 1.0)));
+    (results.win_rate + results.tie_rate / 2.0) / 100.0
+}
+
+// Splits the hands into at most `count` buckets. Fewer come back when hands
+// share strengths (a percentile boundary can't split equal values) or a
+// k-means cluster ends up empty.
+pub fn build_buckets(values: &[HandValue], count: usize, method: BucketMethod, metric: BucketMetric, board: &[Card]) -> Result<Bucketing, String> {
+    if count == 0 {
+        return Err("Ask for at least one bucket".to_string());
+    }
+    if values.is_empty() {
+        return Err("There are no hands to bucket".to_string());
+    }
+    let mut sorted: Vec<(f64, f64)> = values.iter().map(|hand| (hand.value, hand.weight)).collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let groups = match method {
+        BucketMethod::Percentile => percentile_groups(&sorted, count),
+        BucketMethod::KMeans => kmeans_groups(&sorted, count),
+    };
+
+    // Each boundary sits halfway between the strongest hand below it and the weakest above
+    let mut buckets: Vec<Bucket> = Vec::with_capacity(groups.len());
+    for (index, group) in groups.iter().enumerate() {
+        let members = &sorted[group.clone()];
+        let combos: f64 = members.iter().map(|(_, weight)| weight).sum();
+        let upper = match groups.get(index + 1) {
+            Some(next) => (members[members.len() - 1].0 + sorted[next.start].0) / 2.0,
+            None => 1.0,
+        };
+        buckets.push(Bucket {
+            index,
+            lower: buckets.last().map_or(0.0, |previous| previous.upper),
+            upper,
+            center: members.iter().map(|(value, weight)| value * weight).sum::<f64>() / combos,
+            combos,
+        });
+    }
+    Ok(Bucketing { board: board.to_vec(), metric, method, buckets })
+}
+
+// Ranges of `sorted` holding about equal combo weight each, never splitting equal values
+fn percentile_groups(sorted: &[(f64, f64)], count: usize) -> Vec<std::ops::Range<usize>> {
+    let total: f64 = sorted.iter().map(|(_, weight)| weight).sum();
+    let mut groups = Vec::new();
+    let mut start = 0;
+    let mut cumulative = 0.0;
+    for (i, (value, weight)) in sorted.iter().enumerate() {
+        cumulative += weight;
+        let target = total * (groups.len() + 1) as f64 / count as f64;
+        let last = i + 1 == sorted.len();
+        if last || (cumulative >= target && sorted[i + 1].0 > *value) {
+            groups.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    groups
+}
+
+// Lloyd's algorithm in one dimension, started from the percentile buckets so
+// the result doesn't depend on random initialization. Clusters of sorted
+// values are contiguous, so each is returned as a range of `sorted`.
+fn kmeans_groups(sorted: &[(f64, f64)], count: usize) -> Vec<std::ops::Range<usize>> {
+    let weighted_mean = |members: &[(f64, f64)]| {
+        let weight: f64 = members.iter().map(|(_, weight)| weight).sum();
+        members.iter().map(|(value, weight)| value * weight).sum::<f64>() / weight
+    };
+    let mut groups = percentile_groups(sorted, count);
+    for _ in 0..100 {
+        let centers: Vec<f64> = groups.iter().map(|group| weighted_mean(&sorted[group.clone()])).collect();
+        // Every value joins its nearest center; with sorted centers that's a cut at each midpoint
+        let mut next = Vec::with_capacity(centers.len());
+        let mut start = 0;
+        for pair in centers.windows(2) {
+            let midpoint = (pair[0] + pair[1]) / 2.0;
+            let end = start + sorted[start..].partition_point(|(value, _)| *value <= midpoint);
+            next.push(start..end);
+            start = end;
+        }
+        next.push(start..sorted.len());
+        next.retain(|group| !group.is_empty());
+        if next == groups {
+            break;
+        }
+        groups = next;
+    }
+    groups
+}
+
+// Writes the bucket definitions: one row per bucket with its strength boundaries
+pub fn export_buckets_csv(bucketing: &Bucketing, filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "Bucket,Lower,Upper,Center,Combos,Metric,Method,Board")?;
+    let board: String = bucketing.board.iter().map(|card| card.to_string()).collect();
+    for bucket in &bucketing.buckets {
+        writeln!(file, "{},{:.6},{:.6},{:.6},{},{},{},{}",
+                 bucket.index, bucket.lower, bucket.upper, bucket.center, bucket.combos,
+                 bucketing.metric, bucketing.method, board)?;
+    }
+    file.flush()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// Writes every hand with its strength and bucket index
+pub fn export_hand_buckets_csv(values: &[HandValue], bucketing: &Bucketing, filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "Hand,Value,Bucket")?;
+    for hand in values {
+        writeln!(file, "{},{:.6},{}", hand.hand, hand.value, bucketing.bucket_of(hand.value))?;
+    }
+    file.flush()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
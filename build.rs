@@ -0,0 +1,8 @@
+// Generates the gRPC service code that grpc/mod.rs includes with
+// tonic::include_proto!; without the grpc feature there is nothing to build.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=grpc/pokershit.proto");
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("grpc/pokershit.proto")?;
+    Ok(())
+}
@@ -1,7 +1,17 @@
 use std::fmt;
 use rand::Rng;
 
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+
+// Which card counter the evaluator was built with, for reports like `bench`
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub const EVALUATOR_BACKEND: &str = "sse2";
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+pub const EVALUATOR_BACKEND: &str = "scalar";
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Spades,
     Hearts,
@@ -10,6 +20,7 @@ pub enum Suit {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Two = 2,
     Three = 3,
@@ -27,6 +38,7 @@ pub enum Rank {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
@@ -83,7 +95,10 @@ impl Suit {
     }
 
     pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+        Self::random_with(&mut rand::thread_rng())
+    }
+
+    pub fn random_with<R: Rng>(rng: &mut R) -> Self {
         match rng.gen_range(1..=4) {
             1 => Suit::Spades,
             2 => Suit::Hearts,
@@ -114,7 +129,10 @@ impl Rank {
     }
 
     pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+        Self::random_with(&mut rand::thread_rng())
+    }
+
+    pub fn random_with<R: Rng>(rng: &mut R) -> Self {
         match rng.gen_range(1..=13) {
             1 => Rank::Ace,
             2 => Rank::Two,
@@ -137,6 +155,11 @@ impl Rank {
     }
 }
 
+pub const RANKS_DESCENDING: [Rank; 13] = [
+    Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Ten, Rank::Nine, Rank::Eight,
+    Rank::Seven, Rank::Six, Rank::Five, Rank::Four, Rank::Three, Rank::Two,
+];
+
 impl Card {
     pub fn new(rank: Rank, suit: Suit) -> Self {
         Card { rank, suit }
@@ -152,17 +175,90 @@ impl Card {
     pub fn random() -> Self {
         Card::new(Rank::random(), Suit::random())
     }
+
+    pub fn random_with<R: Rng>(rng: &mut R) -> Self {
+        Card::new(Rank::random_with(rng), Suit::random_with(rng))
+    }
+
+    // Unique index 0-51 for the card
+    pub fn index(&self) -> u8 {
+        let suit = match self.suit {
+            Suit::Spades => 0,
+            Suit::Hearts => 1,
+            Suit::Diamonds => 2,
+            Suit::Clubs => 3,
+        };
+        suit * 13 + (self.rank.value() - 2)
+    }
+}
+
+impl std::str::FromStr for Suit {
+    type Err = String;
+
+    // Accepts the display symbols (♠♥♦♣) as well as s/h/d/c
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "♠" | "s" | "S" => Ok(Suit::Spades),
+            "♥" | "h" | "H" => Ok(Suit::Hearts),
+            "♦" | "d" | "D" => Ok(Suit::Diamonds),
+            "♣" | "c" | "C" => Ok(Suit::Clubs),
+            _ => Err(format!("Invalid suit: {}", s)),
+        }
+    }
+}
+
+impl std::str::FromStr for Rank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" | "a" => Ok(Rank::Ace),
+            "K" | "k" => Ok(Rank::King),
+            "Q" | "q" => Ok(Rank::Queen),
+            "J" | "j" => Ok(Rank::Jack),
+            "T" | "t" | "10" => Ok(Rank::Ten),
+            _ => match s.parse::<u8>() {
+                Ok(num) if (2..=9).contains(&num) => Ok(Rank::from_number(num).unwrap()),
+                _ => Err(format!("Invalid rank: {}", s)),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for Card {
+    type Err = String;
+
+    // Parses both the display form ("10♠", "A♥") and short form ("Ts", "Ah")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let suit_start = match s.char_indices().last() {
+            Some((index, _)) => index,
+            None => return Err("Empty card".to_string()),
+        };
+        let rank = s[..suit_start].parse::<Rank>()?;
+        let suit = s[suit_start..].parse::<Suit>()?;
+        Ok(Card::new(rank, suit))
+    }
 }
 
+// All 52 cards in one fixed buffer split into three regions: cards[..removed]
+// were taken out as known cards, cards[removed..dealt] have been drawn and
+// cards[dealt..] are still in the deck. Drawing is one Fisher-Yates step (swap
+// a random remaining card to the front of the remaining region and advance),
+// so the deck never needs an upfront shuffle and never reallocates.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Deck {
     cards: Vec<Card>,
-    used_cards: Vec<Card>,
+    removed: usize,
+    dealt: usize,
+    // peek() already chose cards[dealt] as the next card
+    peeked: bool,
 }
 
 impl Deck {
     pub fn new() -> Self {
-        let mut cards = Vec::new();
+        let mut cards = Vec::with_capacity(52);
         
         // Create a full deck of 52 cards
         for suit_num in 1..=4 {
@@ -175,62 +271,189 @@ impl Deck {
         
         Deck {
             cards,
-            used_cards: Vec::new(),
+            removed: 0,
+            dealt: 0,
+            peeked: false,
+        }
+    }
+
+    // Moves a random remaining card to cards[dealt] unless peek() already did
+    fn choose_next<R: Rng>(&mut self, rng: &mut R) {
+        if !self.peeked {
+            let index = rng.gen_range(self.dealt..self.cards.len());
+            self.cards.swap(self.dealt, index);
         }
     }
 
+    // The methods without an rng argument use the thread-local generator; the
+    // *_with variants take the caller's (e.g. a seeded StdRng)
     pub fn draw(&mut self) -> Option<Card> {
-        if self.cards.is_empty() {
+        self.draw_with(&mut rand::thread_rng())
+    }
+
+    pub fn draw_with<R: Rng>(&mut self, rng: &mut R) -> Option<Card> {
+        if self.dealt == self.cards.len() {
             return None;
         }
 
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..self.cards.len());
-        let card = self.cards.remove(index);
-        self.used_cards.push(card.clone());
-        Some(card)
+        self.choose_next(rng);
+        self.peeked = false;
+        self.dealt += 1;
+        Some(self.cards[self.dealt - 1].clone())
     }
 
-    pub fn add(&mut self, card: Card) -> Result<(), String> {
-        // Check if the card is already used
-        if self.used_cards.contains(&card) {
-            return Err("Card has already been used".to_string());
+    // Draws `count` cards at once; None (drawing nothing) if fewer remain
+    pub fn deal_n(&mut self, count: usize) -> Option<&[Card]> {
+        self.deal_n_with(count, &mut rand::thread_rng())
+    }
+
+    pub fn deal_n_with<R: Rng>(&mut self, count: usize, rng: &mut R) -> Option<&[Card]> {
+        if self.remaining_cards() < count {
+            return None;
         }
 
-        // Check if the card is already in the deck
-        if self.cards.contains(&card) {
-            return Err("Card is already in the deck".to_string());
+        for _ in 0..count {
+            self.choose_next(rng);
+            self.peeked = false;
+            self.dealt += 1;
         }
+        Some(&self.cards[self.dealt - count..self.dealt])
+    }
+
+    // The card the next draw will return, without drawing it
+    pub fn peek(&mut self) -> Option<&Card> {
+        self.peek_with(&mut rand::thread_rng())
+    }
 
-        // Remove from used cards if it's there and add back to deck
-        if let Some(pos) = self.used_cards.iter().position(|c| *c == card) {
-            self.used_cards.remove(pos);
+    pub fn peek_with<R: Rng>(&mut self, rng: &mut R) -> Option<&Card> {
+        if self.dealt == self.cards.len() {
+            return None;
+        }
+
+        self.choose_next(rng);
+        self.peeked = true;
+        Some(&self.cards[self.dealt])
+    }
+
+    // Discards the next card face down; false if the deck is empty
+    pub fn burn(&mut self) -> bool {
+        self.burn_with(&mut rand::thread_rng())
+    }
+
+    pub fn burn_with<R: Rng>(&mut self, rng: &mut R) -> bool {
+        self.draw_with(rng).is_some()
+    }
+
+    // Shuffles the remaining cards in place, discarding any peeked card choice
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rand::thread_rng())
+    }
+
+    pub fn shuffle_with<R: Rng>(&mut self, rng: &mut R) {
+        for i in (self.dealt + 1..self.cards.len()).rev() {
+            let j = rng.gen_range(self.dealt..=i);
+            self.cards.swap(i, j);
+        }
+        self.peeked = false;
+    }
+
+    // Puts every drawn card back while keeping removed cards out. Simulations
+    // remove the known cards once and recycle the deck before each deal.
+    pub fn recycle(&mut self) {
+        self.dealt = self.removed;
+        self.peeked = false;
+    }
+
+    // Puts every drawn and removed card back, giving a full 52-card deck
+    pub fn reset(&mut self) {
+        self.removed = 0;
+        self.dealt = 0;
+        self.peeked = false;
+    }
+
+    // Returns a drawn or removed card to the deck
+    pub fn add(&mut self, card: Card) -> Result<(), String> {
+        let pos = match self.cards.iter().position(|c| *c == card) {
+            Some(pos) if pos >= self.dealt => return Err("Card is already in the deck".to_string()),
+            Some(pos) => pos,
+            None => return Err("Card does not belong to this deck".to_string()),
+        };
+
+        // Move it to the end of its region, then shrink the region(s) in front of it
+        if pos < self.removed {
+            self.cards.swap(pos, self.removed - 1);
+            self.cards.swap(self.removed - 1, self.dealt - 1);
+            self.removed -= 1;
+        } else {
+            self.cards.swap(pos, self.dealt - 1);
+        }
+        self.dealt -= 1;
+        // Keep a peeked card at the front of the remaining cards
+        if self.peeked {
+            self.cards.swap(self.dealt, self.dealt + 1);
         }
-        
-        self.cards.push(card);
         Ok(())
     }
 
+    // A full deck with `known_cards` (hole cards, board, dead cards) already
+    // removed, so recycle() keeps them out. Fails if a card is listed twice.
+    pub fn without(known_cards: &[Card]) -> Result<Self, String> {
+        check_distinct(known_cards.iter())?;
+        let mut deck = Deck::new();
+        for card in known_cards {
+            let pos = deck.cards.iter().position(|c| c == card).expect("a fresh deck has every card");
+            deck.cards.swap(pos, deck.removed);
+            deck.removed += 1;
+        }
+        deck.dealt = deck.removed;
+        Ok(deck)
+    }
+
+    // Cards still in the deck
+    pub fn cards(&self) -> &[Card] {
+        &self.cards[self.dealt..]
+    }
+
+    // Iterates over the cards still in the deck (not in draw order)
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.cards().iter()
+    }
+
     pub fn remaining_cards(&self) -> usize {
-        self.cards.len()
+        self.cards.len() - self.dealt
     }
 
+    // Removed and drawn cards
     pub fn used_cards(&self) -> &[Card] {
-        &self.used_cards
+        &self.cards[..self.dealt]
     }
 
+    // Takes a known card out of the deck; recycle() won't bring it back
     pub fn remove_card(&mut self, card: &Card) -> Result<(), String> {
-        if let Some(pos) = self.cards.iter().position(|c| *c == *card) {
-            let removed_card = self.cards.remove(pos);
-            self.used_cards.push(removed_card);
-            Ok(())
-        } else {
-            Err("Card not found in deck".to_string())
+        let pos = match self.cards[self.dealt..].iter().position(|c| *c == *card) {
+            Some(offset) => self.dealt + offset,
+            None => return Err("Card not found in deck".to_string()),
+        };
+        
+        // Move it to the front of the remaining cards, then across the drawn ones
+        self.cards.swap(pos, self.dealt);
+        self.cards.swap(self.dealt, self.removed);
+        self.removed += 1;
+        self.dealt += 1;
+        // A peeked card that was swapped out of the way goes back to the front
+        if self.peeked {
+            if pos == self.dealt - 1 {
+                self.peeked = false;
+            } else {
+                self.cards.swap(pos, self.dealt);
+            }
         }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HandRank {
     HighCard = 1,
     Pair = 2,
@@ -262,7 +485,46 @@ impl fmt::Display for HandRank {
     }
 }
 
-#[derive(Debug, Clone)]
+// Every hand category, weakest first
+pub const HAND_RANKS: [HandRank; 10] = [
+    HandRank::HighCard,
+    HandRank::Pair,
+    HandRank::TwoPair,
+    HandRank::ThreeOfAKind,
+    HandRank::Straight,
+    HandRank::Flush,
+    HandRank::FullHouse,
+    HandRank::FourOfAKind,
+    HandRank::StraightFlush,
+    HandRank::RoyalFlush,
+];
+
+impl std::str::FromStr for HandRank {
+    type Err = String;
+
+    // Accepts the display names in any case, with spaces, dashes or underscores
+    // ("Full House", "full-house"), plus "trips" and "set"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase();
+        match name.as_str() {
+            "highcard" => Ok(HandRank::HighCard),
+            "pair" => Ok(HandRank::Pair),
+            "twopair" => Ok(HandRank::TwoPair),
+            "threeofakind" | "trips" | "set" => Ok(HandRank::ThreeOfAKind),
+            "straight" => Ok(HandRank::Straight),
+            "flush" => Ok(HandRank::Flush),
+            "fullhouse" => Ok(HandRank::FullHouse),
+            "fourofakind" | "quads" => Ok(HandRank::FourOfAKind),
+            "straightflush" => Ok(HandRank::StraightFlush),
+            "royalflush" => Ok(HandRank::RoyalFlush),
+            _ => Err(format!("Invalid hand category: {}", s)),
+        }
+    }
+}
+
+// Ordering compares hand rank first, then high cards, so the stronger hand is greater
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandEvaluation {
     pub rank: HandRank,
     pub high_cards: Vec<Rank>,
@@ -274,229 +536,319 @@ impl HandEvaluation {
     }
 }
 
+// Error naming the first card that appears more than once
+pub fn check_distinct<'a, I>(cards: I) -> Result<(), String>
+where
+    I: IntoIterator<Item = &'a Card>,
+{
+    let mut seen = std::collections::HashSet::new();
+    for card in cards {
+        if !seen.insert(card) {
+            return Err(format!("Duplicate card: {}", card));
+        }
+    }
+    Ok(())
+}
+
+// evaluate_hand for untrusted input: the board must have three to five cards
+// and no card may appear twice between the hole cards and the board
+pub fn try_evaluate_hand(hole_cards: &[Card; 2], community_cards: &[Card]) -> Result<HandEvaluation, String> {
+    if community_cards.len() < 3 || community_cards.len() > 5 {
+        return Err(format!("A board has three to five cards, found {}", community_cards.len()));
+    }
+    check_distinct(hole_cards.iter().chain(community_cards.iter()))?;
+    Ok(evaluate_hand(hole_cards, community_cards))
+}
+
 pub fn evaluate_hand(hole_cards: &[Card; 2], community_cards: &[Card]) -> HandEvaluation {
-    let mut all_cards = hole_cards.to_vec();
-    all_cards.extend_from_slice(community_cards);
-    
-    // Sort cards by rank (highest first)
-    all_cards.sort_by(|a, b| b.rank.cmp(&a.rank));
-    
-    // Check for each hand type in order of strength
-    if let Some(eval) = check_royal_flush(&all_cards) {
-        return eval;
+    CardCounts::new(hole_cards.iter().chain(community_cards)).evaluate()
+}
+
+// Evaluates every hand on the same board, counting the board's cards once
+// rather than once per hand (e.g. each combo of a villain range on the river)
+pub fn evaluate_many(hands: &[[Card; 2]], board: &[Card]) -> Vec<HandEvaluation> {
+    let board_counts = CardCounts::new(board);
+    hands.iter()
+        .map(|hand| {
+            let mut counts = board_counts;
+            counts.add_all(hand);
+            counts.evaluate()
+        })
+        .collect()
+}
+
+// Evaluates one hand on each of several boards, counting the hole cards once
+pub fn evaluate_many_boards(hand: &[Card; 2], boards: &[Vec<Card>]) -> Vec<HandEvaluation> {
+    let hand_counts = CardCounts::new(hand);
+    boards.iter()
+        .map(|board| {
+            let mut counts = hand_counts;
+            counts.add_all(board);
+            counts.evaluate()
+        })
+        .collect()
+}
+
+// Number of cards of each rank, indexed by Rank::value(); 16 entries so the
+// simd feature can load and store it as one vector
+type RankCounts = [u8; 16];
+
+// Ranks held in each suit (spades, hearts, diamonds, clubs) as rank_bit masks
+type SuitMasks = [u16; 4];
+
+// Everything the evaluator needs to know about a set of cards. Ranks and suits
+// are all that matter, so the cards can be counted in any order, and the
+// counts of a shared board can be copied and topped up with each hand.
+#[derive(Debug, Clone, Copy, Default)]
+struct CardCounts {
+    ranks: RankCounts,
+    suits: SuitMasks,
+}
+
+impl CardCounts {
+    fn new<'a, I: IntoIterator<Item = &'a Card>>(cards: I) -> Self {
+        let mut counts = CardCounts::default();
+        counts.add_all(cards);
+        counts
+    }
+
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    fn add_all<'a, I: IntoIterator<Item = &'a Card>>(&mut self, cards: I) {
+        for card in cards {
+            self.ranks[card.rank.value() as usize] += 1;
+            self.suits[suit_index(&card.suit)] |= rank_bit(card.rank);
+        }
     }
-    if let Some(eval) = check_straight_flush(&all_cards) {
-        return eval;
+
+    // Counts with SSE2 vector ops (see card/simd.rs)
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn add_all<'a, I: IntoIterator<Item = &'a Card>>(&mut self, cards: I) {
+        simd::add_cards(self, cards);
     }
-    if let Some(eval) = check_four_of_a_kind(&all_cards) {
-        return eval;
+
+    // Ranks held at least `count` times, highest first
+    fn ranks_with_count(&self, count: u8) -> impl Iterator<Item = Rank> + '_ {
+        RANKS_DESCENDING.iter().copied().filter(move |rank| self.ranks[rank.value() as usize] >= count)
     }
-    if let Some(eval) = check_full_house(&all_cards) {
-        return eval;
+
+    // Ranks held outside the `made` ranks, highest first. Whenever a kicker is
+    // needed the remaining ranks are unpaired (or the hand would rank higher),
+    // except for two pair, where a third pair still gives the best kicker.
+    fn kickers<'a>(&'a self, made: &'a [Rank]) -> impl Iterator<Item = Rank> + 'a {
+        self.ranks_with_count(1).filter(move |rank| !made.contains(rank))
     }
-    if let Some(eval) = check_flush(&all_cards) {
-        return eval;
+
+    fn evaluate(&self) -> HandEvaluation {
+        // Check for each hand type in order of strength
+        if let Some(eval) = self.check_royal_flush() {
+            return eval;
+        }
+        if let Some(eval) = self.check_straight_flush() {
+            return eval;
+        }
+        if let Some(eval) = self.check_four_of_a_kind() {
+            return eval;
+        }
+        if let Some(eval) = self.check_full_house() {
+            return eval;
+        }
+        if let Some(eval) = self.check_flush() {
+            return eval;
+        }
+        if let Some(eval) = self.check_straight() {
+            return eval;
+        }
+        if let Some(eval) = self.check_three_of_a_kind() {
+            return eval;
+        }
+        if let Some(eval) = self.check_two_pair() {
+            return eval;
+        }
+        if let Some(eval) = self.check_pair() {
+            return eval;
+        }
+        
+        // High card
+        HandEvaluation::new(HandRank::HighCard, self.kickers(&[]).take(5).collect())
     }
-    if let Some(eval) = check_straight(&all_cards) {
-        return eval;
+
+    fn check_royal_flush(&self) -> Option<HandEvaluation> {
+        // A straight flush to the ace in any suit
+        self.suits.iter()
+            .any(|&mask| find_straight(mask) == Some(Rank::Ace))
+            .then(|| HandEvaluation::new(HandRank::RoyalFlush, vec![Rank::Ace]))
     }
-    if let Some(eval) = check_three_of_a_kind(&all_cards) {
-        return eval;
+
+    fn check_straight_flush(&self) -> Option<HandEvaluation> {
+        let high_card = self.suits.iter().filter_map(|&mask| find_straight(mask)).max()?;
+        Some(HandEvaluation::new(HandRank::StraightFlush, vec![high_card]))
     }
-    if let Some(eval) = check_two_pair(&all_cards) {
-        return eval;
+
+    fn check_four_of_a_kind(&self) -> Option<HandEvaluation> {
+        let rank = self.ranks_with_count(4).next()?;
+        let kicker = self.kickers(&[rank]).next().unwrap_or(Rank::Two);
+        Some(HandEvaluation::new(HandRank::FourOfAKind, vec![rank, kicker]))
     }
-    if let Some(eval) = check_pair(&all_cards) {
-        return eval;
+
+    fn check_full_house(&self) -> Option<HandEvaluation> {
+        let trips = self.ranks_with_count(3).next()?;
+        let pair = self.ranks_with_count(2).find(|&rank| rank != trips)?;
+        Some(HandEvaluation::new(HandRank::FullHouse, vec![trips, pair]))
     }
-    
-    // High card
-    let high_cards = all_cards.iter().take(5).map(|c| c.rank).collect();
-    HandEvaluation::new(HandRank::HighCard, high_cards)
-}
 
-fn check_royal_flush(cards: &[Card]) -> Option<HandEvaluation> {
-    // Check each suit for A-K-Q-J-10
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-        let suit_cards: Vec<&Card> = cards.iter().filter(|c| c.suit == suit).collect();
-        if suit_cards.len() >= 5 {
-            let royal_ranks = [Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Ten];
-            if royal_ranks.iter().all(|&rank| suit_cards.iter().any(|c| c.rank == rank)) {
-                return Some(HandEvaluation::new(HandRank::RoyalFlush, vec![Rank::Ace]));
-            }
-        }
+    fn check_flush(&self) -> Option<HandEvaluation> {
+        let mask = self.suits.iter().copied().find(|mask| (mask & !LOW_ACE_BIT).count_ones() >= 5)?;
+        let high_cards = RANKS_DESCENDING.iter().copied().filter(|&rank| mask & rank_bit(rank) != 0).take(5).collect();
+        Some(HandEvaluation::new(HandRank::Flush, high_cards))
     }
-    None
-}
 
-fn check_straight_flush(cards: &[Card]) -> Option<HandEvaluation> {
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-        let mut suit_cards: Vec<&Card> = cards.iter().filter(|c| c.suit == suit).collect();
-        suit_cards.sort_by(|a, b| b.rank.cmp(&a.rank));
-        
-        if let Some(high_card) = find_straight(&suit_cards.iter().map(|c| c.rank).collect::<Vec<_>>()) {
-            return Some(HandEvaluation::new(HandRank::StraightFlush, vec![high_card]));
-        }
+    fn check_straight(&self) -> Option<HandEvaluation> {
+        let high_card = find_straight(self.suits.iter().fold(0, |all, mask| all | mask))?;
+        Some(HandEvaluation::new(HandRank::Straight, vec![high_card]))
     }
-    None
-}
 
-fn check_four_of_a_kind(cards: &[Card]) -> Option<HandEvaluation> {
-    let rank_counts = count_ranks(cards);
-    
-    for (rank, count) in rank_counts.iter() {
-        if *count >= 4 {
-            let kicker = cards.iter()
-                .find(|c| c.rank != *rank)
-                .map(|c| c.rank)
-                .unwrap_or(Rank::Two);
-            return Some(HandEvaluation::new(HandRank::FourOfAKind, vec![*rank, kicker]));
-        }
+    fn check_three_of_a_kind(&self) -> Option<HandEvaluation> {
+        let rank = self.ranks_with_count(3).next()?;
+        let mut result = Vec::with_capacity(3);
+        result.push(rank);
+        result.extend(self.kickers(&[rank]).take(2));
+        Some(HandEvaluation::new(HandRank::ThreeOfAKind, result))
     }
-    None
-}
 
-fn check_full_house(cards: &[Card]) -> Option<HandEvaluation> {
-    let rank_counts = count_ranks(cards);
-    let mut trips = None;
-    let mut pair = None;
-    
-    for (rank, count) in rank_counts.iter() {
-        if *count >= 3 && trips.is_none() {
-            trips = Some(*rank);
-        } else if *count >= 2 && pair.is_none() {
-            pair = Some(*rank);
-        }
+    fn check_two_pair(&self) -> Option<HandEvaluation> {
+        let mut pairs = self.ranks_with_count(2);
+        let (high, low) = (pairs.next()?, pairs.next()?);
+        let kicker = self.kickers(&[high, low]).next().unwrap_or(Rank::Two);
+        Some(HandEvaluation::new(HandRank::TwoPair, vec![high, low, kicker]))
     }
-    
-    if let (Some(trips_rank), Some(pair_rank)) = (trips, pair) {
-        return Some(HandEvaluation::new(HandRank::FullHouse, vec![trips_rank, pair_rank]));
+
+    fn check_pair(&self) -> Option<HandEvaluation> {
+        let rank = self.ranks_with_count(2).next()?;
+        let mut result = Vec::with_capacity(4);
+        result.push(rank);
+        result.extend(self.kickers(&[rank]).take(3));
+        Some(HandEvaluation::new(HandRank::Pair, result))
     }
-    None
 }
 
-fn check_flush(cards: &[Card]) -> Option<HandEvaluation> {
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-        let mut suit_cards: Vec<&Card> = cards.iter().filter(|c| c.suit == suit).collect();
-        if suit_cards.len() >= 5 {
-            suit_cards.sort_by(|a, b| b.rank.cmp(&a.rank));
-            let high_cards = suit_cards.iter().take(5).map(|c| c.rank).collect();
-            return Some(HandEvaluation::new(HandRank::Flush, high_cards));
-        }
+// Position of the suit in SuitMasks
+fn suit_index(suit: &Suit) -> usize {
+    match suit {
+        Suit::Spades => 0,
+        Suit::Hearts => 1,
+        Suit::Diamonds => 2,
+        Suit::Clubs => 3,
     }
-    None
 }
 
-fn check_straight(cards: &[Card]) -> Option<HandEvaluation> {
-    let ranks: Vec<Rank> = cards.iter().map(|c| c.rank).collect();
-    if let Some(high_card) = find_straight(&ranks) {
-        return Some(HandEvaluation::new(HandRank::Straight, vec![high_card]));
+// An ace also sets bit 1, below the two, so the wheel is five consecutive bits
+const LOW_ACE_BIT: u16 = 1 << 1;
+
+// Bit `value` for the rank, plus LOW_ACE_BIT for an ace
+fn rank_bit(rank: Rank) -> u16 {
+    if rank == Rank::Ace {
+        1 << rank.value() | LOW_ACE_BIT
+    } else {
+        1 << rank.value()
     }
-    None
 }
 
-fn check_three_of_a_kind(cards: &[Card]) -> Option<HandEvaluation> {
-    let rank_counts = count_ranks(cards);
-    
-    for (rank, count) in rank_counts.iter() {
-        if *count >= 3 {
-            let mut kickers: Vec<Rank> = cards.iter()
-                .filter(|c| c.rank != *rank)
-                .map(|c| c.rank)
-                .collect();
-            kickers.sort_by(|a, b| b.cmp(a));
-            kickers.truncate(2);
-            
-            let mut result = vec![*rank];
-            result.extend(kickers);
-            return Some(HandEvaluation::new(HandRank::ThreeOfAKind, result));
-        }
-    }
-    None
+// High card of the best straight in a rank mask; a wheel (A-2-3-4-5) is five high
+fn find_straight(mask: u16) -> Option<Rank> {
+    (5..=14u8)
+        .rev()
+        .find(|&high| (mask >> (high - 4)) & 0b11111 == 0b11111)
+        .map(|high| RANKS_DESCENDING[usize::from(14 - high)])
 }
 
-fn check_two_pair(cards: &[Card]) -> Option<HandEvaluation> {
-    let rank_counts = count_ranks(cards);
-    let mut pairs = Vec::new();
-    
-    for (rank, count) in rank_counts.iter() {
-        if *count >= 2 {
-            pairs.push(*rank);
-        }
-    }
-    
-    if pairs.len() >= 2 {
-        pairs.sort_by(|a, b| b.cmp(a));
-        let kicker = cards.iter()
-            .find(|c| c.rank != pairs[0] && c.rank != pairs[1])
-            .map(|c| c.rank)
-            .unwrap_or(Rank::Two);
-        
-        return Some(HandEvaluation::new(HandRank::TwoPair, vec![pairs[0], pairs[1], kicker]));
-    }
-    None
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Holdem,
+    Omaha,
 }
 
-fn check_pair(cards: &[Card]) -> Option<HandEvaluation> {
-    let rank_counts = count_ranks(cards);
-    
-    for (rank, count) in rank_counts.iter() {
-        if *count >= 2 {
-            let mut kickers: Vec<Rank> = cards.iter()
-                .filter(|c| c.rank != *rank)
-                .map(|c| c.rank)
-                .collect();
-            kickers.sort_by(|a, b| b.cmp(a));
-            kickers.truncate(3);
-            
-            let mut result = vec![*rank];
-            result.extend(kickers);
-            return Some(HandEvaluation::new(HandRank::Pair, result));
+impl Variant {
+    pub fn hole_card_count(&self) -> usize {
+        match self {
+            Variant::Holdem => 2,
+            Variant::Omaha => 4,
         }
     }
-    None
 }
 
-fn count_ranks(cards: &[Card]) -> std::collections::HashMap<Rank, usize> {
-    let mut counts = std::collections::HashMap::new();
-    for card in cards {
-        *counts.entry(card.rank).or_insert(0) += 1;
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Variant::Holdem => "Texas Hold'em",
+            Variant::Omaha => "Omaha",
+        };
+        write!(f, "{}", name)
     }
-    counts
 }
 
-fn find_straight(ranks: &[Rank]) -> Option<Rank> {
-    use std::collections::HashSet;
-    let unique_ranks: HashSet<Rank> = ranks.iter().cloned().collect();
-    let mut sorted_ranks: Vec<Rank> = unique_ranks.into_iter().collect();
-    sorted_ranks.sort_by(|a, b| b.cmp(a));
+// Hold'em uses the best five of all seven cards; Omaha must use exactly
+// two hole cards and three board cards
+pub fn evaluate_variant(variant: Variant, hole_cards: &[Card], community_cards: &[Card]) -> HandEvaluation {
+    assert_eq!(hole_cards.len(), variant.hole_card_count(), "{} hands have {} hole cards", variant, variant.hole_card_count());
     
-    // Check for regular straights
-    if sorted_ranks.len() >= 5 {
-        for i in 0..=sorted_ranks.len() - 5 {
-            let mut consecutive = true;
-            for j in 0..4 {
-                if sorted_ranks[i + j].value() != sorted_ranks[i + j + 1].value() + 1 {
-                    consecutive = false;
-                    break;
+    match variant {
+        Variant::Holdem => evaluate_hand(&[hole_cards[0].clone(), hole_cards[1].clone()], community_cards),
+        Variant::Omaha => {
+            let mut best: Option<HandEvaluation> = None;
+            for i in 0..hole_cards.len() {
+                for j in (i + 1)..hole_cards.len() {
+                    let hole = [hole_cards[i].clone(), hole_cards[j].clone()];
+                    for a in 0..community_cards.len() {
+                        for b in (a + 1)..community_cards.len() {
+                            for c in (b + 1)..community_cards.len() {
+                                let board = [community_cards[a].clone(), community_cards[b].clone(), community_cards[c].clone()];
+                                let eval = evaluate_hand(&hole, &board);
+                                if best.as_ref().is_none_or(|current| eval > *current) {
+                                    best = Some(eval);
+                                }
+                            }
+                        }
+                    }
                 }
             }
-            if consecutive {
-                return Some(sorted_ranks[i]);
-            }
+            best.expect("Omaha evaluation needs at least three community cards")
         }
     }
+}
+
+// Scores hands for a particular game. Whatever the game's own ordering,
+// a greater Strength always means the better hand at showdown
+pub trait Evaluator {
+    type Strength: Ord;
+
+    fn evaluate(&self, hole_cards: &[Card], community_cards: &[Card]) -> Self::Strength;
+}
+
+impl Evaluator for Variant {
+    type Strength = HandEvaluation;
+
+    fn evaluate(&self, hole_cards: &[Card], community_cards: &[Card]) -> HandEvaluation {
+        evaluate_variant(*self, hole_cards, community_cards)
+    }
+}
+
+// Parses a list of cards such as "AsKh", "A♠ K♥" or "Ts,9s,2d"
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, String> {
+    let chars: Vec<char> = input.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    let mut cards = Vec::new();
+    let mut i = 0;
     
-    // Check for A-2-3-4-5 straight (wheel)
-    if sorted_ranks.contains(&Rank::Ace) && 
-       sorted_ranks.contains(&Rank::Two) && 
-       sorted_ranks.contains(&Rank::Three) && 
-       sorted_ranks.contains(&Rank::Four) && 
-       sorted_ranks.contains(&Rank::Five) {
-        return Some(Rank::Five); // In wheel straight, 5 is the high card
+    while i < chars.len() {
+        let rank_len = if chars[i] == '1' && chars.get(i + 1) == Some(&'0') { 2 } else { 1 };
+        if i + rank_len >= chars.len() {
+            return Err(format!("Incomplete card at end of '{}'", input));
+        }
+        let text: String = chars[i..=i + rank_len].iter().collect();
+        cards.push(text.parse::<Card>()?);
+        i += rank_len + 1;
     }
     
-    None
+    Ok(cards)
 }
 
 pub fn verify(hand_a: &[Card; 2], hand_b: &[Card; 2], community_cards: &[Card]) -> (String, HandEvaluation, HandEvaluation) {
@@ -520,6 +872,7 @@ pub fn verify(hand_a: &[Card; 2], hand_b: &[Card; 2], community_cards: &[Card])
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimulationResults {
     pub total_games: usize,
     pub wins: usize,
@@ -527,12 +880,106 @@ pub struct SimulationResults {
     pub ties: usize,
     pub win_rate: f64,
     pub tie_rate: f64,
+    pub split_pot: Option<SplitPotResults>,
+    pub street_equity: Option<StreetEquity>,
+    // How often hero finished with each hand category (empty if not tracked)
+    pub made_hands: std::collections::HashMap<HandRank, usize>,
+    pub showdown: Option<ShowdownStats>,
+}
+
+// Extra breakdown for split-pot games, all values in percent
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitPotResults {
+    // Average share of the whole pot won
+    pub pot_equity: f64,
+    // Share of the high halves won (ties count half)
+    pub high_equity: f64,
+    // Share of the low halves won, over deals where some low qualified
+    pub low_equity: f64,
+    // How often any hand qualified for low
+    pub low_qualified_rate: f64,
+    // How often hero won the entire pot outright
+    pub scoop_rate: f64,
+}
+
+// How often hero's hand was ahead (ties count half) once each street was dealt,
+// in percent. The river value is the final showdown equity.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreetEquity {
+    pub flop: f64,
+    pub turn: f64,
+    pub river: f64,
+}
+
+// Losing with this category or better counts as a cooler
+pub const COOLER_RANK: HandRank = HandRank::Flush;
+
+// Strength of the hands hero won with and lost to. Ranks are HandRank values
+// (1 for high card up to 10 for a royal flush).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShowdownStats {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub winning_rank_total: usize,
+    pub losing_to_rank_total: usize,
+    // Losses while holding COOLER_RANK or better
+    pub coolers: usize,
+}
+
+impl ShowdownStats {
+    pub fn record(&mut self, player: &HandEvaluation, opponent: &HandEvaluation) {
+        self.games += 1;
+        match player.cmp(opponent) {
+            std::cmp::Ordering::Greater => {
+                self.wins += 1;
+                self.winning_rank_total += player.rank.clone() as usize;
+            }
+            std::cmp::Ordering::Less => {
+                self.losses += 1;
+                self.losing_to_rank_total += opponent.rank.clone() as usize;
+                if player.rank >= COOLER_RANK {
+                    self.coolers += 1;
+                }
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    pub fn average_winning_rank(&self) -> Option<f64> {
+        (self.wins > 0).then(|| self.winning_rank_total as f64 / self.wins as f64)
+    }
+
+    pub fn average_losing_to_rank(&self) -> Option<f64> {
+        (self.losses > 0).then(|| self.losing_to_rank_total as f64 / self.losses as f64)
+    }
+
+    // Percent of games lost with COOLER_RANK or better
+    pub fn cooler_rate(&self) -> f64 {
+        self.coolers as f64 / self.games.max(1) as f64 * 100.0
+    }
+
+    pub fn combine(&self, other: &ShowdownStats) -> ShowdownStats {
+        ShowdownStats {
+            games: self.games + other.games,
+            wins: self.wins + other.wins,
+            losses: self.losses + other.losses,
+            winning_rank_total: self.winning_rank_total + other.winning_rank_total,
+            losing_to_rank_total: self.losing_to_rank_total + other.losing_to_rank_total,
+            coolers: self.coolers + other.coolers,
+        }
+    }
 }
 
 impl SimulationResults {
-    fn new(total_games: usize, wins: usize, losses: usize, ties: usize) -> Self {
-        let win_rate = (wins as f64 / total_games as f64) * 100.0;
-        let tie_rate = (ties as f64 / total_games as f64) * 100.0;
+    pub fn new(total_games: usize, wins: usize, losses: usize, ties: usize) -> Self {
+        // No games played gives 0% rather than NaN
+        let games = total_games.max(1) as f64;
+        let win_rate = (wins as f64 / games) * 100.0;
+        let tie_rate = (ties as f64 / games) * 100.0;
         
         SimulationResults {
             total_games,
@@ -541,137 +988,1096 @@ impl SimulationResults {
             ties,
             win_rate,
             tie_rate,
+            split_pot: None,
+            street_equity: None,
+            made_hands: std::collections::HashMap::new(),
+            showdown: None,
         }
     }
-}
 
-pub fn monte_carlo_simulation(player_hand: &[Card; 2], num_simulations: usize) -> SimulationResults {
-    let mut wins = 0;
-    let mut losses = 0;
-    let mut ties = 0;
-    
-    for _ in 0..num_simulations {
-        // Create a new deck for each simulation
-        let mut deck = Deck::new();
-        
-        // Remove player's cards from deck
-        for card in player_hand {
-            if let Err(_) = deck.remove_card(card) {
-                // Card not found in deck, skip this simulation
-                continue;
-            }
+    // Percent of games hero finished with this hand category
+    pub fn made_hand_rate(&self, rank: &HandRank) -> f64 {
+        let count = self.made_hands.get(rank).copied().unwrap_or(0);
+        count as f64 / self.total_games.max(1) as f64 * 100.0
+    }
+
+    // Sums the counts of two runs of the same matchup
+    pub fn combine(&self, other: &SimulationResults) -> SimulationResults {
+        let mut combined = SimulationResults::new(
+            self.total_games + other.total_games,
+            self.wins + other.wins,
+            self.losses + other.losses,
+            self.ties + other.ties,
+        );
+        combined.made_hands = self.made_hands.clone();
+        for (rank, count) in &other.made_hands {
+            *combined.made_hands.entry(rank.clone()).or_insert(0) += count;
         }
-        
-        // Deal opponent hand
-        let opp_card1 = match deck.draw() {
-            Some(card) => card,
-            None => continue, // Not enough cards, skip
+        combined.showdown = match (&self.showdown, &other.showdown) {
+            (Some(a), Some(b)) => Some(a.combine(b)),
+            (a, b) => a.clone().or_else(|| b.clone()),
         };
-        let opp_card2 = match deck.draw() {
-            Some(card) => card,
-            None => continue, // Not enough cards, skip
-        };
-        let opponent_hand = [opp_card1, opp_card2];
-        
-        // Deal community cards
-        let mut community_cards = Vec::new();
-        for _ in 0..5 {
-            if let Some(card) = deck.draw() {
-                community_cards.push(card);
-            } else {
-                break; // Not enough cards
-            }
+        combined
+    }
+
+    // 95% normal-approximation confidence interval for the win rate, in percent
+    pub fn win_rate_ci(&self) -> (f64, f64) {
+        if self.total_games == 0 {
+            return (0.0, 0.0);
         }
-        
-        // Skip if we don't have enough community cards
-        if community_cards.len() < 5 {
-            continue;
+        let p = self.win_rate / 100.0;
+        let margin = 1.96 * (p * (1.0 - p) / self.total_games as f64).sqrt() * 100.0;
+        ((self.win_rate - margin).max(0.0), (self.win_rate + margin).min(100.0))
+    }
+
+    // The same for equity (ties count half), from the variance of a deal's pot share
+    pub fn equity_ci(&self) -> (f64, f64) {
+        if self.total_games == 0 {
+            return (0.0, 0.0);
         }
-        
-        // Evaluate hands and determine winner
-        let (winner, _player_eval, _opp_eval) = verify(player_hand, &opponent_hand, &community_cards);
-        
-        match winner.as_str() {
-            "Hand A" => wins += 1,
-            "Hand B" => losses += 1,
-            "Tie" => ties += 1,
-            _ => {} // Should not happen
+        let (win, tie) = (self.win_rate / 100.0, self.tie_rate / 100.0);
+        let equity = win + tie / 2.0;
+        let variance = (win + tie / 4.0 - equity * equity).max(0.0);
+        let margin = 1.96 * (variance / self.total_games as f64).sqrt() * 100.0;
+        ((equity * 100.0 - margin).max(0.0), (equity * 100.0 + margin).min(100.0))
+    }
+
+    // Two-proportion z-test of this run's win rate against another, independent
+    // run's. The p-value uses the pooled rate (both hands equally strong); the
+    // interval uses each run's own rate.
+    pub fn compare_win_rate(&self, other: &SimulationResults) -> WinRateComparison {
+        let difference = self.win_rate - other.win_rate;
+        if self.total_games == 0 || other.total_games == 0 {
+            return WinRateComparison { difference, ci: (difference, difference), z: 0.0, p_value: 1.0 };
+        }
+        let (p1, p2) = (self.win_rate / 100.0, other.win_rate / 100.0);
+        let (n1, n2) = (self.total_games as f64, other.total_games as f64);
+        let pooled = (p1 * n1 + p2 * n2) / (n1 + n2);
+        let null_error = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+        let z = if null_error > 0.0 { (p1 - p2) / null_error } else { 0.0 };
+        let margin = 1.96 * (p1 * (1.0 - p1) / n1 + p2 * (1.0 - p2) / n2).sqrt() * 100.0;
+        WinRateComparison {
+            difference,
+            ci: (difference - margin, difference + margin),
+            z,
+            p_value: 2.0 * (1.0 - normal_cdf(z.abs())),
         }
     }
-    
-    SimulationResults::new(num_simulations, wins, losses, ties)
 }
 
-pub fn monte_carlo_with_community(
-    player_hand: &[Card; 2], 
-    known_community: &[Card], 
-    num_simulations: usize
-) -> SimulationResults {
-    let mut wins = 0;
-    let mut losses = 0;
-    let mut ties = 0;
-    
-    for _ in 0..num_simulations {
-        // Create a new deck for each simulation
-        let mut deck = Deck::new();
-        
-        // Remove player's cards from deck
-        for card in player_hand {
-            if let Err(_) = deck.remove_card(card) {
-                continue;
+// Difference between two runs' win rates, in percentage points
+#[derive(Debug, Clone, Copy)]
+pub struct WinRateComparison {
+    pub difference: f64,
+    // 95% interval for the difference
+    pub ci: (f64, f64),
+    pub z: f64,
+    // Two-sided chance of a gap at least this wide if both hands won equally often
+    pub p_value: f64,
+}
+
+impl WinRateComparison {
+    pub fn significant(&self, level: f64) -> bool {
+        self.p_value < level
+    }
+}
+
+// Standard normal CDF, from Abramowitz and Stegun's erf approximation 7.1.26
+// (error below 1.5e-7)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+// Trials dealt per batch under a time budget; the clock is checked between batches
+const BUDGET_BATCH: usize = 1000;
+
+// How many trials an equity run deals: a fixed count, or as many as fit in
+// a wall-clock budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrialBudget {
+    Count(usize),
+    Time(std::time::Duration),
+}
+
+impl TrialBudget {
+    // Calls `simulate` with the count, or keeps calling it with batches and
+    // summing the results until the time is spent (always at least one batch).
+    // Only counts survive the sum, so a time budget drops street equity and
+    // split pot breakdowns.
+    pub fn run<F>(&self, mut simulate: F) -> SimulationResults
+    where
+        F: FnMut(usize) -> SimulationResults,
+    {
+        match *self {
+            TrialBudget::Count(trials) => simulate(trials),
+            TrialBudget::Time(budget) => {
+                let start_time = std::time::Instant::now();
+                let mut results = simulate(BUDGET_BATCH);
+                while start_time.elapsed() < budget {
+                    results = results.combine(&simulate(BUDGET_BATCH));
+                }
+                results
             }
         }
+    }
+}
+
+impl fmt::Display for TrialBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrialBudget::Count(trials) => write!(f, "{}", trials),
+            TrialBudget::Time(budget) => write!(f, "as many as fit in {:.1}s", budget.as_secs_f64()),
+        }
+    }
+}
+
+// A deal's pot share varies most at 50% equity (ties only make it steadier),
+// so planning there is safe when the equity isn't known yet
+pub const PLANNING_EQUITY: f64 = 50.0;
+
+// Trials for a 95% interval of ±`margin` points around an equity of roughly
+// `equity` percent, e.g. ±0.25 at 50% needs 153,664
+pub fn trials_for_margin(margin: f64, equity: f64) -> usize {
+    let p = (equity / 100.0).clamp(0.0, 1.0);
+    let trials = (1.96 * 100.0 / margin).powi(2) * p * (1.0 - p);
+    trials.ceil().max(1.0) as usize
+}
+
+// The ± half-width, in points, that `trials` give at that equity
+pub fn margin_for_trials(trials: usize, equity: f64) -> f64 {
+    let p = (equity / 100.0).clamp(0.0, 1.0);
+    1.96 * (p * (1.0 - p) / trials.max(1) as f64).sqrt() * 100.0
+}
+
+// The running estimate after some number of trials, all values in percent
+#[derive(Debug, Clone, Copy)]
+pub struct ConvergencePoint {
+    pub trials: usize,
+    pub win_rate: f64,
+    pub tie_rate: f64,
+    pub equity: f64,
+    // 95% interval for equity at this point
+    pub equity_ci: (f64, f64),
+}
+
+// How an estimate settles as trials are added, for plotting against the
+// trial count to check it was enough
+#[derive(Debug, Clone)]
+pub struct ConvergenceTrace {
+    pub every: usize,
+    pub points: Vec<ConvergencePoint>,
+}
+
+impl ConvergenceTrace {
+    pub fn new(every: usize) -> Self {
+        ConvergenceTrace { every: every.max(1), points: Vec::new() }
+    }
+
+    // Like TrialBudget::run, but deals in steps of `every` trials and notes the
+    // running estimate after each. The steps are summed the same way, so street
+    // equity and split pot breakdowns are dropped here too.
+    pub fn run<F>(&mut self, budget: TrialBudget, mut simulate: F) -> SimulationResults
+    where
+        F: FnMut(usize) -> SimulationResults,
+    {
+        let start_time = std::time::Instant::now();
+        let mut results = SimulationResults::new(0, 0, 0, 0);
+        loop {
+            let step = match budget {
+                TrialBudget::Count(trials) => self.every.min(trials.saturating_sub(results.total_games)),
+                TrialBudget::Time(_) => self.every,
+            };
+            let batch = simulate(step);
+            let dealt = batch.total_games;
+            results = results.combine(&batch);
+            self.record(&results);
+            let done = match budget {
+                TrialBudget::Count(trials) => results.total_games >= trials,
+                TrialBudget::Time(time) => start_time.elapsed() >= time,
+            };
+            // A step that deals nothing (every villain hand blocked) would never finish
+            if done || dealt == 0 {
+                return results;
+            }
+        }
+    }
+
+    fn record(&mut self, results: &SimulationResults) {
+        self.points.push(ConvergencePoint {
+            trials: results.total_games,
+            win_rate: results.win_rate,
+            tie_rate: results.tie_rate,
+            equity: results.win_rate + results.tie_rate / 2.0,
+            equity_ci: results.equity_ci(),
+        });
+    }
+}
+
+// CSV, or JSON when the file name ends in .json (before any .gz or .zst)
+pub fn export_convergence(points: &[ConvergencePoint], filename: &str) -> Result<(), std::io::Error> {
+    use std::io::Write;
+    
+    let mut file = crate::compress::create(filename)?;
+    if crate::compress::base_name(filename).to_ascii_lowercase().ends_with(".json") {
+        writeln!(file, "[")?;
+        for (i, point) in points.iter().enumerate() {
+            writeln!(file, "  {{\"trials\": {}, \"win_rate\": {:.4}, \"tie_rate\": {:.4}, \"equity\": {:.4}, \"equity_ci_low\": {:.4}, \"equity_ci_high\": {:.4}}}{}",
+                     point.trials, point.win_rate, point.tie_rate, point.equity, point.equity_ci.0, point.equity_ci.1,
+                     if i + 1 < points.len() { "," } else { "" })?;
+        }
+        writeln!(file, "]")?;
+    } else {
+        writeln!(file, "Trials,Win_Rate,Tie_Rate,Equity,Equity_CI_Low,Equity_CI_High")?;
+        for point in points {
+            writeln!(file, "{},{:.4},{:.4},{:.4},{:.4},{:.4}",
+                     point.trials, point.win_rate, point.tie_rate, point.equity, point.equity_ci.0, point.equity_ci.1)?;
+        }
+    }
+    file.finish()?;
+    
+    tracing::info!("Convergence trace exported to: {}", filename);
+    Ok(())
+}
+
+// How the deals are drawn when several hands are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    // Every hand gets deals of its own
+    #[default]
+    Independent,
+    // Every hand plays the same deals (common random numbers), so the
+    // differences between hands aren't swamped by one drawing better boards
+    Common,
+    // Common deals, half of them played a second time mirrored by rank so a
+    // strong villain hand or board in one is a weak one in the other
+    Antithetic,
+}
+
+impl std::str::FromStr for Sampling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "independent" => Ok(Sampling::Independent),
+            "common" | "crn" => Ok(Sampling::Common),
+            "antithetic" => Ok(Sampling::Antithetic),
+            _ => Err(format!("Invalid sampling '{}', expected independent, common or antithetic", s)),
+        }
+    }
+}
+
+impl fmt::Display for Sampling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sampling::Independent => write!(f, "independent deals"),
+            Sampling::Common => write!(f, "common deals"),
+            Sampling::Antithetic => write!(f, "common deals with antithetic mirrors"),
+        }
+    }
+}
+
+// The deals of a Common or Antithetic comparison, drawn once and replayed for
+// every hand. Each is the top of a shuffled deck (less the board); a hand
+// skips its own two cards and takes its opponents' hole cards and then the
+// rest of the board in order.
+pub struct CommonDeals {
+    // `depth` cards per deal, one deal after another
+    cards: Vec<Card>,
+    depth: usize,
+    num_opponents: usize,
+    board: Vec<Card>,
+    // By card index, the card each one becomes in a deal's antithetic
+    // mirror: the k-th lowest card off the board swaps with the k-th highest.
+    // The same for every hand, so the mirrored deals are common too.
+    mirror: Option<Vec<Card>>,
+}
+
+impl CommonDeals {
+    // Enough deals for `num_trials` showdowns per hand; Antithetic draws half
+    // as many and plays each twice
+    pub fn new<R: Rng>(num_trials: usize, num_opponents: usize, board: &[Card], sampling: Sampling, rng: &mut R) -> Result<Self, String> {
+        let antithetic = sampling == Sampling::Antithetic;
+        let num_deals = if antithetic { num_trials.div_ceil(2) } else { num_trials };
+        // Hero's own cards may turn up among the dealt ones and are skipped
+        let depth = 2 * num_opponents + (5 - board.len()) + 2;
+        let mut deck = Deck::without(board)?;
+        if depth > deck.remaining_cards() {
+            return Err(format!("Not enough cards to deal {} opponents", num_opponents));
+        }
+        let mut cards = Vec::with_capacity(num_deals * depth);
+        for _ in 0..num_deals {
+            deck.recycle();
+            cards.extend_from_slice(deck.deal_n_with(depth, rng).expect("the deck was checked to hold a deal"));
+        }
         
-        // Remove known community cards from deck
-        for card in known_community {
-            if let Err(_) = deck.remove_card(card) {
-                continue;
+        let mirror = antithetic.then(|| {
+            deck.recycle();
+            let mut live: Vec<Card> = deck.iter().cloned().collect();
+            live.sort_by_key(|card| (card.rank.value(), card.index()));
+            let mut mirror: Vec<Card> = Deck::new().iter().cloned().collect();
+            mirror.sort_by_key(Card::index);
+            for (low, high) in live.iter().zip(live.iter().rev()) {
+                mirror[low.index() as usize] = high.clone();
+            }
+            mirror
+        });
+        Ok(CommonDeals { cards, depth, num_opponents, board: board.to_vec(), mirror })
+    }
+
+    // Hero's results over every deal, as monte_carlo_vs_opponents_with_rng
+    pub fn play(&self, player_hand: &[Card; 2]) -> SimulationResults {
+        let mut tally = OpponentsTally::default();
+        let needed = 2 * self.num_opponents + 5 - self.board.len();
+        let mut dealt = Vec::with_capacity(needed);
+        let mut opponents = Vec::with_capacity(self.num_opponents);
+        let mut board = self.board.clone();
+        let passes: &[bool] = if self.mirror.is_some() { &[false, true] } else { &[false] };
+        for deal in self.cards.chunks(self.depth) {
+            for &mirrored in passes {
+                dealt.clear();
+                dealt.extend(deal.iter()
+                    .map(|card| match &self.mirror {
+                        Some(mirror) if mirrored => &mirror[card.index() as usize],
+                        _ => card,
+                    })
+                    .filter(|card| !player_hand.contains(card))
+                    .take(needed)
+                    .cloned());
+                let (hole_cards, rest) = dealt.split_at(2 * self.num_opponents);
+                opponents.clear();
+                opponents.extend(hole_cards.chunks(2).map(|hand| [hand[0].clone(), hand[1].clone()]));
+                board.truncate(self.board.len());
+                board.extend_from_slice(rest);
+                tally.record(player_hand, &opponents, &board);
             }
         }
+        tally.into_results()
+    }
+}
+
+// Every simulator has a *_with_rng variant taking the random number generator,
+// so a run can be seeded and replayed; the plain versions use thread_rng()
+pub fn monte_carlo_simulation(player_hand: &[Card; 2], num_simulations: usize) -> SimulationResults {
+    monte_carlo_simulation_with_rng(player_hand, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_simulation_with_rng<R: Rng>(player_hand: &[Card; 2], num_simulations: usize, rng: &mut R) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut made_hands = std::collections::HashMap::new();
+    let mut showdown = ShowdownStats::default();
+    
+    // One deck serves every deal: the player's cards come out once and the
+    // dealt cards are recycled. Identical hole cards mean no deal can be played.
+    let mut deck = match Deck::without(player_hand) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
         
-        // Deal opponent hand
-        let opp_card1 = match deck.draw() {
-            Some(card) => card,
+        // Deal opponent hand and community cards, skipping if the deck runs out
+        let opponent_hand = match deck.deal_n_with(2, rng) {
+            Some(cards) => [cards[0].clone(), cards[1].clone()],
             None => continue,
         };
-        let opp_card2 = match deck.draw() {
-            Some(card) => card,
+        let community_cards = match deck.deal_n_with(5, rng) {
+            Some(cards) => cards,
             None => continue,
         };
-        let opponent_hand = [opp_card1, opp_card2];
         
-        // Complete community cards
-        let mut community_cards = known_community.to_vec();
-        let cards_needed = 5 - known_community.len();
+        // Evaluate hands and determine winner
+        let player_eval = evaluate_hand(player_hand, community_cards);
+        let opp_eval = evaluate_hand(&opponent_hand, community_cards);
+        showdown.record(&player_eval, &opp_eval);
+        match player_eval.cmp(&opp_eval) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+        *made_hands.entry(player_eval.rank).or_insert(0) += 1;
+    }
+    
+    let mut results = SimulationResults::new(wins + losses + ties, wins, losses, ties);
+    results.made_hands = made_hands;
+    results.showdown = Some(showdown);
+    results
+}
+
+// monte_carlo_simulation for untrusted input, rejecting a pair of identical hole cards
+pub fn try_monte_carlo_simulation(player_hand: &[Card; 2], num_simulations: usize) -> Result<SimulationResults, String> {
+    check_distinct(player_hand.iter())?;
+    if num_simulations == 0 {
+        return Err("The number of simulations must be positive".to_string());
+    }
+    Ok(monte_carlo_simulation(player_hand, num_simulations))
+}
+
+// monte_carlo_with_community for untrusted input: at most five board cards,
+// none of them shared with the hole cards
+pub fn try_monte_carlo_with_community(
+    player_hand: &[Card; 2],
+    known_community: &[Card],
+    num_simulations: usize
+) -> Result<SimulationResults, String> {
+    if known_community.len() > 5 {
+        return Err(format!("The board has at most five cards, found {}", known_community.len()));
+    }
+    check_distinct(player_hand.iter().chain(known_community.iter()))?;
+    if num_simulations == 0 {
+        return Err("The number of simulations must be positive".to_string());
+    }
+    Ok(monte_carlo_with_community(player_hand, known_community, num_simulations))
+}
+
+pub fn monte_carlo_with_community(
+    player_hand: &[Card; 2], 
+    known_community: &[Card], 
+    num_simulations: usize
+) -> SimulationResults {
+    simulate_with_community(player_hand, known_community, num_simulations, false, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_with_community_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> SimulationResults {
+    simulate_with_community(player_hand, known_community, num_simulations, false, rng)
+}
+
+// Same as monte_carlo_with_community, but also records who was ahead after the
+// flop, turn and river of each deal in `street_equity`
+pub fn monte_carlo_with_community_by_street(
+    player_hand: &[Card; 2],
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    simulate_with_community(player_hand, known_community, num_simulations, true, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_with_community_by_street_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> SimulationResults {
+    simulate_with_community(player_hand, known_community, num_simulations, true, rng)
+}
+
+// 1 if hero is ahead on this board, 0.5 if tied, 0 if behind
+fn showdown_points(player_hand: &[Card; 2], opponent_hand: &[Card; 2], board: &[Card]) -> f64 {
+    match evaluate_hand(player_hand, board).cmp(&evaluate_hand(opponent_hand, board)) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Less => 0.0,
+    }
+}
+
+// Every two-card combo that doesn't use a known card (990 on a river heads-up)
+pub fn unseen_combos(known_cards: &[Card]) -> Vec<[Card; 2]> {
+    let unseen: Vec<Card> = Deck::new().cards().iter().filter(|card| !known_cards.contains(card)).cloned().collect();
+    let mut combos = Vec::new();
+    for i in 0..unseen.len() {
+        for j in i + 1..unseen.len() {
+            combos.push([unseen[i].clone(), unseen[j].clone()]);
+        }
+    }
+    combos
+}
+
+// Exact showdown against each opponent combo on a complete board. The counts are
+// numbers of combos; with weighted combos the win and tie rates use the weights.
+pub fn exact_river_showdown<'a, I>(player_hand: &[Card; 2], board: &[Card], opponent_combos: I) -> SimulationResults
+where
+    I: IntoIterator<Item = (&'a [Card; 2], f64)>,
+{
+    let (combos, combo_weights): (Vec<[Card; 2]>, Vec<f64>) = opponent_combos.into_iter()
+        .map(|(combo, weight)| (combo.clone(), weight))
+        .unzip();
+    let player_eval = evaluate_hand(player_hand, board);
+    
+    let mut counts = [0usize; 3];
+    let mut weights = [0.0; 3];
+    for (combo_eval, weight) in evaluate_many(&combos, board).iter().zip(combo_weights) {
+        let outcome = match player_eval.cmp(combo_eval) {
+            std::cmp::Ordering::Greater => 0,
+            std::cmp::Ordering::Less => 1,
+            std::cmp::Ordering::Equal => 2,
+        };
+        counts[outcome] += 1;
+        weights[outcome] += weight;
+    }
+    
+    let total = counts.iter().sum();
+    let mut results = SimulationResults::new(total, counts[0], counts[1], counts[2]);
+    results.made_hands.insert(player_eval.rank, total);
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight > 0.0 {
+        results.win_rate = weights[0] / total_weight * 100.0;
+        results.tie_rate = weights[2] / total_weight * 100.0;
+    }
+    results
+}
+
+fn simulate_with_community<R: Rng>(
+    player_hand: &[Card; 2],
+    known_community: &[Card],
+    num_simulations: usize,
+    track_streets: bool,
+    rng: &mut R
+) -> SimulationResults {
+    // With the whole board known there is nothing to sample
+    if known_community.len() == 5 {
+        let mut known_cards = player_hand.to_vec();
+        known_cards.extend_from_slice(known_community);
+        let combos = unseen_combos(&known_cards);
+        let mut results = exact_river_showdown(player_hand, known_community, combos.iter().map(|combo| (combo, 1.0)));
+        if track_streets {
+            let equity = |board_size: usize| {
+                combos.iter().map(|combo| showdown_points(player_hand, combo, &known_community[..board_size])).sum::<f64>()
+                    / combos.len() as f64 * 100.0
+            };
+            results.street_equity = Some(StreetEquity { flop: equity(3), turn: equity(4), river: equity(5) });
+        }
+        return results;
+    }
+    
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut made_hands = std::collections::HashMap::new();
+    let mut showdown = ShowdownStats::default();
+    // Points per street (1 ahead, 0.5 tied) for the flop, turn and river
+    let mut street_points = [0.0; 3];
+    
+    // Remove player's and known community cards once; no deal can be played if one repeats
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    // Board buffer reused by every deal: the known cards followed by the runout
+    let mut community_cards = known_community.to_vec();
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
         
-        for _ in 0..cards_needed {
-            if let Some(card) = deck.draw() {
-                community_cards.push(card);
-            } else {
-                break;
+        // Deal opponent hand and complete community cards, skipping if the deck runs out
+        let opponent_hand = match deck.deal_n_with(2, rng) {
+            Some(cards) => [cards[0].clone(), cards[1].clone()],
+            None => continue,
+        };
+        community_cards.truncate(known_community.len());
+        match deck.deal_n_with(5 - known_community.len(), rng) {
+            Some(cards) => community_cards.extend_from_slice(cards),
+            None => continue,
+        }
+        
+        if track_streets {
+            for (street, board_size) in [3, 4, 5].iter().enumerate() {
+                street_points[street] += showdown_points(player_hand, &opponent_hand, &community_cards[..*board_size]);
             }
         }
         
-        // Skip if we don't have enough community cards
-        if community_cards.len() < 5 {
+        // Evaluate hands and determine winner
+        let player_eval = evaluate_hand(player_hand, &community_cards);
+        let opp_eval = evaluate_hand(&opponent_hand, &community_cards);
+        showdown.record(&player_eval, &opp_eval);
+        match player_eval.cmp(&opp_eval) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+        *made_hands.entry(player_eval.rank).or_insert(0) += 1;
+    }
+    
+    let mut results = SimulationResults::new(wins + losses + ties, wins, losses, ties);
+    results.made_hands = made_hands;
+    results.showdown = Some(showdown);
+    if track_streets {
+        let played = (wins + losses + ties).max(1) as f64;
+        results.street_equity = Some(StreetEquity {
+            flop: street_points[0] / played * 100.0,
+            turn: street_points[1] / played * 100.0,
+            river: street_points[2] / played * 100.0,
+        });
+    }
+    results
+}
+
+// Outcome distribution for running an all-in out several times
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunItResults {
+    pub runs: usize,
+    pub total_trials: usize,
+    // share_counts[k] counts trials where hero won k/(2 * runs) of the pot;
+    // each run is worth 1/runs of the pot and a tied run is split in half
+    pub share_counts: Vec<usize>,
+    // Average share of the pot won, in percent
+    pub equity: f64,
+}
+
+impl RunItResults {
+    // Probability of each pot share, as (share of pot, probability) pairs in percent
+    pub fn distribution(&self) -> Vec<(f64, f64)> {
+        self.share_counts.iter()
+            .enumerate()
+            .map(|(units, &count)| {
+                (units as f64 / (2 * self.runs) as f64 * 100.0, count as f64 / self.total_trials as f64 * 100.0)
+            })
+            .collect()
+    }
+}
+
+// Deals `runs` independent runouts per trial from the same stub: cards used by
+// one run are not available to the next, as when running it twice live
+pub fn run_it_n_times(
+    player_hand: &[Card; 2],
+    opponent_hand: &[Card; 2],
+    known_community: &[Card],
+    runs: usize,
+    num_trials: usize
+) -> Result<RunItResults, String> {
+    run_it_n_times_with_rng(player_hand, opponent_hand, known_community, runs, num_trials, &mut rand::thread_rng())
+}
+
+pub fn run_it_n_times_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    opponent_hand: &[Card; 2],
+    known_community: &[Card],
+    runs: usize,
+    num_trials: usize,
+    rng: &mut R
+) -> Result<RunItResults, String> {
+    if runs == 0 || known_community.len() > 5 {
+        return Err("Need at least one run and at most five board cards".to_string());
+    }
+    let cards_per_run = 5 - known_community.len();
+    let stub_size = 52 - 4 - known_community.len();
+    if runs * cards_per_run > stub_size {
+        return Err(format!("Only {} cards left, not enough to run it {} times", stub_size, runs));
+    }
+    
+    let mut share_counts = vec![0; 2 * runs + 1];
+    let mut total_units = 0;
+    
+    let known: Vec<Card> = player_hand.iter().chain(opponent_hand.iter()).chain(known_community.iter()).cloned().collect();
+    let mut deck = Deck::without(&known)?;
+    let mut community_cards = known_community.to_vec();
+    
+    for _ in 0..num_trials {
+        deck.recycle();
+        
+        let mut units = 0;
+        for _ in 0..runs {
+            community_cards.truncate(known_community.len());
+            community_cards.extend_from_slice(deck.deal_n_with(cards_per_run, rng).ok_or("Deck ran out of cards")?);
+            
+            units += match evaluate_hand(player_hand, &community_cards).cmp(&evaluate_hand(opponent_hand, &community_cards)) {
+                std::cmp::Ordering::Greater => 2,
+                std::cmp::Ordering::Equal => 1,
+                std::cmp::Ordering::Less => 0,
+            };
+        }
+        
+        share_counts[units] += 1;
+        total_units += units;
+    }
+    
+    Ok(RunItResults {
+        runs,
+        total_trials: num_trials,
+        share_counts,
+        equity: total_units as f64 / (2 * runs * num_trials) as f64 * 100.0,
+    })
+}
+
+// Splits one pot across any number of hands and boards. Each board is worth an
+// equal part of the pot, shared equally between the best hands on that board.
+// Returns each hand's fraction of the whole pot.
+pub fn showdown_pot_shares(hands: &[[Card; 2]], boards: &[Vec<Card>]) -> Vec<f64> {
+    let mut shares = vec![0.0; hands.len()];
+    
+    for board in boards {
+        let evals = evaluate_many(hands, board);
+        let best = match evals.iter().max() {
+            Some(best) => best,
+            None => continue,
+        };
+        let winners: Vec<usize> = (0..hands.len()).filter(|&i| evals[i] == *best).collect();
+        let portion = 1.0 / boards.len() as f64 / winners.len() as f64;
+        for i in winners {
+            shares[i] += portion;
+        }
+    }
+    
+    shares
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiBoardResults {
+    pub total_games: usize,
+    // Won the whole pot
+    pub scoops: usize,
+    // Won part of the pot
+    pub chops: usize,
+    // Won nothing
+    pub losses: usize,
+    // Average share of the pot, in percent
+    pub equity: f64,
+}
+
+impl MultiBoardResults {
+    pub fn scoop_rate(&self) -> f64 {
+        self.scoops as f64 / self.total_games as f64 * 100.0
+    }
+
+    pub fn chop_rate(&self) -> f64 {
+        self.chops as f64 / self.total_games as f64 * 100.0
+    }
+
+    pub fn lose_rate(&self) -> f64 {
+        self.losses as f64 / self.total_games as f64 * 100.0
+    }
+}
+
+// Multi-board (e.g. double-board bomb pot) equity for known hands. `known_boards`
+// holds the known cards of each board (possibly empty); every board is completed
+// independently from the same deck.
+pub fn monte_carlo_multi_board(hands: &[[Card; 2]], known_boards: &[Vec<Card>], num_simulations: usize) -> Vec<MultiBoardResults> {
+    monte_carlo_multi_board_with_rng(hands, known_boards, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_multi_board_with_rng<R: Rng>(hands: &[[Card; 2]], known_boards: &[Vec<Card>], num_simulations: usize, rng: &mut R) -> Vec<MultiBoardResults> {
+    let mut scoops = vec![0; hands.len()];
+    let mut chops = vec![0; hands.len()];
+    let mut losses = vec![0; hands.len()];
+    let mut totals = vec![0.0; hands.len()];
+    let mut games = 0;
+    
+    let known: Vec<Card> = hands.iter().flatten().chain(known_boards.iter().flatten()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return vec![MultiBoardResults::default(); hands.len()],
+    };
+    
+    let mut boards = known_boards.to_vec();
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
+        
+        for (board, known) in boards.iter_mut().zip(known_boards) {
+            board.clone_from(known);
+        }
+        let complete = boards.iter_mut().all(|board| {
+            while board.len() < 5 {
+                match deck.draw_with(rng) {
+                    Some(card) => board.push(card),
+                    None => return false,
+                }
+            }
+            true
+        });
+        if !complete {
             continue;
         }
         
-        // Evaluate hands and determine winner
-        let (winner, _player_eval, _opp_eval) = verify(player_hand, &opponent_hand, &community_cards);
+        games += 1;
+        for (i, share) in showdown_pot_shares(hands, &boards).into_iter().enumerate() {
+            totals[i] += share;
+            if share >= 1.0 - 1e-9 {
+                scoops[i] += 1;
+            } else if share > 0.0 {
+                chops[i] += 1;
+            } else {
+                losses[i] += 1;
+            }
+        }
+    }
+    
+    (0..hands.len())
+        .map(|i| MultiBoardResults {
+            total_games: games,
+            scoops: scoops[i],
+            chops: chops[i],
+            losses: losses[i],
+            equity: if games == 0 { 0.0 } else { totals[i] / games as f64 * 100.0 },
+        })
+        .collect()
+}
+
+// Calls `visit` with every completion of `board` to five cards from `remaining`
+fn for_each_board<F: FnMut(&[Card])>(remaining: &[Card], start: usize, board: &mut Vec<Card>, visit: &mut F) {
+    if board.len() == 5 {
+        visit(board);
+        return;
+    }
+    let needed = 5 - board.len();
+    for i in start..=remaining.len().saturating_sub(needed) {
+        board.push(remaining[i].clone());
+        for_each_board(remaining, i + 1, board, visit);
+        board.pop();
+    }
+}
+
+// Exact heads-up equity of `hand_a` against `hand_b` by enumerating every way to
+// complete the board (1,712,304 boards preflop)
+pub fn exact_heads_up(hand_a: &[Card; 2], hand_b: &[Card; 2], known_community: &[Card]) -> SimulationResults {
+    let known: Vec<Card> = hand_a.iter().chain(hand_b.iter()).chain(known_community.iter()).cloned().collect();
+    let remaining: Vec<Card> = Deck::new().cards().iter().filter(|card| !known.contains(card)).cloned().collect();
+    
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut board = known_community.to_vec();
+    for_each_board(&remaining, 0, &mut board, &mut |board| {
+        match evaluate_hand(hand_a, board).cmp(&evaluate_hand(hand_b, board)) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+    });
+    
+    SimulationResults::new(wins + losses + ties, wins, losses, ties)
+}
+
+// Sampled version of exact_heads_up for when enumerating every board is too slow
+pub fn monte_carlo_heads_up(hand_a: &[Card; 2], hand_b: &[Card; 2], known_community: &[Card], num_simulations: usize) -> SimulationResults {
+    monte_carlo_heads_up_with_rng(hand_a, hand_b, known_community, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_heads_up_with_rng<R: Rng>(hand_a: &[Card; 2], hand_b: &[Card; 2], known_community: &[Card], num_simulations: usize, rng: &mut R) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    
+    let known: Vec<Card> = hand_a.iter().chain(hand_b.iter()).chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    
+    let mut board = known_community.to_vec();
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
         
-        match winner.as_str() {
-            "Hand A" => wins += 1,
-            "Hand B" => losses += 1,
-            "Tie" => ties += 1,
-            _ => {}
+        board.truncate(known_community.len());
+        match deck.deal_n_with(5 - known_community.len(), rng) {
+            Some(cards) => board.extend_from_slice(cards),
+            None => continue,
+        }
+        
+        match evaluate_hand(hand_a, &board).cmp(&evaluate_hand(hand_b, &board)) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
         }
     }
     
-    SimulationResults::new(num_simulations, wins, losses, ties)
+    SimulationResults::new(wins + losses + ties, wins, losses, ties)
+}
+
+// Deals `num_opponents` hands onto `hands` and refills `board` after its first
+// `known` cards; false if the deck runs out. Shared by the simulators that take
+// an opponent count, so they all draw in the same order.
+fn deal_opponents<R: Rng>(deck: &mut Deck, num_opponents: usize, hands: &mut Vec<[Card; 2]>, board: &mut Vec<Card>, known: usize, rng: &mut R) -> bool {
+    for _ in 0..num_opponents {
+        match (deck.draw_with(rng), deck.draw_with(rng)) {
+            (Some(a), Some(b)) => hands.push([a, b]),
+            _ => return false,
+        }
+    }
+    board.truncate(known);
+    match deck.deal_n_with(5 - known, rng) {
+        Some(cards) => {
+            board.extend_from_slice(cards);
+            true
+        },
+        None => false,
+    }
+}
+
+// Hero's showdowns against the best of several opponents, as
+// monte_carlo_vs_opponents and CommonDeals count them
+#[derive(Default)]
+struct OpponentsTally {
+    wins: usize,
+    losses: usize,
+    ties: usize,
+    made_hands: std::collections::HashMap<HandRank, usize>,
+    showdown: ShowdownStats,
+}
+
+impl OpponentsTally {
+    // Nothing is recorded for a deal without opponents
+    fn record(&mut self, player_hand: &[Card; 2], opponents: &[[Card; 2]], board: &[Card]) {
+        let player_eval = evaluate_hand(player_hand, board);
+        let best_opponent = match opponents.iter().map(|hand| evaluate_hand(hand, board)).max() {
+            Some(best) => best,
+            None => return,
+        };
+        self.showdown.record(&player_eval, &best_opponent);
+        match player_eval.cmp(&best_opponent) {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Less => self.losses += 1,
+            std::cmp::Ordering::Equal => self.ties += 1,
+        }
+        *self.made_hands.entry(player_eval.rank).or_insert(0) += 1;
+    }
+
+    fn into_results(self) -> SimulationResults {
+        let mut results = SimulationResults::new(self.wins + self.losses + self.ties, self.wins, self.losses, self.ties);
+        results.made_hands = self.made_hands;
+        results.showdown = Some(self.showdown);
+        results
+    }
+}
+
+// All-in equity against `num_opponents` random hands. A win means beating every
+// opponent, a tie means splitting the pot with at least one of them.
+pub fn monte_carlo_vs_opponents(
+    player_hand: &[Card; 2],
+    num_opponents: usize,
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    monte_carlo_vs_opponents_with_rng(player_hand, num_opponents, known_community, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_vs_opponents_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    num_opponents: usize,
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> SimulationResults {
+    let mut tally = OpponentsTally::default();
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    
+    let mut opponents = Vec::with_capacity(num_opponents);
+    let mut board = known_community.to_vec();
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
+        
+        opponents.clear();
+        if deal_opponents(&mut deck, num_opponents, &mut opponents, &mut board, known_community.len(), rng) {
+            tally.record(player_hand, &opponents, &board);
+        }
+    }
+    tally.into_results()
+}
+
+// Hero's pot share against `num_opponents` random hands, all-in to showdown
+pub fn monte_carlo_multiway(player_hand: &[Card; 2], num_opponents: usize, known_community: &[Card], num_simulations: usize) -> MultiBoardResults {
+    monte_carlo_multiway_with_rng(player_hand, num_opponents, known_community, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_multiway_with_rng<R: Rng>(player_hand: &[Card; 2], num_opponents: usize, known_community: &[Card], num_simulations: usize, rng: &mut R) -> MultiBoardResults {
+    let mut scoops = 0;
+    let mut chops = 0;
+    let mut losses = 0;
+    let mut total = 0.0;
+    let mut games = 0;
+    
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return MultiBoardResults::default(),
+    };
+    
+    let mut hands = Vec::with_capacity(num_opponents + 1);
+    let mut board = known_community.to_vec();
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
+        
+        hands.clear();
+        hands.push(player_hand.clone());
+        if !deal_opponents(&mut deck, num_opponents, &mut hands, &mut board, known_community.len(), rng) {
+            continue;
+        }
+        
+        games += 1;
+        let share = showdown_pot_shares(&hands, std::slice::from_ref(&board))[0];
+        total += share;
+        if share >= 1.0 - 1e-9 {
+            scoops += 1;
+        } else if share > 0.0 {
+            chops += 1;
+        } else {
+            losses += 1;
+        }
+    }
+    
+    MultiBoardResults {
+        total_games: games,
+        scoops,
+        chops,
+        losses,
+        equity: if games == 0 { 0.0 } else { total / games as f64 * 100.0 },
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EquityCurvePoint {
+    pub opponents: usize,
+    pub results: MultiBoardResults,
+}
+
+// Preflop all-in equity against 1 through `max_opponents` random hands
+pub fn equity_curve(player_hand: &[Card; 2], max_opponents: usize, num_simulations: usize) -> Vec<EquityCurvePoint> {
+    equity_curve_with_rng(player_hand, max_opponents, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn equity_curve_with_rng<R: Rng>(player_hand: &[Card; 2], max_opponents: usize, num_simulations: usize, rng: &mut R) -> Vec<EquityCurvePoint> {
+    (1..=max_opponents)
+        .map(|opponents| EquityCurvePoint {
+            opponents,
+            results: monte_carlo_multiway_with_rng(player_hand, opponents, &[], num_simulations, rng),
+        })
+        .collect()
+}
+
+pub fn export_equity_curve_csv(points: &[EquityCurvePoint], filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::Write;
+    
+    let mut file = File::create(filename)?;
+    writeln!(file, "Opponents,Equity,Fair_Share,Scoop_Rate,Chop_Rate,Lose_Rate,Total_Games")?;
+    for point in points {
+        writeln!(file, "{},{:.4},{:.4},{:.4},{:.4},{:.4},{}",
+                 point.opponents,
+                 point.results.equity,
+                 100.0 / (point.opponents + 1) as f64,
+                 point.results.scoop_rate(),
+                 point.results.chop_rate(),
+                 point.results.lose_rate(),
+                 point.results.total_games)?;
+    }
+    
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandResult {
     pub hand: [Card; 2],
     pub hand_description: String,
@@ -689,7 +2095,8 @@ impl HandResult {
     }
 }
 
-fn describe_hand(hand: &[Card; 2]) -> String {
+// Canonical (suit-isomorphic) name of a starting hand, e.g. "AKs", "AKo", "AA(pair)"
+pub fn describe_hand(hand: &[Card; 2]) -> String {
     let card1 = &hand[0];
     let card2 = &hand[1];
     
@@ -731,38 +2138,397 @@ pub fn generate_all_starting_hands() -> Vec<[Card; 2]> {
             hands.push([all_cards[i].clone(), all_cards[j].clone()]);
         }
     }
-    
-    hands
+    
+    hands
+}
+
+// One representative combo for each of the 169 canonical starting hands,
+// ordered from AA down through the suited and offsuit combinations
+pub fn generate_canonical_starting_hands() -> Vec<[Card; 2]> {
+    let mut hands = Vec::new();
+    
+    for (i, &high) in RANKS_DESCENDING.iter().enumerate() {
+        hands.push([Card::new(high, Suit::Spades), Card::new(high, Suit::Hearts)]);
+        for &low in &RANKS_DESCENDING[i + 1..] {
+            hands.push([Card::new(high, Suit::Spades), Card::new(low, Suit::Spades)]);
+            hands.push([Card::new(high, Suit::Spades), Card::new(low, Suit::Hearts)]);
+        }
+    }
+    
+    hands
+}
+
+// Number of concrete combos a canonical hand stands for (6 pairs, 4 suited, 12 offsuit)
+pub fn combo_count(hand: &[Card; 2]) -> usize {
+    if hand[0].rank == hand[1].rank {
+        6
+    } else if hand[0].suit == hand[1].suit {
+        4
+    } else {
+        12
+    }
+}
+
+// Sklansky-Malmuth starting hand groups 1 through 8; everything else is group 9
+const SKLANSKY_GROUPS: [&[&str]; 8] = [
+    &["AA", "KK", "QQ", "JJ", "AKs"],
+    &["TT", "AQs", "AJs", "KQs", "AKo"],
+    &["99", "JTs", "QJs", "KJs", "ATs", "AQo"],
+    &["T9s", "KQo", "88", "QTs", "98s", "J9s", "AJo", "KTs"],
+    &["77", "87s", "Q9s", "T8s", "KJo", "QJo", "JTo", "76s", "97s", "A9s", "A8s", "A7s",
+      "A6s", "A5s", "A4s", "A3s", "A2s", "65s"],
+    &["66", "ATo", "55", "86s", "KTo", "QTo", "54s", "K9s", "J8s", "75s"],
+    &["44", "J9o", "64s", "T9o", "53s", "33", "98o", "43s", "22", "K8s", "K7s", "K6s",
+      "K5s", "K4s", "K3s", "K2s", "T7s", "Q8s"],
+    &["87o", "A9o", "Q9o", "76o", "42s", "32s", "96s", "85s", "J8o", "J7s", "65o", "54o",
+      "74s", "K9o", "T8o"],
+];
+
+// Sklansky group (1-9) of a starting hand
+pub fn sklansky_group(hand: &[Card; 2]) -> u8 {
+    let notation = crate::range::hand_notation(hand);
+    SKLANSKY_GROUPS.iter()
+        .position(|group| group.contains(&notation.as_str()))
+        .map_or(9, |i| i as u8 + 1)
+}
+
+// Bill Chen's starting hand score: points for the high card, doubled for pairs
+// (minimum 5), +2 suited, minus a penalty for the gap between the cards, +1 for
+// connected or one-gap hands below a queen, with halves rounded up
+pub fn chen_score(hand: &[Card; 2]) -> i32 {
+    let (high, low) = if hand[0].rank >= hand[1].rank { (hand[0].rank, hand[1].rank) } else { (hand[1].rank, hand[0].rank) };
+    let card_points = |rank: Rank| match rank {
+        Rank::Ace => 10.0,
+        Rank::King => 8.0,
+        Rank::Queen => 7.0,
+        Rank::Jack => 6.0,
+        _ => rank.value() as f64 / 2.0,
+    };
+    
+    if high == low {
+        return (card_points(high) * 2.0).max(5.0).ceil() as i32;
+    }
+    
+    let mut score = card_points(high);
+    if hand[0].suit == hand[1].suit {
+        score += 2.0;
+    }
+    let gap = high.value() - low.value() - 1;
+    score -= match gap {
+        0 => 0.0,
+        1 => 1.0,
+        2 => 2.0,
+        3 => 4.0,
+        _ => 5.0,
+    };
+    if gap <= 1 && high < Rank::Queen {
+        score += 1.0;
+    }
+    score.ceil() as i32
+}
+
+// Collapses per-combo results into one row per canonical hand by summing their counts
+pub fn aggregate_canonical(results: &[HandResult]) -> Vec<HandResult> {
+    let mut aggregated: Vec<HandResult> = Vec::new();
+    let mut index_by_description = std::collections::HashMap::new();
+    
+    for result in results {
+        match index_by_description.get(&result.hand_description) {
+            Some(&i) => {
+                let existing: &mut HandResult = &mut aggregated[i];
+                existing.results = existing.results.combine(&result.results);
+            }
+            None => {
+                index_by_description.insert(result.hand_description.clone(), aggregated.len());
+                aggregated.push(result.clone());
+            }
+        }
+    }
+    
+    aggregated.sort_by(by_win_rate);
+    aggregated
+}
+
+pub fn bulk_monte_carlo_simulation(simulations_per_hand: usize) -> Vec<HandResult> {
+    let mut rng = rand::thread_rng();
+    let simulate = |hand: &[Card; 2]| random_opponent_simulation(hand, 1, &[], simulations_per_hand, &mut rng);
+    run_bulk_simulation(&generate_all_starting_hands(), TrialBudget::Count(simulations_per_hand), 1, &[], simulate, |_| Ok(()), |_| {})
+        .expect("bulk simulation without a sink cannot fail")
+}
+
+// Where a bulk run stands, handed to the progress callback after every hand
+#[derive(Debug, Clone, Copy)]
+pub struct BulkProgress {
+    pub completed: usize,
+    pub total: usize,
+    // Trials dealt so far, over every finished hand
+    pub simulations: usize,
+    pub elapsed: std::time::Duration,
+}
+
+impl BulkProgress {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.completed as f64 / self.total as f64 * 100.0
+        }
+    }
+
+    pub fn hands_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 { self.completed as f64 / seconds } else { 0.0 }
+    }
+
+    pub fn simulations_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 { self.simulations as f64 / seconds } else { 0.0 }
+    }
+
+    // Time left at the average pace so far; unknown until a hand has finished
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        if self.completed == 0 {
+            return None;
+        }
+        let per_hand = self.elapsed.as_secs_f64() / self.completed as f64;
+        Some(std::time::Duration::from_secs_f64(per_hand * (self.total - self.completed) as f64))
+    }
+}
+
+// Same as bulk_monte_carlo_simulation, but every HandResult is written to
+// `writer` as soon as it completes so a crashed run keeps its finished rows.
+// The streamed rows are unranked; call export_to_csv on the returned results
+// to replace the file with the final ranked output. Each hand plays against
+// `num_opponents` random hands on `board` (empty for preflop) for `budget`;
+// hands holding a board card are skipped. Every deal is drawn from `rng`, and
+// `on_progress` hears about every finished hand.
+pub fn bulk_monte_carlo_simulation_streaming<R, P>(
+    hands: &[[Card; 2]],
+    budget: TrialBudget,
+    num_opponents: usize,
+    board: &[Card],
+    writer: CsvStreamWriter,
+    rng: &mut R,
+    on_progress: P,
+) -> Result<Vec<HandResult>, std::io::Error>
+where
+    R: Rng,
+    P: FnMut(&BulkProgress),
+{
+    let simulate = |hand: &[Card; 2]| budget.run(|trials| random_opponent_simulation(hand, num_opponents, board, trials, rng));
+    bulk_simulation_streaming_with(hands, budget, num_opponents, board, writer, simulate, on_progress)
+}
+
+// Same as bulk_monte_carlo_simulation_streaming, but each hand's results come
+// from `simulate` (e.g. against opponents who fold weak hands) instead of
+// deals against random hands; `simulate` draws from its own rng and is
+// expected to keep to `budget`
+pub fn bulk_simulation_streaming_with<S, P>(
+    hands: &[[Card; 2]],
+    budget: TrialBudget,
+    num_opponents: usize,
+    board: &[Card],
+    mut writer: CsvStreamWriter,
+    simulate: S,
+    on_progress: P,
+) -> Result<Vec<HandResult>, std::io::Error>
+where
+    S: FnMut(&[Card; 2]) -> SimulationResults,
+    P: FnMut(&BulkProgress),
+{
+    let results = run_bulk_simulation(hands, budget, num_opponents, board, simulate, |result| writer.write_result(result), on_progress)?;
+    writer.finish()?;
+    Ok(results)
+}
+
+// Hands whose 95% win rate interval overlaps the next stronger or weaker hand
+// in the ranking, so their order isn't settled yet. Neighbours of the same
+// class (two AKs combos) are skipped, since they only differ by noise.
+// `results` must be sorted best first.
+pub fn unsettled_hands(results: &[HandResult]) -> Vec<usize> {
+    let intervals: Vec<(f64, f64)> = results.iter().map(|result| result.results.win_rate_ci()).collect();
+    let overlaps = |i: usize, j: usize| intervals[i].0 <= intervals[j].1 && intervals[j].0 <= intervals[i].1;
+    (0..results.len())
+        .filter(|&i| {
+            let other_class = |j: &usize| results[*j].hand_description != results[i].hand_description;
+            let stronger = (0..i).rev().find(other_class);
+            let weaker = (i + 1..results.len()).find(other_class);
+            stronger.into_iter().chain(weaker).any(|j| overlaps(i, j))
+        })
+        .collect()
+}
+
+// What adaptive allocation added on top of the first pass
+#[derive(Debug, Clone, Default)]
+pub struct Refinement {
+    pub rounds: usize,
+    pub extra_simulations: usize,
+    // Hands still overlapping a neighbour when the rounds ran out
+    pub unsettled: usize,
+}
+
+// Adaptive allocation after a bulk run: each round gives every unsettled hand
+// another `budget` of trials against `num_opponents` random hands on `board`
+// and re-ranks, until the ordering settles or `max_rounds` are spent.
+// Settled hands keep the trials they have, so a stable ranking costs fewer
+// trials than raising the count for every hand.
+pub fn refine_ranking<R: Rng>(
+    results: &mut [HandResult],
+    budget: TrialBudget,
+    num_opponents: usize,
+    board: &[Card],
+    max_rounds: usize,
+    rng: &mut R,
+) -> Refinement {
+    refine_ranking_with(results, max_rounds, |hand| budget.run(|trials| random_opponent_simulation(hand, num_opponents, board, trials, rng)))
+}
+
+// Same as refine_ranking with each batch of trials coming from `simulate`
+pub fn refine_ranking_with<S>(results: &mut [HandResult], max_rounds: usize, mut simulate: S) -> Refinement
+where
+    S: FnMut(&[Card; 2]) -> SimulationResults,
+{
+    results.sort_by(by_win_rate);
+    let mut refinement = Refinement { unsettled: unsettled_hands(results).len(), ..Refinement::default() };
+    
+    while refinement.rounds < max_rounds && refinement.unsettled > 0 {
+        let unsettled = unsettled_hands(results);
+        refinement.rounds += 1;
+        tracing::info!("Refinement round {}: {} hands overlap a neighbour", refinement.rounds, unsettled.len());
+        for i in unsettled {
+            let extra = simulate(&results[i].hand);
+            refinement.extra_simulations += extra.total_games;
+            results[i].results = results[i].results.combine(&extra);
+        }
+        results.sort_by(by_win_rate);
+        refinement.unsettled = unsettled_hands(results).len();
+    }
+    refinement
+}
+
+fn random_opponent_simulation<R: Rng>(hand: &[Card; 2], num_opponents: usize, board: &[Card], simulations_per_hand: usize, rng: &mut R) -> SimulationResults {
+    if num_opponents > 1 {
+        monte_carlo_vs_opponents_with_rng(hand, num_opponents, board, simulations_per_hand, rng)
+    } else if board.is_empty() {
+        monte_carlo_simulation_with_rng(hand, simulations_per_hand, rng)
+    } else {
+        monte_carlo_with_community_with_rng(hand, board, simulations_per_hand, rng)
+    }
 }
 
-pub fn bulk_monte_carlo_simulation(simulations_per_hand: usize) -> Vec<HandResult> {
-    let all_hands = generate_all_starting_hands();
+fn run_bulk_simulation<S, F, P>(
+    hands: &[[Card; 2]],
+    budget: TrialBudget,
+    num_opponents: usize,
+    board: &[Card],
+    mut simulate: S,
+    mut on_result: F,
+    mut on_progress: P,
+) -> Result<Vec<HandResult>, std::io::Error>
+where
+    S: FnMut(&[Card; 2]) -> SimulationResults,
+    F: FnMut(&HandResult) -> Result<(), std::io::Error>,
+    P: FnMut(&BulkProgress),
+{
     let mut results = Vec::new();
+    let hands: Vec<&[Card; 2]> = hands.iter()
+        .filter(|hand| !hand.iter().any(|card| board.contains(card)))
+        .collect();
     
-    println!("Running Monte Carlo simulation for {} unique starting hands...", all_hands.len());
-    println!("Simulations per hand: {}", simulations_per_hand);
-    println!("Total simulations: {}", all_hands.len() * simulations_per_hand);
-    println!();
+    let _span = tracing::debug_span!("bulk_simulation", hands = hands.len(), num_opponents).entered();
+    tracing::info!("Running Monte Carlo simulation for {} unique starting hands...", hands.len());
+    tracing::info!("Simulations per hand: {}", budget);
+    tracing::info!("Opponents: {}", num_opponents);
+    if !board.is_empty() {
+        tracing::info!("Board: {}", board.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" "));
+    }
+    if let TrialBudget::Count(simulations_per_hand) = budget {
+        tracing::info!("Total simulations: {}", hands.len() * simulations_per_hand);
+    }
     
-    let total_hands = all_hands.len();
+    let total_hands = hands.len();
+    let start_time = std::time::Instant::now();
+    let mut simulations = 0;
     
-    for (index, hand) in all_hands.iter().enumerate() {
-        if index % 100 == 0 {
-            println!("Progress: {}/{} hands completed ({:.1}%)", 
-                     index, total_hands, (index as f64 / total_hands as f64) * 100.0);
-        }
-        
-        let simulation_results = monte_carlo_simulation(hand, simulations_per_hand);
-        let hand_result = HandResult::new(hand.clone(), simulation_results);
+    for (index, &hand) in hands.iter().enumerate() {
+        let hand_result = HandResult::new(hand.clone(), simulate(hand));
+        tracing::trace!("{}: win {:.2}%, tie {:.2}% over {} trials", hand_result.hand_description, hand_result.results.win_rate, hand_result.results.tie_rate, hand_result.results.total_games);
+        on_result(&hand_result)?;
+        simulations += hand_result.results.total_games;
         results.push(hand_result);
+        on_progress(&BulkProgress { completed: index + 1, total: total_hands, simulations, elapsed: start_time.elapsed() });
     }
     
-    println!("Completed all {} hands!", total_hands);
+    tracing::info!("Completed all {} hands!", total_hands);
     
     // Sort by win rate (highest first)
-    results.sort_by(|a, b| b.results.win_rate.partial_cmp(&a.results.win_rate).unwrap());
+    results.sort_by(by_win_rate);
     
-    results
+    Ok(results)
+}
+
+// One slice of the bulk workload, written as "i/n" on the command line (1-based)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (index, count) = spec.split_once('/')
+            .ok_or_else(|| format!("Invalid shard '{}', expected i/n", spec))?;
+        let index: usize = index.trim().parse()
+            .map_err(|_| format!("Invalid shard index '{}'", index))?;
+        let count: usize = count.trim().parse()
+            .map_err(|_| format!("Invalid shard count '{}'", count))?;
+        
+        if count == 0 || index == 0 || index > count {
+            return Err(format!("Shard index must be between 1 and {}", count));
+        }
+        Ok(Shard { index, count })
+    }
+
+    // Hands are dealt round-robin so every shard gets a similar mix of pairs/suited/offsuit
+    pub fn select(&self, hands: &[[Card; 2]]) -> Vec<[Card; 2]> {
+        hands.iter()
+            .enumerate()
+            .filter(|(i, _)| i % self.count == self.index - 1)
+            .map(|(_, hand)| hand.clone())
+            .collect()
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+// Combines partial result sets, summing counts for any hand that appears more than once
+pub fn merge_results(shards: &[Vec<HandResult>]) -> Vec<HandResult> {
+    let mut merged: Vec<HandResult> = Vec::new();
+    let mut index_by_hand = std::collections::HashMap::new();
+    
+    for result in shards.iter().flatten() {
+        // Card order within a hand doesn't matter
+        let (a, b) = (result.hand[0].index(), result.hand[1].index());
+        let key = (a.min(b), a.max(b));
+        
+        match index_by_hand.get(&key) {
+            Some(&i) => {
+                let existing: &mut HandResult = &mut merged[i];
+                existing.results = existing.results.combine(&result.results);
+            }
+            None => {
+                index_by_hand.insert(key, merged.len());
+                merged.push(result.clone());
+            }
+        }
+    }
+    
+    merged.sort_by(by_win_rate);
+    merged
 }
 
 pub fn print_bulk_results(results: &[HandResult], top_n: Option<usize>) {
@@ -770,30 +2536,35 @@ pub fn print_bulk_results(results: &[HandResult], top_n: Option<usize>) {
     let display_count = display_count.min(results.len());
     
     println!("\n=== Monte Carlo Results (Top {} Hands) ===", display_count);
-    println!("{:<12} {:<8} {:<8} {:<8} {:<8} {:<8}", 
-             "Hand", "Win%", "Lose%", "Tie%", "Wins", "Total");
-    println!("{}", "-".repeat(60));
+    println!("{:<12} {:<8} {:<8} {:<8} {:<8} {:<8} {:<6} {:<5}", 
+             "Hand", "Win%", "Lose%", "Tie%", "Wins", "Total", "Group", "Chen");
+    println!("{}", "-".repeat(72));
     
     for (rank, result) in results.iter().take(display_count).enumerate() {
         let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
-        println!("{:<3} {:<8} {:<8.2} {:<8.2} {:<8.2} {:<8} {:<8}",
+        println!("{:<3} {:<8} {:<8.2} {:<8.2} {:<8.2} {:<8} {:<8} {:<6} {:<5}",
                  rank + 1,
                  result.hand_description,
                  result.results.win_rate,
                  lose_rate,
                  result.results.tie_rate,
                  result.results.wins,
-                 result.results.total_games);
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand));
     }
     
     if display_count < results.len() {
         println!("\n... and {} more hands", results.len() - display_count);
     }
     
-    // Print some statistics
+    // Print some statistics (the rows may be sorted by something other than win rate)
+    let by_win_rate = |a: &&HandResult, b: &&HandResult| a.results.win_rate.partial_cmp(&b.results.win_rate).unwrap();
+    let (best_hand, worst_hand) = match (results.iter().max_by(by_win_rate), results.iter().min_by(by_win_rate)) {
+        (Some(best), Some(worst)) => (best, worst),
+        _ => return,
+    };
     let avg_win_rate = results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64;
-    let best_hand = &results[0];
-    let worst_hand = &results[results.len() - 1];
     
     println!("\n=== Summary Statistics ===");
     println!("Average win rate: {:.2}%", avg_win_rate);
@@ -801,21 +2572,249 @@ pub fn print_bulk_results(results: &[HandResult], top_n: Option<usize>) {
     println!("Worst hand: {} ({:.2}%)", worst_hand.hand_description, worst_hand.results.win_rate);
 }
 
-pub fn export_to_csv(results: &[HandResult], filename: &str) -> Result<(), std::io::Error> {
-    use std::fs::File;
-    use std::io::Write;
+// Column the bulk results can be ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    WinRate,
+    LoseRate,
+    TieRate,
+    Hand,
+    Chen,
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "win" | "win-rate" => Ok(SortKey::WinRate),
+            "lose" | "loss" | "lose-rate" | "loss-rate" => Ok(SortKey::LoseRate),
+            "tie" | "tie-rate" => Ok(SortKey::TieRate),
+            "hand" | "description" => Ok(SortKey::Hand),
+            "chen" => Ok(SortKey::Chen),
+            _ => Err(format!("Invalid sort key '{}', expected win, lose, tie, hand or chen", s)),
+        }
+    }
+}
+
+fn lose_rate(result: &HandResult) -> f64 {
+    100.0 - result.results.win_rate - result.results.tie_rate
+}
+
+// Highest win rate first, the order of every ranking and export
+pub fn by_win_rate(a: &HandResult, b: &HandResult) -> std::cmp::Ordering {
+    b.results.win_rate.partial_cmp(&a.results.win_rate).unwrap()
+        .then_with(|| tie_break(a, b))
+}
+
+// Hands level on the sort key go by description and then by their cards, so a
+// ranking comes out the same however its results were gathered
+fn tie_break(a: &HandResult, b: &HandResult) -> std::cmp::Ordering {
+    let cards = |result: &HandResult| [result.hand[0].index(), result.hand[1].index()];
+    a.hand_description.cmp(&b.hand_description)
+        .then_with(|| cards(a).cmp(&cards(b)))
+}
+
+// Rates and Chen score sort highest first, hand descriptions alphabetically
+pub fn sort_results(results: &mut [HandResult], key: SortKey) {
+    let by_rate = |a: f64, b: f64| b.partial_cmp(&a).unwrap();
+    match key {
+        SortKey::WinRate => results.sort_by(by_win_rate),
+        SortKey::LoseRate => results.sort_by(|a, b| by_rate(lose_rate(a), lose_rate(b)).then_with(|| tie_break(a, b))),
+        SortKey::TieRate => results.sort_by(|a, b| by_rate(a.results.tie_rate, b.results.tie_rate).then_with(|| tie_break(a, b))),
+        SortKey::Hand => results.sort_by(tie_break),
+        SortKey::Chen => results.sort_by(|a, b| {
+            chen_score(&b.hand).cmp(&chen_score(&a.hand))
+                .then_with(|| by_win_rate(a, b))
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+// Condition a bulk result row must meet, e.g. "win > 55%", "chen >= 8" or "suited"
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResultFilter {
+    WinRate(Comparison, f64),
+    LoseRate(Comparison, f64),
+    TieRate(Comparison, f64),
+    Chen(Comparison, f64),
+    Pairs,
+    Suited,
+    Offsuit,
+}
+
+impl std::str::FromStr for ResultFilter {
+    type Err = String;
+
+    // Spaces, dashes, underscores and % are ignored, so "win rate > 55%" reads as "winrate>55"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text: String = s.chars().filter(|c| !c.is_whitespace() && !matches!(c, '%' | '_' | '-')).collect::<String>().to_ascii_lowercase();
+        match text.as_str() {
+            "pairs" | "pairsonly" => return Ok(ResultFilter::Pairs),
+            "suited" | "suitedonly" => return Ok(ResultFilter::Suited),
+            "offsuit" | "offsuitonly" => return Ok(ResultFilter::Offsuit),
+            _ => {}
+        }
+        
+        let split = text.find(['<', '>']).ok_or_else(|| format!("Invalid filter '{}'", s))?;
+        let (metric, rest) = text.split_at(split);
+        let (comparison, value) = if let Some(value) = rest.strip_prefix(">=") {
+            (Comparison::GreaterOrEqual, value)
+        } else if let Some(value) = rest.strip_prefix("<=") {
+            (Comparison::LessOrEqual, value)
+        } else if let Some(value) = rest.strip_prefix('>') {
+            (Comparison::Greater, value)
+        } else {
+            (Comparison::Less, &rest[1..])
+        };
+        let value: f64 = value.parse().map_err(|_| format!("Invalid value in filter '{}'", s))?;
+        
+        match metric {
+            "win" | "winrate" => Ok(ResultFilter::WinRate(comparison, value)),
+            "lose" | "loss" | "loserate" | "lossrate" => Ok(ResultFilter::LoseRate(comparison, value)),
+            "tie" | "tierate" => Ok(ResultFilter::TieRate(comparison, value)),
+            "chen" => Ok(ResultFilter::Chen(comparison, value)),
+            _ => Err(format!("Invalid filter '{}', expected win, lose, tie or chen followed by a comparison", s)),
+        }
+    }
+}
+
+impl ResultFilter {
+    pub fn matches(&self, result: &HandResult) -> bool {
+        let compare = |comparison: &Comparison, actual: f64, value: &f64| match comparison {
+            Comparison::Greater => actual > *value,
+            Comparison::GreaterOrEqual => actual >= *value,
+            Comparison::Less => actual < *value,
+            Comparison::LessOrEqual => actual <= *value,
+        };
+        let [a, b] = &result.hand;
+        match self {
+            ResultFilter::WinRate(comparison, value) => compare(comparison, result.results.win_rate, value),
+            ResultFilter::LoseRate(comparison, value) => compare(comparison, lose_rate(result), value),
+            ResultFilter::TieRate(comparison, value) => compare(comparison, result.results.tie_rate, value),
+            ResultFilter::Chen(comparison, value) => compare(comparison, chen_score(&result.hand) as f64, value),
+            ResultFilter::Pairs => a.rank == b.rank,
+            ResultFilter::Suited => a.suit == b.suit,
+            ResultFilter::Offsuit => a.rank != b.rank && a.suit != b.suit,
+        }
+    }
+}
+
+// Rows passing every filter, in their original order
+pub fn filter_results(results: &[HandResult], filters: &[ResultFilter]) -> Vec<HandResult> {
+    results.iter()
+        .filter(|result| filters.iter().all(|filter| filter.matches(result)))
+        .cloned()
+        .collect()
+}
+
+// Representative combo for a cell of the 13x13 starting-hand grid:
+// pairs on the diagonal, suited hands above it, offsuit hands below
+pub fn grid_hand(row: usize, col: usize) -> [Card; 2] {
+    let (r1, r2) = (RANKS_DESCENDING[row], RANKS_DESCENDING[col]);
+    if row == col {
+        [Card::new(r1, Suit::Spades), Card::new(r1, Suit::Hearts)]
+    } else if row < col {
+        [Card::new(r1, Suit::Spades), Card::new(r2, Suit::Spades)]
+    } else {
+        [Card::new(r2, Suit::Spades), Card::new(r1, Suit::Hearts)]
+    }
+}
+
+// Win rate of every grid cell, aggregated over all combos of that canonical hand
+pub fn grid_win_rates(results: &[HandResult]) -> [[Option<f64>; 13]; 13] {
+    let by_description: std::collections::HashMap<String, f64> = aggregate_canonical(results)
+        .into_iter()
+        .map(|r| (r.hand_description, r.results.win_rate))
+        .collect();
     
-    let mut file = File::create(filename)?;
+    let mut grid = [[None; 13]; 13];
+    for (row, cells) in grid.iter_mut().enumerate() {
+        for (col, cell) in cells.iter_mut().enumerate() {
+            *cell = by_description.get(&describe_hand(&grid_hand(row, col))).copied();
+        }
+    }
+    grid
+}
+
+pub fn print_hand_grid(results: &[HandResult]) {
+    // Red through yellow to green (xterm-256 background colors)
+    const PALETTE: [u8; 11] = [196, 202, 208, 214, 220, 226, 190, 154, 118, 82, 46];
+    
+    let grid = grid_win_rates(results);
+    let rates: Vec<f64> = grid.iter().flatten().filter_map(|r| *r).collect();
+    if rates.is_empty() {
+        println!("No results to display");
+        return;
+    }
+    let min = rates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = rates.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    
+    println!("\n=== Starting Hand Win Rates (suited above diagonal, offsuit below) ===");
+    print!("    ");
+    for rank in RANKS_DESCENDING.iter() {
+        print!("{:^6}", rank.to_string());
+    }
+    println!();
+    
+    for (row, cells) in grid.iter().enumerate() {
+        print!("{:>3} ", RANKS_DESCENDING[row].to_string());
+        for cell in cells.iter() {
+            match cell {
+                Some(rate) => {
+                    let scale = if max > min { (rate - min) / (max - min) } else { 1.0 };
+                    let color = PALETTE[(scale * (PALETTE.len() - 1) as f64).round() as usize];
+                    print!("\x1b[48;5;{}m\x1b[30m{:^6.1}\x1b[0m", color, rate);
+                }
+                None => print!("{:^6}", "-"),
+            }
+        }
+        println!();
+    }
+}
+
+// Column names shared by export_to_csv and CsvStreamWriter, ending with the
+// percentage of games hero finished with each hand category
+fn csv_header() -> String {
+    let mut header = "Rank,Hand,Card1,Card2,Win_Rate,Lose_Rate,Tie_Rate,Wins,Losses,Ties,Total_Games,Sklansky_Group,Chen_Score".to_string();
+    for rank in HAND_RANKS.iter() {
+        header.push_str(&format!(",{}_Pct", rank.to_string().replace(' ', "_")));
+    }
+    header
+}
+
+fn made_hand_fields(results: &SimulationResults) -> String {
+    HAND_RANKS.iter().map(|rank| format!(",{:.4}", results.made_hand_rate(rank))).collect()
+}
+
+// Compressed when the name ends in .gz or .zst, as are the JSON export and the stream
+pub fn export_to_csv(results: &[HandResult], filename: &str) -> Result<(), std::io::Error> {
+    let mut file = crate::compress::create(filename)?;
+    write_csv(results, &mut file)?;
+    file.finish()?;
     
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// The rows of export_to_csv to any writer, e.g. stdout for --output csv
+pub fn write_csv<W: std::io::Write>(results: &[HandResult], file: &mut W) -> Result<(), std::io::Error> {
     // Write CSV header
-    writeln!(file, "Rank,Hand,Card1,Card2,Win_Rate,Lose_Rate,Tie_Rate,Wins,Losses,Ties,Total_Games")?;
+    writeln!(file, "{}", csv_header())?;
     
     // Write data rows
     for (rank, result) in results.iter().enumerate() {
         let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
         let losses = result.results.total_games - result.results.wins - result.results.ties;
         
-        writeln!(file, "{},{},{},{},{:.4},{:.4},{:.4},{},{},{},{}",
+        writeln!(file, "{},{},{},{},{:.4},{:.4},{:.4},{},{},{},{},{},{}{}",
                  rank + 1,
                  result.hand_description,
                  result.hand[0],
@@ -826,23 +2825,305 @@ pub fn export_to_csv(results: &[HandResult], filename: &str) -> Result<(), std::
                  result.results.wins,
                  losses,
                  result.results.ties,
-                 result.results.total_games)?;
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand),
+                 made_hand_fields(&result.results))?;
+    }
+    
+    Ok(())
+}
+
+// Writes HandResult rows incrementally, flushing to disk every `flush_interval` rows
+pub struct CsvStreamWriter {
+    writer: crate::compress::Writer,
+    flush_interval: usize,
+    rows_since_flush: usize,
+}
+
+impl CsvStreamWriter {
+    pub fn new(filename: &str, flush_interval: usize) -> Result<Self, std::io::Error> {
+        use std::io::Write;
+        
+        let mut writer = crate::compress::create(filename)?;
+        writeln!(writer, "{}", csv_header())?;
+        writer.flush()?;
+        
+        Ok(CsvStreamWriter {
+            writer,
+            flush_interval: flush_interval.max(1),
+            rows_since_flush: 0,
+        })
+    }
+
+    pub fn write_result(&mut self, result: &HandResult) -> Result<(), std::io::Error> {
+        use std::io::Write;
+        
+        let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
+        let losses = result.results.total_games - result.results.wins - result.results.ties;
+        
+        // Rank is left empty since the final ordering isn't known yet
+        writeln!(self.writer, ",{},{},{},{:.4},{:.4},{:.4},{},{},{},{},{},{}{}",
+                 result.hand_description,
+                 result.hand[0],
+                 result.hand[1],
+                 result.results.win_rate,
+                 lose_rate,
+                 result.results.tie_rate,
+                 result.results.wins,
+                 losses,
+                 result.results.ties,
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand),
+                 made_hand_fields(&result.results))?;
+        
+        self.rows_since_flush += 1;
+        if self.rows_since_flush >= self.flush_interval {
+            self.writer.flush()?;
+            self.rows_since_flush = 0;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), std::io::Error> {
+        self.writer.finish()
+    }
+}
+
+// Reads a file written by export_to_csv (or a streamed partial file) back into
+// HandResults, decompressing a gzip or zstd export
+pub fn import_from_csv(filename: &str) -> Result<Vec<HandResult>, std::io::Error> {
+    use std::io::{BufRead, Error, ErrorKind};
+    
+    let reader = crate::compress::open(filename)?;
+    let mut results = Vec::new();
+    
+    for (line_number, line) in reader.lines().enumerate().skip(1) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        
+        let invalid = |msg: String| Error::new(ErrorKind::InvalidData, format!("{}:{}: {}", filename, line_number + 1, msg));
+        let fields: Vec<&str> = line.split(',').collect();
+        // Files from older versions stop before the Sklansky_Group, Chen_Score and made hand columns
+        if fields.len() < 11 {
+            return Err(invalid(format!("expected at least 11 columns, found {}", fields.len())));
+        }
+        
+        let card1: Card = fields[2].parse().map_err(invalid)?;
+        let card2: Card = fields[3].parse().map_err(invalid)?;
+        let count = |field: &str| field.trim().parse::<usize>().map_err(|_| invalid(format!("invalid count '{}'", field)));
+        let wins = count(fields[7])?;
+        let losses = count(fields[8])?;
+        let ties = count(fields[9])?;
+        let total_games = count(fields[10])?;
+        
+        let mut simulation = SimulationResults::new(total_games, wins, losses, ties);
+        for (rank, field) in HAND_RANKS.iter().zip(fields.iter().skip(13)) {
+            let rate: f64 = field.trim().parse().map_err(|_| invalid(format!("invalid percentage '{}'", field)))?;
+            let count = (rate / 100.0 * total_games as f64).round() as usize;
+            if count > 0 {
+                simulation.made_hands.insert(rank.clone(), count);
+            }
+        }
+        results.push(HandResult::new([card1, card2], simulation));
+    }
+    
+    Ok(results)
+}
+
+// Same rows as export_to_csv as an array of objects, with the made hand
+// percentages nested under "made_hands"
+pub fn export_to_json(results: &[HandResult], filename: &str) -> Result<(), std::io::Error> {
+    let mut file = crate::compress::create(filename)?;
+    write_json(results, &mut file)?;
+    file.finish()?;
+    
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// The array of export_to_json to any writer, e.g. stdout for --output json
+pub fn write_json<W: std::io::Write>(results: &[HandResult], file: &mut W) -> Result<(), std::io::Error> {
+    use crate::json::quote;
+    
+    writeln!(file, "[")?;
+    for (rank, result) in results.iter().enumerate() {
+        let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
+        let losses = result.results.total_games - result.results.wins - result.results.ties;
+        let made_hands: Vec<String> = HAND_RANKS.iter()
+            .map(|hand_rank| format!("{}: {:.4}", quote(&hand_rank.to_string()), result.results.made_hand_rate(hand_rank)))
+            .collect();
+        
+        writeln!(file, "  {{\"rank\": {}, \"hand\": {}, \"card1\": {}, \"card2\": {}, \"win_rate\": {:.4}, \"lose_rate\": {:.4}, \"tie_rate\": {:.4}, \"wins\": {}, \"losses\": {}, \"ties\": {}, \"total_games\": {}, \"sklansky_group\": {}, \"chen_score\": {}, \"made_hands\": {{{}}}}}{}",
+                 rank + 1,
+                 quote(&result.hand_description),
+                 quote(&result.hand[0].to_string()),
+                 quote(&result.hand[1].to_string()),
+                 result.results.win_rate,
+                 lose_rate,
+                 result.results.tie_rate,
+                 result.results.wins,
+                 losses,
+                 result.results.ties,
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand),
+                 made_hands.join(", "),
+                 if rank + 1 < results.len() { "," } else { "" })?;
+    }
+    writeln!(file, "]")?;
+    Ok(())
+}
+
+pub fn import_from_json(filename: &str) -> Result<Vec<HandResult>, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+    
+    let invalid = |msg: String| Error::new(ErrorKind::InvalidData, format!("{}: {}", filename, msg));
+    let document = crate::json::parse(&crate::compress::read_to_string(filename)?).map_err(invalid)?;
+    let rows = document.as_array().ok_or_else(|| invalid("expected an array of results".to_string()))?;
+    
+    let mut results = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        let invalid = |msg: String| invalid(format!("result {}: {}", index + 1, msg));
+        let card = |key: &str| -> Result<Card, Error> {
+            row.get(key).and_then(|value| value.as_str())
+                .ok_or_else(|| invalid(format!("missing {}", key)))?
+                .parse()
+                .map_err(invalid)
+        };
+        let count = |key: &str| -> Result<usize, Error> {
+            row.get(key).and_then(|value| value.as_f64())
+                .filter(|value| *value >= 0.0 && value.fract() == 0.0)
+                .map(|value| value as usize)
+                .ok_or_else(|| invalid(format!("missing or invalid {}", key)))
+        };
+        
+        let total_games = count("total_games")?;
+        let mut simulation = SimulationResults::new(total_games, count("wins")?, count("losses")?, count("ties")?);
+        // Older exports may not have made hand rates
+        if let Some(made_hands) = row.get("made_hands").and_then(|value| value.as_object()) {
+            for (name, rate) in made_hands {
+                let hand_rank: HandRank = name.parse().map_err(invalid)?;
+                let rate = rate.as_f64().ok_or_else(|| invalid(format!("invalid percentage for {}", name)))?;
+                let count = (rate / 100.0 * total_games as f64).round() as usize;
+                if count > 0 {
+                    simulation.made_hands.insert(hand_rank, count);
+                }
+            }
+        }
+        results.push(HandResult::new([card("card1")?, card("card2")?], simulation));
+    }
+    
+    Ok(results)
+}
+
+// Loads a results export, picking the format from the file extension under any .gz or .zst
+pub fn import_results(filename: &str) -> Result<Vec<HandResult>, std::io::Error> {
+    if crate::compress::base_name(filename).to_ascii_lowercase().ends_with(".json") {
+        import_from_json(filename)
+    } else {
+        import_from_csv(filename)
+    }
+}
+
+// The ranking as a GitHub-flavored Markdown table, to paste into a forum
+// post, an issue or study notes
+pub fn export_to_markdown(results: &[HandResult], filename: &str) -> Result<(), std::io::Error> {
+    use std::io::Write;
+    
+    let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    write_markdown(results, &mut file)?;
+    file.flush()?;
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// The summary table followed by the ranking, for a whole bulk run
+pub fn export_markdown_report(results: &[HandResult], filename: &str, simulations_per_hand: usize, board: &[Card], duration: std::time::Duration) -> Result<(), std::io::Error> {
+    use std::io::Write;
+    
+    let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    writeln!(file, "## Summary")?;
+    writeln!(file)?;
+    write_markdown_summary(results, simulations_per_hand, board, duration, &mut file)?;
+    writeln!(file)?;
+    writeln!(file, "## Ranking")?;
+    writeln!(file)?;
+    write_markdown(results, &mut file)?;
+    file.flush()?;
+    tracing::info!("Markdown report exported to: {}", filename);
+    Ok(())
+}
+
+// The rows of export_to_markdown to any writer, e.g. stdout for --output markdown
+pub fn write_markdown<W: std::io::Write>(results: &[HandResult], file: &mut W) -> Result<(), std::io::Error> {
+    writeln!(file, "| Rank | Hand | Cards | Win % | Lose % | Tie % | Equity % | Games | Group | Chen |")?;
+    writeln!(file, "| ---: | :--- | :--- | ---: | ---: | ---: | ---: | ---: | ---: | ---: |")?;
+    for (rank, result) in results.iter().enumerate() {
+        let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
+        writeln!(file, "| {} | {} | {} {} | {:.2} | {:.2} | {:.2} | {:.2} | {} | {} | {} |",
+                 rank + 1,
+                 markdown_cell(&result.hand_description),
+                 result.hand[0],
+                 result.hand[1],
+                 result.results.win_rate,
+                 lose_rate,
+                 result.results.tie_rate,
+                 result.results.win_rate + result.results.tie_rate / 2.0,
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand))?;
+    }
+    Ok(())
+}
+
+// The figures of export_summary_to_csv as a two-column table
+pub fn write_markdown_summary<W: std::io::Write>(results: &[HandResult], simulations_per_hand: usize, board: &[Card], duration: std::time::Duration, file: &mut W) -> Result<(), std::io::Error> {
+    let by_win_rate = |a: &&HandResult, b: &&HandResult| a.results.win_rate.partial_cmp(&b.results.win_rate).unwrap();
+    let total_simulations = results.len() * simulations_per_hand;
+    let seconds = duration.as_secs_f64();
+    let mut rows = vec![
+        ("Hands".to_string(), results.len().to_string()),
+        ("Simulations per hand".to_string(), simulations_per_hand.to_string()),
+        ("Total simulations".to_string(), total_simulations.to_string()),
+        ("Time".to_string(), format!("{:.2}s", seconds)),
+        ("Simulations per second".to_string(), format!("{:.0}", if seconds > 0.0 { total_simulations as f64 / seconds } else { 0.0 })),
+    ];
+    if !board.is_empty() {
+        rows.push(("Board".to_string(), board.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" ")));
+    }
+    if !results.is_empty() {
+        let average = results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64;
+        rows.push(("Average win rate".to_string(), format!("{:.2}%", average)));
+    }
+    for (label, hand) in [("Best hand", results.iter().max_by(by_win_rate)), ("Worst hand", results.iter().min_by(by_win_rate))] {
+        if let Some(hand) = hand {
+            rows.push((label.to_string(), format!("{} ({:.2}%)", hand.hand_description, hand.results.win_rate)));
+        }
     }
     
-    println!("Results exported to: {}", filename);
+    writeln!(file, "| Statistic | Value |")?;
+    writeln!(file, "| :--- | ---: |")?;
+    for (label, value) in rows {
+        writeln!(file, "| {} | {} |", label, markdown_cell(&value))?;
+    }
     Ok(())
 }
 
-pub fn export_summary_to_csv(results: &[HandResult], filename: &str, simulations_per_hand: usize, duration: std::time::Duration) -> Result<(), std::io::Error> {
+// A pipe would end the cell early
+pub fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn export_summary_to_csv(results: &[HandResult], filename: &str, simulations_per_hand: usize, board: &[Card], duration: std::time::Duration) -> Result<(), std::io::Error> {
     use std::fs::File;
     use std::io::Write;
     
     let mut file = File::create(filename)?;
     
-    // Calculate summary statistics
-    let avg_win_rate = results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64;
-    let best_hand = &results[0];
-    let worst_hand = &results[results.len() - 1];
     let total_simulations = results.len() * simulations_per_hand;
     
     // Write summary information
@@ -853,14 +3134,175 @@ pub fn export_summary_to_csv(results: &[HandResult], filename: &str, simulations
     writeln!(file, "Total_Simulations,{}", total_simulations)?;
     writeln!(file, "Execution_Time_Seconds,{:.2}", duration.as_secs_f64())?;
     writeln!(file, "Simulations_Per_Second,{:.0}", total_simulations as f64 / duration.as_secs_f64())?;
-    writeln!(file, "")?;
-    writeln!(file, "Average_Win_Rate,{:.4}", avg_win_rate)?;
-    writeln!(file, "Best_Hand,{}", best_hand.hand_description)?;
-    writeln!(file, "Best_Hand_Win_Rate,{:.4}", best_hand.results.win_rate)?;
-    writeln!(file, "Worst_Hand,{}", worst_hand.hand_description)?;
-    writeln!(file, "Worst_Hand_Win_Rate,{:.4}", worst_hand.results.win_rate)?;
-    
-    println!("Summary exported to: {}", filename);
+    if let Ok(texture) = crate::texture::classify_board(board) {
+        writeln!(file, "Board,{}", board.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" "))?;
+        writeln!(file, "{}", crate::texture::BoardTexture::CSV_HEADER)?;
+        writeln!(file, "{}", texture.csv_fields())?;
+    }
+    
+    // Results come ranked, so the best and worst hands are the ends; an empty
+    // run has only the header above
+    if let (Some(best_hand), Some(worst_hand)) = (results.first(), results.last()) {
+        let avg_win_rate = results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64;
+        writeln!(file, "")?;
+        writeln!(file, "Average_Win_Rate,{:.4}", avg_win_rate)?;
+        writeln!(file, "Best_Hand,{}", best_hand.hand_description)?;
+        writeln!(file, "Best_Hand_Win_Rate,{:.4}", best_hand.results.win_rate)?;
+        writeln!(file, "Worst_Hand,{}", worst_hand.hand_description)?;
+        writeln!(file, "Worst_Hand_Win_Rate,{:.4}", worst_hand.results.win_rate)?;
+    }
+    
+    tracing::info!("Summary exported to: {}", filename);
+    Ok(())
+}
+
+
+#[cfg(feature = "parquet")]
+pub fn export_to_parquet(results: &[HandResult], filename: &str, simulations_per_hand: usize) -> Result<(), std::io::Error> {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Float64Array, Int32Array, StringArray, UInt8Array, UInt64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let to_io = |e: Box<dyn std::error::Error + Send + Sync>| std::io::Error::other(e);
+
+    // Run metadata is stored on the schema so it travels with the file
+    let mut metadata = HashMap::new();
+    metadata.insert("timestamp".to_string(), chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string());
+    metadata.insert("total_hands".to_string(), results.len().to_string());
+    metadata.insert("simulations_per_hand".to_string(), simulations_per_hand.to_string());
+
+    let schema = Arc::new(Schema::new_with_metadata(vec![
+        Field::new("rank", DataType::UInt64, false),
+        Field::new("hand", DataType::Utf8, false),
+        Field::new("card1", DataType::Utf8, false),
+        Field::new("card2", DataType::Utf8, false),
+        Field::new("win_rate", DataType::Float64, false),
+        Field::new("lose_rate", DataType::Float64, false),
+        Field::new("tie_rate", DataType::Float64, false),
+        Field::new("win_rate_ci_low", DataType::Float64, false),
+        Field::new("win_rate_ci_high", DataType::Float64, false),
+        Field::new("wins", DataType::UInt64, false),
+        Field::new("losses", DataType::UInt64, false),
+        Field::new("ties", DataType::UInt64, false),
+        Field::new("total_games", DataType::UInt64, false),
+        Field::new("simulations_per_hand", DataType::UInt64, false),
+        Field::new("sklansky_group", DataType::UInt8, false),
+        Field::new("chen_score", DataType::Int32, false),
+    ], metadata));
+
+    let ci: Vec<(f64, f64)> = results.iter().map(|r| r.results.win_rate_ci()).collect();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values((1..=results.len()).map(|i| i as u64))),
+        Arc::new(StringArray::from_iter_values(results.iter().map(|r| r.hand_description.clone()))),
+        Arc::new(StringArray::from_iter_values(results.iter().map(|r| r.hand[0].to_string()))),
+        Arc::new(StringArray::from_iter_values(results.iter().map(|r| r.hand[1].to_string()))),
+        Arc::new(Float64Array::from_iter_values(results.iter().map(|r| r.results.win_rate))),
+        Arc::new(Float64Array::from_iter_values(results.iter().map(|r| 100.0 - r.results.win_rate - r.results.tie_rate))),
+        Arc::new(Float64Array::from_iter_values(results.iter().map(|r| r.results.tie_rate))),
+        Arc::new(Float64Array::from_iter_values(ci.iter().map(|c| c.0))),
+        Arc::new(Float64Array::from_iter_values(ci.iter().map(|c| c.1))),
+        Arc::new(UInt64Array::from_iter_values(results.iter().map(|r| r.results.wins as u64))),
+        Arc::new(UInt64Array::from_iter_values(results.iter().map(|r| (r.results.total_games - r.results.wins - r.results.ties) as u64))),
+        Arc::new(UInt64Array::from_iter_values(results.iter().map(|r| r.results.ties as u64))),
+        Arc::new(UInt64Array::from_iter_values(results.iter().map(|r| r.results.total_games as u64))),
+        Arc::new(UInt64Array::from_iter_values(results.iter().map(|_| simulations_per_hand as u64))),
+        Arc::new(UInt8Array::from_iter_values(results.iter().map(|r| sklansky_group(&r.hand)))),
+        Arc::new(Int32Array::from_iter_values(results.iter().map(|r| chen_score(&r.hand)))),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| to_io(Box::new(e)))?;
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+
+    let file = File::create(filename)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props)).map_err(|e| to_io(Box::new(e)))?;
+    writer.write(&batch).map_err(|e| to_io(Box::new(e)))?;
+    writer.close().map_err(|e| to_io(Box::new(e)))?;
+
+    tracing::info!("Results exported to: {}", filename);
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best(hole: &str, board: &str) -> HandEvaluation {
+        let hole: [Card; 2] = parse_cards(hole).unwrap().try_into().unwrap();
+        evaluate_hand(&hole, &parse_cards(board).unwrap())
+    }
+
+    #[test]
+    fn full_house_takes_the_best_pair() {
+        // Two pairs to go with the trips: the queens fill it, not the deuces
+        let eval = best("KsKh", "KdQsQh2c2d");
+        assert_eq!(eval, HandEvaluation::new(HandRank::FullHouse, vec![Rank::King, Rank::Queen]));
+        assert_eq!(best("KsKh", "KdQsQh2cAd"), eval);
+    }
+
+    #[test]
+    fn ace_plays_low_in_the_wheel() {
+        assert_eq!(best("As2d", "3c4h5s9dKc"), HandEvaluation::new(HandRank::Straight, vec![Rank::Five]));
+        assert_eq!(best("As2s", "3s4s5s9dKc"), HandEvaluation::new(HandRank::StraightFlush, vec![Rank::Five]));
+        // A six on top makes the straight six high
+        assert_eq!(best("As2d", "3c4h5s6dKc"), HandEvaluation::new(HandRank::Straight, vec![Rank::Six]));
+    }
+
+    #[test]
+    fn flush_beats_the_straight_it_contains() {
+        // Broadway is there, but only five spades make a hand
+        assert_eq!(
+            best("AsKs", "QdJhTs7s2s"),
+            HandEvaluation::new(HandRank::Flush, vec![Rank::Ace, Rank::King, Rank::Ten, Rank::Seven, Rank::Two]),
+        );
+        // The straight flush is nine high, though the straight runs to the jack
+        assert_eq!(best("9s8s", "7s6s5sThJd"), HandEvaluation::new(HandRank::StraightFlush, vec![Rank::Nine]));
+        assert_eq!(best("AhKh", "QhJhTh9h8h"), HandEvaluation::new(HandRank::RoyalFlush, vec![Rank::Ace]));
+    }
+
+    #[test]
+    fn quads_take_the_best_kicker() {
+        assert_eq!(best("9s9h", "9d9cKsKh2c"), HandEvaluation::new(HandRank::FourOfAKind, vec![Rank::Nine, Rank::King]));
+        // A single ace outkicks the paired kings
+        assert_eq!(best("9s9h", "9d9cKsKhAc"), HandEvaluation::new(HandRank::FourOfAKind, vec![Rank::Nine, Rank::Ace]));
+    }
+
+    #[test]
+    fn two_trips_make_a_full_house() {
+        assert_eq!(best("KsKh", "KdQsQhQc2d"), HandEvaluation::new(HandRank::FullHouse, vec![Rank::King, Rank::Queen]));
+        assert_eq!(best("2s2h", "2dQsQhQcKd"), HandEvaluation::new(HandRank::FullHouse, vec![Rank::Queen, Rank::Two]));
+    }
+
+    #[test]
+    fn batches_match_single_evaluations() {
+        let board = parse_cards("Ks9s9d4c2s").unwrap();
+        let hands: Vec<[Card; 2]> = generate_canonical_starting_hands().into_iter()
+            .filter(|hand| !hand.iter().any(|card| board.contains(card)))
+            .collect();
+        let expected: Vec<HandEvaluation> = hands.iter().map(|hand| evaluate_hand(hand, &board)).collect();
+        assert_eq!(evaluate_many(&hands, &board), expected);
+
+        let hand = [Card::new(Rank::Ace, Suit::Spades), Card::new(Rank::Five, Suit::Spades)];
+        let boards: Vec<Vec<Card>> = ["Ks9s9d4c2s", "2c3d4h", "3s4s2sKdKh", "AdAhAc5d5h"].iter()
+            .map(|board| parse_cards(board).unwrap())
+            .collect();
+        let expected: Vec<HandEvaluation> = boards.iter().map(|board| evaluate_hand(&hand, board)).collect();
+        assert_eq!(evaluate_many_boards(&hand, &boards), expected);
+    }
+
+    #[test]
+    fn empty_summary_has_only_the_header() {
+        let path = std::env::temp_dir().join(format!("pokershit_empty_summary_{}.csv", std::process::id()));
+        let filename = path.to_str().unwrap();
+        export_summary_to_csv(&[], filename, 100, &[], std::time::Duration::from_secs(1)).unwrap();
+        let summary = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(summary.contains("Total_Hands,0"));
+        assert!(!summary.contains("Best_Hand"));
+    }
+}
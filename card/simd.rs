@@ -0,0 +1,71 @@
+// SSE2 version of CardCounts::add_all for the "simd" feature. Lane i of a
+// 16-byte vector stands for the rank with value i: each card is one compare
+// against the lane numbers, which is subtracted from the rank counts (a match
+// is -1) and OR-ed into its suit's lanes. One movemask per suit then gives the
+// suit's rank mask.
+use std::arch::x86_64::{
+    __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128, _mm_set1_epi8,
+    _mm_setzero_si128, _mm_storeu_si128, _mm_sub_epi8,
+};
+
+use super::{suit_index, Card, CardCounts, LOW_ACE_BIT};
+
+const LANE_RANKS: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+pub(super) fn add_cards<'a, I: IntoIterator<Item = &'a Card>>(counts: &mut CardCounts, cards: I) {
+    // SSE2 is part of the x86_64 baseline, so the intrinsics are always available;
+    // every load and store covers exactly one 16-byte array
+    unsafe {
+        let lane_ranks = _mm_loadu_si128(LANE_RANKS.as_ptr() as *const __m128i);
+        let mut ranks = _mm_loadu_si128(counts.ranks.as_ptr() as *const __m128i);
+        let mut suits = [_mm_setzero_si128(); 4];
+
+        for card in cards {
+            let lane = _mm_cmpeq_epi8(lane_ranks, _mm_set1_epi8(card.rank.value() as i8));
+            ranks = _mm_sub_epi8(ranks, lane);
+            let suit = &mut suits[suit_index(&card.suit)];
+            *suit = _mm_or_si128(*suit, lane);
+        }
+
+        _mm_storeu_si128(counts.ranks.as_mut_ptr() as *mut __m128i, ranks);
+        for (mask, suit) in counts.suits.iter_mut().zip(suits) {
+            let lanes = _mm_movemask_epi8(suit) as u16;
+            *mask |= if lanes & 1 << 14 != 0 { lanes | LOW_ACE_BIT } else { lanes };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{rank_bit, Deck};
+
+    fn scalar_counts(cards: &[&Card]) -> CardCounts {
+        let mut counts = CardCounts::default();
+        for card in cards {
+            counts.ranks[card.rank.value() as usize] += 1;
+            counts.suits[suit_index(&card.suit)] |= rank_bit(card.rank);
+        }
+        counts
+    }
+
+    #[test]
+    fn vector_counts_match_scalar_counts() {
+        let deck = Deck::new().cards;
+        for start in 0..deck.len() {
+            // Seven distinct cards, spread over every rank and suit as start moves
+            let cards: Vec<&Card> = (0..7).map(|i| &deck[(start + i * 11) % deck.len()]).collect();
+            let expected = scalar_counts(&cards);
+
+            let mut counts = CardCounts::default();
+            add_cards(&mut counts, cards.iter().copied());
+            assert_eq!((counts.ranks, counts.suits), (expected.ranks, expected.suits));
+
+            // Topping up a board's counts, as evaluate_many does
+            let mut counts = CardCounts::default();
+            add_cards(&mut counts, cards[2..].iter().copied());
+            add_cards(&mut counts, cards[..2].iter().copied());
+            assert_eq!((counts.ranks, counts.suits), (expected.ranks, expected.suits));
+        }
+    }
+}
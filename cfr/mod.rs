@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::bucket::{Bucketing, river_strength};
+use crate::card::{Card, HandEvaluation, evaluate_hand};
+use crate::range::Range;
+
+// Counterfactual regret minimization for small two-player zero-sum games.
+// Every information set keeps the regret of each action (how much better it
+// would have done than the mix actually played); playing in proportion to
+// positive regret and averaging the strategies over all iterations converges
+// to a Nash equilibrium. Vanilla CFR walks every deal each iteration,
+// chance-sampled Monte Carlo CFR walks one random deal.
+
+// A game CFR can solve. States are full histories including every player's
+// private cards; info_set hides what the player to act can't see.
+pub trait CfrGame {
+    type State: Clone;
+
+    // Every possible deal with its probability
+    fn deals(&self) -> Vec<(Self::State, f64)>;
+
+    // Player to act (0 or 1), or None once the hand is over
+    fn player(&self, state: &Self::State) -> Option<usize>;
+
+    // Names of the actions available to the player to act
+    fn actions(&self, state: &Self::State) -> Vec<String>;
+
+    fn play(&self, state: &Self::State, action: usize) -> Self::State;
+
+    // What the player to act knows, as a key shared by every state they can't tell apart
+    fn info_set(&self, state: &Self::State) -> String;
+
+    // Player 0's payoff at a finished hand; player 1 gets the negative
+    fn utility(&self, state: &Self::State) -> f64;
+}
+
+#[derive(Debug, Clone)]
+struct InfoSetNode {
+    actions: Vec<String>,
+    regret_sum: Vec<f64>,
+    strategy_sum: Vec<f64>,
+}
+
+impl InfoSetNode {
+    fn new(actions: Vec<String>) -> Self {
+        let count = actions.len();
+        InfoSetNode { actions, regret_sum: vec![0.0; count], strategy_sum: vec![0.0; count] }
+    }
+
+    // Regret matching: play each action in proportion to its positive regret
+    fn current_strategy(&self) -> Vec<f64> {
+        normalized(&self.regret_sum.iter().map(|r| r.max(0.0)).collect::<Vec<_>>())
+    }
+
+    fn average_strategy(&self) -> Vec<f64> {
+        normalized(&self.strategy_sum)
+    }
+}
+
+// Scales to sum to 1, or uniform when everything is 0
+fn normalized(weights: &[f64]) -> Vec<f64> {
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter().map(|w| w / total).collect()
+    } else {
+        vec![1.0 / weights.len() as f64; weights.len()]
+    }
+}
+
+// One row of a solved strategy table
+#[derive(Debug, Clone)]
+pub struct InfoSetStrategy {
+    pub info_set: String,
+    pub actions: Vec<String>,
+    // Average strategy, one probability per action
+    pub probabilities: Vec<f64>,
+}
+
+pub struct CfrSolver<G: CfrGame> {
+    pub game: G,
+    deals: Vec<(G::State, f64)>,
+    nodes: HashMap<String, InfoSetNode>,
+    pub iterations: usize,
+}
+
+impl<G: CfrGame> CfrSolver<G> {
+    pub fn new(game: G) -> Result<Self, String> {
+        let deals = game.deals();
+        if deals.is_empty() {
+            return Err("The game has no possible deals".to_string());
+        }
+        Ok(CfrSolver { game, deals, nodes: HashMap::new(), iterations: 0 })
+    }
+
+    // Vanilla CFR: every iteration walks the whole tree once per deal
+    pub fn run_vanilla(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            for index in 0..self.deals.len() {
+                let (state, probability) = self.deals[index].clone();
+                self.cfr(&state, [1.0, 1.0], probability);
+            }
+            self.iterations += 1;
+        }
+    }
+
+    // Chance-sampled MCCFR: every iteration walks the tree for one deal drawn
+    // by its probability, so each is far cheaper when there are many deals
+    pub fn run_chance_sampled<R: Rng>(&mut self, iterations: usize, rng: &mut R) {
+        let total: f64 = self.deals.iter().map(|(_, probability)| probability).sum();
+        for _ in 0..iterations {
+            let mut target = rng.gen_range(0.0..total);
+            let index = self.deals.iter()
+                .position(|(_, probability)| {
+                    target -= probability;
+                    target < 0.0
+                })
+                .unwrap_or(self.deals.len() - 1);
+            let state = self.deals[index].0.clone();
+            self.cfr(&state, [1.0, 1.0], 1.0);
+            self.iterations += 1;
+        }
+    }
+
+    // Walks the tree below `state`, updating regrets and strategy sums, and
+    // returns player 0's expected payoff under the current strategies.
+    // `reach` is each player's probability of playing to here, `chance` the
+    // deal's.
+    fn cfr(&mut self, state: &G::State, reach: [f64; 2], chance: f64) -> f64 {
+        let Some(player) = self.game.player(state) else {
+            return self.game.utility(state);
+        };
+        let key = self.game.info_set(state);
+        let strategy = match self.nodes.get(&key) {
+            Some(node) => node.current_strategy(),
+            None => {
+                let node = InfoSetNode::new(self.game.actions(state));
+                let strategy = node.current_strategy();
+                self.nodes.insert(key.clone(), node);
+                strategy
+            },
+        };
+
+        let mut action_values = vec![0.0; strategy.len()];
+        let mut value = 0.0;
+        for (action, &probability) in strategy.iter().enumerate() {
+            let mut next_reach = reach;
+            next_reach[player] *= probability;
+            action_values[action] = self.cfr(&self.game.play(state, action), next_reach, chance);
+            value += probability * action_values[action];
+        }
+
+        // Regrets are from the acting player's side; player 1 gains what player 0 loses
+        let sign = if player == 0 { 1.0 } else { -1.0 };
+        let node = self.nodes.get_mut(&key).expect("node was just visited");
+        for action in 0..strategy.len() {
+            node.regret_sum[action] += chance * reach[1 - player] * sign * (action_values[action] - value);
+            node.strategy_sum[action] += reach[player] * strategy[action];
+        }
+        value
+    }
+
+    // The average strategy at `info_set`, uniform if it was never visited
+    fn average_at(&self, info_set: &str, actions: usize) -> Vec<f64> {
+        self.nodes.get(info_set).map_or_else(|| vec![1.0 / actions as f64; actions], InfoSetNode::average_strategy)
+    }
+
+    // Every information set with its average strategy, sorted by name
+    pub fn strategy_table(&self) -> Vec<InfoSetStrategy> {
+        let mut table: Vec<InfoSetStrategy> = self.nodes.iter()
+            .map(|(info_set, node)| InfoSetStrategy {
+                info_set: info_set.clone(),
+                actions: node.actions.clone(),
+                probabilities: node.average_strategy(),
+            })
+            .collect();
+        table.sort_by(|a, b| a.info_set.cmp(&b.info_set));
+        table
+    }
+
+    // Player 0's expected payoff when both play the average strategies
+    pub fn game_value(&self) -> f64 {
+        self.deals.iter().map(|(state, probability)| probability * self.average_value(state)).sum()
+    }
+
+    fn average_value(&self, state: &G::State) -> f64 {
+        if self.game.player(state).is_none() {
+            return self.game.utility(state);
+        }
+        let actions = self.game.actions(state).len();
+        self.average_at(&self.game.info_set(state), actions).iter()
+            .enumerate()
+            .map(|(action, probability)| probability * self.average_value(&self.game.play(state, action)))
+            .sum()
+    }
+
+    // How much a best response gains against the average strategies,
+    // averaged over both players: 0 at an exact equilibrium
+    pub fn exploitability(&self) -> f64 {
+        (self.best_response_value(0) + self.best_response_value(1)) / 2.0
+    }
+
+    // `player`'s payoff when best-responding to the other's average strategy.
+    // Each pass picks the best action at every info set given the choices
+    // below it; with perfect recall the choices stop changing after at most
+    // one pass per level of the tree.
+    pub fn best_response_value(&self, player: usize) -> f64 {
+        let mut policy: HashMap<String, usize> = HashMap::new();
+        loop {
+            let mut action_values: HashMap<String, Vec<f64>> = HashMap::new();
+            let value: f64 = self.deals.iter()
+                .map(|(state, probability)| probability * self.response_value(state, player, *probability, &policy, &mut action_values))
+                .sum();
+            let mut changed = false;
+            for (info_set, values) in action_values {
+                let best = (0..values.len()).max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap_or(0);
+                if policy.insert(info_set, best) != Some(best) {
+                    changed = true;
+                }
+            }
+            if !changed {
+                return value;
+            }
+        }
+    }
+
+    // `player`'s payoff below `state` playing `policy` against the average
+    // strategy, adding each action's value weighted by the chance and
+    // opponent reach `weight` into `action_values`
+    fn response_value(
+        &self,
+        state: &G::State,
+        player: usize,
+        weight: f64,
+        policy: &HashMap<String, usize>,
+        action_values: &mut HashMap<String, Vec<f64>>,
+    ) -> f64 {
+        let Some(acting) = self.game.player(state) else {
+            let utility = self.game.utility(state);
+            return if player == 0 { utility } else { -utility };
+        };
+        let actions = self.game.actions(state).len();
+        let info_set = self.game.info_set(state);
+        if acting == player {
+            let values: Vec<f64> = (0..actions)
+                .map(|action| self.response_value(&self.game.play(state, action), player, weight, policy, action_values))
+                .collect();
+            let totals = action_values.entry(info_set.clone()).or_insert_with(|| vec![0.0; actions]);
+            for (total, value) in totals.iter_mut().zip(&values) {
+                *total += weight * value;
+            }
+            values[policy.get(&info_set).copied().unwrap_or(0)]
+        } else {
+            self.average_at(&info_set, actions).iter()
+                .enumerate()
+                .filter(|(_, &probability)| probability > 0.0)
+                .map(|(action, &probability)| {
+                    probability * self.response_value(&self.game.play(state, action), player, weight * probability, policy, action_values)
+                })
+                .sum()
+        }
+    }
+}
+
+// Writes a strategy table as CSV: one row per info set and action
+pub fn export_strategy_csv(table: &[InfoSetStrategy], filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "Info_Set,Action,Probability")?;
+    for row in table {
+        for (action, probability) in row.actions.iter().zip(&row.probabilities) {
+            writeln!(file, "{},{},{:.6}", row.info_set, action, probability)?;
+        }
+    }
+    file.flush()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// Kuhn poker: a three-card deck (J, Q, K), one card each, an ante of 1 and
+// a single bet of 1. Player 0 checks or bets; a check can be answered with a
+// bet, and a bet must be called or folded. Player 0's equilibrium value is -1/18.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KuhnPoker;
+
+#[derive(Debug, Clone)]
+pub struct KuhnState {
+    // 0 = J, 1 = Q, 2 = K
+    pub cards: [u8; 2],
+    // 'p' for a check or fold, 'b' for a bet or call
+    pub history: String,
+}
+
+const KUHN_CARDS: [char; 3] = ['J', 'Q', 'K'];
+
+impl CfrGame for KuhnPoker {
+    type State = KuhnState;
+
+    fn deals(&self) -> Vec<(KuhnState, f64)> {
+        let mut deals = Vec::new();
+        for first in 0..3 {
+            for second in (0..3).filter(|&card| card != first) {
+                deals.push((KuhnState { cards: [first, second], history: String::new() }, 1.0 / 6.0));
+            }
+        }
+        deals
+    }
+
+    fn player(&self, state: &KuhnState) -> Option<usize> {
+        match state.history.as_str() {
+            "pp" | "bp" | "bb" | "pbp" | "pbb" => None,
+            history => Some(history.len() % 2),
+        }
+    }
+
+    fn actions(&self, state: &KuhnState) -> Vec<String> {
+        let names = if state.history.ends_with('b') { ["fold", "call"] } else { ["check", "bet"] };
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn play(&self, state: &KuhnState, action: usize) -> KuhnState {
+        let mut next = state.clone();
+        next.history.push(if action == 0 { 'p' } else { 'b' });
+        next
+    }
+
+    fn info_set(&self, state: &KuhnState) -> String {
+        let player = state.history.len() % 2;
+        format!("{} {}", KUHN_CARDS[state.cards[player] as usize], state.history).trim_end().to_string()
+    }
+
+    fn utility(&self, state: &KuhnState) -> f64 {
+        let showdown = if state.cards[0] > state.cards[1] { 1.0 } else { -1.0 };
+        match state.history.as_str() {
+            "pp" => showdown,
+            "bb" | "pbb" => 2.0 * showdown,
+            // Player 1 folded to a bet
+            "bp" => 1.0,
+            // Player 0 folded to a bet
+            "pbp" => -1.0,
+            history => panic!("utility of unfinished Kuhn history '{}'", history),
+        }
+    }
+}
+
+// A single river decision on a real board: player 0 is out of position and
+// either checks or bets one of `bet_sizes` (fractions of the pot); player 1
+// checks back or bets after a check. A bet can be folded, called or raised
+// all-in once. Payoffs are chips won from the pot and the river bets,
+// counting half the starting pot as each player's own.
+#[derive(Debug, Clone)]
+pub struct RiverGame {
+    pub board: Vec<Card>,
+    pub pot: f64,
+    // Effective stack behind at the start of the river
+    pub stack: f64,
+    pub bet_sizes: Vec<f64>,
+    // Each player's combos, their weights, labels and showdown strength
+    combos: [Vec<[Card; 2]>; 2],
+    weights: [Vec<f64>; 2],
+    labels: [Vec<String>; 2],
+    strengths: [Vec<HandEvaluation>; 2],
+}
+
+#[derive(Debug, Clone)]
+pub struct RiverState {
+    // Index of each player's combo
+    pub hands: [usize; 2],
+    // Chips each player has put in on the river
+    pub invested: [f64; 2],
+    // Actions so far: "x" check, "b<chips>" bet, "c" call, "f" fold, "r<chips>" raise
+    pub history: Vec<String>,
+    // Who acts next; None when the hand is over
+    pub to_act: Option<usize>,
+    raised: bool,
+    folded: Option<usize>,
+}
+
+impl RiverGame {
+    pub fn new(board: Vec<Card>, ranges: [&Range; 2], pot: f64, stack: f64, bet_sizes: Vec<f64>) -> Result<Self, String> {
+        if board.len() != 5 {
+            return Err(format!("The river game needs a full 5-card board, got {} cards", board.len()));
+        }
+        crate::card::check_distinct(board.iter())?;
+        if !(pot > 0.0 && stack > 0.0 && pot.is_finite() && stack.is_finite()) {
+            return Err("The pot and stack must be positive".to_string());
+        }
+        if bet_sizes.is_empty() || bet_sizes.iter().any(|size| !(*size > 0.0 && size.is_finite())) {
+            return Err("Give at least one positive bet size".to_string());
+        }
+
+        let mut combos: [Vec<[Card; 2]>; 2] = [Vec::new(), Vec::new()];
+        let mut weights: [Vec<f64>; 2] = [Vec::new(), Vec::new()];
+        for (player, range) in ranges.iter().enumerate() {
+            for (combo, weight) in range.available(&board) {
+                combos[player].push(combo.clone());
+                weights[player].push(weight);
+            }
+            if combos[player].is_empty() {
+                return Err(format!("Player {}'s range has no combos left on this board", player + 1));
+            }
+        }
+        let labels = combos.clone().map(|hands| hands.iter().map(|hand| format!("{}{}", hand[0], hand[1])).collect());
+        let strengths = combos.clone().map(|hands| hands.iter().map(|hand| evaluate_hand(hand, &board)).collect());
+        Ok(RiverGame { board, pot, stack, bet_sizes, combos, weights, labels, strengths })
+    }
+
+    // Labels every combo by its strength bucket instead of its cards, so the
+    // combos in a bucket share info sets and play one strategy
+    pub fn abstract_hands(&mut self, bucketing: &Bucketing) {
+        for player in 0..2 {
+            self.labels[player] = self.combos[player].iter()
+                .map(|hand| format!("B{}", bucketing.bucket_of(river_strength(hand, &self.board))))
+                .collect();
+        }
+    }
+
+    // Bet amounts for an unbet pot, capped at the stack, smallest first and without repeats
+    fn bet_amounts(&self) -> Vec<f64> {
+        let mut amounts: Vec<f64> = self.bet_sizes.iter().map(|size| (size * self.pot).min(self.stack)).collect();
+        amounts.sort_by(f64::total_cmp);
+        amounts.dedup();
+        amounts
+    }
+
+    fn facing_bet(&self, state: &RiverState, player: usize) -> bool {
+        state.invested[1 - player] > state.invested[player]
+    }
+}
+
+// Chip amounts to two decimals without trailing zeros: "5", "3.33"
+fn chips(amount: f64) -> String {
+    let text = format!("{:.2}", amount);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+impl CfrGame for RiverGame {
+    type State = RiverState;
+
+    fn deals(&self) -> Vec<(RiverState, f64)> {
+        let mut deals = Vec::new();
+        for (first, hand0) in self.combos[0].iter().enumerate() {
+            for (second, hand1) in self.combos[1].iter().enumerate() {
+                if hand0.iter().any(|card| hand1.contains(card)) {
+                    continue;
+                }
+                let state = RiverState {
+                    hands: [first, second],
+                    invested: [0.0; 2],
+                    history: Vec::new(),
+                    to_act: Some(0),
+                    raised: false,
+                    folded: None,
+                };
+                deals.push((state, self.weights[0][first] * self.weights[1][second]));
+            }
+        }
+        let total: f64 = deals.iter().map(|(_, weight)| weight).sum();
+        deals.into_iter().map(|(state, weight)| (state, weight / total)).collect()
+    }
+
+    fn player(&self, state: &RiverState) -> Option<usize> {
+        state.to_act
+    }
+
+    fn actions(&self, state: &RiverState) -> Vec<String> {
+        let Some(player) = state.to_act else {
+            return Vec::new();
+        };
+        if self.facing_bet(state, player) {
+            let mut actions = vec!["fold".to_string(), "call".to_string()];
+            if !state.raised && state.invested[1 - player] < self.stack {
+                actions.push("raise all-in".to_string());
+            }
+            actions
+        } else {
+            let mut actions = vec!["check".to_string()];
+            actions.extend(self.bet_amounts().iter().map(|&amount| {
+                if amount >= self.stack { "bet all-in".to_string() } else { format!("bet {}", chips(amount)) }
+            }));
+            actions
+        }
+    }
+
+    fn play(&self, state: &RiverState, action: usize) -> RiverState {
+        let mut next = state.clone();
+        let player = state.to_act.expect("only live states are played");
+        if self.facing_bet(state, player) {
+            match action {
+                0 => {
+                    next.history.push("f".to_string());
+                    next.folded = Some(player);
+                    next.to_act = None;
+                },
+                1 => {
+                    next.history.push("c".to_string());
+                    next.invested[player] = state.invested[1 - player];
+                    next.to_act = None;
+                },
+                _ => {
+                    next.history.push(format!("r{}", chips(self.stack)));
+                    next.invested[player] = self.stack;
+                    next.raised = true;
+                    next.to_act = Some(1 - player);
+                },
+            }
+        } else if action == 0 {
+            next.history.push("x".to_string());
+            // A check by player 1 ends the betting
+            next.to_act = if player == 0 { Some(1) } else { None };
+        } else {
+            let amount = self.bet_amounts()[action - 1];
+            next.history.push(format!("b{}", chips(amount)));
+            next.invested[player] = amount;
+            next.to_act = Some(1 - player);
+        }
+        next
+    }
+
+    fn info_set(&self, state: &RiverState) -> String {
+        let player = state.to_act.unwrap_or(0);
+        format!("{} {}", self.labels[player][state.hands[player]], state.history.join(" ")).trim_end().to_string()
+    }
+
+    fn utility(&self, state: &RiverState) -> f64 {
+        let final_pot = self.pot + state.invested[0] + state.invested[1];
+        let share = match state.folded {
+            Some(0) => 0.0,
+            Some(_) => 1.0,
+            None => {
+                let (hero, villain) = (&self.strengths[0][state.hands[0]], &self.strengths[1][state.hands[1]]);
+                match hero.cmp(villain) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Less => 0.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                }
+            },
+        };
+        share * final_pot - state.invested[0] - self.pot / 2.0
+    }
+}
@@ -0,0 +1,108 @@
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::card::{aggregate_canonical, grid_hand, grid_win_rates, describe_hand, HandResult, RANKS_DESCENDING};
+
+const CELL_SIZE: u32 = 56;
+const LABEL_SIZE: u32 = 32;
+
+fn to_io_error<E: std::error::Error + Send + Sync>(e: DrawingAreaErrorKind<E>) -> std::io::Error {
+    std::io::Error::other(e.to_string())
+}
+
+// Red (weakest) through yellow to green (strongest)
+fn heat_color(scale: f64) -> RGBColor {
+    let scale = scale.clamp(0.0, 1.0);
+    if scale < 0.5 {
+        RGBColor(220, (80.0 + 350.0 * scale) as u8, 60)
+    } else {
+        RGBColor((220.0 - 300.0 * (scale - 0.5)) as u8, 200, 60)
+    }
+}
+
+fn draw_grid<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, results: &[HandResult]) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let grid = grid_win_rates(results);
+    let rates: Vec<f64> = grid.iter().flatten().filter_map(|r| *r).collect();
+    let min = rates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = rates.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    root.fill(&WHITE)?;
+    let label_font = ("sans-serif", 16).into_font();
+    let cell_font = ("sans-serif", 12).into_font();
+
+    for (i, rank) in RANKS_DESCENDING.iter().enumerate() {
+        let offset = (LABEL_SIZE + i as u32 * CELL_SIZE + CELL_SIZE / 2 - 5) as i32;
+        root.draw(&Text::new(rank.to_string(), (offset, 8), label_font.clone()))?;
+        root.draw(&Text::new(rank.to_string(), (8, offset), label_font.clone()))?;
+    }
+
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            let x0 = (LABEL_SIZE + col as u32 * CELL_SIZE) as i32;
+            let y0 = (LABEL_SIZE + row as u32 * CELL_SIZE) as i32;
+            let x1 = x0 + CELL_SIZE as i32;
+            let y1 = y0 + CELL_SIZE as i32;
+
+            let color = match cell {
+                Some(rate) if max > min => heat_color((rate - min) / (max - min)),
+                Some(_) => heat_color(1.0),
+                None => RGBColor(230, 230, 230),
+            };
+            root.draw(&Rectangle::new([(x0, y0), (x1, y1)], color.filled()))?;
+            root.draw(&Rectangle::new([(x0, y0), (x1, y1)], WHITE.stroke_width(1)))?;
+
+            let description = describe_hand(&grid_hand(row, col)).replace("(pair)", "");
+            root.draw(&Text::new(description, (x0 + 4, y0 + 6), cell_font.clone()))?;
+            if let Some(rate) = cell {
+                root.draw(&Text::new(format!("{:.1}", rate), (x0 + 4, y0 + 30), cell_font.clone()))?;
+            }
+        }
+    }
+
+    root.present()
+}
+
+fn draw_ranked_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, results: &[HandResult]) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let canonical = aggregate_canonical(results);
+    let rates: Vec<f64> = canonical.iter().map(|r| r.results.win_rate).collect();
+
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(root)
+        .caption("Canonical starting hands ranked by win rate", ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..rates.len(), 0f64..100f64)?;
+
+    chart.configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Hand rank")
+        .y_desc("Win %")
+        .draw()?;
+
+    chart.draw_series(rates.iter().enumerate().map(|(i, rate)| {
+        Rectangle::new([(i, 0.0), (i + 1, *rate)], RGBColor(60, 110, 200).filled())
+    }))?;
+
+    root.present()
+}
+
+// Writes the 13x13 equity grid and ranked bar chart as PNG and SVG files
+// named `<prefix>_grid.{png,svg}` and `<prefix>_chart.{png,svg}`
+pub fn export_images(results: &[HandResult], prefix: &str) -> Result<(), std::io::Error> {
+    let grid_size = (LABEL_SIZE + 13 * CELL_SIZE, LABEL_SIZE + 13 * CELL_SIZE);
+    let chart_size = (1200, 600);
+
+    let png_grid = format!("{}_grid.png", prefix);
+    draw_grid(&BitMapBackend::new(&png_grid, grid_size).into_drawing_area(), results).map_err(to_io_error)?;
+    let svg_grid = format!("{}_grid.svg", prefix);
+    draw_grid(&SVGBackend::new(&svg_grid, grid_size).into_drawing_area(), results).map_err(to_io_error)?;
+
+    let png_chart = format!("{}_chart.png", prefix);
+    draw_ranked_chart(&BitMapBackend::new(&png_chart, chart_size).into_drawing_area(), results).map_err(to_io_error)?;
+    let svg_chart = format!("{}_chart.svg", prefix);
+    draw_ranked_chart(&SVGBackend::new(&svg_chart, chart_size).into_drawing_area(), results).map_err(to_io_error)?;
+
+    tracing::info!("Images exported to: {}, {}, {}, {}", png_grid, svg_grid, png_chart, svg_chart);
+    Ok(())
+}
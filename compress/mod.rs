@@ -0,0 +1,176 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::str::FromStr;
+
+// Exports that keep every combo, hand or matchup get large, so they can be
+// written compressed: a file name ending in .gz is gzip and one ending in .zst
+// is zstd. Readers go by the first bytes of the file rather than its name, so
+// an importer takes results.csv, results.csv.gz and a renamed file alike.
+//
+// The codecs come with the compress feature; without it, compressed names are
+// refused with an error rather than written as plain text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+impl Compression {
+    // Appended to an export's name, after its format's own extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    pub fn from_filename(filename: &str) -> Option<Compression> {
+        let lower = filename.to_ascii_lowercase();
+        if lower.ends_with(".gz") {
+            Some(Compression::Gzip)
+        } else if lower.ends_with(".zst") {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    fn detect(header: &[u8]) -> Option<Compression> {
+        if header.starts_with(&GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if header.starts_with(&ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(format!("Invalid compression '{}', expected gzip or zstd", text)),
+        }
+    }
+}
+
+// The name without its .gz or .zst, for picking the format underneath
+pub fn base_name(filename: &str) -> &str {
+    match Compression::from_filename(filename) {
+        Some(compression) => &filename[..filename.len() - compression.extension().len()],
+        None => filename,
+    }
+}
+
+// A file being written, compressed or not. finish() writes the compressed
+// stream's trailer; a file dropped without it is cut short.
+pub enum Writer {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "compress")]
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+    #[cfg(feature = "compress")]
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl Writer {
+    pub fn finish(self) -> Result<(), io::Error> {
+        match self {
+            Writer::Plain(mut file) => file.flush(),
+            #[cfg(feature = "compress")]
+            Writer::Gzip(encoder) => encoder.finish()?.flush(),
+            #[cfg(feature = "compress")]
+            Writer::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Writer::Plain(file) => file.write(buf),
+            #[cfg(feature = "compress")]
+            Writer::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "compress")]
+            Writer::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    // A compressed stream is flushed to a point a reader can decode up to,
+    // so a streamed export can be followed while it's being written
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Writer::Plain(file) => file.flush(),
+            #[cfg(feature = "compress")]
+            Writer::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "compress")]
+            Writer::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+// Creates the file, compressed when its name ends in .gz or .zst
+pub fn create(filename: &str) -> Result<Writer, io::Error> {
+    match Compression::from_filename(filename) {
+        Some(compression) => compressed_writer(filename, compression),
+        None => Ok(Writer::Plain(BufWriter::new(File::create(filename)?))),
+    }
+}
+
+// Opens the file for reading, decompressing it if it starts like gzip or zstd
+pub fn open(filename: &str) -> Result<Box<dyn BufRead>, io::Error> {
+    let mut reader = BufReader::new(File::open(filename)?);
+    match Compression::detect(reader.fill_buf()?) {
+        Some(compression) => compressed_reader(reader, compression),
+        None => Ok(Box::new(reader)),
+    }
+}
+
+pub fn read_to_string(filename: &str) -> Result<String, io::Error> {
+    let mut text = String::new();
+    open(filename)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+#[cfg(feature = "compress")]
+fn compressed_writer(filename: &str, compression: Compression) -> Result<Writer, io::Error> {
+    let file = BufWriter::new(File::create(filename)?);
+    Ok(match compression {
+        Compression::Gzip => Writer::Gzip(flate2::write::GzEncoder::new(file, flate2::Compression::default())),
+        Compression::Zstd => Writer::Zstd(zstd::stream::write::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?),
+    })
+}
+
+#[cfg(not(feature = "compress"))]
+fn compressed_writer(_filename: &str, compression: Compression) -> Result<Writer, io::Error> {
+    Err(needs_feature(compression))
+}
+
+// Concatenated gzip members, as from `cat a.gz b.gz`, read as one file
+#[cfg(feature = "compress")]
+fn compressed_reader(reader: BufReader<File>, compression: Compression) -> Result<Box<dyn BufRead>, io::Error> {
+    Ok(match compression {
+        Compression::Gzip => Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(reader)?)),
+    })
+}
+
+#[cfg(not(feature = "compress"))]
+fn compressed_reader(_reader: BufReader<File>, compression: Compression) -> Result<Box<dyn BufRead>, io::Error> {
+    Err(needs_feature(compression))
+}
+
+#[cfg(not(feature = "compress"))]
+fn needs_feature(compression: Compression) -> io::Error {
+    let codec = match compression {
+        Compression::Gzip => "gzip",
+        Compression::Zstd => "zstd",
+    };
+    io::Error::new(io::ErrorKind::Unsupported, format!("{} compression requires building with the compress feature", codec))
+}
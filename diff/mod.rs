@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::card::{aggregate_canonical, HandResult};
+
+// How one canonical starting hand moved between two bulk runs
+#[derive(Debug, Clone)]
+pub struct HandDiff {
+    pub hand: String,
+    // Pot share (wins plus half the ties) in percent
+    pub before_equity: f64,
+    pub after_equity: f64,
+    // Position in each run ordered by equity, 1 being the strongest
+    pub before_rank: usize,
+    pub after_rank: usize,
+}
+
+impl HandDiff {
+    pub fn equity_delta(&self) -> f64 {
+        self.after_equity - self.before_equity
+    }
+
+    // Positive when the hand climbed the ranking
+    pub fn rank_change(&self) -> i64 {
+        self.before_rank as i64 - self.after_rank as i64
+    }
+}
+
+// Equity and 1-based rank of every canonical hand in a run
+fn ranked_equities(results: &[HandResult]) -> HashMap<String, (f64, usize)> {
+    let mut equities: Vec<(String, f64)> = aggregate_canonical(results).into_iter()
+        .map(|result| (result.hand_description, result.results.win_rate + result.results.tie_rate / 2.0))
+        .collect();
+    equities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then_with(|| a.0.cmp(&b.0)));
+    equities.into_iter()
+        .enumerate()
+        .map(|(i, (hand, equity))| (hand, (equity, i + 1)))
+        .collect()
+}
+
+// Compares two runs hand by hand. Per-combo files are collapsed to canonical
+// hands first so a 1,326-row run can be compared against a 169-row one; hands
+// missing from either run are skipped. Sorted by the size of the equity change,
+// then by hand.
+pub fn diff_results(before: &[HandResult], after: &[HandResult]) -> Vec<HandDiff> {
+    let before = ranked_equities(before);
+    let after = ranked_equities(after);
+
+    let mut diffs: Vec<HandDiff> = before.iter()
+        .filter_map(|(hand, &(before_equity, before_rank))| {
+            after.get(hand).map(|&(after_equity, after_rank)| HandDiff {
+                hand: hand.clone(),
+                before_equity,
+                after_equity,
+                before_rank,
+                after_rank,
+            })
+        })
+        .collect();
+    diffs.sort_by(|a, b| b.equity_delta().abs().partial_cmp(&a.equity_delta().abs()).unwrap().then_with(|| a.hand.cmp(&b.hand)));
+    diffs
+}
+
+pub fn export_diff_csv(diffs: &[HandDiff], filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "Hand,Before_Equity,After_Equity,Equity_Delta,Before_Rank,After_Rank,Rank_Change")?;
+    for diff in diffs {
+        writeln!(file, "{},{:.4},{:.4},{:.4},{},{},{}",
+                 diff.hand,
+                 diff.before_equity,
+                 diff.after_equity,
+                 diff.equity_delta(),
+                 diff.before_rank,
+                 diff.after_rank,
+                 diff.rank_change())?;
+    }
+    file.flush()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
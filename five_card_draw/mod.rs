@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::card::{Card, Deck, HandEvaluation, HandRank, Rank, SimulationResults, evaluate_hand};
+
+pub fn evaluate_five(cards: &[Card]) -> HandEvaluation {
+    assert_eq!(cards.len(), 5, "five-card draw hands have five cards");
+    evaluate_hand(&[cards[0].clone(), cards[1].clone()], &cards[2..])
+}
+
+// A named choice of which cards to keep before the draw
+#[derive(Debug, Clone)]
+pub struct DrawOption {
+    pub label: String,
+    pub keep: Vec<Card>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawAnalysis {
+    pub option: DrawOption,
+    // Percent of draws that finished in a better hand category than we started with
+    pub improvement_rate: f64,
+    // How often each final hand category was made
+    pub final_hands: HashMap<HandRank, usize>,
+    pub results: SimulationResults,
+}
+
+fn rank_counts(cards: &[Card]) -> HashMap<Rank, usize> {
+    let mut counts = HashMap::new();
+    for card in cards {
+        *counts.entry(card.rank).or_insert(0) += 1;
+    }
+    counts
+}
+
+// Cards that make up a pair, two pair, trips or quads
+fn made_hand_cards(cards: &[Card]) -> Vec<Card> {
+    let counts = rank_counts(cards);
+    cards.iter().filter(|c| counts[&c.rank] >= 2).cloned().collect()
+}
+
+fn four_flush(cards: &[Card]) -> Option<Vec<Card>> {
+    cards.iter()
+        .map(|card| cards.iter().filter(|c| c.suit == card.suit).cloned().collect::<Vec<_>>())
+        .find(|suited| suited.len() == 4)
+}
+
+// Four distinct ranks that fit inside a five-rank window (open-ended or gutshot)
+fn four_straight(cards: &[Card]) -> Option<Vec<Card>> {
+    let value = |rank: Rank, ace_low: bool| if ace_low && rank == Rank::Ace { 1 } else { rank.value() };
+    for ace_low in [false, true] {
+        for low in 1..=10u8 {
+            let mut kept: Vec<Card> = Vec::new();
+            for card in cards {
+                let v = value(card.rank, ace_low);
+                if v >= low && v < low + 5 && !kept.iter().any(|k| k.rank == card.rank) {
+                    kept.push(card.clone());
+                }
+            }
+            if kept.len() == 4 {
+                return Some(kept);
+            }
+        }
+    }
+    None
+}
+
+// The standard discard choices that apply to this hand
+pub fn candidate_discards(hand: &[Card]) -> Vec<DrawOption> {
+    let mut options = vec![DrawOption { label: "Stand pat".to_string(), keep: hand.to_vec() }];
+
+    let made = made_hand_cards(hand);
+    if !made.is_empty() && made.len() < 5 {
+        options.push(DrawOption { label: format!("Keep {}", evaluate_five(hand).rank), keep: made });
+    }
+    if let Some(flush_draw) = four_flush(hand) {
+        options.push(DrawOption { label: "Draw to flush".to_string(), keep: flush_draw });
+    }
+    if let Some(straight_draw) = four_straight(hand) {
+        options.push(DrawOption { label: "Draw to straight".to_string(), keep: straight_draw });
+    }
+
+    let mut by_rank = hand.to_vec();
+    by_rank.sort_by_key(|card| std::cmp::Reverse(card.rank));
+    options.push(DrawOption { label: "Keep two high cards".to_string(), keep: by_rank[..2].to_vec() });
+    options.push(DrawOption { label: "Draw five".to_string(), keep: Vec::new() });
+
+    options
+}
+
+// Simple opponent policy: keep made hands, then four-flushes, then four-straights,
+// otherwise the two highest cards
+fn default_keep(hand: &[Card]) -> Vec<Card> {
+    if evaluate_five(hand).rank >= HandRank::Straight {
+        return hand.to_vec();
+    }
+    let made = made_hand_cards(hand);
+    if !made.is_empty() {
+        return made;
+    }
+    if let Some(flush_draw) = four_flush(hand) {
+        return flush_draw;
+    }
+    if let Some(straight_draw) = four_straight(hand) {
+        return straight_draw;
+    }
+    let mut by_rank = hand.to_vec();
+    by_rank.sort_by_key(|card| std::cmp::Reverse(card.rank));
+    by_rank.truncate(2);
+    by_rank
+}
+
+fn fill_from_deck(deck: &mut Deck, cards: &mut Vec<Card>) -> bool {
+    while cards.len() < 5 {
+        match deck.draw() {
+            Some(card) => cards.push(card),
+            None => return false,
+        }
+    }
+    true
+}
+
+// Simulates hero keeping `option.keep` from `hand` and drawing against `num_opponents`
+// random opponents who draw using a basic keep policy
+pub fn simulate_draw(hand: &[Card], option: &DrawOption, num_opponents: usize, num_simulations: usize) -> DrawAnalysis {
+    let starting_rank = evaluate_five(hand).rank;
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut improved = 0;
+    let mut final_hands = HashMap::new();
+
+    // Discarded cards are out of play too
+    let mut deck = match Deck::without(hand) {
+        Ok(deck) => deck,
+        Err(_) => return DrawAnalysis {
+            option: option.clone(),
+            improvement_rate: 0.0,
+            final_hands,
+            results: SimulationResults::new(0, 0, 0, 0),
+        },
+    };
+
+    for _ in 0..num_simulations {
+        deck.recycle();
+
+        let mut opponent_hands: Vec<Vec<Card>> = Vec::new();
+        for _ in 0..num_opponents {
+            let mut opponent = Vec::new();
+            if !fill_from_deck(&mut deck, &mut opponent) {
+                break;
+            }
+            opponent_hands.push(opponent);
+        }
+        if opponent_hands.len() < num_opponents {
+            continue;
+        }
+
+        let mut final_hand = option.keep.clone();
+        if !fill_from_deck(&mut deck, &mut final_hand) {
+            continue;
+        }
+
+        let mut complete = true;
+        for opponent in opponent_hands.iter_mut() {
+            let mut kept = default_keep(opponent);
+            complete &= fill_from_deck(&mut deck, &mut kept);
+            *opponent = kept;
+        }
+        if !complete {
+            continue;
+        }
+
+        let player_eval = evaluate_five(&final_hand);
+        if player_eval.rank > starting_rank {
+            improved += 1;
+        }
+        *final_hands.entry(player_eval.rank.clone()).or_insert(0) += 1;
+
+        let best_opponent = opponent_hands.iter().map(|h| evaluate_five(h)).max();
+        match best_opponent.map(|best| player_eval.cmp(&best)) {
+            Some(std::cmp::Ordering::Greater) | None => wins += 1,
+            Some(std::cmp::Ordering::Less) => losses += 1,
+            Some(std::cmp::Ordering::Equal) => ties += 1,
+        }
+    }
+
+    DrawAnalysis {
+        option: option.clone(),
+        improvement_rate: improved as f64 / num_simulations as f64 * 100.0,
+        final_hands,
+        results: SimulationResults::new(num_simulations, wins, losses, ties),
+    }
+}
+
+// Runs every candidate discard for the hand, best showdown equity first
+pub fn analyze_draws(hand: &[Card], num_opponents: usize, num_simulations: usize) -> Vec<DrawAnalysis> {
+    let mut analyses: Vec<DrawAnalysis> = candidate_discards(hand)
+        .iter()
+        .map(|option| simulate_draw(hand, option, num_opponents, num_simulations))
+        .collect();
+
+    analyses.sort_by(|a, b| {
+        let equity = |d: &DrawAnalysis| d.results.win_rate + d.results.tie_rate / 2.0;
+        equity(b).partial_cmp(&equity(a)).unwrap()
+    });
+    analyses
+}
@@ -0,0 +1,491 @@
+use crate::card::{Card, Deck, evaluate_hand};
+use crate::pot::{OddChipRule, distribute_pots};
+use rand::Rng;
+use std::fmt;
+
+// One hand of No-Limit Hold'em from the blinds to the showdown: seats with
+// stacks, the deck and board, whose turn it is and what they may do, and
+// every chip that went in. Callers loop until is_over(), playing an action
+// while to_act is set and otherwise calling next_street_with() (which also
+// runs the board out once everyone is all-in), then finish() pays the pots.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+}
+
+impl Street {
+    fn next(self) -> Street {
+        match self {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn => Street::River,
+            Street::River | Street::Showdown => Street::Showdown,
+        }
+    }
+
+    // Board cards dealt when the street starts
+    fn cards_dealt(self) -> usize {
+        match self {
+            Street::Flop => 3,
+            Street::Turn | Street::River => 1,
+            Street::Preflop | Street::Showdown => 0,
+        }
+    }
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Street::Preflop => "Preflop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+            Street::Showdown => "Showdown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    // Bet or raise so the player's total for the street becomes this many chips
+    Bet(u32),
+    AllIn,
+}
+
+impl std::str::FromStr for Action {
+    type Err = String;
+
+    // "fold", "check", "call", "bet 60", "raise 150" or "all-in"; the first letter is enough
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = text.split_whitespace().collect();
+        let word = parts.first().map(|w| w.to_lowercase()).unwrap_or_default();
+        match (word.as_str(), parts.len()) {
+            ("f" | "fold", 1) => Ok(Action::Fold),
+            ("k" | "x" | "check", 1) => Ok(Action::Check),
+            ("c" | "call", 1) => Ok(Action::Call),
+            ("a" | "allin" | "all-in", 1) => Ok(Action::AllIn),
+            ("b" | "bet" | "r" | "raise", 2) => parts[1].parse()
+                .map(Action::Bet)
+                .map_err(|_| format!("Invalid amount '{}'", parts[1])),
+            ("b" | "bet" | "r" | "raise", _) => Err(format!("{} needs an amount, e.g. {} 60", word, word)),
+            _ => Err(format!("Unknown action '{}', expected fold, check, call, bet <n>, raise <n> or all-in", text.trim())),
+        }
+    }
+}
+
+// What the player to act may do; bet and raise amounts are street totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegalAction {
+    Fold,
+    Check,
+    Call(u32),
+    Bet { min: u32, max: u32 },
+    Raise { min: u32, max: u32 },
+}
+
+impl fmt::Display for LegalAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegalAction::Fold => write!(f, "fold"),
+            LegalAction::Check => write!(f, "check"),
+            LegalAction::Call(amount) => write!(f, "call {}", amount),
+            LegalAction::Bet { min, max } if min == max => write!(f, "bet {}", max),
+            LegalAction::Bet { min, max } => write!(f, "bet <{}-{}>", min, max),
+            LegalAction::Raise { min, max } if min == max => write!(f, "raise {}", max),
+            LegalAction::Raise { min, max } => write!(f, "raise <{}-{}>", min, max),
+        }
+    }
+}
+
+// An action as it was played. All-ins are recorded as the call or bet they
+// amounted to, with `all_in` set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionRecord {
+    pub street: Street,
+    pub seat: usize,
+    pub action: Action,
+    pub all_in: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stakes {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    // Posted by every player before the blinds
+    pub ante: u32,
+}
+
+impl Stakes {
+    pub fn new(small_blind: u32, big_blind: u32) -> Self {
+        Stakes { small_blind, big_blind, ante: 0 }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub stack: u32,
+    pub hole_cards: Option<[Card; 2]>,
+    // Chips put in on the current street, and over the whole hand
+    pub street_bet: u32,
+    pub contributed: u32,
+    pub folded: bool,
+    // Acted since the last full bet or raise
+    acted: bool,
+}
+
+impl Player {
+    fn new(stack: u32) -> Self {
+        Player { stack, hole_cards: None, street_bet: 0, contributed: 0, folded: false, acted: false }
+    }
+
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.stack == 0
+    }
+
+    // Still in the hand with chips behind
+    fn can_act(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub players: Vec<Player>,
+    pub stakes: Stakes,
+    pub button: usize,
+    pub street: Street,
+    pub board: Vec<Card>,
+    // Seat whose turn it is; None once the street's betting is over
+    pub to_act: Option<usize>,
+    pub history: Vec<ActionRecord>,
+    deck: Deck,
+    // Size of the last full bet or raise; the next raise must be at least this much more
+    last_raise: u32,
+}
+
+impl Game {
+    // Seats one player per stack (in seat order), posts the antes and blinds
+    // and gives the first preflop decision to the player after the big blind.
+    // Heads-up the button posts the small blind and acts first preflop.
+    pub fn new(stacks: &[u32], stakes: Stakes, button: usize) -> Result<Self, String> {
+        if stacks.len() < 2 || stacks.len() > 10 {
+            return Err(format!("A game needs 2 to 10 players, found {}", stacks.len()));
+        }
+        if let Some(seat) = stacks.iter().position(|&stack| stack == 0) {
+            return Err(format!("Seat {} has no chips", seat));
+        }
+        if button >= stacks.len() {
+            return Err(format!("Button seat {} is not at the table", button));
+        }
+        if stakes.big_blind == 0 || stakes.small_blind > stakes.big_blind {
+            return Err(format!("Invalid blinds {}/{}", stakes.small_blind, stakes.big_blind));
+        }
+
+        let mut game = Game {
+            players: stacks.iter().map(|&stack| Player::new(stack)).collect(),
+            stakes,
+            button,
+            street: Street::Preflop,
+            board: Vec::new(),
+            to_act: None,
+            history: Vec::new(),
+            deck: Deck::new(),
+            last_raise: stakes.big_blind,
+        };
+
+        // Antes go straight into the pot rather than counting toward the street's bets
+        for player in &mut game.players {
+            let ante = stakes.ante.min(player.stack);
+            player.stack -= ante;
+            player.contributed += ante;
+        }
+        game.put_in(game.small_blind_seat(), stakes.small_blind);
+        game.put_in(game.big_blind_seat(), stakes.big_blind);
+        game.to_act = game.first_to_act(game.seat_after(game.big_blind_seat()));
+        Ok(game)
+    }
+
+    pub fn small_blind_seat(&self) -> usize {
+        if self.players.len() == 2 { self.button } else { self.seat_after(self.button) }
+    }
+
+    pub fn big_blind_seat(&self) -> usize {
+        self.seat_after(self.small_blind_seat())
+    }
+
+    fn seat_after(&self, seat: usize) -> usize {
+        (seat + 1) % self.players.len()
+    }
+
+    // Seats in the order they are dealt and act after the flop, starting left of the button
+    fn seats_from_button(&self) -> impl Iterator<Item = usize> + '_ {
+        (1..=self.players.len()).map(move |offset| (self.button + offset) % self.players.len())
+    }
+
+    // BTN, SB, BB, then UTG, UTG+1, ... LJ, HJ and CO toward the button
+    pub fn position_name(&self, seat: usize) -> String {
+        let count = self.players.len();
+        let offset = (seat + count - self.button) % count;
+        if offset == 0 {
+            return "BTN".to_string();
+        }
+        if count == 2 {
+            return "BB".to_string();
+        }
+        match offset {
+            1 => "SB".to_string(),
+            2 => "BB".to_string(),
+            _ => {
+                // CO, HJ and LJ count back from the button, but the first seat is always UTG
+                let middle = count - 3;
+                let index = offset - 3;
+                let from_button = middle - 1 - index;
+                match ["CO", "HJ", "LJ"].get(from_button) {
+                    Some(name) if from_button < middle - 1 => name.to_string(),
+                    _ if index == 0 => "UTG".to_string(),
+                    _ => format!("UTG+{}", index),
+                }
+            },
+        }
+    }
+
+    // Gives `seat` known hole cards (e.g. the user's), taking them out of the deck
+    pub fn set_hole_cards(&mut self, seat: usize, cards: [Card; 2]) -> Result<(), String> {
+        if self.players[seat].hole_cards.is_some() {
+            return Err(format!("Seat {} already has cards", seat));
+        }
+        for card in &cards {
+            self.deck.remove_card(card).map_err(|_| format!("{} is not in the deck", card))?;
+        }
+        self.players[seat].hole_cards = Some(cards);
+        Ok(())
+    }
+
+    // Deals two cards to every seat that doesn't have any yet
+    pub fn deal_hole_cards_with<R: Rng>(&mut self, rng: &mut R) {
+        let seats: Vec<usize> = self.seats_from_button().collect();
+        for seat in seats {
+            if self.players[seat].hole_cards.is_none() {
+                let cards = self.deck.deal_n_with(2, rng).expect("a deck covers ten players");
+                self.players[seat].hole_cards = Some([cards[0].clone(), cards[1].clone()]);
+            }
+        }
+    }
+
+    // Moves chips from a stack to the street's bets, at most the whole stack
+    fn put_in(&mut self, seat: usize, amount: u32) {
+        let player = &mut self.players[seat];
+        let amount = amount.min(player.stack);
+        player.stack -= amount;
+        player.street_bet += amount;
+        player.contributed += amount;
+    }
+
+    // Every chip put in this hand, including the current street's bets
+    pub fn pot(&self) -> u32 {
+        self.players.iter().map(|p| p.contributed).sum()
+    }
+
+    // The largest bet on the current street
+    pub fn current_bet(&self) -> u32 {
+        self.players.iter().map(|p| p.street_bet).max().unwrap_or(0)
+    }
+
+    // Chips `seat` needs to call, capped at their stack
+    pub fn to_call(&self, seat: usize) -> u32 {
+        let player = &self.players[seat];
+        (self.current_bet() - player.street_bet).min(player.stack)
+    }
+
+    fn live_players(&self) -> usize {
+        self.players.iter().filter(|p| !p.folded).count()
+    }
+
+    // The hand is decided: everyone else folded, or the showdown is reached
+    pub fn is_over(&self) -> bool {
+        self.live_players() <= 1 || self.street == Street::Showdown
+    }
+
+    fn needs_to_act(&self, seat: usize) -> bool {
+        let player = &self.players[seat];
+        if !player.can_act() || self.live_players() <= 1 {
+            return false;
+        }
+        // Nobody left to bet against once everyone else is all-in
+        let others_can_act = (0..self.players.len()).any(|other| other != seat && self.players[other].can_act());
+        player.street_bet < self.current_bet() || (!player.acted && others_can_act)
+    }
+
+    // The first seat from `start` onwards that still has a decision to make
+    fn first_to_act(&self, start: usize) -> Option<usize> {
+        (0..self.players.len())
+            .map(|offset| (start + offset) % self.players.len())
+            .find(|&seat| self.needs_to_act(seat))
+    }
+
+    // Raising needs chips beyond a call, an opponent who can still put chips
+    // in, and no full raise since the player last acted (a short all-in
+    // doesn't reopen the betting)
+    fn can_raise(&self, seat: usize) -> bool {
+        let player = &self.players[seat];
+        player.stack > self.to_call(seat)
+            && !player.acted
+            && (0..self.players.len()).any(|other| other != seat && self.players[other].can_act())
+    }
+
+    pub fn legal_actions(&self) -> Vec<LegalAction> {
+        let Some(seat) = self.to_act else {
+            return Vec::new();
+        };
+        let player = &self.players[seat];
+        let to_call = self.to_call(seat);
+        let mut actions = if to_call > 0 {
+            vec![LegalAction::Fold, LegalAction::Call(to_call)]
+        } else {
+            vec![LegalAction::Check]
+        };
+        if self.can_raise(seat) {
+            let max = player.street_bet + player.stack;
+            let min = (self.current_bet() + self.last_raise).min(max);
+            actions.push(if self.current_bet() == 0 { LegalAction::Bet { min, max } } else { LegalAction::Raise { min, max } });
+        }
+        actions
+    }
+
+    // Plays `action` for the player to act, or explains why it isn't allowed
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        let seat = self.to_act.ok_or("Nobody is to act")?;
+        let to_call = self.to_call(seat);
+        let all_in_total = self.players[seat].street_bet + self.players[seat].stack;
+        // All-in is a bet when raising is allowed, otherwise a call
+        let action = match action {
+            Action::AllIn if self.can_raise(seat) => Action::Bet(all_in_total),
+            Action::AllIn if to_call > 0 => Action::Call,
+            Action::AllIn => return Err("You cannot bet here, check instead".to_string()),
+            action => action,
+        };
+
+        match action {
+            Action::Fold if to_call == 0 => return Err("Nothing to call, check instead".to_string()),
+            Action::Fold => self.players[seat].folded = true,
+            Action::Check if to_call > 0 => return Err(format!("{} to call, you cannot check", to_call)),
+            Action::Check => {},
+            Action::Call if to_call == 0 => return Err("Nothing to call, check instead".to_string()),
+            Action::Call => self.put_in(seat, to_call),
+            Action::Bet(total) => self.bet_to(seat, total, all_in_total)?,
+            Action::AllIn => unreachable!("all-in was resolved to a call or bet"),
+        }
+        self.players[seat].acted = true;
+        self.history.push(ActionRecord { street: self.street, seat, action, all_in: self.players[seat].is_all_in() });
+        self.to_act = self.first_to_act(self.seat_after(seat));
+        Ok(())
+    }
+
+    fn bet_to(&mut self, seat: usize, total: u32, all_in_total: u32) -> Result<(), String> {
+        if !self.can_raise(seat) {
+            return Err("You cannot raise here; call or fold".to_string());
+        }
+        if total > all_in_total {
+            return Err(format!("You only have {} chips (all-in makes it {})", self.players[seat].stack, all_in_total));
+        }
+        // An all-in may be short of a full raise
+        let min = self.current_bet() + self.last_raise;
+        if total < min && total < all_in_total {
+            return Err(format!("The minimum is {}", min));
+        }
+
+        let raise = total - self.current_bet();
+        self.put_in(seat, total - self.players[seat].street_bet);
+        // Only a full raise gives the players who already acted another decision
+        if raise >= self.last_raise {
+            self.last_raise = raise;
+            for player in &mut self.players {
+                player.acted = false;
+            }
+        }
+        Ok(())
+    }
+
+    // Hands back the part of the street's largest bet nobody matched
+    fn return_uncalled(&mut self) {
+        let mut bets: Vec<(u32, usize)> = self.players.iter().enumerate().map(|(seat, p)| (p.street_bet, seat)).collect();
+        bets.sort_unstable_by(|a, b| b.cmp(a));
+        let (top, seat) = bets[0];
+        let uncalled = top - bets[1].0;
+        if uncalled > 0 {
+            let player = &mut self.players[seat];
+            player.stack += uncalled;
+            player.street_bet -= uncalled;
+            player.contributed -= uncalled;
+        }
+    }
+
+    // Ends the street's betting and deals the next street (burning a card
+    // first), or moves to the showdown after the river
+    pub fn next_street_with<R: Rng>(&mut self, rng: &mut R) -> Result<(), String> {
+        if self.to_act.is_some() {
+            return Err(format!("The {} betting isn't finished", self.street));
+        }
+        if self.is_over() {
+            return Err("The hand is over".to_string());
+        }
+
+        self.return_uncalled();
+        for player in &mut self.players {
+            player.street_bet = 0;
+            player.acted = false;
+        }
+        self.last_raise = self.stakes.big_blind;
+        self.street = self.street.next();
+        let count = self.street.cards_dealt();
+        if count > 0 {
+            self.deck.burn_with(rng);
+            self.board.extend_from_slice(self.deck.deal_n_with(count, rng).expect("a deck covers a full board"));
+        }
+        self.to_act = if self.street == Street::Showdown { None } else { self.first_to_act(self.seat_after(self.button)) };
+        Ok(())
+    }
+
+    // Pays out the pots once the hand is over (see pot::distribute_pots, with
+    // odd chips left of the button) and returns what each seat won
+    pub fn finish(&mut self) -> Result<Vec<u32>, String> {
+        if !self.is_over() {
+            return Err("The hand is not over yet".to_string());
+        }
+        self.return_uncalled();
+
+        let contributions: Vec<u32> = self.players.iter().map(|p| p.contributed).collect();
+        let odd_chip = OddChipRule::LeftOfButton(self.button);
+        let won = if self.live_players() > 1 {
+            let mut evaluations = Vec::with_capacity(self.players.len());
+            for (seat, player) in self.players.iter().enumerate() {
+                evaluations.push(match (&player.hole_cards, player.folded) {
+                    (_, true) => None,
+                    (Some(hole_cards), false) => Some(evaluate_hand(hole_cards, &self.board)),
+                    (None, false) => return Err(format!("Seat {} has no cards to show", seat)),
+                });
+            }
+            distribute_pots(&contributions, &evaluations, &odd_chip).payouts
+        } else {
+            let standing: Vec<Option<()>> = self.players.iter().map(|p| (!p.folded).then_some(())).collect();
+            distribute_pots(&contributions, &standing, &odd_chip).payouts
+        };
+
+        for (player, &amount) in self.players.iter_mut().zip(&won) {
+            player.stack += amount;
+            player.contributed = 0;
+            player.street_bet = 0;
+        }
+        self.to_act = None;
+        Ok(won)
+    }
+}
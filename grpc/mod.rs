@@ -0,0 +1,163 @@
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::api;
+use crate::json::JsonValue;
+use crate::server::ServerConfig;
+
+// The gRPC service in pokershit.proto, for backends that already speak gRPC.
+// Requests become the same JSON parameters the HTTP and JSON-RPC front ends
+// pass to api, so every front end validates and answers alike. The build
+// script compiles the proto with tonic-build when the grpc feature is on.
+
+pub mod proto {
+    tonic::include_proto!("pokershit");
+}
+
+use proto::engine_server::{Engine, EngineServer};
+
+// Bulk events waiting to be sent before the simulation waits for the client
+const STREAM_BUFFER: usize = 64;
+
+struct EngineService {
+    config: ServerConfig,
+}
+
+#[tonic::async_trait]
+impl Engine for EngineService {
+    async fn evaluate(&self, request: Request<proto::EvaluateRequest>) -> Result<Response<proto::EvaluateResponse>, Status> {
+        let request = request.into_inner();
+        let params = params(vec![("hand", given_text(request.hand)), ("board", given_text(request.board))]);
+        let answer = api::evaluate(&params).map_err(Status::invalid_argument)?;
+        Ok(Response::new(proto::EvaluateResponse {
+            hand: text(&answer, "hand"),
+            board: text(&answer, "board"),
+            rank: text(&answer, "rank"),
+            category: number(&answer, "category") as u32,
+            high_cards: text(&answer, "high_cards"),
+        }))
+    }
+
+    async fn equity(&self, request: Request<proto::EquityRequest>) -> Result<Response<proto::EquityResponse>, Status> {
+        let request = request.into_inner();
+        let params = params(vec![
+            ("hero", given_text(request.hero)),
+            ("board", given_text(request.board)),
+            ("villain", given_text(request.villain)),
+            ("opponents", given_number(request.opponents as u64)),
+            ("trials", given_number(request.trials)),
+        ]);
+        // Simulations are long enough to stall the runtime's worker threads
+        let config = self.config;
+        let answer = tokio::task::spawn_blocking(move || api::equity(&params, config.max_trials, &mut config.rng.build()))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(Status::invalid_argument)?;
+        Ok(Response::new(proto::EquityResponse {
+            hero: text(&answer, "hero"),
+            board: text(&answer, "board"),
+            villain: text(&answer, "villain"),
+            opponents: number(&answer, "opponents") as u32,
+            results: Some(results(&answer)),
+        }))
+    }
+
+    type BulkSimulateStream = ReceiverStream<Result<proto::BulkEvent, Status>>;
+
+    // An invalid request ends the stream with an INVALID_ARGUMENT status; a
+    // client that hangs up stops the simulation at the next event
+    async fn bulk_simulate(&self, request: Request<proto::BulkRequest>) -> Result<Response<Self::BulkSimulateStream>, Status> {
+        let request = request.into_inner();
+        let mut fields = vec![
+            ("trials", given_number(request.trials)),
+            ("opponents", given_number(request.opponents as u64)),
+            ("board", given_text(request.board)),
+            ("only", given_text(request.only)),
+        ];
+        if request.canonical {
+            fields.push(("canonical", Some(JsonValue::Bool(true))));
+        }
+        let params = params(fields);
+        let config = self.config;
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let send = |event: JsonValue| sender.blocking_send(Ok(bulk_event(&event))).is_ok();
+            if let Err(message) = api::bulk(&params, config.max_trials, &mut config.rng.build(), send) {
+                let _ = sender.blocking_send(Err(Status::invalid_argument(message)));
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+// Serves until the process is stopped
+pub fn serve(address: &str, config: ServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let address: std::net::SocketAddr = address.parse()?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    println!("gRPC listening on {} (max {} trials per request, {} rng)", address, config.max_trials, config.rng);
+    runtime.block_on(
+        tonic::transport::Server::builder()
+            .add_service(EngineServer::new(EngineService { config }))
+            .serve(address),
+    )?;
+    Ok(())
+}
+
+// Request fields that were given, as api parameters
+fn params(fields: Vec<(&str, Option<JsonValue>)>) -> JsonValue {
+    JsonValue::Object(fields.into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value?)))
+        .collect())
+}
+
+// proto3 sends unset fields as "" and 0, which leave api's defaults in place
+fn given_text(text: String) -> Option<JsonValue> {
+    if text.is_empty() { None } else { Some(JsonValue::String(text)) }
+}
+
+fn given_number(number: u64) -> Option<JsonValue> {
+    if number == 0 { None } else { Some(JsonValue::Number(number as f64)) }
+}
+
+fn text(answer: &JsonValue, key: &str) -> String {
+    answer.get(key).and_then(|value| value.as_str()).unwrap_or_default().to_string()
+}
+
+fn number(answer: &JsonValue, key: &str) -> f64 {
+    answer.get(key).and_then(|value| value.as_f64()).unwrap_or_default()
+}
+
+fn results(answer: &JsonValue) -> proto::Results {
+    proto::Results {
+        trials: number(answer, "trials") as u64,
+        win_rate: number(answer, "win_rate"),
+        tie_rate: number(answer, "tie_rate"),
+        lose_rate: number(answer, "lose_rate"),
+        equity: number(answer, "equity"),
+    }
+}
+
+fn bulk_event(event: &JsonValue) -> proto::BulkEvent {
+    use proto::bulk_event::Event;
+
+    let kind = match text(event, "event").as_str() {
+        "start" => Event::Start(proto::BulkStart {
+            trials: number(event, "trials") as u64,
+            opponents: number(event, "opponents") as u32,
+            board: text(event, "board"),
+        }),
+        "result" => Event::Hand(proto::HandResult {
+            hand: text(event, "hand"),
+            notation: text(event, "notation"),
+            description: text(event, "description"),
+            results: Some(results(event)),
+        }),
+        "progress" => Event::Progress(proto::Progress { elapsed: number(event, "elapsed"), remaining: number(event, "remaining") }),
+        _ => Event::Done(proto::Done { elapsed: number(event, "elapsed") }),
+    };
+    proto::BulkEvent {
+        completed: number(event, "completed") as u64,
+        total: number(event, "total") as u64,
+        event: Some(kind),
+    }
+}
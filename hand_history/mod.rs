@@ -0,0 +1,1149 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::card::{Card, Rank, Suit, evaluate_hand, parse_cards};
+use crate::game::{Action, ActionRecord, Game, Stakes, Street};
+use crate::pot::{OddChipRule, distribute_pots};
+
+// Played hands read from and written to PokerStars hand-history text files
+// and the open PHH format. Each hand keeps the seats, hole cards that were
+// dealt or shown, every action as the game engine records it (seats are
+// indices into `players`, bets are street totals) and what each player put
+// in and collected. Cash-game amounts are
+// stored in cents and tournament amounts in chips, so everything stays in
+// the engine's whole-number chips.
+
+#[derive(Debug, Clone)]
+pub struct HistoryPlayer {
+    pub name: String,
+    // Seat number at the table (1-based, may skip empty seats)
+    pub seat: u32,
+    // Stack at the start of the hand
+    pub stack: u32,
+    pub hole_cards: Option<[Card; 2]>,
+    // Antes, blinds, calls, bets and raises, less any uncalled bet returned
+    pub contributed: u32,
+    // Collected from the pots
+    pub won: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct HandHistory {
+    pub id: String,
+    pub tournament: Option<String>,
+    pub table: String,
+    pub date: String,
+    pub stakes: Stakes,
+    // Currency symbol for cash games (amounts in cents); None for tournament chips
+    pub currency: Option<char>,
+    pub players: Vec<HistoryPlayer>,
+    // Index into players
+    pub button: usize,
+    // The player whose hole cards were dealt face up to the file's owner
+    pub hero: Option<usize>,
+    pub actions: Vec<ActionRecord>,
+    pub board: Vec<Card>,
+    pub rake: u32,
+}
+
+impl HandHistory {
+    pub fn player_index(&self, name: &str) -> Option<usize> {
+        self.players.iter().position(|player| player.name == name)
+    }
+
+    // Chips a player won or lost over the hand
+    pub fn net(&self, player: usize) -> i64 {
+        self.players[player].won as i64 - self.players[player].contributed as i64
+    }
+
+    // Every chip put in, before rake
+    pub fn pot(&self) -> u32 {
+        self.players.iter().map(|player| player.contributed).sum()
+    }
+
+    // Players who reached the end without folding
+    pub fn final_players(&self) -> Vec<usize> {
+        (0..self.players.len())
+            .filter(|&player| !self.actions.iter().any(|record| record.seat == player && record.action == Action::Fold))
+            .collect()
+    }
+
+    // More than one player was left at the end, so the cards were turned over
+    pub fn went_to_showdown(&self) -> bool {
+        self.final_players().len() > 1
+    }
+
+    // "$1.50" for cash games, "1500" for tournament chips
+    pub fn format_amount(&self, amount: i64) -> String {
+        match self.currency {
+            Some(symbol) => {
+                let sign = if amount < 0 { "-" } else { "" };
+                format!("{}{}{}.{:02}", sign, symbol, amount.abs() / 100, amount.abs() % 100)
+            },
+            None => amount.to_string(),
+        }
+    }
+
+    // A finished engine hand: `game` after finish() paid out `won`, with the
+    // stacks each seat started with. Every seat's hole cards are kept;
+    // the writer only shows the hero's and those that reach a showdown.
+    pub fn from_game(game: &Game, starting_stacks: &[u32], won: &[u32], names: &[String], id: &str, table: &str) -> HandHistory {
+        let players = game.players.iter().enumerate()
+            .map(|(seat, player)| HistoryPlayer {
+                name: names[seat].clone(),
+                seat: seat as u32 + 1,
+                stack: starting_stacks[seat],
+                hole_cards: player.hole_cards.clone(),
+                contributed: starting_stacks[seat] + won[seat] - player.stack,
+                won: won[seat],
+            })
+            .collect();
+        HandHistory {
+            id: id.to_string(),
+            tournament: None,
+            table: table.to_string(),
+            date: chrono::Utc::now().format("%Y/%m/%d %H:%M:%S UTC").to_string(),
+            stakes: game.stakes,
+            currency: None,
+            players,
+            button: game.button,
+            hero: None,
+            actions: game.history.clone(),
+            board: game.board.clone(),
+            rake: 0,
+        }
+    }
+}
+
+// Every hand in a PokerStars hand-history file
+pub fn import_pokerstars(filename: &str) -> Result<Vec<HandHistory>, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+
+    let text = crate::compress::read_to_string(filename)?;
+    parse_pokerstars(&text).map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {}", filename, e)))
+}
+
+// Splits text into hands at each "PokerStars Hand #" header and parses them all
+pub fn parse_pokerstars(text: &str) -> Result<Vec<HandHistory>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut hands = Vec::new();
+    let mut current: Vec<(usize, &str)> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if is_header(line) && !current.is_empty() {
+            hands.push(parse_hand(&current)?);
+            current.clear();
+        }
+        if is_header(line) || !current.is_empty() {
+            current.push((number + 1, line.trim()));
+        }
+    }
+    if !current.is_empty() {
+        hands.push(parse_hand(&current)?);
+    }
+    if hands.is_empty() {
+        return Err("No PokerStars hands found".to_string());
+    }
+    Ok(hands)
+}
+
+fn is_header(line: &str) -> bool {
+    let line = line.trim_start_matches('\u{feff}').trim();
+    line.starts_with("PokerStars ") && (line.contains(" Hand #") || line.contains(" Game #"))
+}
+
+// "$1,250.50" into cents with a currency, "1500" into chips
+fn parse_amount(text: &str, cash: bool) -> Result<u32, String> {
+    let digits: String = text.trim().chars().filter(|c| c.is_ascii_digit() || *c == '.').collect();
+    let value: f64 = digits.parse().map_err(|_| format!("Invalid amount '{}'", text.trim()))?;
+    let units = if cash { (value * 100.0).round() } else { value.round() };
+    if !(0.0..=u32::MAX as f64).contains(&units) {
+        return Err(format!("Amount '{}' is out of range", text.trim()));
+    }
+    Ok(units as u32)
+}
+
+// The cards in the last [...] group of a line
+fn bracketed_cards(line: &str) -> Result<Vec<Card>, String> {
+    let start = line.rfind('[').ok_or_else(|| format!("Expected cards in brackets in '{}'", line))?;
+    let end = line[start..].find(']').ok_or_else(|| format!("Unclosed bracket in '{}'", line))? + start;
+    parse_cards(&line[start + 1..end])
+}
+
+fn hole_cards(line: &str) -> Result<[Card; 2], String> {
+    let cards = bracketed_cards(line)?;
+    cards.try_into().map_err(|cards: Vec<Card>| format!("Only Hold'em hands are supported, found {} hole cards", cards.len()))
+}
+
+// What parse_line needs to remember between the lines of one hand
+struct Tracker {
+    cash: bool,
+    street: Street,
+    // Each player's chips in on the current street
+    street_bets: Vec<u32>,
+    // Table seat number of the button, from the "Table" line
+    button_seat: Option<u32>,
+    // Past the "*** SUMMARY ***" marker
+    summary: bool,
+}
+
+fn parse_hand(lines: &[(usize, &str)]) -> Result<HandHistory, String> {
+    let (first_line, header) = lines[0];
+    let context = |number: usize, message: String| format!("line {}: {}", number, message);
+
+    let id = header.split('#').nth(1)
+        .and_then(|rest| rest.split(':').next())
+        .map(|id| id.trim().to_string())
+        .ok_or_else(|| context(first_line, "Missing hand number".to_string()))?;
+    if !header.contains("Hold'em") {
+        return Err(context(first_line, format!("Hand #{} is not Hold'em; only Hold'em hands are supported", id)));
+    }
+    let tournament = header.split("Tournament #").nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .map(|number| number.trim().to_string());
+    let date = header.rsplit(" - ").next().unwrap_or_default().trim().to_string();
+
+    // Blinds come from the first "(small/big ...)" group of the header
+    let blinds = header.split('(').skip(1)
+        .filter_map(|group| group.split(')').next())
+        .find(|group| group.contains('/'))
+        .ok_or_else(|| context(first_line, format!("Hand #{} has no blinds in its header", id)))?;
+    let currency = if tournament.is_none() { blinds.chars().find(|c| matches!(c, '$' | '€' | '£')) } else { None };
+    let cash = currency.is_some();
+    let (small, big) = blinds.split_once('/').expect("group was chosen for containing '/'");
+    let big = big.split_whitespace().next().unwrap_or_default();
+    let stakes = Stakes::new(
+        parse_amount(small, cash).map_err(|e| context(first_line, e))?,
+        parse_amount(big, cash).map_err(|e| context(first_line, e))?,
+    );
+
+    let mut hand = HandHistory {
+        id,
+        tournament,
+        table: String::new(),
+        date,
+        stakes,
+        currency,
+        players: Vec::new(),
+        button: 0,
+        hero: None,
+        actions: Vec::new(),
+        board: Vec::new(),
+        rake: 0,
+    };
+    let mut tracker = Tracker { cash, street: Street::Preflop, street_bets: Vec::new(), button_seat: None, summary: false };
+    for &(number, line) in &lines[1..] {
+        parse_line(&mut hand, &mut tracker, line).map_err(|e| context(number, format!("Hand #{}: {}", hand.id, e)))?;
+    }
+
+    if hand.players.len() < 2 {
+        return Err(context(first_line, format!("Hand #{} has fewer than two seated players", hand.id)));
+    }
+    // The button may be on an empty seat; the player before it acts as the button
+    hand.button = match tracker.button_seat {
+        Some(seat) => hand.players.iter().rposition(|player| player.seat <= seat).unwrap_or(hand.players.len() - 1),
+        None => 0,
+    };
+    Ok(hand)
+}
+
+fn parse_line(hand: &mut HandHistory, tracker: &mut Tracker, line: &str) -> Result<(), String> {
+    if line.is_empty() {
+        return Ok(());
+    }
+    if let Some(rest) = line.strip_prefix("Table '") {
+        let (table, rest) = rest.rsplit_once('\'').ok_or("Unclosed table name")?;
+        hand.table = table.to_string();
+        tracker.button_seat = rest.split("Seat #").nth(1)
+            .and_then(|seat| seat.split_whitespace().next())
+            .and_then(|seat| seat.parse().ok());
+        return Ok(());
+    }
+    if let Some(marker) = line.strip_prefix("*** ") {
+        return street_marker(hand, tracker, marker, line);
+    }
+    if tracker.summary {
+        return summary_line(hand, line);
+    }
+    if line.starts_with("Seat ") && line.contains(" in chips") && hand.actions.is_empty() {
+        return seat_line(hand, tracker, line);
+    }
+    if let Some(rest) = line.strip_prefix("Dealt to ") {
+        // Other players' "Dealt to" lines carry no cards
+        if rest.contains('[') {
+            let (name, _) = rest.rsplit_once(" [").ok_or("Expected hole cards")?;
+            let player = hand.player_index(name).ok_or_else(|| format!("'{}' is not seated", name))?;
+            hand.players[player].hole_cards = Some(hole_cards(line)?);
+            // Files written for every player deal all the cards face up; the first is the hero
+            hand.hero.get_or_insert(player);
+        }
+        return Ok(());
+    }
+    if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+        let (amount, name) = rest.split_once(") returned to ").ok_or("Malformed uncalled bet line")?;
+        let player = hand.player_index(name).ok_or_else(|| format!("'{}' is not seated", name))?;
+        let amount = parse_amount(amount, tracker.cash)?;
+        hand.players[player].contributed = hand.players[player].contributed.saturating_sub(amount);
+        tracker.street_bets[player] = tracker.street_bets[player].saturating_sub(amount);
+        return Ok(());
+    }
+    if let Some((name, rest)) = line.split_once(" collected ") {
+        if let Some(player) = hand.player_index(name) {
+            let amount = rest.split(" from ").next().unwrap_or_default();
+            hand.players[player].won += parse_amount(amount, tracker.cash)?;
+            return Ok(());
+        }
+    }
+    // Anything else worth reading is "<player>: <action>"; chat and table messages are skipped
+    match acting_player(hand, line) {
+        Some((player, action)) => player_action(hand, tracker, player, action),
+        None => Ok(()),
+    }
+}
+
+fn seat_line(hand: &mut HandHistory, tracker: &mut Tracker, line: &str) -> Result<(), String> {
+    let rest = line.strip_prefix("Seat ").expect("caller checked the prefix");
+    let (seat, rest) = rest.split_once(": ").ok_or("Malformed seat line")?;
+    let (name, chips) = rest.rsplit_once(" (").ok_or("Malformed seat line")?;
+    let chips = chips.split(" in chips").next().unwrap_or_default();
+    hand.players.push(HistoryPlayer {
+        name: name.to_string(),
+        seat: seat.trim().parse().map_err(|_| format!("Invalid seat number '{}'", seat))?,
+        stack: parse_amount(chips, tracker.cash)?,
+        hole_cards: None,
+        contributed: 0,
+        won: 0,
+    });
+    tracker.street_bets.push(0);
+    Ok(())
+}
+
+fn street_marker(hand: &mut HandHistory, tracker: &mut Tracker, marker: &str, line: &str) -> Result<(), String> {
+    let street = match marker.split(" ***").next().unwrap_or_default() {
+        "HOLE CARDS" => Street::Preflop,
+        "FLOP" => Street::Flop,
+        "TURN" => Street::Turn,
+        "RIVER" => Street::River,
+        "SHOW DOWN" => Street::Showdown,
+        "SUMMARY" => {
+            tracker.summary = true;
+            return Ok(());
+        },
+        other if other.starts_with("FIRST") || other.starts_with("SECOND") => {
+            return Err("Hands run more than once are not supported".to_string());
+        },
+        _ => return Ok(()),
+    };
+    if street != tracker.street {
+        tracker.street = street;
+        tracker.street_bets.iter_mut().for_each(|bet| *bet = 0);
+    }
+    if matches!(street, Street::Flop | Street::Turn | Street::River) {
+        let cards = bracketed_cards(line)?;
+        hand.board.extend(cards);
+    }
+    Ok(())
+}
+
+fn summary_line(hand: &mut HandHistory, line: &str) -> Result<(), String> {
+    let cash = hand.currency.is_some();
+    if let Some(rest) = line.strip_prefix("Total pot ") {
+        if let Some(rake) = rest.split("Rake ").nth(1) {
+            hand.rake = parse_amount(rake.split_whitespace().next().unwrap_or_default(), cash)?;
+        }
+    } else if line.starts_with("Board [") {
+        hand.board = bracketed_cards(line)?;
+    } else if let Some(rest) = line.strip_prefix("Seat ") {
+        // Cards shown or mucked at showdown: "Seat 2: Name (big blind) showed [Jc Td] and won ($17.75)"
+        let Some((_, rest)) = rest.split_once(": ") else {
+            return Ok(());
+        };
+        for verb in [" showed [", " mucked ["] {
+            if let Some((who, _)) = rest.split_once(verb) {
+                let name = who.split(" (").next().unwrap_or(who);
+                if let Some(player) = hand.player_index(name) {
+                    let shown = rest.split(verb).nth(1).and_then(|cards| cards.split(']').next()).unwrap_or_default();
+                    let cards: [Card; 2] = parse_cards(shown)?.try_into()
+                        .map_err(|_| "Only Hold'em hands are supported".to_string())?;
+                    hand.players[player].hole_cards = Some(cards);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// The seated player a "<name>: ..." line belongs to (the longest matching
+// name, in case one name is a prefix of another) and the rest of the line
+fn acting_player<'a>(hand: &HandHistory, line: &'a str) -> Option<(usize, &'a str)> {
+    hand.players.iter().enumerate()
+        .filter(|(_, player)| line.starts_with(&player.name) && line[player.name.len()..].starts_with(": "))
+        .max_by_key(|(_, player)| player.name.len())
+        .map(|(index, player)| (index, &line[player.name.len() + 2..]))
+}
+
+fn player_action(hand: &mut HandHistory, tracker: &mut Tracker, player: usize, text: &str) -> Result<(), String> {
+    let cash = tracker.cash;
+    let all_in = text.ends_with("and is all-in");
+    let text = text.trim_end_matches("and is all-in").trim();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let street_bet = tracker.street_bets[player];
+
+    let action = match words.as_slice() {
+        ["posts", "the", "ante", amount] => {
+            let amount = parse_amount(amount, cash)?;
+            hand.stakes.ante = hand.stakes.ante.max(amount);
+            hand.players[player].contributed += amount;
+            return Ok(());
+        },
+        ["posts", "small", "&", "big", "blinds", amount] => {
+            // The small blind part is dead money; only the big blind counts toward calling
+            let amount = parse_amount(amount, cash)?;
+            hand.players[player].contributed += amount;
+            tracker.street_bets[player] += amount.min(hand.stakes.big_blind);
+            return Ok(());
+        },
+        ["posts", .., amount] => {
+            let amount = parse_amount(amount, cash)?;
+            hand.players[player].contributed += amount;
+            tracker.street_bets[player] += amount;
+            return Ok(());
+        },
+        ["folds", ..] => Action::Fold,
+        ["checks"] => Action::Check,
+        ["calls", amount] => {
+            let amount = parse_amount(amount, cash)?;
+            hand.players[player].contributed += amount;
+            tracker.street_bets[player] += amount;
+            Action::Call
+        },
+        ["bets", amount] => {
+            let amount = parse_amount(amount, cash)?;
+            hand.players[player].contributed += amount;
+            tracker.street_bets[player] += amount;
+            Action::Bet(street_bet + amount)
+        },
+        ["raises", _, "to", total] => {
+            let total = parse_amount(total, cash)?;
+            let added = total.checked_sub(street_bet).ok_or("Raise to less than the player's current bet")?;
+            hand.players[player].contributed += added;
+            tracker.street_bets[player] = total;
+            Action::Bet(total)
+        },
+        ["shows", ..] => {
+            hand.players[player].hole_cards = Some(hole_cards(text)?);
+            return Ok(());
+        },
+        // Mucks, sit-outs, time bank and connection messages
+        _ => return Ok(()),
+    };
+    if tracker.street == Street::Showdown {
+        return Err("Betting action after the showdown".to_string());
+    }
+    hand.actions.push(ActionRecord { street: tracker.street, seat: player, action, all_in });
+    Ok(())
+}
+
+// "Ts": rank and suit letters as hand-history files write cards
+pub fn card_code(card: &Card) -> String {
+    let suit = match card.suit {
+        Suit::Spades => 's',
+        Suit::Hearts => 'h',
+        Suit::Diamonds => 'd',
+        Suit::Clubs => 'c',
+    };
+    match card.rank {
+        Rank::Ten => format!("T{}", suit),
+        rank => format!("{}{}", rank, suit),
+    }
+}
+
+fn card_codes(cards: &[Card]) -> String {
+    cards.iter().map(card_code).collect::<Vec<_>>().join(" ")
+}
+
+// Writes hands in the PokerStars text format, readable by import_pokerstars
+// and by hand replayers. Blinds are posted by position from the button.
+pub fn export_pokerstars(hands: &[HandHistory], filename: &str) -> Result<(), std::io::Error> {
+    let mut file = crate::compress::create(filename)?;
+    for hand in hands {
+        write_pokerstars(hand, &mut file)?;
+        writeln!(file, "\n\n")?;
+    }
+    file.finish()?;
+
+    tracing::info!("Hand history exported to: {}", filename);
+    Ok(())
+}
+
+pub fn write_pokerstars<W: Write>(hand: &HandHistory, out: &mut W) -> Result<(), std::io::Error> {
+    let count = hand.players.len();
+    let amount = |chips: u32| hand.format_amount(chips as i64);
+    let name = |player: usize| &hand.players[player].name;
+
+    let blinds = format!("{}/{}", amount(hand.stakes.small_blind), amount(hand.stakes.big_blind));
+    let blinds = match hand.currency {
+        Some('$') => format!("{} USD", blinds),
+        Some('€') => format!("{} EUR", blinds),
+        Some('£') => format!("{} GBP", blinds),
+        _ => blinds,
+    };
+    match &hand.tournament {
+        Some(tournament) => writeln!(out, "PokerStars Hand #{}: Tournament #{}, Hold'em No Limit ({}) - {}", hand.id, tournament, blinds, hand.date)?,
+        None => writeln!(out, "PokerStars Hand #{}:  Hold'em No Limit ({}) - {}", hand.id, blinds, hand.date)?,
+    }
+    writeln!(out, "Table '{}' {}-max Seat #{} is the button", hand.table, count, hand.players[hand.button].seat)?;
+    for player in &hand.players {
+        writeln!(out, "Seat {}: {} ({} in chips)", player.seat, player.name, amount(player.stack))?;
+    }
+
+    // Chips behind and on the current street, replayed action by action
+    let mut behind: Vec<u32> = hand.players.iter().map(|player| player.stack).collect();
+    let mut bets = vec![0; count];
+    if hand.stakes.ante > 0 {
+        for (player, stack) in behind.iter_mut().enumerate() {
+            let ante = hand.stakes.ante.min(*stack);
+            *stack -= ante;
+            writeln!(out, "{}: posts the ante {}", name(player), amount(ante))?;
+        }
+    }
+    let small_blind = if count == 2 { hand.button } else { (hand.button + 1) % count };
+    let big_blind = (small_blind + 1) % count;
+    for (player, blind, label) in [(small_blind, hand.stakes.small_blind, "small"), (big_blind, hand.stakes.big_blind, "big")] {
+        let posted = blind.min(behind[player]);
+        behind[player] -= posted;
+        bets[player] = posted;
+        let all_in = if behind[player] == 0 { " and is all-in" } else { "" };
+        writeln!(out, "{}: posts {} blind {}{}", name(player), label, amount(posted), all_in)?;
+    }
+
+    writeln!(out, "*** HOLE CARDS ***")?;
+    // Only the hero's cards, or everyone's for hands no one player was dealt
+    for (player, details) in hand.players.iter().enumerate() {
+        if let Some(cards) = &details.hole_cards {
+            if hand.hero.is_none_or(|hero| hero == player) {
+                writeln!(out, "Dealt to {} [{}]", details.name, card_codes(cards))?;
+            }
+        }
+    }
+
+    for (street, cards) in [(Street::Preflop, 0), (Street::Flop, 3), (Street::Turn, 4), (Street::River, 5)] {
+        if street != Street::Preflop {
+            if hand.board.len() < cards {
+                break;
+            }
+            write_uncalled(hand, &mut bets, out)?;
+            bets.iter_mut().for_each(|bet| *bet = 0);
+            let (shown, dealt) = hand.board[..cards].split_at(if cards == 3 { 0 } else { cards - 1 });
+            let marker = street.to_string().to_uppercase();
+            match shown.is_empty() {
+                true => writeln!(out, "*** {} *** [{}]", marker, card_codes(dealt))?,
+                false => writeln!(out, "*** {} *** [{}] [{}]", marker, card_codes(shown), card_codes(dealt))?,
+            }
+        }
+        for record in hand.actions.iter().filter(|record| record.street == street) {
+            let player = record.seat;
+            let current = bets.iter().copied().max().unwrap_or(0);
+            let text = match record.action {
+                Action::Fold => "folds".to_string(),
+                Action::Check => "checks".to_string(),
+                Action::Call => {
+                    let call = (current - bets[player]).min(behind[player]);
+                    behind[player] -= call;
+                    bets[player] += call;
+                    format!("calls {}", amount(call))
+                },
+                Action::Bet(_) | Action::AllIn => {
+                    let total = match record.action {
+                        Action::Bet(total) => total,
+                        _ => bets[player] + behind[player],
+                    };
+                    let added = total.saturating_sub(bets[player]).min(behind[player]);
+                    behind[player] -= added;
+                    bets[player] += added;
+                    match current {
+                        0 => format!("bets {}", amount(added)),
+                        _ => format!("raises {} to {}", amount(bets[player].saturating_sub(current)), amount(bets[player])),
+                    }
+                },
+            };
+            let all_in = if record.all_in { " and is all-in" } else { "" };
+            writeln!(out, "{}: {}{}", name(player), text, all_in)?;
+        }
+    }
+    write_uncalled(hand, &mut bets, out)?;
+
+    let showdown = hand.went_to_showdown();
+    if showdown {
+        writeln!(out, "*** SHOW DOWN ***")?;
+        for player in hand.final_players() {
+            if let Some(cards) = &hand.players[player].hole_cards {
+                let rank = if hand.board.len() == 5 { format!(" ({})", evaluate_hand(cards, &hand.board).rank) } else { String::new() };
+                writeln!(out, "{}: shows [{}]{}", name(player), card_codes(cards), rank)?;
+            }
+        }
+    }
+    for details in &hand.players {
+        if details.won > 0 {
+            writeln!(out, "{} collected {} from pot", details.name, amount(details.won))?;
+        }
+    }
+
+    writeln!(out, "*** SUMMARY ***")?;
+    writeln!(out, "Total pot {} | Rake {}", amount(hand.pot()), amount(hand.rake))?;
+    if !hand.board.is_empty() {
+        writeln!(out, "Board [{}]", card_codes(&hand.board))?;
+    }
+    for (player, details) in hand.players.iter().enumerate() {
+        let position = if player == hand.button {
+            " (button)"
+        } else if player == small_blind {
+            " (small blind)"
+        } else if player == big_blind {
+            " (big blind)"
+        } else {
+            ""
+        };
+        let folded = hand.actions.iter().find(|record| record.seat == player && record.action == Action::Fold);
+        let outcome = match (folded, &details.hole_cards) {
+            (Some(record), _) if record.street == Street::Preflop => "folded before Flop".to_string(),
+            (Some(record), _) => format!("folded on the {}", record.street),
+            (None, Some(cards)) if showdown && details.won > 0 => format!("showed [{}] and won ({})", card_codes(cards), amount(details.won)),
+            (None, Some(cards)) if showdown => format!("showed [{}] and lost", card_codes(cards)),
+            (None, None) if showdown => "mucked".to_string(),
+            _ => format!("collected ({})", amount(details.won)),
+        };
+        writeln!(out, "Seat {}: {}{} {}", details.seat, details.name, position, outcome)?;
+    }
+    Ok(())
+}
+
+// Hands the part of the street's largest bet nobody matched back to the bettor
+fn write_uncalled<W: Write>(hand: &HandHistory, bets: &mut [u32], out: &mut W) -> Result<(), std::io::Error> {
+    let Some(top) = (0..bets.len()).max_by_key(|&player| bets[player]) else {
+        return Ok(());
+    };
+    let second = (0..bets.len()).filter(|&player| player != top).map(|player| bets[player]).max().unwrap_or(0);
+    if bets[top] > second {
+        writeln!(out, "Uncalled bet ({}) returned to {}", hand.format_amount((bets[top] - second) as i64), hand.players[top].name)?;
+        bets[top] = second;
+    }
+    Ok(())
+}
+
+// PHH, the open Poker Hand History format (https://phh.readthedocs.io): a
+// TOML file per hand (.phh), or several hands under [section] headers
+// (.phhs). Only no-limit Hold'em ("NT") is read. Players are listed from the
+// small blind round to the button. Heads-up the button posts the small
+// blind, so, as in PokerKit, the two blinds_or_straddles entries apply in
+// reverse order.
+
+#[derive(Debug, Clone, PartialEq)]
+enum PhhValue {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    List(Vec<PhhValue>),
+}
+
+// One hand's fields by key
+type PhhTable = HashMap<String, PhhValue>;
+
+// The text of a line before any comment outside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {},
+        }
+    }
+    line
+}
+
+// Parses one value from the start of `text`, returning it and the rest
+fn parse_phh_value(text: &str) -> Result<(PhhValue, &str), String> {
+    let text = text.trim_start();
+    if let Some(rest) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((PhhValue::Text(value), &rest[i + 1..])),
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        return Err("Unterminated string".to_string());
+    }
+    if let Some(mut rest) = text.strip_prefix('[') {
+        let mut items = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((PhhValue::List(items), after));
+            }
+            let (item, after) = parse_phh_value(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            rest = match rest.strip_prefix(',') {
+                Some(after) => after,
+                None if rest.starts_with(']') => rest,
+                None => return Err("Expected ',' or ']' in a list".to_string()),
+            };
+        }
+    }
+    let end = text.find(|c: char| c == ',' || c == ']' || c.is_whitespace()).unwrap_or(text.len());
+    let (word, rest) = text.split_at(end);
+    let value = match word {
+        "true" => PhhValue::Bool(true),
+        "false" => PhhValue::Bool(false),
+        _ => PhhValue::Number(word.replace('_', "").parse().map_err(|_| format!("Invalid value '{}'", word))?),
+    };
+    Ok((value, rest))
+}
+
+// Every table of key = value pairs in a .phh or .phhs file, with its first line number
+fn parse_phh_tables(text: &str) -> Result<Vec<(usize, PhhTable)>, String> {
+    let mut tables: Vec<(usize, PhhTable)> = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let number = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') && !line.contains('=') {
+            tables.push((number, PhhTable::new()));
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| format!("line {}: Expected key = value", number))?;
+        // A list may carry on over the following lines
+        let mut value = value.trim().to_string();
+        while value.starts_with('[') && parse_phh_value(&value).is_err() {
+            match lines.next() {
+                Some((_, next)) => {
+                    value.push(' ');
+                    value.push_str(strip_comment(next).trim());
+                },
+                None => break,
+            }
+        }
+        let (parsed, rest) = parse_phh_value(&value).map_err(|e| format!("line {}: {}", number, e))?;
+        if !rest.trim().is_empty() {
+            return Err(format!("line {}: Unexpected '{}' after the value", number, rest.trim()));
+        }
+        if tables.is_empty() {
+            tables.push((number, PhhTable::new()));
+        }
+        let key = key.trim().trim_matches('"').to_string();
+        tables.last_mut().expect("a table was just added").1.insert(key, parsed);
+    }
+    Ok(tables)
+}
+
+// Every hand in a .phh or .phhs file
+pub fn import_phh(filename: &str) -> Result<Vec<HandHistory>, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+
+    let text = crate::compress::read_to_string(filename)?;
+    parse_phh(&text).map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {}", filename, e)))
+}
+
+pub fn parse_phh(text: &str) -> Result<Vec<HandHistory>, String> {
+    let tables = parse_phh_tables(text)?;
+    if tables.is_empty() {
+        return Err("No PHH hands found".to_string());
+    }
+    tables.iter().enumerate()
+        .map(|(index, (number, fields))| phh_hand(fields, index).map_err(|e| format!("line {}: {}", number, e)))
+        .collect()
+}
+
+fn phh_numbers(fields: &PhhTable, key: &str) -> Result<Vec<f64>, String> {
+    match fields.get(key) {
+        Some(PhhValue::Number(value)) => Ok(vec![*value]),
+        Some(PhhValue::List(items)) => items.iter()
+            .map(|item| match item {
+                PhhValue::Number(value) => Ok(*value),
+                _ => Err(format!("'{}' should hold numbers", key)),
+            })
+            .collect(),
+        Some(_) => Err(format!("'{}' should hold numbers", key)),
+        None => Err(format!("Missing '{}'", key)),
+    }
+}
+
+fn phh_text(fields: &PhhTable, key: &str) -> Option<String> {
+    match fields.get(key)? {
+        PhhValue::Text(text) => Some(text.clone()),
+        PhhValue::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn phh_hand(fields: &PhhTable, index: usize) -> Result<HandHistory, String> {
+    match phh_text(fields, "variant").as_deref() {
+        Some("NT") => {},
+        Some(variant) => return Err(format!("Variant '{}' is not supported, only no-limit Hold'em (NT)", variant)),
+        None => return Err("Missing 'variant'".to_string()),
+    }
+    let currency = phh_text(fields, "currency").map(|code| match code.as_str() {
+        "EUR" => '€',
+        "GBP" => '£',
+        _ => '$',
+    });
+    let scale = if currency.is_some() { 100.0 } else { 1.0 };
+    let units = |value: f64| -> Result<u32, String> {
+        let units = (value * scale).round();
+        if !(0.0..=u32::MAX as f64).contains(&units) {
+            return Err(format!("Amount {} is out of range", value));
+        }
+        Ok(units as u32)
+    };
+
+    let stacks: Vec<u32> = phh_numbers(fields, "starting_stacks")?.into_iter().map(units).collect::<Result<_, _>>()?;
+    let count = stacks.len();
+    if count < 2 {
+        return Err("A hand needs at least two players".to_string());
+    }
+    let per_player = |key: &str| -> Result<Vec<u32>, String> {
+        let values = phh_numbers(fields, key)?;
+        match values.len() {
+            1 => Ok(vec![units(values[0])?; count]),
+            n if n == count => values.into_iter().map(units).collect(),
+            n => Err(format!("'{}' has {} entries for {} players", key, n, count)),
+        }
+    };
+    let antes = if fields.contains_key("antes") { per_player("antes")? } else { vec![0; count] };
+    let mut blinds = per_player("blinds_or_straddles")?;
+    if blinds.iter().skip(2).any(|&blind| blind > 0) {
+        return Err("Straddles are not supported".to_string());
+    }
+    let mut stakes = Stakes::new(blinds[0], blinds[1]);
+    stakes.ante = antes.iter().copied().max().unwrap_or(0);
+    if count == 2 {
+        blinds.reverse();
+    }
+
+    let names: Vec<String> = match fields.get("players") {
+        Some(PhhValue::List(items)) => items.iter().map(|item| match item {
+            PhhValue::Text(name) => Ok(name.clone()),
+            _ => Err("'players' should hold names".to_string()),
+        }).collect::<Result<_, _>>()?,
+        _ => (1..=count).map(|player| format!("p{}", player)).collect(),
+    };
+    let seats: Vec<u32> = match phh_numbers(fields, "seats") {
+        Ok(seats) => seats.into_iter().map(|seat| seat as u32).collect(),
+        Err(_) => (1..=count as u32).collect(),
+    };
+    if names.len() != count || seats.len() != count {
+        return Err(format!("'players' and 'seats' need one entry for each of the {} players", count));
+    }
+
+    let date = match (phh_numbers(fields, "year"), phh_numbers(fields, "month"), phh_numbers(fields, "day")) {
+        (Ok(year), Ok(month), Ok(day)) => {
+            let time = phh_text(fields, "time").map(|time| format!(" {}", time)).unwrap_or_default();
+            format!("{}/{:02}/{:02}{}", year[0], month[0], day[0], time)
+        },
+        _ => String::new(),
+    };
+    let mut hand = HandHistory {
+        id: phh_text(fields, "hand").unwrap_or_else(|| (index + 1).to_string()),
+        tournament: phh_text(fields, "event").and_then(|event| event.strip_prefix("Tournament #").map(str::to_string)),
+        table: phh_text(fields, "table").unwrap_or_default(),
+        date,
+        stakes,
+        currency,
+        players: (0..count)
+            .map(|player| HistoryPlayer { name: names[player].clone(), seat: seats[player], stack: stacks[player], hole_cards: None, contributed: 0, won: 0 })
+            .collect(),
+        button: count - 1,
+        hero: None,
+        actions: Vec::new(),
+        board: Vec::new(),
+        rake: 0,
+    };
+
+    // Chips behind and on the current street
+    let mut behind = stacks.clone();
+    let mut bets = vec![0; count];
+    for player in 0..count {
+        let ante = antes[player].min(behind[player]);
+        let blind = blinds[player].min(behind[player] - ante);
+        behind[player] -= ante + blind;
+        bets[player] = blind;
+        hand.players[player].contributed = ante + blind;
+    }
+
+    let actions = match fields.get("actions") {
+        Some(PhhValue::List(actions)) => actions,
+        _ => return Err("Missing 'actions'".to_string()),
+    };
+    let mut street = Street::Preflop;
+    for action in actions {
+        let PhhValue::Text(action) = action else {
+            return Err("'actions' should hold strings".to_string());
+        };
+        let words: Vec<&str> = strip_comment(action).split_whitespace().collect();
+        let player_of = |word: &str| word.strip_prefix('p')
+            .and_then(|number| number.parse::<usize>().ok())
+            .filter(|&number| number >= 1 && number <= count)
+            .map(|number| number - 1)
+            .ok_or_else(|| format!("Unknown player '{}' in action '{}'", word, action));
+        let record = match words.as_slice() {
+            ["d", "dh", player, cards] => {
+                if !cards.contains('?') {
+                    let cards: [Card; 2] = parse_cards(cards)?.try_into()
+                        .map_err(|_| "Only Hold'em hands are supported".to_string())?;
+                    hand.players[player_of(player)?].hole_cards = Some(cards);
+                }
+                None
+            },
+            ["d", "db", cards] => {
+                return_uncalled(&mut hand, &mut bets, &mut behind);
+                bets.iter_mut().for_each(|bet| *bet = 0);
+                hand.board.extend(parse_cards(cards)?);
+                street = match hand.board.len() {
+                    3 => Street::Flop,
+                    4 => Street::Turn,
+                    5 => Street::River,
+                    n => return Err(format!("A {}-card board is not a Hold'em street", n)),
+                };
+                None
+            },
+            [player, "f"] => Some((player_of(player)?, Action::Fold)),
+            [player, "cc"] => {
+                let player = player_of(player)?;
+                let call = (bets.iter().copied().max().unwrap_or(0) - bets[player]).min(behind[player]);
+                behind[player] -= call;
+                bets[player] += call;
+                hand.players[player].contributed += call;
+                Some((player, if call > 0 { Action::Call } else { Action::Check }))
+            },
+            [player, "cbr", amount] => {
+                let player = player_of(player)?;
+                let total = units(amount.parse().map_err(|_| format!("Invalid amount in action '{}'", action))?)?;
+                let added = total.checked_sub(bets[player]).filter(|&added| added <= behind[player])
+                    .ok_or_else(|| format!("Action '{}' doesn't fit the player's stack", action))?;
+                behind[player] -= added;
+                bets[player] = total;
+                hand.players[player].contributed += added;
+                Some((player, Action::Bet(total)))
+            },
+            [player, "sm", rest @ ..] => {
+                let player = player_of(player)?;
+                if let [cards] = rest {
+                    if *cards != "-" && !cards.contains('?') {
+                        let cards: [Card; 2] = parse_cards(cards)?.try_into()
+                            .map_err(|_| "Only Hold'em hands are supported".to_string())?;
+                        hand.players[player].hole_cards = Some(cards);
+                    }
+                }
+                None
+            },
+            _ => return Err(format!("Unsupported action '{}'", action)),
+        };
+        if let Some((player, action)) = record {
+            let all_in = action != Action::Fold && behind[player] == 0;
+            hand.actions.push(ActionRecord { street, seat: player, action, all_in });
+        }
+    }
+    return_uncalled(&mut hand, &mut bets, &mut behind);
+
+    match phh_numbers(fields, "finishing_stacks") {
+        Ok(finishing) if finishing.len() == count => {
+            for player in 0..count {
+                let finish = units(finishing[player])?;
+                hand.players[player].won = finish.checked_sub(behind[player])
+                    .ok_or_else(|| format!("{} finishes with less than they kept behind", hand.players[player].name))?;
+            }
+            let paid: u32 = hand.players.iter().map(|player| player.won).sum();
+            hand.rake = hand.pot().saturating_sub(paid);
+        },
+        _ => pay_pots(&mut hand)?,
+    }
+    Ok(hand)
+}
+
+// The part of the street's largest bet nobody matched goes back to the bettor
+fn return_uncalled(hand: &mut HandHistory, bets: &mut [u32], behind: &mut [u32]) {
+    let Some(top) = (0..bets.len()).max_by_key(|&player| bets[player]) else {
+        return;
+    };
+    let second = (0..bets.len()).filter(|&player| player != top).map(|player| bets[player]).max().unwrap_or(0);
+    let uncalled = bets[top] - second.min(bets[top]);
+    bets[top] -= uncalled;
+    behind[top] += uncalled;
+    hand.players[top].contributed -= uncalled;
+}
+
+// Awards the pots from the cards when the file doesn't give finishing stacks
+fn pay_pots(hand: &mut HandHistory) -> Result<(), String> {
+    let contributions: Vec<u32> = hand.players.iter().map(|player| player.contributed).collect();
+    let finalists = hand.final_players();
+    let odd_chip = OddChipRule::LeftOfButton(hand.button);
+    let payouts = if finalists.len() == 1 {
+        let standing: Vec<Option<()>> = (0..hand.players.len()).map(|player| finalists.contains(&player).then_some(())).collect();
+        distribute_pots(&contributions, &standing, &odd_chip).payouts
+    } else {
+        if hand.board.len() != 5 {
+            return Err("The board is incomplete and there are no finishing_stacks".to_string());
+        }
+        let mut evaluations = Vec::with_capacity(hand.players.len());
+        for (player, details) in hand.players.iter().enumerate() {
+            evaluations.push(match (&details.hole_cards, finalists.contains(&player)) {
+                (_, false) => None,
+                (Some(cards), true) => Some(evaluate_hand(cards, &hand.board)),
+                (None, true) => return Err(format!("{} reached the showdown without known cards and there are no finishing_stacks", details.name)),
+            });
+        }
+        distribute_pots(&contributions, &evaluations, &odd_chip).payouts
+    };
+    for (player, won) in hand.players.iter_mut().zip(payouts) {
+        player.won = won;
+    }
+    Ok(())
+}
+
+// Writes hands as PHH: a single hand as a plain .phh table, more under
+// numbered [sections] as in .phhs
+pub fn export_phh(hands: &[HandHistory], filename: &str) -> Result<(), std::io::Error> {
+    let mut file = crate::compress::create(filename)?;
+    for (index, hand) in hands.iter().enumerate() {
+        if hands.len() > 1 {
+            writeln!(file, "[{}]", index + 1)?;
+        }
+        write_phh(hand, &mut file)?;
+        writeln!(file)?;
+    }
+    file.finish()?;
+
+    tracing::info!("Hand history exported to: {}", filename);
+    Ok(())
+}
+
+pub fn write_phh<W: Write>(hand: &HandHistory, out: &mut W) -> Result<(), std::io::Error> {
+    let count = hand.players.len();
+    // PHH order starts left of the button and ends on it
+    let order: Vec<usize> = (1..=count).map(|offset| (hand.button + offset) % count).collect();
+    let mut position = vec![0; count];
+    for (index, &player) in order.iter().enumerate() {
+        position[player] = index + 1;
+    }
+    let amount = |chips: u32| match hand.currency {
+        Some(_) => format!("{}.{:02}", chips / 100, chips % 100),
+        None => chips.to_string(),
+    };
+    let list = |values: Vec<String>| format!("[{}]", values.join(", "));
+    let quoted = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+    writeln!(out, "variant = \"NT\"")?;
+    writeln!(out, "ante_trimming_status = true")?;
+    writeln!(out, "antes = {}", list(vec![amount(hand.stakes.ante); count]))?;
+    let mut blinds = vec![amount(0); count];
+    blinds[0] = amount(hand.stakes.small_blind);
+    blinds[1] = amount(hand.stakes.big_blind);
+    writeln!(out, "blinds_or_straddles = {}", list(blinds))?;
+    writeln!(out, "min_bet = {}", amount(hand.stakes.big_blind))?;
+    writeln!(out, "starting_stacks = {}", list(order.iter().map(|&player| amount(hand.players[player].stack)).collect()))?;
+
+    writeln!(out, "actions = [")?;
+    let mut actions = Vec::new();
+    for &player in &order {
+        let cards = hand.players[player].hole_cards.as_ref().map_or("????".to_string(), |cards| cards.iter().map(card_code).collect());
+        actions.push(format!("d dh p{} {}", position[player], cards));
+    }
+    for (street, cards) in [(Street::Preflop, 0), (Street::Flop, 3), (Street::Turn, 4), (Street::River, 5)] {
+        if street != Street::Preflop {
+            if hand.board.len() < cards {
+                break;
+            }
+            let dealt = if cards == 3 { &hand.board[..3] } else { &hand.board[cards - 1..cards] };
+            actions.push(format!("d db {}", dealt.iter().map(card_code).collect::<String>()));
+        }
+        for record in hand.actions.iter().filter(|record| record.street == street) {
+            let action = match record.action {
+                Action::Fold => "f".to_string(),
+                Action::Check | Action::Call => "cc".to_string(),
+                Action::Bet(total) => format!("cbr {}", amount(total)),
+                Action::AllIn => unreachable!("records hold all-ins as the call or bet they amounted to"),
+            };
+            actions.push(format!("p{} {}", position[record.seat], action));
+        }
+    }
+    if hand.went_to_showdown() {
+        let finalists = hand.final_players();
+        for &player in order.iter().filter(|player| finalists.contains(player)) {
+            if let Some(cards) = &hand.players[player].hole_cards {
+                actions.push(format!("p{} sm {}", position[player], cards.iter().map(card_code).collect::<String>()));
+            }
+        }
+    }
+    for action in actions {
+        writeln!(out, "  {},", quoted(&action))?;
+    }
+    writeln!(out, "]")?;
+
+    writeln!(out, "players = {}", list(order.iter().map(|&player| quoted(&hand.players[player].name)).collect()))?;
+    writeln!(out, "seats = {}", list(order.iter().map(|&player| hand.players[player].seat.to_string()).collect()))?;
+    let finishing = order.iter()
+        .map(|&player| {
+            let details = &hand.players[player];
+            amount(details.stack - details.contributed + details.won)
+        })
+        .collect();
+    writeln!(out, "finishing_stacks = {}", list(finishing))?;
+    match hand.id.parse::<u64>() {
+        Ok(id) => writeln!(out, "hand = {}", id)?,
+        Err(_) => writeln!(out, "hand = {}", quoted(&hand.id))?,
+    }
+    if !hand.table.is_empty() {
+        writeln!(out, "table = {}", quoted(&hand.table))?;
+    }
+    if let Some(tournament) = &hand.tournament {
+        writeln!(out, "event = {}", quoted(&format!("Tournament #{}", tournament)))?;
+    }
+    if let Some(symbol) = hand.currency {
+        let code = match symbol {
+            '€' => "EUR",
+            '£' => "GBP",
+            _ => "USD",
+        };
+        writeln!(out, "currency = \"{}\"", code)?;
+    }
+    // "2024/01/01 12:00:00 ET" into the date fields
+    let mut date = hand.date.split_whitespace();
+    let day: Vec<u32> = date.next().unwrap_or_default().split('/').filter_map(|part| part.parse().ok()).collect();
+    if let [year, month, day] = day[..] {
+        writeln!(out, "year = {}\nmonth = {}\nday = {}", year, month, day)?;
+        if let Some(time) = date.next() {
+            writeln!(out, "time = {}", quoted(time))?;
+        }
+    }
+    Ok(())
+}
+
+// Reads .phh and .phhs files as PHH and anything else as PokerStars text,
+// either of them possibly gzip- or zstd-compressed (hands.phhs.zst)
+pub fn import_hands(filename: &str) -> Result<Vec<HandHistory>, std::io::Error> {
+    if is_phh(filename) { import_phh(filename) } else { import_pokerstars(filename) }
+}
+
+// Writes PHH to .phh and .phhs files and PokerStars text to anything else
+pub fn export_hands(hands: &[HandHistory], filename: &str) -> Result<(), std::io::Error> {
+    if is_phh(filename) { export_phh(hands, filename) } else { export_pokerstars(hands, filename) }
+}
+
+fn is_phh(filename: &str) -> bool {
+    let lower = crate::compress::base_name(filename).to_ascii_lowercase();
+    lower.ends_with(".phh") || lower.ends_with(".phhs")
+}
@@ -0,0 +1,56 @@
+// Independent Chip Model: turns tournament chip stacks into shares of the
+// prize pool. Malmuth-Harville: a player finishes first with probability
+// proportional to their stack, and each later place is decided the same way
+// among whoever is left.
+
+// Every subset of players is visited, so the table size is capped
+pub const MAX_PLAYERS: usize = 20;
+
+// Each player's expected prize given `stacks` and `payouts` (first place
+// first). Places beyond the payouts pay nothing, and a player with no chips
+// has already busted and gets nothing.
+pub fn icm_equities(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, String> {
+    if stacks.is_empty() {
+        return Err("ICM needs at least one stack".to_string());
+    }
+    if let Some(stack) = stacks.iter().find(|s| !(**s >= 0.0 && s.is_finite())) {
+        return Err(format!("Invalid stack {}", stack));
+    }
+    if let Some(payout) = payouts.iter().find(|p| !(**p >= 0.0 && p.is_finite())) {
+        return Err(format!("Invalid payout {}", payout));
+    }
+
+    // Busted players drop out; the rest are numbered 0..live.len()
+    let live: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i] > 0.0).collect();
+    if live.is_empty() {
+        return Err("Every stack is empty".to_string());
+    }
+    if live.len() > MAX_PLAYERS {
+        return Err(format!("ICM is limited to {} players with chips, found {}", MAX_PLAYERS, live.len()));
+    }
+    let live_stacks: Vec<f64> = live.iter().map(|&i| stacks[i]).collect();
+    let total: f64 = live_stacks.iter().sum();
+    let places = payouts.len().min(live.len());
+
+    // finished[mask]: chance that exactly the players in `mask` took the top
+    // places, in any order
+    let mut finished = vec![0.0; 1 << live.len()];
+    finished[0] = 1.0;
+    let mut equities = vec![0.0; stacks.len()];
+    for mask in 0..finished.len() {
+        let place = (mask as u32).count_ones() as usize;
+        if place >= places || finished[mask] == 0.0 {
+            continue;
+        }
+        let remaining = total - (0..live.len()).filter(|&i| mask & (1 << i) != 0).map(|i| live_stacks[i]).sum::<f64>();
+        for (i, &stack) in live_stacks.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                continue;
+            }
+            let chance = finished[mask] * stack / remaining;
+            equities[live[i]] += chance * payouts[place];
+            finished[mask | (1 << i)] += chance;
+        }
+    }
+    Ok(equities)
+}
@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+
+use crate::card::{Card, Deck, Evaluator, HandEvaluation, HandRank, Rank, SimulationResults, evaluate_hand};
+
+// Deuce-to-seven lowball: aces are always high, straights and flushes count
+// against you, and the worst high hand wins. 7-5-4-3-2 offsuit is the nuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeuceToSevenLow {
+    pub hand: HandEvaluation,
+}
+
+impl Ord for DeuceToSevenLow {
+    // Reversed: the weaker high hand is the stronger low
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.hand.cmp(&self.hand)
+    }
+}
+
+impl PartialOrd for DeuceToSevenLow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub struct DeuceToSevenEvaluator;
+
+impl Evaluator for DeuceToSevenEvaluator {
+    type Strength = DeuceToSevenLow;
+
+    fn evaluate(&self, hole_cards: &[Card], community_cards: &[Card]) -> DeuceToSevenLow {
+        let cards: Vec<Card> = hole_cards.iter().chain(community_cards.iter()).cloned().collect();
+        evaluate_deuce_to_seven(&cards)
+    }
+}
+
+pub fn evaluate_deuce_to_seven(cards: &[Card]) -> DeuceToSevenLow {
+    assert_eq!(cards.len(), 5, "deuce-to-seven hands have five cards");
+    let mut hand = evaluate_hand(&[cards[0].clone(), cards[1].clone()], &cards[2..]);
+
+    // With aces high, A-2-3-4-5 is not a straight, just ace high (or an ace-high flush)
+    if matches!(hand.rank, HandRank::Straight | HandRank::StraightFlush) && hand.high_cards[0] == Rank::Five {
+        hand = HandEvaluation {
+            rank: if hand.rank == HandRank::StraightFlush { HandRank::Flush } else { HandRank::HighCard },
+            high_cards: vec![Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two],
+        };
+    }
+
+    DeuceToSevenLow { hand }
+}
+
+// Ace-to-five low (razz, and the low half of hi/lo games): aces are low and
+// straights and flushes don't count. `key` holds the category (0 unpaired,
+// then pair, two pair, trips, full house and quads) followed by the ranks
+// grouped by multiplicity, highest first, so a lexicographically smaller key
+// is the better low. 5-4-3-2-A is the nuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AceToFiveLow {
+    pub key: Vec<u8>,
+}
+
+impl AceToFiveLow {
+    // Highest card of the low (8 for an eight-low); aces count as 1
+    pub fn high_card(&self) -> u8 {
+        self.key[1]
+    }
+
+    pub fn is_unpaired(&self) -> bool {
+        self.key[0] == 0
+    }
+}
+
+impl Ord for AceToFiveLow {
+    // Reversed so the better low compares greater
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+impl PartialOrd for AceToFiveLow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn ace_low_value(rank: Rank) -> u8 {
+    if rank == Rank::Ace { 1 } else { rank.value() }
+}
+
+// Best ace-to-five low from five or more cards; pairs only play when there
+// aren't five distinct ranks
+pub fn evaluate_ace_to_five(cards: &[Card]) -> AceToFiveLow {
+    assert!(cards.len() >= 5, "ace-to-five lows need at least five cards");
+
+    let mut counts = [0u8; 14];
+    for card in cards {
+        counts[ace_low_value(card.rank) as usize] += 1;
+    }
+
+    // Take the lowest distinct ranks first, then second copies, and so on
+    let mut used = [0u8; 14];
+    let mut taken = 0;
+    'outer: for copy in 1..=4 {
+        for value in 1..=13 {
+            if counts[value] >= copy {
+                used[value] += 1;
+                taken += 1;
+                if taken == 5 {
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(u8, u8)> = (1..=13u8)
+        .filter(|&v| used[v as usize] > 0)
+        .map(|v| (used[v as usize], v))
+        .collect();
+    groups.sort_by(|a, b| b.cmp(a));
+
+    // By the sizes of the groups, largest first; the number of distinct ranks
+    // alone can't tell trips from two pair or quads from a full house
+    let sizes: Vec<u8> = groups.iter().map(|&(count, _)| count).collect();
+    let category = match sizes.as_slice() {
+        [1, ..] => 0,
+        [2, 1, ..] => 1,
+        [2, 2, ..] => 2,
+        [3, 1, ..] => 3,
+        [3, 2] => 4,
+        _ => 5,
+    };
+    let mut key = vec![category];
+    for (count, value) in groups {
+        for _ in 0..count {
+            key.push(value);
+        }
+    }
+    AceToFiveLow { key }
+}
+
+// Unpaired low with every card at or below `qualifier` (8 for eight-or-better)
+pub fn evaluate_ace_to_five_qualified(cards: &[Card], qualifier: u8) -> Option<AceToFiveLow> {
+    let low = evaluate_ace_to_five(cards);
+    if low.is_unpaired() && low.high_card() <= qualifier {
+        Some(low)
+    } else {
+        None
+    }
+}
+
+pub struct AceToFiveEvaluator;
+
+impl Evaluator for AceToFiveEvaluator {
+    type Strength = AceToFiveLow;
+
+    fn evaluate(&self, hole_cards: &[Card], community_cards: &[Card]) -> AceToFiveLow {
+        let cards: Vec<Card> = hole_cards.iter().chain(community_cards.iter()).cloned().collect();
+        evaluate_ace_to_five(&cards)
+    }
+}
+
+fn fill_from_deck(deck: &mut Deck, cards: &mut Vec<Card>) -> bool {
+    while cards.len() < 5 {
+        match deck.draw() {
+            Some(card) => cards.push(card),
+            None => return false,
+        }
+    }
+    true
+}
+
+// 2-7 single draw all-in equity. Each player keeps the given cards and draws the
+// rest; `dead_cards` are the discards, which can't come back in the draw.
+pub fn monte_carlo_deuce_to_seven(
+    player_keep: &[Card],
+    opponent_keep: &[Card],
+    dead_cards: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+
+    let known: Vec<Card> = player_keep.iter().chain(opponent_keep.iter()).chain(dead_cards.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+
+    for _ in 0..num_simulations {
+        deck.recycle();
+
+        let mut player_hand = player_keep.to_vec();
+        let mut opponent_hand = opponent_keep.to_vec();
+        if !fill_from_deck(&mut deck, &mut player_hand) || !fill_from_deck(&mut deck, &mut opponent_hand) {
+            continue;
+        }
+
+        match DeuceToSevenEvaluator.evaluate(&player_hand, &[]).cmp(&DeuceToSevenEvaluator.evaluate(&opponent_hand, &[])) {
+            Ordering::Greater => wins += 1,
+            Ordering::Less => losses += 1,
+            Ordering::Equal => ties += 1,
+        }
+    }
+
+    SimulationResults::new(num_simulations, wins, losses, ties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    fn low(cards: &str) -> AceToFiveLow {
+        evaluate_ace_to_five(&parse_cards(cards).unwrap())
+    }
+
+    #[test]
+    fn categories_follow_the_group_sizes() {
+        // Each beats the next: unpaired, pair, two pair, trips, full house, quads
+        let ladder = ["KsQdJh9c8s", "AsAd2h3c4s", "AsAd2h2c3s", "AsAdAh2c3s", "AsAdAh2c2s", "AsAdAhAc2s"];
+        for pair in ladder.windows(2) {
+            assert!(low(pair[0]) > low(pair[1]), "{} should beat {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn seven_cards_play_the_best_five() {
+        assert_eq!(low("As2d3h4c5sKdKh"), low("As2d3h4c5s"));
+        assert!(!low("AsAd2h2c3s3d4h").is_unpaired());
+        assert_eq!(low("AsAd2h2c3s3d4h"), low("AsAd2h3c4s"));
+    }
+}
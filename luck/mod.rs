@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::card::{Card, exact_heads_up, monte_carlo_heads_up_with_rng};
+use crate::game::Street;
+use crate::hand_history::HandHistory;
+
+// All-in EV of played hands. When two players got all their chips in before
+// the board was complete, the cards that came next are luck: a player's
+// all-in EV is their equity at that moment times the pot, less what they put
+// in. Every other hand counts its actual result as its EV, so the gap between
+// actual and EV results is exactly what the all-in run-outs added or took
+// away. Amounts are in big blinds so cash games and tournaments add up.
+
+// The moment the last chips went in
+#[derive(Debug, Clone)]
+pub struct AllIn {
+    pub street: Street,
+    // Board cards out when the chips went in
+    pub board: Vec<Card>,
+    pub hero_cards: [Card; 2],
+    pub villain: String,
+    pub villain_cards: [Card; 2],
+    // Hero's share of the pot, ties counted as half, between 0 and 1
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HandLuck {
+    pub id: String,
+    // Tournament number, or the day the cash hands were played
+    pub session: String,
+    pub date: String,
+    pub player: String,
+    pub net_bb: f64,
+    pub ev_bb: f64,
+    pub all_in: Option<AllIn>,
+}
+
+impl HandLuck {
+    // What the run-out added to (positive) or took from the EV result
+    pub fn luck_bb(&self) -> f64 {
+        self.net_bb - self.ev_bb
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionLuck {
+    pub session: String,
+    pub hands: usize,
+    pub all_ins: usize,
+    // Average equity in the all-ins, between 0 and 1
+    pub average_equity: f64,
+    pub net_bb: f64,
+    pub ev_bb: f64,
+}
+
+impl SessionLuck {
+    pub fn luck_bb(&self) -> f64 {
+        self.net_bb - self.ev_bb
+    }
+}
+
+// Results of `hero` (the player dealt the hole cards unless a name is given)
+// in every hand they played, in file order. Preflop all-in equity uses
+// `num_simulations` Monte Carlo deals; later streets are exact.
+pub fn analyze_hands(hands: &[HandHistory], hero: Option<&str>, num_simulations: usize) -> Vec<HandLuck> {
+    analyze_hands_with_rng(hands, hero, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn analyze_hands_with_rng<R: Rng>(hands: &[HandHistory], hero: Option<&str>, num_simulations: usize, rng: &mut R) -> Vec<HandLuck> {
+    hands.iter()
+        .filter_map(|hand| {
+            let player = match hero {
+                Some(name) => hand.player_index(name)?,
+                None => hand.hero?,
+            };
+            Some(analyze_hand_with_rng(hand, player, num_simulations, rng))
+        })
+        .collect()
+}
+
+pub fn analyze_hand_with_rng<R: Rng>(hand: &HandHistory, player: usize, num_simulations: usize, rng: &mut R) -> HandLuck {
+    let big_blind = hand.stakes.big_blind.max(1) as f64;
+    let net_bb = hand.net(player) as f64 / big_blind;
+    let all_in = find_all_in(hand, player, num_simulations, rng);
+    // Rake comes out of the pot the equity is a share of
+    let ev_bb = match &all_in {
+        Some(all_in) => (all_in.equity * (hand.pot() - hand.rake) as f64 - hand.players[player].contributed as f64) / big_blind,
+        None => net_bb,
+    };
+    HandLuck {
+        id: hand.id.clone(),
+        session: session_name(hand),
+        date: hand.date.clone(),
+        player: hand.players[player].name.clone(),
+        net_bb,
+        ev_bb,
+        all_in,
+    }
+}
+
+fn session_name(hand: &HandHistory) -> String {
+    match &hand.tournament {
+        Some(tournament) => format!("Tournament #{}", tournament),
+        None => hand.date.split_whitespace().next().unwrap_or_default().to_string(),
+    }
+}
+
+// Heads-up all-ins with both hands face up and board cards still to come.
+// Multiway all-ins split into side pots and keep their actual result.
+fn find_all_in<R: Rng>(hand: &HandHistory, player: usize, num_simulations: usize, rng: &mut R) -> Option<AllIn> {
+    let finalists = hand.final_players();
+    if finalists.len() != 2 || !finalists.contains(&player) || !hand.actions.iter().any(|record| record.all_in) {
+        return None;
+    }
+    let villain = finalists.into_iter().find(|&other| other != player)?;
+    let hero_cards = hand.players[player].hole_cards.clone()?;
+    let villain_cards = hand.players[villain].hole_cards.clone()?;
+
+    // Nobody can bet once a player is all-in, so the last action is the call that closed it
+    let street = hand.actions.last()?.street;
+    let board_cards = match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River | Street::Showdown => return None,
+    };
+    let board = hand.board.get(..board_cards)?.to_vec();
+    let results = if board.is_empty() {
+        monte_carlo_heads_up_with_rng(&hero_cards, &villain_cards, &board, num_simulations, rng)
+    } else {
+        exact_heads_up(&hero_cards, &villain_cards, &board)
+    };
+    Some(AllIn {
+        street,
+        board,
+        hero_cards,
+        villain: hand.players[villain].name.clone(),
+        villain_cards,
+        equity: (results.win_rate + results.tie_rate / 2.0) / 100.0,
+    })
+}
+
+// Totals per session in the order sessions first appear
+pub fn session_summary(hands: &[HandLuck]) -> Vec<SessionLuck> {
+    let mut sessions: Vec<SessionLuck> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut equity_sums: Vec<f64> = Vec::new();
+    for hand in hands {
+        let position = *index.entry(hand.session.as_str()).or_insert_with(|| {
+            sessions.push(SessionLuck { session: hand.session.clone(), hands: 0, all_ins: 0, average_equity: 0.0, net_bb: 0.0, ev_bb: 0.0 });
+            equity_sums.push(0.0);
+            sessions.len() - 1
+        });
+        let session = &mut sessions[position];
+        session.hands += 1;
+        session.net_bb += hand.net_bb;
+        session.ev_bb += hand.ev_bb;
+        if let Some(all_in) = &hand.all_in {
+            session.all_ins += 1;
+            equity_sums[position] += all_in.equity;
+        }
+    }
+    for (session, equity_sum) in sessions.iter_mut().zip(equity_sums) {
+        session.average_equity = if session.all_ins > 0 { equity_sum / session.all_ins as f64 } else { 0.0 };
+    }
+    sessions
+}
+
+// Writes one row per hand; the all-in columns are empty when there was no all-in
+pub fn export_luck_csv(hands: &[HandLuck], filename: &str) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "Hand,Session,Date,Player,All_In_Street,Cards,Villain,Villain_Cards,Board,Equity,Net_BB,EV_BB,Luck_BB")?;
+    for hand in hands {
+        let all_in = match &hand.all_in {
+            Some(all_in) => format!("{},{}{},{},{}{},{},{:.6}",
+                                    all_in.street, all_in.hero_cards[0], all_in.hero_cards[1], all_in.villain,
+                                    all_in.villain_cards[0], all_in.villain_cards[1],
+                                    all_in.board.iter().map(|card| card.to_string()).collect::<String>(), all_in.equity),
+            None => ",,,,,".to_string(),
+        };
+        writeln!(file, "{},{},{},{},{},{:.4},{:.4},{:.4}",
+                 hand.id, hand.session, hand.date, hand.player, all_in, hand.net_bb, hand.ev_bb, hand.luck_bb())?;
+    }
+    file.flush()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
@@ -1,97 +1,4110 @@
+mod api;
+mod bankroll;
+mod bench;
+mod bucket;
 mod card;
+mod cfr;
+mod compress;
+mod diff;
+mod five_card_draw;
+mod game;
+mod hand_history;
+mod icm;
+mod json;
+mod lowball;
+mod luck;
+mod matrix;
+mod omaha;
+mod opponent;
+mod output;
+mod pineapple;
+mod play;
+mod postflop;
+mod pot;
+mod pushfold;
+mod range;
+mod repl;
+mod report;
+mod rng;
+mod rpc;
+mod server;
+mod sng;
+mod strategy;
+mod stud;
+mod texture;
+mod tournament;
+mod trainer;
+#[cfg(feature = "plotters")]
+mod chart;
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "xlsx")]
+mod spreadsheet;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "wasm")]
+mod wasm;
 
-use card::{Card, Deck, verify, monte_carlo_simulation, monte_carlo_with_community, SimulationResults, bulk_monte_carlo_simulation, print_bulk_results, export_to_csv, export_summary_to_csv};
+use card::{Card, Deck, Suit, SortKey, ResultFilter, TrialBudget, filter_results, sort_results, monte_carlo_simulation, monte_carlo_with_community, monte_carlo_with_community_with_rng, monte_carlo_with_community_by_street_with_rng, SimulationResults, bulk_monte_carlo_simulation_streaming, print_bulk_results, export_to_csv, export_summary_to_csv, generate_all_starting_hands, generate_canonical_starting_hands, parse_cards, run_it_n_times_with_rng, monte_carlo_multi_board_with_rng, import_results, merge_results, Shard};
 use std::io;
 use std::time::Instant;
 
+// Number of hands written to the streamed CSV between flushes
+const STREAM_FLUSH_INTERVAL: usize = 25;
+
+// Command-line options for the interactive bulk run
+struct BulkOptions {
+    shard: Option<Shard>,
+    opponents: usize,
+    board: Vec<Card>,
+    // Only simulate starting hands in this range
+    only: Option<range::Range>,
+    // Opponents fold everything but this top percent of hands preflop
+    villain_top: Option<f64>,
+    // Ordering and row filters for the results table and custom exports
+    sort: Option<SortKey>,
+    filters: Vec<ResultFilter>,
+    canonical: bool,
+    json: bool,
+    markdown: bool,
+    xlsx: bool,
+    // The results CSV and JSON are written as .gz or .zst
+    compress: Option<compress::Compression>,
+    rng: rng::RngChoice,
+    images: bool,
+    report: bool,
+    // Skips the prompt for simulations per hand
+    simulations: Option<usize>,
+    // Plans the simulations per hand for this ± margin on each win rate
+    precision: Option<f64>,
+    // Each hand runs for this long instead of a number of simulations
+    duration: Option<std::time::Duration>,
+    // Rounds of extra trials for hands whose place in the ranking isn't settled
+    adaptive: usize,
+    // Common deals make the hands' differences, and so the ranking, steadier
+    sampling: card::Sampling,
+    // Print only the ranked table in this format, without the menu
+    output: Option<output::OutputFormat>,
+}
+
+fn parse_bulk_options(args: &[String]) -> Result<BulkOptions, String> {
+    let mut options = BulkOptions { shard: None, opponents: 1, board: Vec::new(), only: None, villain_top: None, sort: None, filters: Vec::new(), canonical: false, json: false, markdown: false, xlsx: false, compress: None, rng: rng::RngChoice::default(), images: false, report: false, simulations: None, precision: None, duration: None, adaptive: 0, sampling: card::Sampling::Independent, output: None };
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--shard" => {
+                let spec = iter.next().ok_or("--shard requires a value like 1/4")?;
+                options.shard = Some(Shard::parse(spec)?);
+            },
+            "--opponents" => {
+                let count = iter.next().ok_or("--opponents requires a number from 1 to 9")?;
+                options.opponents = match count.parse::<usize>() {
+                    Ok(n) if (1..=9).contains(&n) => n,
+                    _ => return Err(format!("Invalid opponent count '{}', expected 1-9", count)),
+                };
+            },
+            "--board" => {
+                let cards = iter.next().ok_or("--board requires a flop, turn or river like AsKd7h")?;
+                options.board = parse_cards(cards)?;
+                card::check_distinct(options.board.iter())?;
+                texture::classify_board(&options.board)?;
+            },
+            "--only" => {
+                let range = iter.next().ok_or("--only requires a range like pairs, suited, broadway or AJs+,KQs")?;
+                options.only = Some(range::Range::parse(range)?);
+            },
+            "--villain-top" => {
+                let percent = iter.next().ok_or("--villain-top requires a percent of hands like 20")?;
+                options.villain_top = match percent.trim_end_matches('%').parse::<f64>() {
+                    Ok(p) if p > 0.0 && p <= 100.0 => Some(p),
+                    _ => return Err(format!("Invalid --villain-top '{}', expected a percent above 0 and up to 100", percent)),
+                };
+            },
+            "--sort" => {
+                let key = iter.next().ok_or("--sort requires win, lose, tie, hand or chen")?;
+                options.sort = Some(key.parse()?);
+            },
+            "--filter" => {
+                let filter = iter.next().ok_or("--filter requires a condition like \"win > 55%\" or suited")?;
+                options.filters.push(filter.parse()?);
+            },
+            "--canonical" => options.canonical = true,
+            "--json" => options.json = true,
+            "--markdown" => options.markdown = true,
+            "--xlsx" => options.xlsx = true,
+            "--compress" => {
+                let codec = iter.next().ok_or("--compress requires gzip or zstd")?;
+                options.compress = Some(codec.parse()?);
+            },
+            "--rng" => {
+                let choice = iter.next().ok_or("--rng requires small, chacha or os")?;
+                options.rng = choice.parse()?;
+            },
+            "--images" => options.images = true,
+            "--report" => options.report = true,
+            "-n" | "--simulations" => options.simulations = Some(parse_simulations(iter.next())?),
+            "--duration" => options.duration = Some(parse_duration(iter.next())?),
+            "--sampling" => {
+                let sampling = iter.next().ok_or("--sampling requires independent, common or antithetic")?;
+                options.sampling = sampling.parse()?;
+            },
+            "--adaptive" => {
+                let rounds = iter.next().ok_or("--adaptive requires a number of rounds like 5")?;
+                options.adaptive = match rounds.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid --adaptive '{}', expected a positive number of rounds", rounds)),
+                };
+            },
+            "--output" => options.output = Some(parse_output(iter.next())?),
+            "--precision" => options.precision = Some(parse_precision(iter.next())?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    
+    if [options.simulations.is_some(), options.duration.is_some(), options.precision.is_some()].iter().filter(|&&given| given).count() > 1 {
+        return Err("Give one of -n simulations per hand, --duration per hand or --precision, not several".to_string());
+    }
+    if let Some(margin) = options.precision {
+        options.simulations = Some(card::trials_for_margin(margin, card::PLANNING_EQUITY));
+    }
+    // Shared deals are drawn up front against random hands
+    if options.sampling != card::Sampling::Independent && (options.duration.is_some() || options.adaptive > 0 || options.villain_top.is_some()) {
+        return Err("--sampling common or antithetic cannot be combined with --duration, --adaptive or --villain-top".to_string());
+    }
+    if options.output.is_some() && options.simulations.is_none() && options.duration.is_none() {
+        return Err("--output requires -n simulations per hand (or --duration or --precision), since it doesn't prompt for them".to_string());
+    }
+    
+    // A board breaks suit symmetry, so one combo no longer stands for its whole class
+    if options.canonical && !options.board.is_empty() {
+        return Err("--canonical cannot be combined with --board; use menu option 5 to group the results".to_string());
+    }
+    
+    Ok(options)
+}
+
 fn main() {
-    println!("=== Texas Hold'em Monte Carlo Analysis ===");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    // Bare --output results keep stderr quiet too, unless -v asks for the notices
+    let bare_output = args.iter().any(|arg| arg == "--output");
+    init_logging(take_verbosity(&mut args).saturating_sub(bare_output as i8));
+    
+    match args.first().map(|s| s.as_str()) {
+        Some("merge") => run_merge(&args[1..]),
+        Some("omaha") => run_omaha(&args[1..]),
+        Some("stud") => run_stud(&args[1..]),
+        Some("draw") => run_five_card_draw(&args[1..]),
+        Some("lowball") => run_lowball(&args[1..]),
+        Some("pineapple") => run_pineapple(&args[1..]),
+        Some("runit") => run_it(&args[1..]),
+        Some("bombpot") => run_bomb_pot(&args[1..]),
+        Some("equity") => run_equity(&args[1..]),
+        Some("presets") => list_presets(),
+        Some("blockers") => run_blockers(&args[1..]),
+        Some("outs") => run_outs(&args[1..]),
+        Some("multiway") => run_multiway(&args[1..]),
+        Some("compare") => run_compare(&args[1..]),
+        Some("compare-stats") => run_compare_stats(&args[1..]),
+        Some("matrix") => run_matrix(&args[1..]),
+        Some("bench") => run_bench(&args[1..]),
+        Some("play") => play_single_hand(&args[1..]),
+        Some("potodds") => run_pot_odds(&args[1..]),
+        Some("shove") => run_shove(&args[1..]),
+        Some("bankroll") => run_bankroll(&args[1..]),
+        Some("ruin") => run_ruin(&args[1..]),
+        Some("icm") => run_icm(&args[1..]),
+        Some("pushfold") => run_push_fold(&args[1..]),
+        Some("tournament") => run_tournament(&args[1..]),
+        Some("husng") => run_heads_up_sng(&args[1..]),
+        Some("cfr") => run_cfr(&args[1..]),
+        Some("buckets") => run_buckets(&args[1..]),
+        Some("history") => run_history(&args[1..]),
+        Some("luck") => run_luck(&args[1..]),
+        Some("serve") => run_serve(&args[1..]),
+        Some("rpc") => run_rpc(&args[1..]),
+        #[cfg(feature = "grpc")]
+        Some("grpc") => run_grpc(&args[1..]),
+        #[cfg(not(feature = "grpc"))]
+        Some("grpc") => println!("The gRPC server requires building with the grpc feature"),
+        #[cfg(feature = "tui")]
+        Some("tui") => run_tui(&args[1..]),
+        #[cfg(not(feature = "tui"))]
+        Some("tui") => println!("The dashboard requires building with the tui feature"),
+        Some("train") => run_train(&args[1..]),
+        Some("flashcards") => run_flashcards(&args[1..]),
+        Some("potquiz") => run_pot_quiz(&args[1..]),
+        Some("repl") => run_repl(&args[1..]),
+        Some(command @ ("mdf" | "alpha")) => run_bet_math(&args[1..], command),
+        Some("diff") => run_diff(&args[1..]),
+        Some("view") => run_view(&args[1..]),
+        _ => match parse_bulk_options(&args) {
+            Ok(options) => run_bulk(options),
+            Err(e) => {
+                println!("{}", e);
+                println!("Usage: pokershit [--shard i/n] [--opponents n] [--villain-top percent] [--board <cards>] [--only <range>] [--sort <key>] [--filter <condition>]... [--canonical] [--json] [--markdown] [--xlsx] [--compress <gzip|zstd>] [--rng <small|chacha|os>[:seed]] [--images] [--report] [-n simulations per hand | --duration <time per hand> | --precision <±percent>] [--adaptive rounds] [--sampling <independent|common|antithetic>] [--output <json|csv|plain|markdown>]");
+                println!("       pokershit merge <shard.csv>... [-o output.csv]");
+                println!("       pokershit omaha <4 hole cards> [board cards] [-n simulations] [--hilo]");
+                println!("       pokershit stud <your cards> [--opp <upcards>]... [--dead <cards>] [-n simulations]");
+                println!("       pokershit draw <5 cards> [--opponents n] [-n simulations]");
+                println!("       pokershit lowball <your kept cards> <opponent kept cards> [--dead <cards>] [-n simulations]");
+                println!("       pokershit pineapple <3 cards> [--crazy] [--flop <cards>] [-n simulations]");
+                println!("       pokershit runit <your hand> <opponent hand> [board] [--times n] [-n trials] [--rng <kind>]");
+                println!("       pokershit bombpot <hand> <hand>... [--board1 <cards>] [--board2 <cards>] [-n simulations] [--rng <kind>]");
+                println!("       pokershit equity <your hand | --hero-range <range>> [board] [--range <villain range> | --model <opponent model>] [--ranking <results.csv>] [--histogram] [--streets] [--by-turn] [--stratified] [-n simulations | --duration <time> | --precision <±percent> [--estimate <equity>]] [--trace <trace.csv|trace.json> [--trace-every n]] [--rng <kind>] [--output <json|csv|plain|markdown>]");
+                println!("       pokershit presets");
+                println!("       pokershit blockers <board> --range <villain range> [--top n]");
+                println!("       pokershit outs <your hand> <board> (--vs <villain hand> | --target <hand category>)");
+                println!("       pokershit multiway <your hand> [--max n] [-n simulations] [-o output.csv] [--rng <kind>]");
+                println!("       pokershit compare <hand> <hand> [board] [--sample n]");
+                println!("       pokershit compare-stats <hand> <hand> ([board] [--range <villain range>] [-n simulations] [--rng <kind>] | --results <results.csv|results.json>)");
+                println!("       pokershit matrix [-n simulations per matchup] [--threads n] [-o output prefix]");
+                println!("       pokershit play [--stack chips] [--blinds small/big] [-n bot simulations] [--history <hand.txt|hand.phh>] [--rng <kind>]");
+                println!("       pokershit potodds <pot> <to call> (--equity <percent> | <your hand> [board] [--range <villain range>]) [-n simulations] [--rng <kind>]");
+                println!("       pokershit shove <pot> <effective stack> --fold <percent> (--equity <percent> | <your hand> [board] [--range <calling range>]) [-n simulations] [--rng <kind>]");
+                println!("       pokershit bankroll <win rate bb/100> <std dev bb/100> [--fraction f] [--buyin bb] [--bankroll amount]");
+                println!("       pokershit ruin <win rate bb/100> <std dev bb/100> <bankroll bb> [--hands n] [--trials n] [--rng <kind>]");
+                println!("       pokershit icm <stacks> <payouts>");
+                println!("       pokershit pushfold <stacks bb> [--ante bb] [--payouts <payouts>] [--iterations n] [--matrix <equity_matrix.csv> | -n simulations per matchup] [--threads n]");
+                println!("       pokershit tournament [--strategy <strategy>] [--field <strategies>] [--players n] [--table-size n] [--stack chips] [--levels <sb/bb[/ante],...>] [--hands-per-level n] [--payouts <percents>] [-n tournaments] [--history <hands.txt|hands.phhs>] [--rng <kind>]");
+                println!("       pokershit husng [--depths <bb,...>] [--strategy <nash|strategy>] [--villain <nash|strategy>] [--payouts <first,second>] [--hands-per-level n] [-n matches per depth] [--iterations n] [--matrix <equity_matrix.csv> | --simulations per matchup] [--threads n] [--rng <kind>]");
+                println!("       pokershit cfr (kuhn | river <board> --oop <range> --ip <range> [--pot chips] [--stack chips] [--bets <pot fractions>] [--buckets n]) [-n iterations] [--vanilla | --sampled] [-o <strategy.csv>] [--rng <kind>]");
+                println!("       pokershit buckets [board] [--metric <equity|ehs>] [--method <percentile|kmeans>] [-k buckets] [-n samples per hand] [-o <buckets.csv>] [--hands <hand_buckets.csv>] [--rng <kind>]");
+                println!("       pokershit history <hand_history.txt|hands.phhs> [--hero <name>] [--hand <id>] [--convert <hands.phhs|hands.txt>] [-n simulations] [--rng <kind>]");
+                println!("       pokershit luck <hand_history.txt|hands.phhs>... [--hero <name>] [-n simulations] [-o <luck.csv>] [--report <luck.html>] [--rng <kind>]");
+                println!("       pokershit serve [--host address] [--port n] [--max-trials n] [--rng <kind>]");
+                println!("       pokershit rpc [--max-trials n] [--rng <kind>]");
+                println!("       pokershit grpc [--host address] [--port n] [--max-trials n] [--rng <kind>]");
+                println!("       pokershit mdf <pot> [bet]");
+                println!("       pokershit alpha <pot> [bet]");
+                println!("       pokershit bench [-n simulations] [--evaluations n] [--threads n] [--rng <kind>]");
+                println!("       pokershit diff <before.csv> <after.csv> [--top n] [-o output.csv]");
+                println!("       pokershit view <results.csv|results.json> [--sort <key>] [--filter <condition>]...");
+                println!("       pokershit tui [results.csv|results.json] [--rng <kind>]");
+                println!("       pokershit train [--villain <hand|range|mixed>] [--street <street>] [--spots n] [--log <training.csv>] [--stats] [-n simulations] [--rng <kind>]");
+                println!("       pokershit flashcards [--quiz <rank|equity>] [--results <results.csv|results.json>] [--cards n] [--log <training.csv>] [--stats] [-n simulations] [--rng <kind>]");
+                println!("       pokershit potquiz [--seconds n] [--rounds n] [--log <training.csv>] [--stats] [--rng <kind>]");
+                println!("       pokershit repl [-n trials] [--rng <kind>]");
+                println!("Every command also takes -q (warnings only), -v (debug detail) or -vv (trace every hand)");
+            }
+        },
+    }
+}
+
+fn list_presets() {
+    println!("\n=== Range Presets ===");
+    for (name, notation) in range::PRESETS.iter() {
+        let combos = range::Range::preset(name).map_or(0, |r| r.len());
+        println!("{:<10} {:>4} combos ({:.1}%)  {}", name, combos, combos as f64 / 1326.0 * 100.0, notation);
+    }
+    println!("\nUse a preset name anywhere a range is accepted, e.g. equity AhKh --range btn-open");
+}
+
+fn run_equity(args: &[String]) {
+    let usage = "Usage: pokershit equity <your hand | --hero-range <range>> [board] [--range <villain range> | --model <opponent model>] [--ranking <results.csv>] [--histogram] [--streets] [--by-turn] [--stratified] [-n simulations | --duration <time> | --precision <±percent> [--estimate <equity>]] [--trace <trace.csv|trace.json> [--trace-every n]] [--rng <small|chacha|os>[:seed]] [--output <json|csv|plain|markdown>], e.g. equity AhKh Qh7c2d --range \"QQ+,AKs,AQs+,20%\", equity Kh9c --model tight or equity --hero-range btn-open Qh7c2d --range bb-3bet";
+    let mut card_args = Vec::new();
+    let mut hero_range_text = None;
+    let mut range_text = None;
+    let mut model_text = None;
+    let mut ranking = None;
+    let mut histogram = false;
+    let mut streets = false;
+    let mut by_turn = false;
+    let mut stratified = false;
+    let mut num_sims = None;
+    let mut duration = None;
+    let mut precision = None;
+    let mut estimate = None;
+    let mut trace_file = None;
+    let mut trace_every = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut output = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--range" => iter.next().ok_or("--range requires a range".to_string())
+                .map(|v| range_text = Some(v.clone())),
+            "--hero-range" => iter.next().ok_or("--hero-range requires a range".to_string())
+                .map(|v| hero_range_text = Some(v.clone())),
+            "--model" => iter.next().ok_or("--model requires random, tight, loose-passive, top:<percent> or equity:<percent>".to_string())
+                .map(|v| model_text = Some(v.clone())),
+            "--ranking" => iter.next().ok_or("--ranking requires a results file".to_string())
+                .and_then(|f| import_results(f).map_err(|e| format!("Error reading {}: {}", f, e)))
+                .map(|results| ranking = Some(range::ranking_from_results(&results))),
+            "--histogram" => {
+                histogram = true;
+                Ok(())
+            },
+            "--streets" => {
+                streets = true;
+                Ok(())
+            },
+            "--by-turn" => {
+                by_turn = true;
+                Ok(())
+            },
+            "--stratified" => {
+                stratified = true;
+                Ok(())
+            },
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = Some(n)),
+            "--duration" => parse_duration(iter.next()).map(|time| duration = Some(time)),
+            "--precision" => parse_precision(iter.next()).map(|margin| precision = Some(margin)),
+            "--estimate" => parse_percent(iter.next()).map(|equity| estimate = Some(equity * 100.0)),
+            "--trace" => iter.next().ok_or("--trace requires a file, e.g. trace.csv or trace.json".to_string())
+                .map(|file| trace_file = Some(file.clone())),
+            "--trace-every" => parse_simulations(iter.next()).map(|n| trace_every = Some(n)),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            "--output" => parse_output(iter.next()).map(|format| output = Some(format)),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    if output.is_some() && (histogram || by_turn) {
+        println!("--output cannot be combined with --histogram or --by-turn\n{}", usage);
+        return;
+    }
+    if duration.is_some() && (num_sims.is_some() || histogram || by_turn || streets) {
+        println!("--duration cannot be combined with -n, --histogram, --by-turn or --streets\n{}", usage);
+        return;
+    }
+    // The weighted deals are reported with their own interval, so they need a fixed count
+    if stratified && (range_text.is_none() || hero_range_text.is_some() || duration.is_some() || histogram || by_turn || streets) {
+        println!("--stratified needs a villain --range and cannot be combined with --hero-range, --duration, --histogram, --by-turn or --streets\n{}", usage);
+        return;
+    }
+    if precision.is_some() && (num_sims.is_some() || duration.is_some() || histogram || by_turn) {
+        println!("--precision cannot be combined with -n, --duration, --histogram or --by-turn\n{}", usage);
+        return;
+    }
+    if estimate.is_some() && precision.is_none() {
+        println!("--estimate only plans the simulations for --precision\n{}", usage);
+        return;
+    }
+    if trace_every.is_some() && trace_file.is_none() {
+        println!("--trace-every needs a --trace file\n{}", usage);
+        return;
+    }
+    // The trace sums steps of trials, which keeps only the counts
+    if trace_file.is_some() && (streets || stratified || histogram || by_turn) {
+        println!("--trace cannot be combined with --streets, --stratified, --histogram or --by-turn\n{}", usage);
+        return;
+    }
+    let mut trace = trace_file.as_ref().map(|_| card::ConvergenceTrace::new(trace_every.unwrap_or(TRACE_EVERY)));
+    let save_trace = |trace: Option<card::ConvergenceTrace>| {
+        if let (Some(trace), Some(file)) = (trace, &trace_file) {
+            if let Err(e) = card::export_convergence(&trace.points, file) {
+                println!("Error exporting the convergence trace: {}", e);
+            }
+        }
+    };
+    // Without an estimate the plan assumes an even matchup, the most trials any equity needs
+    let estimate = estimate.unwrap_or(card::PLANNING_EQUITY);
+    let num_sims = match precision {
+        Some(margin) => card::trials_for_margin(margin, estimate),
+        None => num_sims.unwrap_or(10000),
+    };
+    let budget = duration.map_or(TrialBudget::Count(num_sims), TrialBudget::Time);
+    
+    // Percentage entries use the bulk results' ranking when --ranking is given
+    let parse_range = |text: &String| match &ranking {
+        Some(ranking) => range::Range::parse_with_ranking(text, ranking),
+        None => range::Range::parse(text),
+    };
+    let villain_range = match range_text.as_ref().map(parse_range).transpose() {
+        Ok(villain_range) => villain_range,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    // With a hero range every card given is the board
+    if let Some(text) = &hero_range_text {
+        if model_text.is_some() || histogram || by_turn || streets {
+            println!("--hero-range cannot be combined with --model, --histogram, --by-turn or --streets\n{}", usage);
+            return;
+        }
+        let hero_range = match parse_range(text) {
+            Ok(hero_range) => hero_range,
+            Err(e) => {
+                println!("{}\n{}", e, usage);
+                return;
+            }
+        };
+        let board: Vec<Card> = card_args.iter().flatten().cloned().collect();
+        if trace.is_some() && board.len() == 5 {
+            println!("The river is counted exactly, so there is no convergence to trace");
+            return;
+        }
+        let villain_text = range_text.as_deref().unwrap_or("random");
+        run_range_vs_range((text, &hero_range), (villain_text, &villain_range.unwrap_or_else(range::Range::random)), &board, budget, rng_choice, trace.as_mut(), output);
+        save_trace(trace);
+        return;
+    }
+    
+    // A model decides which hands the villain plays, so it replaces the range
+    if model_text.is_some() && (villain_range.is_some() || histogram || by_turn || streets) {
+        println!("--model cannot be combined with --range, --histogram, --by-turn or --streets\n{}", usage);
+        return;
+    }
+    // Equity by street is only tracked against a random hand
+    if streets && villain_range.is_some() {
+        println!("--streets cannot be combined with --range\n{}", usage);
+        return;
+    }
+    let model = match model_text.as_deref().map(opponent::parse_model).transpose() {
+        Ok(model) => model,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    let hand: [Card; 2] = match card_args.first().map(|cards| cards.clone().try_into()) {
+        Some(Ok(hand)) => hand,
+        _ => {
+            println!("Enter your two hole cards\n{}", usage);
+            return;
+        }
+    };
+    let board: Vec<Card> = card_args.iter().skip(1).flatten().cloned().collect();
+    if board.len() > 5 {
+        println!("The board has at most five cards");
+        return;
+    }
+    if let Err(e) = card::check_distinct(hand.iter().chain(board.iter())) {
+        println!("{}", e);
+        return;
+    }
+    
+    // On the river every villain hand is counted exactly, so there's nothing to budget
+    let exact = model.is_none() && board.len() == 5;
+    let budget = if exact { TrialBudget::Count(num_sims) } else { budget };
+    if exact && trace.is_some() {
+        println!("The river is counted exactly, so there is no convergence to trace");
+        return;
+    }
+    
+    if let Some(format) = output {
+        let mut rng = rng_choice.build();
+        let mut fields = vec![
+            ("hero".to_string(), json::JsonValue::String(api::cards_text(&hand))),
+            ("board".to_string(), json::JsonValue::String(api::cards_text(&board))),
+        ];
+        let mut weighting = None;
+        let (villain, results, uncontested) = match (&model, &villain_range) {
+            (Some(model), _) => {
+                let outcome = run_model(&hand, &board, model.as_ref(), budget, &mut rng, trace.as_mut());
+                let uncontested = outcome.uncontested_rate();
+                (model.name(), outcome.results, Some(uncontested))
+            },
+            (None, Some(villain_range)) if stratified => {
+                let outcome = range::stratified_vs_range_with_rng(&hand, villain_range, &board, num_sims, &mut rng);
+                let results = outcome.results.clone();
+                weighting = Some(outcome);
+                (range_text.clone().unwrap_or_default(), results, None)
+            },
+            (None, Some(villain_range)) => {
+                let villain = range_text.clone().unwrap_or_default();
+                (villain, run_budget(budget, trace.as_mut(), |trials| range::monte_carlo_vs_range_with_rng(&hand, villain_range, &board, trials, &mut rng)), None)
+            },
+            (None, None) if streets => ("random".to_string(), monte_carlo_with_community_by_street_with_rng(&hand, &board, num_sims, &mut rng), None),
+            (None, None) => ("random".to_string(), run_budget(budget, trace.as_mut(), |trials| monte_carlo_with_community_with_rng(&hand, &board, trials, &mut rng)), None),
+        };
+        if results.total_games == 0 {
+            println!("No villain hand was left to play against {}", villain);
+            return;
+        }
+        fields.push(("villain".to_string(), json::JsonValue::String(villain)));
+        fields.extend(api::result_fields(&results));
+        if let Some(rate) = uncontested {
+            fields.push(("uncontested_rate".to_string(), json::JsonValue::Number(rate)));
+        }
+        if let Some(streets) = &results.street_equity {
+            for (street, ahead) in [("flop", streets.flop), ("turn", streets.turn), ("river", streets.river)] {
+                fields.push((format!("ahead_{}", street), json::JsonValue::Number(ahead)));
+            }
+        }
+        if duration.is_some() && !exact {
+            fields.extend(confidence_fields(&results));
+        }
+        if let Some(outcome) = weighting.filter(|_| !exact) {
+            fields.extend(stratified_fields(&outcome));
+        }
+        print_record(format, &fields);
+        save_trace(trace);
+        return;
+    }
+    
+    println!("\n=== Equity ===");
+    println!("Your hand: {}", format_cards(&hand));
+    if !board.is_empty() {
+        println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    }
+    if board.len() >= 3 {
+        println!("Made hand: {}", postflop::analyze_draws(&hand, &board));
+    }
+    
+    if histogram {
+        let villain_range = villain_range.unwrap_or_else(range::Range::random);
+        print_equity_distribution(&hand, &villain_range, &board, num_sims, &mut rng_choice.build());
+        return;
+    }
+    if by_turn {
+        let villain_range = villain_range.unwrap_or_else(range::Range::random);
+        print_turn_card_equities(&hand, &villain_range, &board, num_sims, &mut rng_choice.build());
+        return;
+    }
+    
+    if let Some(margin) = precision.filter(|_| !exact) {
+        println!("\n±{}% at about {:.0}% equity needs {} simulations (95% confidence)", margin, estimate, num_sims);
+    }
+    
+    let mut rng = rng_choice.build();
+    let start_time = Instant::now();
+    if let Some(model) = &model {
+        println!("Villain: {}", model.name());
+        print_running(budget, 0);
+        let outcome = run_model(&hand, &board, model.as_ref(), budget, &mut rng, trace.as_mut());
+        if outcome.results.total_games == 0 {
+            println!("The villain never found a hand to play");
+            return;
+        }
+        print_simulation_results(&outcome.results, start_time.elapsed());
+        println!("Villain gave up before showdown: {:.2}%", outcome.uncontested_rate());
+        if duration.is_some() {
+            print_confidence(&outcome.results);
+        }
+        save_trace(trace);
+        return;
+    }
+    let mut weighting = None;
+    let results = match &villain_range {
+        Some(villain_range) => {
+            let mut dead_cards = hand.to_vec();
+            dead_cards.extend_from_slice(&board);
+            let available: f64 = villain_range.available(&dead_cards).iter().map(|(_, weight)| weight).sum();
+            println!("Villain range: {:.1} combos ({:.1} after card removal)", villain_range.total_weight(), available);
+            if available == 0.0 {
+                println!("Every combo in the villain range is blocked by known cards");
+                return;
+            }
+            print_running(budget, board.len());
+            if stratified {
+                let outcome = range::stratified_vs_range_with_rng(&hand, villain_range, &board, num_sims, &mut rng);
+                let results = outcome.results.clone();
+                weighting = Some(outcome);
+                results
+            } else {
+                run_budget(budget, trace.as_mut(), |trials| range::monte_carlo_vs_range_with_rng(&hand, villain_range, &board, trials, &mut rng))
+            }
+        },
+        None => {
+            println!("Villain: random hand");
+            print_running(budget, board.len());
+            if streets {
+                monte_carlo_with_community_by_street_with_rng(&hand, &board, num_sims, &mut rng)
+            } else {
+                run_budget(budget, trace.as_mut(), |trials| monte_carlo_with_community_with_rng(&hand, &board, trials, &mut rng))
+            }
+        },
+    };
+    print_simulation_results(&results, start_time.elapsed());
+    if duration.is_some() && !exact {
+        print_confidence(&results);
+    }
+    if let Some(outcome) = weighting.filter(|_| !exact) {
+        print_stratified(&outcome);
+    }
+    save_trace(trace);
+    
+    if board.len() == 3 || board.len() == 4 {
+        let villain_range = villain_range.unwrap_or_else(range::Range::random);
+        if let Ok(analysis) = postflop::analyze_postflop_with_rng(&hand, &villain_range, &board, num_sims, &mut rng) {
+            println!("\n=== Hand Potential ===");
+            println!("Hand strength: {:.1}%", analysis.potential.hand_strength * 100.0);
+            println!("Positive potential (PPOT): {:.1}%", analysis.potential.positive * 100.0);
+            println!("Negative potential (NPOT): {:.1}%", analysis.potential.negative * 100.0);
+            println!("Effective hand strength (EHS): {:.1}%", analysis.effective_strength * 100.0);
+        }
+    }
+}
+
+// Each range comes with the text it was given as, for --output
+fn run_range_vs_range(hero: (&str, &range::Range), villain: (&str, &range::Range), board: &[Card], budget: TrialBudget, rng_choice: rng::RngChoice, mut trace: Option<&mut card::ConvergenceTrace>, output: Option<output::OutputFormat>) {
+    let ((hero_text, hero_range), (villain_text, villain_range)) = (hero, villain);
+    if board.len() > 5 {
+        println!("The board has at most five cards");
+        return;
+    }
+    if let Err(e) = card::check_distinct(board.iter()) {
+        println!("{}", e);
+        return;
+    }
+    
+    // The river is enumerated exactly, so a time budget only applies before it
+    let timed = matches!(budget, TrialBudget::Time(_)) && board.len() < 5;
+    let budget = if board.len() == 5 { TrialBudget::Count(1) } else { budget };
+    let mut rng = rng_choice.build();
+    let mut run = || run_budget(budget, trace.as_deref_mut(), |trials| range::range_vs_range_with_rng(hero_range, villain_range, board, trials, &mut rng));
+    
+    if let Some(format) = output {
+        let results = run();
+        if results.total_games == 0 {
+            println!("The two ranges have no pair of combos left once the board is removed");
+            return;
+        }
+        let mut fields = vec![
+            ("hero".to_string(), json::JsonValue::String(hero_text.to_string())),
+            ("board".to_string(), json::JsonValue::String(api::cards_text(board))),
+            ("villain".to_string(), json::JsonValue::String(villain_text.to_string())),
+        ];
+        fields.extend(api::result_fields(&results));
+        if timed {
+            fields.extend(confidence_fields(&results));
+        }
+        print_record(format, &fields);
+        return;
+    }
+    
+    println!("\n=== Range vs Range Equity ===");
+    if !board.is_empty() {
+        println!("Board: {}{}", format_cards(board), texture_label(board));
+    }
+    for (name, player_range) in [("Hero", hero_range), ("Villain", villain_range)] {
+        let available: f64 = player_range.available(board).iter().map(|(_, weight)| weight).sum();
+        println!("{} range: {:.1} combos ({:.1} after card removal)", name, player_range.total_weight(), available);
+    }
+    if board.len() == 5 {
+        println!("\nEnumerating every pair of combos on the river...");
+    } else {
+        print_running(budget, 0);
+    }
+    
+    let start_time = Instant::now();
+    let results = run();
+    if results.total_games == 0 {
+        println!("The two ranges have no pair of combos left once the board is removed");
+        return;
+    }
+    print_simulation_results(&results, start_time.elapsed());
+    if timed {
+        print_confidence(&results);
+    }
+}
+
+// Announces the run; full boards are counted exactly rather than simulated
+fn print_running(budget: TrialBudget, board_size: usize) {
+    match budget {
+        _ if board_size == 5 => println!("\nEnumerating every villain combo on the river..."),
+        TrialBudget::Count(num_sims) => println!("\nRunning {} simulations...", num_sims),
+        TrialBudget::Time(time) => println!("\nRunning simulations for {:.1}s...", time.as_secs_f64()),
+    }
+}
+
+// The model's uncontested deals are counted across every batch of the budget
+fn run_model(hand: &[Card; 2], board: &[Card], model: &dyn opponent::OpponentModel, budget: TrialBudget, rng: &mut rng::SimRng, trace: Option<&mut card::ConvergenceTrace>) -> opponent::ModelResults {
+    let mut uncontested = 0;
+    let results = run_budget(budget, trace, |trials| {
+        let outcome = opponent::monte_carlo_vs_model_with_rng(hand, 1, board, trials, model, rng);
+        uncontested += outcome.uncontested;
+        outcome.results
+    });
+    opponent::ModelResults { results, uncontested }
+}
+
+// Trials between the points of a convergence trace, unless --trace-every says otherwise
+const TRACE_EVERY: usize = 1000;
+
+// Runs the budget through the convergence trace when --trace asked for one
+fn run_budget<F>(budget: TrialBudget, trace: Option<&mut card::ConvergenceTrace>, simulate: F) -> SimulationResults
+where
+    F: FnMut(usize) -> SimulationResults,
+{
+    match trace {
+        Some(trace) => trace.run(budget, simulate),
+        None => budget.run(simulate),
+    }
+}
+
+// What a time budget bought: the 95% intervals at the number of trials it fit
+fn print_confidence(results: &SimulationResults) {
+    let (win_low, win_high) = results.win_rate_ci();
+    let (equity_low, equity_high) = results.equity_ci();
+    println!("95% confidence: win {:.2}-{:.2}%, equity {:.2}-{:.2}% (±{:.2}%)", win_low, win_high, equity_low, equity_high, (equity_high - equity_low) / 2.0);
+}
+
+fn confidence_fields(results: &SimulationResults) -> Vec<(String, json::JsonValue)> {
+    let (win_low, win_high) = results.win_rate_ci();
+    let (equity_low, equity_high) = results.equity_ci();
+    [("win_rate_ci_low", win_low), ("win_rate_ci_high", win_high), ("equity_ci_low", equity_low), ("equity_ci_high", equity_high)].into_iter()
+        .map(|(name, value)| (name.to_string(), json::JsonValue::Number(value)))
+        .collect()
+}
+
+fn print_stratified(outcome: &range::StratifiedResults) {
+    if !outcome.weighted {
+        println!("\nStratified sampling skipped: too few villain combos or simulations for a pilot, sampled plainly");
+        return;
+    }
+    let (low, high) = outcome.equity_ci;
+    println!("\n=== Stratified Sampling ===");
+    println!("Pilot deals: {}", outcome.pilot_trials);
+    println!("Worth {:.0} plain deals ({:.1}x the ones played)", outcome.equivalent_trials, outcome.equivalent_trials / outcome.results.total_games.max(1) as f64);
+    println!("95% confidence: equity {:.2}-{:.2}% (±{:.2}%)", low, high, (high - low) / 2.0);
+}
+
+fn stratified_fields(outcome: &range::StratifiedResults) -> Vec<(String, json::JsonValue)> {
+    vec![
+        ("stratified_sampling".to_string(), json::JsonValue::Bool(outcome.weighted)),
+        ("pilot_simulations".to_string(), json::JsonValue::Number(outcome.pilot_trials as f64)),
+        ("equivalent_simulations".to_string(), json::JsonValue::Number(outcome.equivalent_trials.round())),
+        ("equity_ci_low".to_string(), json::JsonValue::Number(outcome.equity_ci.0)),
+        ("equity_ci_high".to_string(), json::JsonValue::Number(outcome.equity_ci.1)),
+    ]
+}
+
+// Splits `num_sims` across the villain's combos (at least 100 each) and prints how
+// the range is spread across 10% equity bands
+fn print_equity_distribution(hand: &[Card; 2], villain_range: &range::Range, board: &[Card], num_sims: usize, rng: &mut rng::SimRng) {
+    let mut dead_cards = hand.to_vec();
+    dead_cards.extend_from_slice(board);
+    let available = villain_range.available(&dead_cards).len();
+    if available == 0 {
+        println!("Every combo in the villain range is blocked by known cards");
+        return;
+    }
+    
+    let per_combo = (num_sims / available).max(100);
+    println!("Villain range: {} combos after card removal", available);
+    println!("\nRunning {} simulations against each combo...", per_combo);
+    
+    let start_time = Instant::now();
+    let distribution = range::equity_distribution_with_rng(hand, villain_range, board, per_combo, rng);
+    
+    println!("\n=== Equity Distribution ===");
+    println!("{:<10} {:>7}", "Equity", "Range%");
+    for (i, share) in distribution.buckets.iter().enumerate() {
+        let bar = "#".repeat((share / 2.0).round() as usize);
+        println!("{:<10} {:>6.1}% {}", format!("{}-{}%", i * 10, (i + 1) * 10), share, bar);
+    }
+    println!("\nAverage equity: {:.2}%", distribution.average_equity());
+    if let (Some(worst), Some(best)) = (distribution.combos.first(), distribution.combos.last()) {
+        println!("Worst matchup: {} ({:.1}%)", format_cards(&worst.combo), worst.equity);
+        println!("Best matchup: {} ({:.1}%)", format_cards(&best.combo), best.equity);
+    }
+    println!("Time: {:.2}s", start_time.elapsed().as_secs_f64());
+}
+
+fn run_blockers(args: &[String]) {
+    let usage = "Usage: pokershit blockers <board> --range <villain range> [--top n], e.g. blockers Ks9s4s --range btn-open";
+    let mut board = Vec::new();
+    let mut villain_range = None;
+    let mut top = 5;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--range" => iter.next().ok_or("--range requires a range".to_string())
+                .and_then(|v| range::Range::parse(v)).map(|r| villain_range = Some(r)),
+            "--top" => parse_simulations(iter.next()).map(|n| top = n),
+            _ => parse_cards(arg).map(|cards| board.extend(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let villain_range = match villain_range {
+        Some(villain_range) => villain_range,
+        None => {
+            println!("Enter the villain range\n{}", usage);
+            return;
+        }
+    };
+    let reports = match range::blocker_report(&villain_range, &board, card::HandRank::TwoPair) {
+        Ok(reports) => reports,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Blockers ===");
+    println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    if reports.is_empty() {
+        println!("The villain range has no two pair or better on this board");
+    }
+    for report in reports {
+        println!("\n{} ({:.1} combos)", report.category, report.total_weight);
+        for blocker in report.blockers.iter().take(top) {
+            println!("  Holding the {} removes {:.0}% of them ({:.1} combos)", blocker.card, blocker.removed_pct, blocker.removed);
+        }
+    }
+}
+
+fn run_outs(args: &[String]) {
+    let usage = "Usage: pokershit outs <your hand> <board> (--vs <villain hand> | --target <hand category>), e.g. outs AhKh Qh7h2c --vs QcJd";
+    let mut card_args = Vec::new();
+    let mut target = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--vs" => iter.next().ok_or("--vs requires a hand".to_string())
+                .and_then(|v| parse_cards(v))
+                .and_then(|cards| cards.try_into().map_err(|_| "--vs takes two cards".to_string()))
+                .map(|hand| target = Some(postflop::OutsTarget::BeatHand(hand))),
+            "--target" => iter.next().ok_or("--target requires a hand category".to_string())
+                .and_then(|v| v.parse::<card::HandRank>())
+                .map(|rank| target = Some(postflop::OutsTarget::Reach(rank))),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let (hand, target): ([Card; 2], _) = match (card_args.first().map(|cards| cards.clone().try_into()), target) {
+        (Some(Ok(hand)), Some(target)) => (hand, target),
+        _ => {
+            println!("Enter your hand and either --vs or --target\n{}", usage);
+            return;
+        }
+    };
+    let board: Vec<Card> = card_args.iter().skip(1).flatten().cloned().collect();
+    
+    let outs = match postflop::find_outs(&hand, &board, &target) {
+        Ok(outs) => outs,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    
+    println!("\n=== Outs ===");
+    println!("Your hand: {}", format_cards(&hand));
+    println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    println!("Made hand: {}", postflop::analyze_draws(&hand, &board));
+    match &target {
+        postflop::OutsTarget::BeatHand(villain) => println!("Target: beat {}", format_cards(villain)),
+        postflop::OutsTarget::Reach(rank) => println!("Target: {} or better", rank),
+    }
+    println!("\nClean outs ({}): {}", outs.clean.len(), format_cards(&outs.clean));
+    println!("Tainted outs ({}): {}", outs.tainted.len(), format_cards(&outs.tainted));
+    println!("Total: {} of {} unseen cards", outs.count(), outs.unseen);
+    println!("Next card: {:.1}%", outs.next_card_probability());
+    if board.len() == 3 {
+        println!("By the river: {:.1}%", outs.by_river_probability(board.len()));
+    }
+}
+
+// Splits `num_sims` across the possible turn cards (at least 200 each) and lists
+// hero's equity on each, best first
+fn print_turn_card_equities(hand: &[Card; 2], villain_range: &range::Range, flop: &[Card], num_sims: usize, rng: &mut rng::SimRng) {
+    let per_card = (num_sims / 47).max(200);
+    println!("\nRunning {} simulations for each turn card...", per_card);
+    
+    let start_time = Instant::now();
+    let equities = match postflop::turn_card_equities_with_rng(hand, villain_range, flop, per_card, rng) {
+        Ok(equities) => equities,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    
+    println!("\n=== Equity by Turn Card ===");
+    for (rank, turn) in equities.iter().enumerate() {
+        let bar = "#".repeat((turn.equity / 2.0).round() as usize);
+        println!("{:<3} {:<4} {:>6.2}% {}", rank + 1, turn.card, turn.equity, bar);
+    }
+    let average = equities.iter().map(|turn| turn.equity).sum::<f64>() / equities.len().max(1) as f64;
+    println!("\nAverage equity: {:.2}%", average);
+    println!("Time: {:.2}s", start_time.elapsed().as_secs_f64());
+}
+
+fn run_multiway(args: &[String]) {
+    let usage = "Usage: pokershit multiway <your hand> [--max n] [-n simulations] [-o output.csv] [--rng <small|chacha|os>[:seed]], e.g. multiway AhKh --max 9";
+    let mut hand = None;
+    let mut max_opponents = 9;
+    let mut num_sims = 10000;
+    let mut output = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--max" => parse_simulations(iter.next()).map(|n| max_opponents = n),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "-o" | "--output" => iter.next().ok_or("-o requires a file name".to_string()).map(|f| output = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => parse_cards(arg)
+                .and_then(|cards| cards.try_into().map_err(|_| "Enter exactly two hole cards".to_string()))
+                .map(|cards: [Card; 2]| hand = Some(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let hand = match hand {
+        Some(hand) if (1..=9).contains(&max_opponents) => hand,
+        _ => {
+            println!("Enter your hole cards and between 1 and 9 opponents\n{}", usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Equity vs 1-{} Opponents ===", max_opponents);
+    println!("Your hand: {}", format_cards(&hand));
+    println!("\nRunning {} simulations per table size...", num_sims);
+    
+    let start_time = Instant::now();
+    let curve = card::equity_curve_with_rng(&hand, max_opponents, num_sims, &mut rng_choice.build());
+    
+    println!("\n{:<10} {:>8} {:>11} {:>8}", "Opponents", "Equity", "Fair share", "Scoop");
+    for point in &curve {
+        println!("{:<10} {:>7.2}% {:>10.2}% {:>7.2}%",
+                 point.opponents,
+                 point.results.equity,
+                 100.0 / (point.opponents + 1) as f64,
+                 point.results.scoop_rate());
+    }
+    println!("\nTime: {:.2}s", start_time.elapsed().as_secs_f64());
+    
+    if let Some(output) = output {
+        if let Err(e) = card::export_equity_curve_csv(&curve, &output) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+}
+
+fn run_compare(args: &[String]) {
+    let usage = "Usage: pokershit compare <hand> <hand> [board] [--sample n], e.g. compare AhAd KsKd";
+    let mut card_args = Vec::new();
+    let mut sample = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--sample" => parse_simulations(iter.next()).map(|n| sample = Some(n)),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let hands: Vec<[Card; 2]> = card_args.iter().take(2).filter_map(|cards| cards.clone().try_into().ok()).collect();
+    if hands.len() < 2 {
+        println!("Enter two hands of two cards each\n{}", usage);
+        return;
+    }
+    let board: Vec<Card> = card_args.iter().skip(2).flatten().cloned().collect();
+    if board.len() > 5 {
+        println!("The board has at most five cards");
+        return;
+    }
+    
+    println!("\n=== Head to Head ===");
+    println!("Hand A: {}", format_cards(&hands[0]));
+    println!("Hand B: {}", format_cards(&hands[1]));
+    if !board.is_empty() {
+        println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    }
+    
+    let mut all_cards: Vec<&Card> = hands.iter().flatten().chain(board.iter()).collect();
+    all_cards.sort_by_key(|card| card.index());
+    if all_cards.windows(2).any(|pair| pair[0] == pair[1]) {
+        println!("The same card appears more than once");
+        return;
+    }
+    
+    let start_time = Instant::now();
+    let results = match sample {
+        Some(num_trials) => {
+            println!("\nSampling {} boards...", num_trials);
+            card::monte_carlo_heads_up(&hands[0], &hands[1], &board, num_trials)
+        }
+        None => {
+            println!("\nEnumerating every board...");
+            card::exact_heads_up(&hands[0], &hands[1], &board)
+        }
+    };
+    let duration = start_time.elapsed();
+    
+    let lose_rate = 100.0 - results.win_rate - results.tie_rate;
+    println!("\n{:<8} {:>10} {:>8} {:>10} {:>8} {:>10} {:>8} {:>8}", "Hand", "Wins", "Win%", "Losses", "Lose%", "Ties", "Tie%", "Equity");
+    println!("{:<8} {:>10} {:>7.3}% {:>10} {:>7.3}% {:>10} {:>7.3}% {:>7.3}%",
+             "A", results.wins, results.win_rate, results.losses, lose_rate, results.ties, results.tie_rate,
+             results.win_rate + results.tie_rate / 2.0);
+    println!("{:<8} {:>10} {:>7.3}% {:>10} {:>7.3}% {:>10} {:>7.3}% {:>7.3}%",
+             "B", results.losses, lose_rate, results.wins, results.win_rate, results.ties, results.tie_rate,
+             lose_rate + results.tie_rate / 2.0);
+    println!("\nBoards: {}", results.total_games);
+    println!("Time: {:.2}s", duration.as_secs_f64());
+}
+
+// Each hand plays its own run against the villain, or both come from a bulk
+// results file, and the gap between their win rates is tested against noise
+fn run_compare_stats(args: &[String]) {
+    let usage = "Usage: pokershit compare-stats <hand> <hand> ([board] [--range <villain range>] [-n simulations] [--rng <small|chacha|os>[:seed]] | --results <results.csv|results.json>), e.g. compare-stats AhKh AhQh -n 20000 or compare-stats AKo AQs --results poker_results.csv";
+    let mut hand_args = Vec::new();
+    let mut range_text = None;
+    let mut results_file = None;
+    let mut num_sims = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--range" => iter.next().ok_or("--range requires a range".to_string())
+                .map(|v| range_text = Some(v.clone())),
+            "--results" => iter.next().ok_or("--results requires a results file".to_string())
+                .map(|f| results_file = Some(f.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => {
+                hand_args.push(arg.clone());
+                Ok(())
+            },
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    if hand_args.len() < 2 {
+        println!("Enter two hands to compare\n{}", usage);
+        return;
+    }
+    
+    println!("\n=== Win Rate Comparison ===");
+    let (a, b) = if let Some(file) = &results_file {
+        if hand_args.len() > 2 || range_text.is_some() {
+            println!("--results cannot be combined with a board or --range\n{}", usage);
+            return;
+        }
+        let results = match import_results(file) {
+            Ok(results) => results,
+            Err(e) => {
+                println!("Error reading {}: {}", file, e);
+                return;
+            }
+        };
+        // "AKs" sums every combo of the class; "AhKh" picks the one combo
+        let find = |name: &str| {
+            let matches = |result: &&card::HandResult| match parse_cards(name) {
+                Ok(cards) => cards.len() == 2 && cards.iter().all(|card| result.hand.contains(card)),
+                Err(_) => range::hand_notation(&result.hand).eq_ignore_ascii_case(name),
+            };
+            results.iter().filter(matches).map(|result| result.results.clone())
+                .reduce(|total, results| total.combine(&results))
+                .ok_or(format!("{} has no results for {}", file, name))
+        };
+        match (find(&hand_args[0]), find(&hand_args[1])) {
+            (Ok(a), Ok(b)) => {
+                println!("Results: {}", file);
+                (a, b)
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                println!("{}", e);
+                return;
+            }
+        }
+    } else {
+        let mut hands = Vec::new();
+        for text in &hand_args[..2] {
+            match parse_cards(text).map(<[Card; 2]>::try_from) {
+                Ok(Ok(hand)) => hands.push(hand),
+                _ => {
+                    println!("'{}' is not a two-card hand; hand classes like AKs need --results\n{}", text, usage);
+                    return;
+                }
+            }
+        }
+        let mut board = Vec::new();
+        for text in &hand_args[2..] {
+            match parse_cards(text) {
+                Ok(cards) => board.extend(cards),
+                Err(e) => {
+                    println!("{}\n{}", e, usage);
+                    return;
+                }
+            }
+        }
+        if board.len() > 5 {
+            println!("The board has at most five cards");
+            return;
+        }
+        for hand in &hands {
+            if let Err(e) = card::check_distinct(hand.iter().chain(board.iter())) {
+                println!("{}", e);
+                return;
+            }
+        }
+        let villain_range = match range_text.as_deref().map(range::Range::parse).transpose() {
+            Ok(villain_range) => villain_range,
+            Err(e) => {
+                println!("{}\n{}", e, usage);
+                return;
+            }
+        };
+        
+        println!("Villain: {}", range_text.as_deref().unwrap_or("random hand"));
+        if !board.is_empty() {
+            println!("Board: {}{}", format_cards(&board), texture_label(&board));
+        }
+        println!("\nRunning {} simulations for each hand...", num_sims);
+        let mut rng = rng_choice.build();
+        let mut run = |hand: &[Card; 2]| match &villain_range {
+            Some(villain_range) => range::monte_carlo_vs_range_with_rng(hand, villain_range, &board, num_sims, &mut rng),
+            None => monte_carlo_with_community_with_rng(hand, &board, num_sims, &mut rng),
+        };
+        let (a, b) = (run(&hands[0]), run(&hands[1]));
+        if a.total_games == 0 || b.total_games == 0 {
+            println!("Every combo in the villain range is blocked for one of the hands");
+            return;
+        }
+        (a, b)
+    };
+    
+    println!("\n{:<8} {:>10} {:>8} {:>16}", "Hand", "Games", "Win%", "95% CI");
+    for (name, results) in [(&hand_args[0], &a), (&hand_args[1], &b)] {
+        let (low, high) = results.win_rate_ci();
+        println!("{:<8} {:>10} {:>7.2}% {:>7.2}-{:.2}%", name, results.total_games, results.win_rate, low, high);
+    }
+    let comparison = a.compare_win_rate(&b);
+    println!("\nDifference: {:+.2} points (95% CI {:+.2} to {:+.2})", comparison.difference, comparison.ci.0, comparison.ci.1);
+    println!("z = {:.2}, p = {:.4}", comparison.z, comparison.p_value);
+    if comparison.significant(0.05) {
+        let (better, worse) = if comparison.difference > 0.0 { (&hand_args[0], &hand_args[1]) } else { (&hand_args[1], &hand_args[0]) };
+        println!("Significant at the 5% level: {} wins more often than {}", better, worse);
+    } else {
+        println!("Not significant at the 5% level: at these trial counts the gap could be noise");
+    }
+}
+
+fn run_matrix(args: &[String]) {
+    let usage = "Usage: pokershit matrix [-n simulations per matchup] [--threads n] [-o output prefix]";
+    let mut num_sims = 1000;
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut prefix = format!("equity_matrix_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--threads" => parse_simulations(iter.next()).map(|n| threads = n),
+            "-o" | "--output" => iter.next().ok_or("-o requires a file prefix".to_string()).map(|p| prefix = p.clone()),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    println!("\n=== 169x169 Preflop Equity Matrix ===");
+    println!("Simulating 14,365 matchups ({} simulations per distinct suit pattern) on {} threads...", num_sims, threads);
+    
+    let start_time = Instant::now();
+    let equity_matrix = matrix::compute_equity_matrix(num_sims, threads);
+    println!("Completed in {:.2}s", start_time.elapsed().as_secs_f64());
+    
+    if let Err(e) = matrix::export_matrix_csv(&equity_matrix, &format!("{}.csv", prefix)) {
+        println!("Error exporting to CSV: {}", e);
+    }
+    #[cfg(feature = "parquet")]
+    if let Err(e) = matrix::export_matrix_parquet(&equity_matrix, &format!("{}.parquet", prefix), num_sims) {
+        println!("Error exporting to Parquet: {}", e);
+    }
+}
+
+fn run_bench(args: &[String]) {
+    let usage = "Usage: pokershit bench [-n simulations] [--evaluations n] [--threads n] [--rng <small|chacha|os>[:seed]], e.g. bench --threads 4 --rng small";
+    let mut num_sims = 200_000;
+    let mut num_evaluations = 1_000_000;
+    let mut max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut rng_choice = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--evaluations" => parse_simulations(iter.next()).map(|n| num_evaluations = n),
+            "--threads" => parse_simulations(iter.next()).map(|n| max_threads = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = Some(choice)),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    // Without --rng every generator is measured, each unseeded
+    let rng_choices: Vec<rng::RngChoice> = match rng_choice {
+        Some(choice) => vec![choice],
+        None => rng::RngKind::ALL.iter().map(|&kind| rng::RngChoice { kind, seed: None }).collect(),
+    };
+    
+    println!("\n=== Benchmark ===");
+    println!("Evaluator backend: {}", card::EVALUATOR_BACKEND);
+    println!("Available threads: {}", std::thread::available_parallelism().map_or(1, |n| n.get()));
+    println!("Evaluating {} hands, then {} heads-up games per configuration...", num_evaluations, num_sims);
+    
+    let mut results = bench::bench_evaluators(num_evaluations, rng_choices[0]);
+    for &choice in &rng_choices {
+        for threads in bench::thread_counts(max_threads) {
+            results.push(bench::bench_simulations(num_sims, choice, threads));
+        }
+    }
+    
+    // Each benchmark is compared with its own first configuration
+    println!("\n{:<12} {:<22} {:>7} {:>12} {:>9} {:>14} {:>8}", "Benchmark", "Configuration", "Threads", "Operations", "Time", "Per second", "Speedup");
+    for (i, result) in results.iter().enumerate() {
+        let baseline = results.iter().find(|r| r.benchmark == result.benchmark).unwrap_or(result);
+        let speedup = if baseline.per_second() > 0.0 { result.per_second() / baseline.per_second() } else { 0.0 };
+        if i > 0 && results[i - 1].benchmark != result.benchmark {
+            println!();
+        }
+        println!("{:<12} {:<22} {:>7} {:>12} {:>8.3}s {:>14.0} {:>7.2}x",
+                 result.benchmark, result.configuration, result.threads, result.operations,
+                 result.elapsed.as_secs_f64(), result.per_second(), speedup);
+    }
+    
+    let best = results.iter()
+        .filter(|r| r.benchmark == "simulation")
+        .max_by(|a, b| a.per_second().total_cmp(&b.per_second()));
+    if let Some(best) = best {
+        println!("\nFastest simulation setup: {} on {} thread{} ({:.0} games/s)",
+                 best.configuration, best.threads, if best.threads == 1 { "" } else { "s" }, best.per_second());
+    }
+}
+
+fn run_pot_odds(args: &[String]) {
+    let usage = "Usage: pokershit potodds <pot> <to call> (--equity <percent> | <your hand> [board] [--range <villain range>]) [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. potodds 150 50 AhKh Qh7c2d --range \"QQ+,AK\"";
+    let mut amounts = Vec::new();
+    let mut card_args = Vec::new();
+    let mut equity = None;
+    let mut range_text = None;
+    let mut num_sims = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--equity" => parse_percent(iter.next()).map(|e| equity = Some(e)),
+            "--range" => iter.next().ok_or("--range requires a range".to_string())
+                .map(|v| range_text = Some(v.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ if amounts.len() < 2 => arg.parse::<f64>()
+                .map(|amount| amounts.push(amount))
+                .map_err(|_| format!("Invalid amount '{}'", arg)),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let (pot, to_call) = match amounts[..] {
+        [pot, to_call] => (pot, to_call),
+        _ => {
+            println!("Enter the pot and the amount to call\n{}", usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Pot Odds ===");
+    let equity = match (equity, card_args.is_empty()) {
+        (Some(equity), _) => equity,
+        (None, false) => match simulated_equity(&card_args, range_text.as_deref(), num_sims, rng_choice) {
+            Ok(equity) => equity,
+            Err(e) => {
+                println!("{}\n{}", e, usage);
+                return;
+            }
+        },
+        (None, true) => {
+            println!("Give your equity with --equity, or your hand to simulate it\n{}", usage);
+            return;
+        }
+    };
+    
+    let analysis = match strategy::analyze_call(pot, to_call, equity) {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    println!("Pot: {}  To call: {}", analysis.pot, analysis.to_call);
+    println!("Pot odds: {:.2} to 1", analysis.odds_ratio);
+    println!("Required equity: {:.2}%", analysis.required_equity * 100.0);
+    println!("Your equity: {:.2}%", analysis.equity * 100.0);
+    println!("EV of calling: {:+.2}", analysis.ev);
+    println!("Verdict: {}", analysis.verdict);
+}
+
+fn run_shove(args: &[String]) {
+    let usage = "Usage: pokershit shove <pot> <effective stack> --fold <percent> (--equity <percent> | <your hand> [board] [--range <calling range>]) [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. shove 30 100 Jh9h Th8c2h --fold 40 --range \"TT+,AT+,KTs+\"";
+    let mut amounts = Vec::new();
+    let mut card_args = Vec::new();
+    let mut fold_frequency = None;
+    let mut equity = None;
+    let mut range_text = None;
+    let mut num_sims = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--fold" => parse_percent(iter.next()).map(|f| fold_frequency = Some(f)),
+            "--equity" => parse_percent(iter.next()).map(|e| equity = Some(e)),
+            "--range" => iter.next().ok_or("--range requires a range".to_string())
+                .map(|v| range_text = Some(v.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ if amounts.len() < 2 => arg.parse::<f64>()
+                .map(|amount| amounts.push(amount))
+                .map_err(|_| format!("Invalid amount '{}'", arg)),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let (pot, stack) = match amounts[..] {
+        [pot, stack] => (pot, stack),
+        _ => {
+            println!("Enter the pot and the effective stack\n{}", usage);
+            return;
+        }
+    };
+    let Some(fold_frequency) = fold_frequency else {
+        println!("Give villain's estimated fold frequency with --fold\n{}", usage);
+        return;
+    };
+    
+    println!("\n=== Shove EV ===");
+    let equity = match (equity, card_args.is_empty()) {
+        (Some(equity), _) => equity,
+        (None, false) => match simulated_equity(&card_args, range_text.as_deref(), num_sims, rng_choice) {
+            Ok(equity) => equity,
+            Err(e) => {
+                println!("{}\n{}", e, usage);
+                return;
+            }
+        },
+        (None, true) => {
+            println!("Give your equity when called with --equity, or your hand to simulate it\n{}", usage);
+            return;
+        }
+    };
+    
+    let analysis = match strategy::analyze_shove(pot, stack, fold_frequency, equity) {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    println!("Pot: {}  Effective stack: {}", analysis.pot, analysis.effective_stack);
+    println!("Villain folds: {:.1}%", analysis.fold_frequency * 100.0);
+    println!("Equity when called: {:.2}%", analysis.equity_when_called * 100.0);
+    println!("EV when called: {:+.2}", analysis.ev_when_called);
+    println!("EV of jamming: {:+.2}", analysis.ev_shove);
+    println!("EV of checking: {:+.2}", analysis.ev_check);
+    println!("Break-even fold frequency: {:.1}%", analysis.break_even_fold * 100.0);
+    let verdict = match analysis.ev_shove - analysis.ev_check {
+        gain if gain > 0.0 => format!("Jam (+{:.2} over checking)", gain),
+        gain if gain < 0.0 => format!("Check (jamming loses {:.2})", -gain),
+        _ => "Indifferent".to_string(),
+    };
+    println!("Verdict: {}", verdict);
+}
+
+fn run_bankroll(args: &[String]) {
+    let usage = "Usage: pokershit bankroll <win rate bb/100> <std dev bb/100> [--fraction f] [--buyin bb] [--bankroll amount], e.g. bankroll 5 80 --fraction 0.5 --bankroll 2000";
+    let mut amounts = Vec::new();
+    let mut fraction = None;
+    let mut buy_in = 100.0;
+    let mut bankroll = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--fraction" => parse_amount(iter.next(), "--fraction").map(|f| fraction = Some(f)),
+            "--buyin" => parse_amount(iter.next(), "--buyin").map(|b| buy_in = b),
+            "--bankroll" => parse_amount(iter.next(), "--bankroll").map(|b| bankroll = Some(b)),
+            _ if amounts.len() < 2 => arg.parse::<f64>()
+                .map(|amount| amounts.push(amount))
+                .map_err(|_| format!("Invalid number '{}'", arg)),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let (win_rate, std_dev) = match amounts[..] {
+        [win_rate, std_dev] => (win_rate, std_dev),
+        _ => {
+            println!("Enter your win rate and standard deviation in bb/100\n{}", usage);
+            return;
+        }
+    };
+    
+    let mut fractions = bankroll::COMMON_KELLY_FRACTIONS.to_vec();
+    if let Some(fraction) = fraction.filter(|f| !fractions.contains(f)) {
+        fractions.push(fraction);
+    }
+    let sizings: Result<Vec<bankroll::KellySizing>, String> = fractions.iter()
+        .map(|&fraction| bankroll::kelly_sizing(win_rate, std_dev, fraction, buy_in))
+        .collect();
+    let sizings = match sizings {
+        Ok(sizings) => sizings,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Bankroll (Kelly) ===");
+    println!("Win rate: {} bb/100  Standard deviation: {} bb/100  Buy-in: {} bb", win_rate, std_dev, buy_in);
+    print!("\n{:>8} {:>12} {:>10} {:>14}", "Kelly", "Bankroll bb", "Buy-ins", "Risk of ruin");
+    if bankroll.is_some() {
+        print!(" {:>14}", "Max big blind");
+    }
+    println!();
+    for sizing in &sizings {
+        let marker = if Some(sizing.fraction) == fraction { " <" } else { "" };
+        print!("{:>7.0}% {:>12.0} {:>10.1} {:>13.2}%", sizing.fraction * 100.0, sizing.bankroll_bb, sizing.buy_ins, sizing.risk_of_ruin * 100.0);
+        if let Some(bankroll) = bankroll {
+            match bankroll::max_big_blind(bankroll, win_rate, std_dev, sizing.fraction) {
+                Ok(big_blind) => print!(" {:>14.2}", big_blind),
+                Err(e) => print!(" {}", e),
+            }
+        }
+        println!("{}", marker);
+    }
+    println!("\nFull Kelly grows the bankroll fastest but swings hard; most players use half Kelly or less.");
+}
+
+fn run_ruin(args: &[String]) {
+    let usage = "Usage: pokershit ruin <win rate bb/100> <std dev bb/100> <bankroll bb> [--hands n] [--trials n] [--rng <small|chacha|os>[:seed]], e.g. ruin 5 80 2500 --hands 200000";
+    let mut amounts = Vec::new();
+    let mut hands = 100_000;
+    let mut trials = 10_000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--hands" => parse_simulations(iter.next()).map(|n| hands = n),
+            "--trials" => parse_simulations(iter.next()).map(|n| trials = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ if amounts.len() < 3 => arg.parse::<f64>()
+                .map(|amount| amounts.push(amount))
+                .map_err(|_| format!("Invalid number '{}'", arg)),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let (win_rate, std_dev, bankroll_bb) = match amounts[..] {
+        [win_rate, std_dev, bankroll_bb] => (win_rate, std_dev, bankroll_bb),
+        _ => {
+            println!("Enter your win rate and standard deviation in bb/100 and your bankroll in big blinds\n{}", usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Risk of Ruin ===");
+    println!("Win rate: {} bb/100  Standard deviation: {} bb/100  Bankroll: {} bb", win_rate, std_dev, bankroll_bb);
+    println!("Simulating {} careers of {} hands...", trials, hands);
+    let start_time = Instant::now();
+    let simulation = match bankroll::simulate_bankroll_with_rng(win_rate, std_dev, bankroll_bb, hands as u64, trials, &mut rng_choice.build()) {
+        Ok(simulation) => simulation,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    println!("\nRisk of ruin within {} hands: {:.2}%", simulation.hands, simulation.risk_of_ruin * 100.0);
+    println!("Risk of ruin playing forever (formula): {:.2}%", bankroll::risk_of_ruin(win_rate, std_dev, bankroll_bb) * 100.0);
+    
+    println!("\nLargest downswing (bb):");
+    println!("  Mean: {:.0}", simulation.mean_max_downswing());
+    for (label, fraction) in [("Median", 0.5), ("90th percentile", 0.9), ("99th percentile", 0.99)] {
+        if let Some(downswing) = bankroll::percentile(&simulation.max_downswings, fraction) {
+            println!("  {}: {:.0}", label, downswing);
+        }
+    }
+    
+    println!("\nDoubled the bankroll: {:.1}% of careers", simulation.doubled() * 100.0);
+    for (label, fraction) in [("Fastest quarter", 0.25), ("Median", 0.5), ("Slowest quarter", 0.75)] {
+        if let Some(hands) = bankroll::percentile(&simulation.hands_to_double, fraction) {
+            println!("  {}: {} hands", label, hands);
+        }
+    }
+    println!("\nTime: {:.2}s", start_time.elapsed().as_secs_f64());
+}
+
+fn run_icm(args: &[String]) {
+    let usage = "Usage: pokershit icm <stacks> <payouts>, comma separated, e.g. icm 5000,3000,2000,1500 50,30,20";
+    let lists: Result<Vec<Vec<f64>>, String> = args.iter().map(|arg| parse_number_list(arg)).collect();
+    let (stacks, payouts) = match lists.as_deref() {
+        Ok([stacks, payouts]) => (stacks, payouts),
+        Ok(_) => {
+            println!("Enter the chip stacks and the payouts\n{}", usage);
+            return;
+        },
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    let equities = match icm::icm_equities(stacks, payouts) {
+        Ok(equities) => equities,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    let total_chips: f64 = stacks.iter().sum();
+    let prize_pool: f64 = payouts.iter().sum();
+    println!("\n=== ICM Equity ===");
+    println!("{} players, {} chips, prize pool {} paid to {} places", stacks.len(), total_chips, prize_pool, payouts.len());
+    println!("\n{:<8} {:>12} {:>8} {:>12} {:>8} {:>12}", "Player", "Stack", "Chips%", "ICM equity", "Prize%", "Per 1k chips");
+    for (i, (&stack, &equity)) in stacks.iter().zip(&equities).enumerate() {
+        let per_thousand = if stack > 0.0 { equity / stack * 1000.0 } else { 0.0 };
+        println!("{:<8} {:>12} {:>7.2}% {:>12.2} {:>7.2}% {:>12.3}",
+                 i + 1, stack, stack / total_chips * 100.0, equity, equity / prize_pool * 100.0, per_thousand);
+    }
+}
+
+fn run_push_fold(args: &[String]) {
+    let usage = "Usage: pokershit pushfold <stacks bb> [--ante bb] [--payouts <payouts>] [--iterations n] [--matrix <equity_matrix.csv> | -n simulations per matchup] [--threads n], e.g. pushfold 10 or pushfold 12,20,30,38 --ante 0.1 --payouts 50,30,20";
+    let mut stacks = None;
+    let mut ante = 0.0;
+    let mut payouts = None;
+    let mut iterations = 10000;
+    let mut matrix_file = None;
+    let mut num_sims = 1000;
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--ante" => parse_amount(iter.next(), "--ante").map(|a| ante = a),
+            "--payouts" => iter.next().ok_or("--payouts requires a comma separated list".to_string())
+                .and_then(|p| parse_number_list(p))
+                .map(|p| payouts = Some(p)),
+            "--iterations" => parse_simulations(iter.next()).map(|n| iterations = n),
+            "--matrix" => iter.next().ok_or("--matrix requires a matrix CSV file".to_string())
+                .map(|f| matrix_file = Some(f.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--threads" => parse_simulations(iter.next()).map(|n| threads = n),
+            _ if stacks.is_none() => parse_number_list(arg).map(|s| stacks = Some(s)),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    // A single stack is the effective stack for both blinds
+    let stacks = match stacks {
+        Some(stacks) if stacks.len() == 1 => vec![stacks[0]; 2],
+        Some(stacks) => stacks,
+        None => {
+            println!("Enter the stacks in big blinds, small blind first\n{}", usage);
+            return;
+        }
+    };
+    let payoffs = match &payouts {
+        Some(payouts) => pushfold::icm_payoffs(&stacks, ante, payouts),
+        None => pushfold::chip_payoffs(&stacks, ante),
+    };
+    let payoffs = match payoffs {
+        Ok(payoffs) => payoffs,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+
+    let equity_matrix = match load_equity_matrix(matrix_file.as_deref(), num_sims, threads) {
+        Ok(equity_matrix) => equity_matrix,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let start_time = Instant::now();
+    let solution = match pushfold::solve_push_fold(&equity_matrix, &payoffs, iterations) {
+        Ok(solution) => solution,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+
+    let units = if payouts.is_some() { "" } else { " bb" };
+    println!("\n=== Push/Fold Equilibrium ===");
+    println!("Small blind {} bb vs big blind {} bb, blinds {}/{}, ante {} bb", stacks[0], stacks[1], pushfold::SMALL_BLIND, pushfold::BIG_BLIND, ante);
+    match &payouts {
+        Some(payouts) => println!("ICM with {} players, payouts {}", stacks.len(), payouts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("/")),
+        None => println!("Chip EV"),
+    }
+    println!("{} iterations of fictitious play in {:.2}s", iterations, start_time.elapsed().as_secs_f64());
+
+    let or_nothing = |range: String| if range.is_empty() { "(nothing)".to_string() } else { range };
+    println!("\nSmall blind jams {:.1}% of hands:", solution.jam_percent());
+    println!("  {}", or_nothing(solution.jam_range(0.05)));
+    println!("Big blind calls {:.1}% of hands:", solution.call_percent());
+    println!("  {}", or_nothing(solution.call_range(0.05)));
+    println!("\nSmall blind EV: {:+.4}{}  Big blind EV: {:+.4}{}", solution.sb_value, units, solution.bb_value, units);
+    println!("Exploitability: {:.5}{} (run more iterations to lower it)", solution.exploitability, units);
+}
+
+fn run_tournament(args: &[String]) {
+    let usage = "Usage: pokershit tournament [--strategy <strategy>] [--field <strategies>] [--players n] [--table-size n] [--stack chips] [--levels <sb/bb[/ante],...>] [--hands-per-level n] [--payouts <percents>] [-n tournaments] [--history <hands.txt|hands.phhs>] [--rng <small|chacha|os>[:seed]], e.g. tournament --strategy lag --field tag,nit,station --players 18 --payouts 40,25,15,12,8";
+    let strategy_names = tournament::STRATEGY_PRESETS.iter().map(|(name, ..)| *name).collect::<Vec<_>>().join(", ");
+    let mut config = tournament::TournamentConfig::default();
+    let mut hero = tournament::BotStrategy::preset("tag").expect("tag is a preset");
+    let mut field = vec![hero.clone()];
+    let mut trials = 1000;
+    let mut history_file = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--strategy" => iter.next().ok_or("--strategy requires a strategy".to_string())
+                .and_then(|s| tournament::BotStrategy::parse(s))
+                .map(|s| hero = s),
+            "--field" => iter.next().ok_or("--field requires comma separated strategies".to_string())
+                .and_then(|f| f.split(',').map(|s| tournament::BotStrategy::parse(s.trim())).collect())
+                .map(|f| field = f),
+            "--players" => parse_simulations(iter.next()).map(|n| config.players = n),
+            "--table-size" => parse_simulations(iter.next()).map(|n| config.table_size = n),
+            "--stack" => parse_simulations(iter.next())
+                .and_then(|n| u32::try_from(n).map_err(|_| format!("Stack {} is too large", n)))
+                .map(|n| config.starting_stack = n),
+            "--levels" => iter.next().ok_or("--levels requires a blind schedule".to_string())
+                .and_then(|l| tournament::parse_levels(l))
+                .map(|l| config.levels = l),
+            "--hands-per-level" => parse_simulations(iter.next()).map(|n| config.hands_per_level = n),
+            "--payouts" => iter.next().ok_or("--payouts requires a comma separated list".to_string())
+                .and_then(|p| parse_number_list(p))
+                .map(|p| config.payouts = p),
+            "-n" | "--trials" => parse_simulations(iter.next()).map(|n| trials = n),
+            "--history" => iter.next().ok_or("--history requires a file name".to_string())
+                .map(|f| history_file = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\nStrategies: {}\n{}", e, strategy_names, usage);
+            return;
+        }
+    }
+    if let Err(e) = config.validate() {
+        println!("{}\n{}", e, usage);
+        return;
+    }
+
+    let describe = |s: &tournament::BotStrategy| {
+        let push_fold = if s.push_fold_below.is_infinite() { "always".to_string() } else { format!("at {} bb or less", s.push_fold_below) };
+        format!("{} (opens {:.0}%, defends {:.0}%, push/fold {}{})", s.name, s.open * 100.0, s.defend * 100.0, push_fold,
+                if s.calling_station { ", calls down" } else { "" })
+    };
+    println!("\n=== Tournament Simulation ===");
+    println!("{} players, {}-handed tables, {} chips each, {} hands per level", config.players, config.table_size, config.starting_stack, config.hands_per_level);
+    println!("Levels: {} (then doubling)", config.levels.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", "));
+    println!("Payouts: {}% of the prize pool", config.payouts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("/"));
+    println!("Strategy under test: {}", describe(&hero));
+    for strategy in &field {
+        println!("Field: {}", describe(strategy));
+    }
+    println!("Simulating {} tournaments using {} rng...", trials, rng_choice);
+
+    let start_time = Instant::now();
+    let mut rng = rng_choice.build();
+    let mut history = Vec::new();
+    let recorded = history_file.as_ref().map(|_| &mut history);
+    let reports = match tournament::simulate_tournaments_with_rng(&config, &hero, &field, trials, recorded, &mut rng) {
+        Ok(reports) => reports,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    println!("Completed in {:.2}s", start_time.elapsed().as_secs_f64());
+
+    let paid = config.payouts.len();
+    let tested = &reports[0];
+    println!("\nFinishes of the strategy under test:");
+    println!("{:<8} {:>8} {:>8} {:>10}", "Place", "Count", "Share", "Prize");
+    for (place, &count) in tested.finishes.iter().enumerate().take(paid) {
+        println!("{:<8} {:>8} {:>7.1}% {:>7.2} BI", place + 1, count, count as f64 / tested.entries as f64 * 100.0, config.prize(place + 1));
+    }
+    let unpaid: usize = tested.finishes.iter().skip(paid).sum();
+    println!("{:<8} {:>8} {:>7.1}%", "Unpaid", unpaid, unpaid as f64 / tested.entries as f64 * 100.0);
+
+    println!("\n{:<24} {:>8} {:>11} {:>8} {:>9}", "Strategy", "Entries", "Avg finish", "ITM", "ROI");
+    for report in &reports {
+        println!("{:<24} {:>8} {:>11.2} {:>7.1}% {:>+8.1}%",
+                 report.name, report.entries, report.average_finish(), report.in_the_money(paid) * 100.0, report.roi() * 100.0);
+    }
+
+    if let Some(file) = history_file {
+        println!("\n{} hands of the first tournament", history.len());
+        if let Err(e) = hand_history::export_hands(&history, &file) {
+            println!("Error exporting hand history: {}", e);
+        }
+    }
+}
+
+// The preflop equity matrix from a file saved by the matrix command, or simulated now
+fn load_equity_matrix(matrix_file: Option<&str>, num_sims: usize, threads: usize) -> Result<matrix::EquityMatrix, String> {
+    match matrix_file {
+        Some(file) => matrix::import_matrix_csv(file).map_err(|e| format!("Error reading {}: {}", file, e)),
+        None => {
+            println!("Simulating the preflop equity matrix ({} simulations per distinct suit pattern) on {} threads...", num_sims, threads);
+            println!("Save it once with `pokershit matrix` and pass --matrix to skip this step");
+            Ok(matrix::compute_equity_matrix(num_sims, threads))
+        },
+    }
+}
+
+fn run_heads_up_sng(args: &[String]) {
+    let usage = "Usage: pokershit husng [--depths <bb,...>] [--strategy <nash|strategy>] [--villain <nash|strategy>] [--payouts <first,second>] [--hands-per-level n] [-n matches per depth] [--iterations n] [--matrix <equity_matrix.csv> | --simulations per matchup] [--threads n] [--rng <small|chacha|os>[:seed]], e.g. husng --depths 8,12,20 --strategy lag --villain nash --matrix equity_matrix.csv";
+    let mut depths = vec![5, 10, 15, 20];
+    let mut hero = sng::SngStrategy::parse("tag").expect("tag is a preset");
+    let mut villain = sng::SngStrategy::Nash;
+    let mut payouts = vec![100.0];
+    let mut hands_per_level = 20;
+    let mut trials = 500;
+    let mut iterations = 2000;
+    let mut matrix_file = None;
+    let mut num_sims = 1000;
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--depths" => iter.next().ok_or("--depths requires comma separated stack depths".to_string())
+                .and_then(|d| d.split(',').map(|depth| match depth.trim().parse::<u32>() {
+                    Ok(depth) if depth >= 2 => Ok(depth),
+                    _ => Err(format!("Invalid depth '{}', expected a whole number of big blinds from 2", depth.trim())),
+                }).collect())
+                .map(|d| depths = d),
+            "--strategy" => iter.next().ok_or("--strategy requires a strategy".to_string())
+                .and_then(|s| sng::SngStrategy::parse(s))
+                .map(|s| hero = s),
+            "--villain" => iter.next().ok_or("--villain requires a strategy".to_string())
+                .and_then(|s| sng::SngStrategy::parse(s))
+                .map(|s| villain = s),
+            "--payouts" => iter.next().ok_or("--payouts requires a comma separated list".to_string())
+                .and_then(|p| parse_number_list(p))
+                .and_then(|p| if p.len() <= 2 { Ok(p) } else { Err("A heads-up match pays at most two places".to_string()) })
+                .map(|p| payouts = p),
+            "--hands-per-level" => parse_simulations(iter.next()).map(|n| hands_per_level = n),
+            "-n" | "--trials" => parse_simulations(iter.next()).map(|n| trials = n),
+            "--iterations" => parse_simulations(iter.next()).map(|n| iterations = n),
+            "--matrix" => iter.next().ok_or("--matrix requires a matrix CSV file".to_string())
+                .map(|f| matrix_file = Some(f.clone())),
+            "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--threads" => parse_simulations(iter.next()).map(|n| threads = n),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let prize_pool: f64 = payouts.iter().sum();
+    if !(prize_pool > 0.0 && prize_pool.is_finite()) {
+        println!("The payouts must add up to a positive prize pool\n{}", usage);
+        return;
+    }
+
+    let equity_matrix = match load_equity_matrix(matrix_file.as_deref(), num_sims, threads) {
+        Ok(equity_matrix) => equity_matrix,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let max_depth = depths.iter().copied().max().unwrap_or(2);
+    let start_time = Instant::now();
+    let chart = match sng::NashChart::solve(&equity_matrix, max_depth, &payouts, iterations) {
+        Ok(chart) => chart,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    let solve_time = start_time.elapsed();
+
+    let start_time = Instant::now();
+    let mut rng = rng_choice.build();
+    let mut win_rates = Vec::with_capacity(depths.len());
+    for &depth in &depths {
+        match sng::simulate_sng_with_rng(depth, &hero, &villain, &chart, hands_per_level, trials, &mut rng) {
+            Ok(win_rate) => win_rates.push(win_rate),
+            Err(e) => {
+                println!("{}\n{}", e, usage);
+                return;
+            }
+        }
+    }
+
+    println!("\n=== Heads-Up Sit-and-Go EV ===");
+    println!("Payouts: {}  Blinds double every {} hands", payouts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("/"), hands_per_level);
+    println!("Solved push/fold from 2 to {} bb in {:.2}s; played {} matches of {} vs {} per depth in {:.2}s using {} rng",
+             max_depth, solve_time.as_secs_f64(), trials, hero.name(), villain.name(), start_time.elapsed().as_secs_f64(), rng_choice);
+
+    println!("\n{:<8} {:>8} {:>9} {:>11} {:>8} {:>9} {:>9}", "Depth", "SB jams", "BB calls", "Nash SB EV", "Win", "Equity", "ROI");
+    for (&depth, &win_rate) in depths.iter().zip(&win_rates) {
+        let solution = chart.depth(depth).expect("every depth up to the deepest is solved");
+        let equity = sng::finish_equity(win_rate, &payouts);
+        println!("{:<8} {:>7.1}% {:>8.1}% {:>10.2}% {:>7.1}% {:>8.2}% {:>+8.1}%",
+                 format!("{} bb", depth), solution.jam_percent(), solution.call_percent(), solution.sb_value / prize_pool * 100.0,
+                 win_rate * 100.0, equity * 100.0, (equity * 2.0 - 1.0) * 100.0);
+    }
+    println!("Nash SB EV is the small blind's share of the prize pool after one equilibrium hand; Win, Equity and ROI are {} against {}", hero.name(), villain.name());
+
+    println!("\nFinish equity by starting hand (% of the prize pool after one equilibrium hand):");
+    print!("{:<6}", "Hand");
+    for depth in &depths {
+        print!(" {:>9} {:>9}", format!("{}bb SB", depth), format!("{}bb BB", depth));
+    }
+    println!();
+    for (hand, notation) in equity_matrix.hands.iter().enumerate() {
+        print!("{:<6}", notation);
+        for &depth in &depths {
+            let solution = chart.depth(depth).expect("every depth up to the deepest is solved");
+            print!(" {:>8.2}% {:>8.2}%", solution.sb_hand_values[hand] / prize_pool * 100.0, solution.bb_hand_values[hand] / prize_pool * 100.0);
+        }
+        println!();
+    }
+}
+
+fn run_cfr(args: &[String]) {
+    let usage = "Usage: pokershit cfr (kuhn | river <board> --oop <range> --ip <range> [--pot chips] [--stack chips] [--bets <pot fractions>] [--buckets n]) [-n iterations] [--vanilla | --sampled] [-o <strategy.csv>] [--rng <small|chacha|os>[:seed]], e.g. cfr kuhn or cfr river AsKd7h2c2s --oop \"AA,KK,77,QJs\" --ip \"AK,AQ,KQ\" --bets 0.5,1";
+    let Some(game) = args.first() else {
+        println!("Choose a game to solve\n{}", usage);
+        return;
+    };
+    let mut board = None;
+    let mut ranges = [None, None];
+    let mut pot = 10.0;
+    let mut stack = 20.0;
+    let mut bet_sizes = vec![0.5, 1.0];
+    let mut bucket_count = None;
+    let mut iterations = None;
+    let mut sampled = None;
+    let mut output_file = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args[1..].iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--oop" => iter.next().ok_or("--oop requires a range".to_string())
+                .and_then(|r| range::Range::parse(r))
+                .map(|r| ranges[0] = Some(r)),
+            "--ip" => iter.next().ok_or("--ip requires a range".to_string())
+                .and_then(|r| range::Range::parse(r))
+                .map(|r| ranges[1] = Some(r)),
+            "--pot" => parse_amount(iter.next(), "--pot").map(|p| pot = p),
+            "--stack" => parse_amount(iter.next(), "--stack").map(|s| stack = s),
+            "--bets" => iter.next().ok_or("--bets requires comma separated pot fractions".to_string())
+                .and_then(|b| parse_number_list(b))
+                .map(|b| bet_sizes = b),
+            "--buckets" => parse_simulations(iter.next()).map(|n| bucket_count = Some(n)),
+            "-n" | "--iterations" => parse_simulations(iter.next()).map(|n| iterations = Some(n)),
+            "--vanilla" => { sampled = Some(false); Ok(()) },
+            "--sampled" => { sampled = Some(true); Ok(()) },
+            "-o" | "--output" => iter.next().ok_or("-o requires a CSV file name".to_string())
+                .map(|f| output_file = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ if game == "river" && board.is_none() => parse_cards(arg).map(|b| board = Some(b)),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    let mut rng = rng_choice.build();
+    let start_time = Instant::now();
+    let (title, table, value, exploitability, iterations, sampled) = match game.as_str() {
+        "kuhn" => {
+            let mut solver = cfr::CfrSolver::new(cfr::KuhnPoker).expect("Kuhn poker has deals");
+            // Kuhn poker has only six deals, so walking them all is cheap
+            let (iterations, sampled) = (iterations.unwrap_or(10000), sampled.unwrap_or(false));
+            if sampled {
+                solver.run_chance_sampled(iterations, &mut rng);
+            } else {
+                solver.run_vanilla(iterations);
+            }
+            ("Kuhn poker".to_string(), solver.strategy_table(), solver.game_value(), solver.exploitability(), iterations, sampled)
+        },
+        "river" => {
+            let (Some(board), [Some(oop), Some(ip)]) = (board, &ranges) else {
+                println!("The river game needs a board and both ranges\n{}", usage);
+                return;
+            };
+            let mut river = match cfr::RiverGame::new(board, [oop, ip], pot, stack, bet_sizes) {
+                Ok(river) => river,
+                Err(e) => {
+                    println!("{}\n{}", e, usage);
+                    return;
+                }
+            };
+            let mut title = format!("River on {}, pot {}, stack {}", format_cards(&river.board), river.pot, river.stack);
+            // Hands in a strength bucket share a strategy; river strengths are exact, so no samples are drawn
+            if let Some(count) = bucket_count {
+                let bucketing = bucket::hand_values(&river.board, bucket::BucketMetric::Equity, 1)
+                    .and_then(|values| bucket::build_buckets(&values, count, bucket::BucketMethod::Percentile, bucket::BucketMetric::Equity, &river.board));
+                match bucketing {
+                    Ok(bucketing) => {
+                        river.abstract_hands(&bucketing);
+                        title.push_str(&format!(", {} strength buckets", bucketing.buckets.len()));
+                    },
+                    Err(e) => {
+                        println!("{}\n{}", e, usage);
+                        return;
+                    }
+                }
+            }
+            let mut solver = cfr::CfrSolver::new(river).expect("ranges were checked for combos");
+            // Every range-vs-range deal makes vanilla iterations slow, so sample them by default
+            let (iterations, sampled) = (iterations.unwrap_or(200000), sampled.unwrap_or(true));
+            if sampled {
+                solver.run_chance_sampled(iterations, &mut rng);
+            } else {
+                solver.run_vanilla(iterations);
+            }
+            (title, solver.strategy_table(), solver.game_value(), solver.exploitability(), iterations, sampled)
+        },
+        other => {
+            println!("Unknown game '{}', expected kuhn or river\n{}", other, usage);
+            return;
+        }
+    };
+
+    println!("\n=== CFR: {} ===", title);
+    println!("{} {} iterations in {:.2}s{}", iterations, if sampled { "chance-sampled" } else { "vanilla" },
+             start_time.elapsed().as_secs_f64(), if sampled { format!(" using {} rng", rng_choice) } else { String::new() });
+    println!("Game value for the first player: {:+.4}", value);
+    println!("Exploitability: {:.4} per hand", exploitability);
+
+    println!("\n{:<24} Strategy", "Info set");
+    for row in &table {
+        let strategy: Vec<String> = row.actions.iter().zip(&row.probabilities)
+            .map(|(action, probability)| format!("{} {:.1}%", action, probability * 100.0))
+            .collect();
+        println!("{:<24} {}", row.info_set, strategy.join(", "));
+    }
+
+    if let Some(file) = output_file {
+        if let Err(e) = cfr::export_strategy_csv(&table, &file) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+}
+
+fn run_buckets(args: &[String]) {
+    let usage = "Usage: pokershit buckets [board] [--metric <equity|ehs>] [--method <percentile|kmeans>] [-k buckets] [-n samples per hand] [-o <buckets.csv>] [--hands <hand_buckets.csv>] [--rng <small|chacha|os>[:seed]], e.g. buckets Qh7c2d --metric ehs --method kmeans -k 8 -o flop_buckets.csv";
+    let mut board = Vec::new();
+    let mut metric = bucket::BucketMetric::Equity;
+    let mut method = bucket::BucketMethod::Percentile;
+    let mut count = 8;
+    let mut samples = 1000;
+    let mut output_file = None;
+    let mut hands_file = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--metric" => iter.next().ok_or("--metric requires equity or ehs".to_string())
+                .and_then(|m| m.parse())
+                .map(|m| metric = m),
+            "--method" => iter.next().ok_or("--method requires percentile or kmeans".to_string())
+                .and_then(|m| m.parse())
+                .map(|m| method = m),
+            "-k" | "--buckets" => parse_simulations(iter.next()).map(|n| count = n),
+            "-n" | "--samples" => parse_simulations(iter.next()).map(|n| samples = n),
+            "-o" | "--output" => iter.next().ok_or("-o requires a CSV file name".to_string())
+                .map(|f| output_file = Some(f.clone())),
+            "--hands" => iter.next().ok_or("--hands requires a CSV file name".to_string())
+                .map(|f| hands_file = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ if board.is_empty() => parse_cards(arg).map(|b| board = b),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    let start_time = Instant::now();
+    let mut rng = rng_choice.build();
+    let bucketed = bucket::hand_values_with_rng(&board, metric, samples, &mut rng)
+        .and_then(|values| Ok((bucket::build_buckets(&values, count, method, metric, &board)?, values)));
+    let (bucketing, values) = match bucketed {
+        Ok(bucketed) => bucketed,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+
+    println!("\n=== Hand Buckets: {} ===", if board.is_empty() { "Preflop".to_string() } else { format!("{}{}", format_cards(&board), texture_label(&board)) });
+    println!("{} {} buckets by {} from {} hands in {:.2}s using {} rng",
+             bucketing.buckets.len(), method, metric, values.len(), start_time.elapsed().as_secs_f64(), rng_choice);
+
+    println!("\n{:<7} {:>15} {:>8} {:>8}  Hands", "Bucket", "Strength", "Center", "Combos");
+    for bucket in &bucketing.buckets {
+        let mut members: Vec<&bucket::HandValue> = values.iter().filter(|hand| bucketing.bucket_of(hand.value) == bucket.index).collect();
+        members.sort_by(|a, b| b.value.total_cmp(&a.value));
+        // Preflop buckets read as a range; postflop ones list their strongest combos
+        let hands = if board.is_empty() {
+            let weights: Vec<(&str, f64)> = members.iter().map(|hand| (hand.hand.as_str(), 1.0)).collect();
+            range::compact_notation(&weights).unwrap_or_default()
+        } else {
+            let shown: Vec<&str> = members.iter().take(6).map(|hand| hand.hand.as_str()).collect();
+            let more = if members.len() > shown.len() { format!(" +{} more", members.len() - shown.len()) } else { String::new() };
+            format!("{}{}", shown.join(" "), more)
+        };
+        println!("{:<7} {:>15} {:>7.1}% {:>8}  {}", bucket.index, format!("{:.1}-{:.1}%", bucket.lower * 100.0, bucket.upper * 100.0),
+                 bucket.center * 100.0, bucket.combos, hands);
+    }
+
+    if let Some(file) = output_file {
+        if let Err(e) = bucket::export_buckets_csv(&bucketing, &file) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+    if let Some(file) = hands_file {
+        if let Err(e) = bucket::export_hand_buckets_csv(&values, &bucketing, &file) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+}
+
+fn run_history(args: &[String]) {
+    let usage = "Usage: pokershit history <hand_history.txt|hands.phhs> [--hero <name>] [--hand <id>] [--convert <hands.phhs|hands.txt>] [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. history HH20240101.txt --hand 245178432123 or history HH20240101.txt --convert hands.phhs";
+    let mut filename = None;
+    let mut hero_name = None;
+    let mut hand_id = None;
+    let mut convert_file = None;
+    let mut num_sims = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--hero" => iter.next().ok_or("--hero requires a player name".to_string())
+                .map(|name| hero_name = Some(name.clone())),
+            "--hand" => iter.next().ok_or("--hand requires a hand number".to_string())
+                .map(|id| hand_id = Some(id.trim_start_matches('#').to_string())),
+            "--convert" => iter.next().ok_or("--convert requires a file name".to_string())
+                .map(|f| convert_file = Some(f.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ if filename.is_none() => {
+                filename = Some(arg.clone());
+                Ok(())
+            },
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let Some(filename) = filename else {
+        println!("{}", usage);
+        return;
+    };
+
+    let hands = match hand_history::import_hands(&filename) {
+        Ok(hands) => hands,
+        Err(e) => {
+            println!("Error reading {}: {}", filename, e);
+            return;
+        }
+    };
+    if let Some(file) = convert_file {
+        println!("{} hands read from {}", hands.len(), filename);
+        if let Err(e) = hand_history::export_hands(&hands, &file) {
+            println!("Error exporting hand history: {}", e);
+        }
+        return;
+    }
+    // The player the file was dealt to unless another is named
+    let hero_of = |hand: &hand_history::HandHistory| match &hero_name {
+        Some(name) => hand.player_index(name),
+        None => hand.hero,
+    };
+
+    if let Some(id) = hand_id {
+        match hands.iter().find(|hand| hand.id == id) {
+            Some(hand) => print_history_hand(hand, hero_of(hand), num_sims, &mut rng_choice.build()),
+            None => println!("Hand #{} is not in {}", id, filename),
+        }
+        return;
+    }
+
+    println!("\n=== Hand History: {} ===", filename);
+    println!("\n{:<14} {:<12} {:<8} {:<16} {:>10}", "Hand", "Player", "Cards", "Board", "Net");
+    let mut played = 0;
+    let mut net_big_blinds = 0.0;
+    let mut showdowns = 0;
+    for hand in &hands {
+        let Some(hero) = hero_of(hand) else {
+            continue;
+        };
+        let player = &hand.players[hero];
+        let cards = player.hole_cards.as_ref().map_or("-".to_string(), |cards| format!("{}{}", cards[0], cards[1]));
+        let board: String = hand.board.iter().map(|card| card.to_string()).collect();
+        let net = hand.net(hero);
+        println!("{:<14} {:<12} {:<8} {:<16} {:>10}", hand.id, player.name, cards, board, hand.format_amount(net));
+        played += 1;
+        net_big_blinds += net as f64 / hand.stakes.big_blind.max(1) as f64;
+        if hand.went_to_showdown() && hand.final_players().contains(&hero) {
+            showdowns += 1;
+        }
+    }
+    if played == 0 {
+        println!("No hands played by {}", hero_name.as_deref().unwrap_or("the hero"));
+        return;
+    }
+    println!("\n{} of {} hands played, {:+.1} bb ({:+.1} bb/100), {} went to showdown",
+             played, hands.len(), net_big_blinds, net_big_blinds / played as f64 * 100.0, showdowns);
+}
+
+// One hand street by street, with each player's equity on every street when
+// two known hands reached the showdown
+fn print_history_hand<R: rand::Rng>(hand: &hand_history::HandHistory, hero: Option<usize>, num_sims: usize, rng: &mut R) {
+    use game::{Action, Street};
+
+    let kind = match &hand.tournament {
+        Some(tournament) => format!("Tournament #{}", tournament),
+        None => "Cash game".to_string(),
+    };
+    println!("\n=== Hand #{}: {} {}/{} ===", hand.id, kind,
+             hand.format_amount(hand.stakes.small_blind as i64), hand.format_amount(hand.stakes.big_blind as i64));
+    // PHH files may leave out the table and date
+    let table = (!hand.table.is_empty()).then(|| format!("Table '{}'", hand.table));
+    let place: Vec<String> = table.into_iter().chain((!hand.date.is_empty()).then(|| hand.date.clone())).collect();
+    if !place.is_empty() {
+        println!("{}", place.join(", "));
+    }
+
+    println!();
+    for (index, player) in hand.players.iter().enumerate() {
+        let cards = player.hole_cards.as_ref().map_or(String::new(), |cards| format_cards(cards));
+        let marker = if index == hand.button { " (button)" } else { "" };
+        let you = if Some(index) == hero { " *" } else { "" };
+        println!("Seat {:<2} {:<16} {:>10}  {}{}{}", player.seat, player.name, hand.format_amount(player.stack as i64), cards, marker, you);
+    }
+
+    for (street, cards) in [(Street::Preflop, 0), (Street::Flop, 3), (Street::Turn, 4), (Street::River, 5)] {
+        let actions: Vec<_> = hand.actions.iter().filter(|record| record.street == street).collect();
+        if actions.is_empty() && hand.board.len() < cards.max(1) {
+            continue;
+        }
+        let board = if cards > 0 { format!(" {}", format_cards(&hand.board[..cards.min(hand.board.len())])) } else { String::new() };
+        println!("\n{}:{}", street, board);
+        let mut bet_made = street == Street::Preflop;
+        for record in actions {
+            let action = match record.action {
+                Action::Fold => "folds".to_string(),
+                Action::Check => "checks".to_string(),
+                Action::Call => "calls".to_string(),
+                Action::Bet(total) if bet_made => format!("raises to {}", hand.format_amount(total as i64)),
+                Action::Bet(total) => format!("bets {}", hand.format_amount(total as i64)),
+                Action::AllIn => "goes all-in".to_string(),
+            };
+            bet_made |= matches!(record.action, Action::Bet(_));
+            let all_in = if record.all_in { " (all-in)" } else { "" };
+            println!("  {} {}{}", hand.players[record.seat].name, action, all_in);
+        }
+    }
+
+    println!("\nResult:");
+    for (index, player) in hand.players.iter().enumerate() {
+        if player.contributed > 0 || player.won > 0 {
+            println!("  {:<16} {:>10}", player.name, hand.format_amount(hand.net(index)));
+        }
+    }
+    if hand.rake > 0 {
+        println!("  Rake {} from a {} pot", hand.format_amount(hand.rake as i64), hand.format_amount(hand.pot() as i64));
+    }
+
+    // Heads-up showdowns with both hands face up: how the equity moved as the board came
+    let shown: Vec<(usize, &[card::Card; 2])> = hand.final_players().into_iter()
+        .filter_map(|player| hand.players[player].hole_cards.as_ref().map(|cards| (player, cards)))
+        .collect();
+    if hand.went_to_showdown() && shown.len() == 2 && hand.final_players().len() == 2 {
+        let (a, b) = (shown[0], shown[1]);
+        println!("\n{:<8} {:>16} {:>16}", "Street", hand.players[a.0].name, hand.players[b.0].name);
+        for (street, cards) in [(Street::Preflop, 0), (Street::Flop, 3), (Street::Turn, 4), (Street::River, 5)] {
+            if hand.board.len() < cards {
+                break;
+            }
+            let board = &hand.board[..cards];
+            let results = if cards == 0 {
+                card::monte_carlo_heads_up_with_rng(a.1, b.1, board, num_sims, rng)
+            } else {
+                card::exact_heads_up(a.1, b.1, board)
+            };
+            let equity = results.win_rate + results.tie_rate / 2.0;
+            println!("{:<8} {:>15.1}% {:>15.1}%", street.to_string(), equity, 100.0 - equity);
+        }
+    }
+}
+
+fn run_luck(args: &[String]) {
+    let usage = "Usage: pokershit luck <hand_history.txt|hands.phhs>... [--hero <name>] [-n simulations] [-o <luck.csv>] [--report <luck.html>] [--rng <small|chacha|os>[:seed]], e.g. luck HH20240101.txt HH20240102.txt --report luck.html";
+    let mut filenames = Vec::new();
+    let mut hero_name = None;
+    let mut num_sims = 10000;
+    let mut output_file = None;
+    let mut report_file = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--hero" => iter.next().ok_or("--hero requires a player name".to_string())
+                .map(|name| hero_name = Some(name.clone())),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "-o" | "--output" => iter.next().ok_or("-o requires a CSV file name".to_string())
+                .map(|f| output_file = Some(f.clone())),
+            "--report" => iter.next().ok_or("--report requires an HTML file name".to_string())
+                .map(|f| report_file = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ if arg.starts_with('-') => Err(format!("Unknown argument: {}", arg)),
+            _ => {
+                filenames.push(arg.clone());
+                Ok(())
+            },
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    if filenames.is_empty() {
+        println!("{}", usage);
+        return;
+    }
+
+    let mut hands = Vec::new();
+    for filename in &filenames {
+        match hand_history::import_hands(filename) {
+            Ok(file_hands) => hands.extend(file_hands),
+            Err(e) => {
+                println!("Error reading {}: {}", filename, e);
+                return;
+            }
+        }
+    }
+
+    let start_time = Instant::now();
+    let mut rng = rng_choice.build();
+    let analyzed = luck::analyze_hands_with_rng(&hands, hero_name.as_deref(), num_sims, &mut rng);
+    if analyzed.is_empty() {
+        println!("No hands played by {}", hero_name.as_deref().unwrap_or("the hero"));
+        return;
+    }
+    let sessions = luck::session_summary(&analyzed);
+    let all_ins: Vec<&luck::HandLuck> = analyzed.iter().filter(|hand| hand.all_in.is_some()).collect();
+    let net: f64 = analyzed.iter().map(|hand| hand.net_bb).sum();
+    let ev: f64 = analyzed.iter().map(|hand| hand.ev_bb).sum();
+
+    println!("\n=== All-in EV: {} ===", analyzed[0].player);
+    println!("{} hands from {} file(s), {} all-ins, analyzed in {:.2}s using {} rng",
+             analyzed.len(), filenames.len(), all_ins.len(), start_time.elapsed().as_secs_f64(), rng_choice);
+    println!("Actual:    {:+.1} bb ({:+.2} bb/100)", net, net / analyzed.len() as f64 * 100.0);
+    println!("All-in EV: {:+.1} bb ({:+.2} bb/100)", ev, ev / analyzed.len() as f64 * 100.0);
+    println!("Luck:      {:+.1} bb", net - ev);
+
+    println!("\n{:<22} {:>6} {:>8} {:>9} {:>9} {:>9} {:>9}", "Session", "Hands", "All-ins", "Equity", "Net bb", "EV bb", "Luck bb");
+    for session in &sessions {
+        let equity = if session.all_ins > 0 { format!("{:.1}%", session.average_equity * 100.0) } else { "-".to_string() };
+        println!("{:<22} {:>6} {:>8} {:>9} {:>+9.1} {:>+9.1} {:>+9.1}",
+                 session.session, session.hands, session.all_ins, equity, session.net_bb, session.ev_bb, session.luck_bb());
+    }
+
+    if !all_ins.is_empty() {
+        println!("\n{:<14} {:<8} {:<6} {:<22} {:<16} {:>7} {:>9} {:>9}", "Hand", "Street", "Cards", "Villain", "Board", "Equity", "Net bb", "EV bb");
+        for hand in &all_ins {
+            let Some(all_in) = &hand.all_in else {
+                continue;
+            };
+            let board: String = all_in.board.iter().map(|card| card.to_string()).collect();
+            let villain = format!("{} {}{}", all_in.villain, all_in.villain_cards[0], all_in.villain_cards[1]);
+            println!("{:<14} {:<8} {:<6} {:<22} {:<16} {:>6.1}% {:>+9.1} {:>+9.1}",
+                     hand.id, all_in.street.to_string(), format!("{}{}", all_in.hero_cards[0], all_in.hero_cards[1]),
+                     villain, board, all_in.equity * 100.0, hand.net_bb, hand.ev_bb);
+        }
+    }
+
+    if let Some(file) = output_file {
+        if let Err(e) = luck::export_luck_csv(&analyzed, &file) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+    if let Some(file) = report_file {
+        if let Err(e) = report::export_luck_report(&analyzed, &sessions, &file) {
+            println!("Error exporting report: {}", e);
+        }
+    }
+}
+
+fn run_serve(args: &[String]) {
+    let usage = "Usage: pokershit serve [--host address] [--port n] [--max-trials n] [--rng <small|chacha|os>[:seed]], e.g. serve --port 8080, then POST {\"hero\": \"AhKh\", \"villain\": \"QQ+,AKs\"} to /equity, GET /preflop/AKs or stream a bulk run from ws://127.0.0.1:8080/bulk?trials=1000";
+    let mut host = "127.0.0.1".to_string();
+    let mut port: u16 = 8080;
+    let mut config = server::ServerConfig { max_trials: 1_000_000, rng: rng::RngChoice::default() };
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--host" => iter.next().ok_or("--host requires an address".to_string())
+                .map(|address| host = address.clone()),
+            "--port" => iter.next().and_then(|n| n.parse().ok())
+                .ok_or("--port requires a port number".to_string())
+                .map(|n| port = n),
+            "--max-trials" => parse_simulations(iter.next())
+                .map_err(|_| "--max-trials requires a positive number".to_string())
+                .map(|n| config.max_trials = n),
+            "--rng" => parse_rng(iter.next()).map(|r| config.rng = r),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    if let Err(e) = server::serve(&format!("{}:{}", host, port), config) {
+        println!("Error starting server on {}:{}: {}", host, port, e);
+    }
+}
+
+// Standard output carries the responses, so everything else goes to standard error
+fn run_rpc(args: &[String]) {
+    let usage = "Usage: pokershit rpc [--max-trials n] [--rng <small|chacha|os>[:seed]], then one JSON-RPC request per line on standard input, e.g. {\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"equity\", \"params\": {\"hero\": \"AhKh\"}}";
+    let mut max_trials = 1_000_000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--max-trials" => parse_simulations(iter.next())
+                .map_err(|_| "--max-trials requires a positive number".to_string())
+                .map(|n| max_trials = n),
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    let mut rng = rng_choice.build();
+    if let Err(e) = rpc::run(io::stdin().lock(), io::stdout().lock(), max_trials, &mut rng) {
+        eprintln!("Error reading requests: {}", e);
+    }
+}
+
+#[cfg(feature = "grpc")]
+fn run_grpc(args: &[String]) {
+    let usage = "Usage: pokershit grpc [--host address] [--port n] [--max-trials n] [--rng <small|chacha|os>[:seed]], e.g. grpc --port 50051 (the service is described in grpc/pokershit.proto)";
+    let mut host = "127.0.0.1".to_string();
+    let mut port: u16 = 50051;
+    let mut config = server::ServerConfig { max_trials: 1_000_000, rng: rng::RngChoice::default() };
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--host" => iter.next().ok_or("--host requires an address".to_string())
+                .map(|address| host = address.clone()),
+            "--port" => iter.next().and_then(|n| n.parse().ok())
+                .ok_or("--port requires a port number".to_string())
+                .map(|n| port = n),
+            "--max-trials" => parse_simulations(iter.next())
+                .map_err(|_| "--max-trials requires a positive number".to_string())
+                .map(|n| config.max_trials = n),
+            "--rng" => parse_rng(iter.next()).map(|r| config.rng = r),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    if let Err(e) = grpc::serve(&format!("{}:{}", host, port), config) {
+        println!("Error running gRPC server on {}:{}: {}", host, port, e);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(args: &[String]) {
+    let usage = "Usage: pokershit tui [results.csv|results.json] [--rng <small|chacha|os>[:seed]], e.g. tui poker_results.csv";
+    let mut input = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--rng" => parse_rng(iter.next()).map(|r| rng_choice = r),
+            _ if input.is_none() && !arg.starts_with('-') => {
+                input = Some(arg.clone());
+                Ok(())
+            },
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    let mut results = match &input {
+        Some(input) => match import_results(input) {
+            Ok(results) => results,
+            Err(e) => {
+                println!("Error reading {}: {}", input, e);
+                return;
+            }
+        },
+        None => Vec::new(),
+    };
+    results.sort_by(card::by_win_rate);
+    if let Err(e) = tui::run(results, None, Vec::new(), rng_choice) {
+        println!("Error running the dashboard: {}", e);
+    }
+}
+
+fn run_train(args: &[String]) {
+    let usage = "Usage: pokershit train [--villain <hand|range|mixed>] [--street <preflop|flop|turn|river>] [--spots n] [--log <training.csv>] [--stats] [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. train --villain range --street flop --spots 10";
+    let mut opposition = trainer::Opposition::Mixed;
+    let mut street = None;
+    let mut spots = None;
+    let mut log = trainer::DEFAULT_LOG.to_string();
+    let mut stats_only = false;
+    let mut num_sims = 20000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--villain" => iter.next().ok_or("--villain requires hand, range or mixed".to_string())
+                .and_then(|v| v.parse()).map(|o| opposition = o),
+            "--street" => iter.next().ok_or("--street requires preflop, flop, turn or river".to_string())
+                .and_then(|v| trainer::parse_street(v)).map(|s| street = Some(s)),
+            "--spots" => parse_simulations(iter.next()).map(|n| spots = Some(n)),
+            "--log" => iter.next().ok_or("--log requires a file name".to_string())
+                .map(|f| log = f.clone()),
+            "--stats" => {
+                stats_only = true;
+                Ok(())
+            },
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    if !stats_only {
+        println!("\n=== Equity Trainer ===");
+        println!("Estimate hero's all-in equity in percent, or press Enter to stop. Answers are logged to {}", log);
+        let mut rng = rng_choice.build();
+        let mut session = Vec::new();
+        while spots.is_none_or(|spots| session.len() < spots) {
+            let spot = trainer::deal_equity_spot(opposition, street, &mut rng);
+            println!("\nSpot {} ({})", session.len() + 1, spot.street);
+            println!("Hero: {}", format_cards(&spot.hero));
+            match &spot.villain {
+                trainer::Villain::Hand(hand) => println!("Villain: {}", format_cards(hand)),
+                trainer::Villain::Range(name, range) => println!("Villain: {} ({:.0} combos)", name, range.total_weight()),
+            }
+            if !spot.board.is_empty() {
+                println!("Board: {}{}", format_cards(&spot.board), texture_label(&spot.board));
+            }
+            let Some(estimate) = read_estimate("Your estimate: ", 0.0, 100.0) else { break };
+
+            let answer = spot.equity(num_sims, &mut rng);
+            let error = estimate - answer;
+            println!("Hero's equity is {:.1}%: you were {:.1} points {}", answer, error.abs(), if error > 0.0 { "high" } else { "low" });
+            let attempt = trainer::Attempt::new("equity", &spot.street.to_string().to_lowercase(), &spot.describe(), estimate, answer);
+            if let Err(e) = trainer::append_attempts(std::slice::from_ref(&attempt), &log) {
+                println!("Error writing {}: {}", log, e);
+            }
+            session.push(attempt);
+        }
+        if let Some(stats) = trainer::error_stats(&session, 5.0) {
+            println!("\nThis session: {} spots, average error {:.1} points, {:.0}% within 5 points", stats.attempts, stats.mean_absolute_error, stats.close_rate);
+        }
+    }
+
+    match trainer::load_attempts(&log) {
+        Ok(attempts) => print_training_summary(&attempts, "equity", 5.0, "Within 5"),
+        Err(e) => println!("Error reading {}: {}", log, e),
+    }
+}
+
+fn run_flashcards(args: &[String]) {
+    let usage = "Usage: pokershit flashcards [--quiz <rank|equity>] [--results <results.csv|results.json>] [--cards n] [--log <training.csv>] [--stats] [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. flashcards --quiz equity --results poker_results.csv";
+    let mut by_equity = false;
+    let mut results_file = None;
+    let mut cards = None;
+    let mut log = trainer::DEFAULT_LOG.to_string();
+    let mut stats_only = false;
+    let mut num_sims = 20000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--quiz" => match iter.next().map(|v| v.to_ascii_lowercase()).as_deref() {
+                Some(quiz @ ("rank" | "equity")) => {
+                    by_equity = quiz == "equity";
+                    Ok(())
+                },
+                _ => Err("--quiz requires rank or equity".to_string()),
+            },
+            "--results" => iter.next().ok_or("--results requires a results file".to_string())
+                .map(|f| results_file = Some(f.clone())),
+            "--cards" => parse_simulations(iter.next()).map(|n| cards = Some(n)),
+            "--log" => iter.next().ok_or("--log requires a file name".to_string())
+                .map(|f| log = f.clone()),
+            "--stats" => {
+                stats_only = true;
+                Ok(())
+            },
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    // Answers within these of the right one count as known
+    let (quiz, tolerance) = if by_equity { ("preflop-equity", 3.0) } else { ("preflop-rank", 10.0) };
+    let mut attempts = match trainer::load_attempts(&log) {
+        Ok(attempts) => attempts,
+        Err(e) => {
+            println!("Error reading {}: {}", log, e);
+            return;
+        }
+    };
+
+    if !stats_only {
+        // The ranking of the bulk results given, or the built-in one
+        let deck = match &results_file {
+            Some(file) => match import_results(file) {
+                Ok(results) => trainer::results_flashcards(&results),
+                Err(e) => {
+                    println!("Error reading {}: {}", file, e);
+                    return;
+                }
+            },
+            None => trainer::ranking_flashcards(),
+        };
+        if deck.is_empty() {
+            println!("No starting hands to ask about");
+            return;
+        }
+        let names: Vec<String> = deck.iter().map(|card| card.notation.clone()).collect();
+        let mut weights = trainer::card_weights(&attempts, quiz, &names, tolerance);
+
+        println!("\n=== Preflop Flashcards ===");
+        if by_equity {
+            println!("Estimate each hand's all-in equity against a random hand in percent, or press Enter to stop.");
+        } else {
+            println!("Give each hand's rank among the {} starting hands (1 is the strongest), or press Enter to stop.", deck.len());
+        }
+        println!("Hands you miss come back more often. Answers are logged to {}", log);
+        let mut rng = rng_choice.build();
+        let mut session = Vec::new();
+        let mut last = None;
+        while cards.is_none_or(|cards| session.len() < cards) {
+            let Some(i) = trainer::pick_card(&weights, last, &mut rng) else { break };
+            let card = &deck[i];
+            println!("\nCard {}: {}", session.len() + 1, card.notation);
+            let (estimate, answer) = if by_equity {
+                let Some(estimate) = read_estimate("Equity against a random hand: ", 0.0, 100.0) else { break };
+                let answer = card.equity(num_sims, &mut rng);
+                let error = estimate - answer;
+                println!("{} has {:.1}% equity: you were {:.1} points {}", card.notation, answer, error.abs(), if error > 0.0 { "high" } else { "low" });
+                (estimate, answer)
+            } else {
+                let Some(estimate) = read_estimate("Rank: ", 1.0, deck.len() as f64) else { break };
+                let places = estimate.round() as isize - card.rank as isize;
+                let verdict = if places == 0 { "exactly right".to_string() } else { format!("{} places {}", places.abs(), if places > 0 { "too low" } else { "too high" }) };
+                println!("{} ranks {} (top {:.1}%): {}", card.notation, card.rank, card.top_percent, verdict);
+                (estimate.round(), card.rank as f64)
+            };
+
+            let attempt = trainer::Attempt::new(quiz, card.category(), &card.notation, estimate, answer);
+            trainer::update_weight(&mut weights[i], attempt.error(), tolerance);
+            if let Err(e) = trainer::append_attempts(std::slice::from_ref(&attempt), &log) {
+                println!("Error writing {}: {}", log, e);
+            }
+            session.push(attempt);
+            last = Some(i);
+        }
+        if let Some(stats) = trainer::error_stats(&session, tolerance) {
+            println!("\nThis session: {} cards, average error {:.1}, {:.0}% within {}", stats.attempts, stats.mean_absolute_error, stats.close_rate, tolerance);
+        }
+        attempts.extend(session);
+    }
+    print_training_summary(&attempts, quiz, tolerance, &format!("Within {}", tolerance));
+}
+
+fn run_pot_quiz(args: &[String]) {
+    let usage = "Usage: pokershit potquiz [--seconds n] [--rounds n] [--log <training.csv>] [--stats] [--rng <small|chacha|os>[:seed]], e.g. potquiz --seconds 5 --rounds 20";
+    let mut seconds = 8.0;
+    let mut rounds = None;
+    let mut log = trainer::DEFAULT_LOG.to_string();
+    let mut stats_only = false;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--seconds" => iter.next().and_then(|v| v.parse::<f64>().ok()).filter(|s| *s > 0.0 && s.is_finite())
+                .ok_or("--seconds requires a positive number".to_string())
+                .map(|s| seconds = s),
+            "--rounds" => parse_simulations(iter.next()).map(|n| rounds = Some(n)),
+            "--log" => iter.next().ok_or("--log requires a file name".to_string())
+                .map(|f| log = f.clone()),
+            "--stats" => {
+                stats_only = true;
+                Ok(())
+            },
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    let mut attempts = match trainer::load_attempts(&log) {
+        Ok(attempts) => attempts,
+        Err(e) => {
+            println!("Error reading {}: {}", log, e);
+            return;
+        }
+    };
+
+    // Each answer is logged as the EV of the action taken against the EV of
+    // the better one, so the errors are the chips given up
+    if !stats_only {
+        println!("\n=== Pot Odds Quiz ===");
+        println!("Call or fold each bet within {} seconds; a late answer folds. Press Enter to stop. Answers are logged to {}", seconds, log);
+        let mut rng = rng_choice.build();
+        let mut session: Vec<trainer::Attempt> = Vec::new();
+        let mut streak = 0;
+        while rounds.is_none_or(|rounds| session.len() < rounds) {
+            let spot = trainer::deal_pot_odds_spot(&mut rng);
+            let cards = if spot.cards_to_come() == 2 { "all-in, both cards to come" } else { "one card to come" };
+            println!("\nRound {}: {} on the {} ({} outs), {}", session.len() + 1, spot.draw, spot.street.to_string().to_lowercase(), spot.outs, cards);
+            println!("Villain bets {} into {}", spot.bet, spot.pot);
+
+            let start_time = Instant::now();
+            let Some(call) = read_decision("Call or fold (c/f): ") else { break };
+            let late = start_time.elapsed().as_secs_f64() > seconds;
+            let called = call && !late;
+
+            let analysis = spot.analysis();
+            let best = analysis.ev.max(0.0);
+            let taken = if called { analysis.ev } else { 0.0 };
+            let right = taken >= best || analysis.verdict == strategy::Verdict::Indifferent;
+            streak = if right { streak + 1 } else { 0 };
+            if late {
+                println!("Too slow ({:.1}s): that's a fold", start_time.elapsed().as_secs_f64());
+            }
+            println!("{}: {:.1}% to hit, {:.1}% needed at {:.1} to 1, calling is worth {:+.1}",
+                     if right { "Right" } else { "Wrong" }, analysis.equity * 100.0, analysis.required_equity * 100.0, analysis.odds_ratio, analysis.ev);
+            if streak > 1 {
+                println!("{} in a row", streak);
+            }
+
+            let attempt = trainer::Attempt::new("pot-odds", &spot.street.to_string().to_lowercase(), &spot.describe(), taken, best);
+            if let Err(e) = trainer::append_attempts(std::slice::from_ref(&attempt), &log) {
+                println!("Error writing {}: {}", log, e);
+            }
+            session.push(attempt);
+        }
+        if let Some(stats) = trainer::error_stats(&session, 0.0) {
+            let lost: f64 = session.iter().map(|attempt| -attempt.error()).sum();
+            println!("\nThis session: {:.0}% right over {} rounds, {:.1} chips of EV given up", stats.close_rate, stats.attempts, lost);
+        }
+        attempts.extend(session);
+    }
+    print_training_summary(&attempts, "pot-odds", 0.0, "Right");
+}
+
+// Reads "c"/"call" or "f"/"fold" as whether to call, re-prompting until one
+// parses; a blank line, "q" or the end of input gives None
+fn read_decision(prompt: &str) -> Option<bool> {
+    loop {
+        println!("{}", prompt);
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            return None;
+        }
+        match input.trim().to_ascii_lowercase().as_str() {
+            "" | "q" => return None,
+            "c" | "call" => return Some(true),
+            "f" | "fold" => return Some(false),
+            _ => println!("Enter c to call or f to fold"),
+        }
+    }
+}
+
+fn run_repl(args: &[String]) {
+    use std::io::IsTerminal;
+
+    let usage = "Usage: pokershit repl [-n trials] [--rng <small|chacha|os>[:seed]], e.g. repl -n 50000, then commands such as hero AhKh, board Qh Jh 2c, villain 22+,ATs+, equity";
+    let mut trials = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "-n" | "--trials" => parse_simulations(iter.next()).map(|n| trials = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+
+    // Commands piped in from a file don't need a prompt or the greeting
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        println!("pokershit {} trials per equity run, {} rng. Type help for the commands.", trials, rng_choice);
+    }
+    if let Err(e) = repl::run(io::stdin().lock(), io::stdout().lock(), interactive, trials, &mut rng_choice.build()) {
+        eprintln!("Error: {}", e);
+    }
+}
+
+// Reads a number between `min` and `max` such as "55" or "55%", re-prompting
+// until one parses; a blank line, "q" or the end of input gives None
+fn read_estimate(prompt: &str, min: f64, max: f64) -> Option<f64> {
+    loop {
+        println!("{}", prompt);
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            return None;
+        }
+        let input = input.trim();
+        if input.is_empty() || input.eq_ignore_ascii_case("q") {
+            return None;
+        }
+        match input.trim_end_matches('%').trim().parse::<f64>() {
+            Ok(number) if (min..=max).contains(&number) => return Some(number),
+            _ => println!("Enter a number between {} and {}", min, max),
+        }
+    }
+}
+
+// Errors of every logged answer to `quiz`, overall, lately and by category;
+// `close_label` heads the column of answers within `close`
+fn print_training_summary(attempts: &[trainer::Attempt], quiz: &str, close: f64, close_label: &str) {
+    let Some(summary) = trainer::summarize(attempts, quiz, close) else {
+        println!("\nNo {} answers logged yet", quiz);
+        return;
+    };
+    println!("\n=== Progress ===");
+    println!("{:<12} {:>8} {:>10} {:>7} {:>9} {:>7}", "", "Answers", "Avg error", "Bias", close_label, "Worst");
+    let row = |label: &str, stats: &trainer::ErrorStats| {
+        println!("{:<12} {:>8} {:>10.1} {:>+7.1} {:>8.0}% {:>7.1}", label, stats.attempts, stats.mean_absolute_error, stats.bias, stats.close_rate, stats.worst_error);
+    };
+    row("All time", &summary.overall);
+    row(&format!("Last {}", summary.recent.attempts), &summary.recent);
+    for (category, stats) in &summary.by_category {
+        row(category, stats);
+    }
+    // Without a tolerance an answer is only right or wrong, with no side to lean to
+    if close > 0.0 && summary.overall.bias.abs() >= close / 2.0 {
+        println!("Your estimates tend to run {}", if summary.overall.bias > 0.0 { "high" } else { "low" });
+    }
+}
+
+// Pulls -q/--quiet and -v/-vv/--verbose out of the arguments wherever they
+// appear, so each command's own parser never sees them
+fn take_verbosity(args: &mut Vec<String>) -> i8 {
+    let mut verbosity = 0i8;
+    args.retain(|arg| {
+        let change = match arg.as_str() {
+            "-q" | "--quiet" => -1,
+            "-v" | "--verbose" => 1,
+            "-vv" => 2,
+            _ => return true,
+        };
+        verbosity = verbosity.saturating_add(change);
+        false
+    });
+    verbosity
+}
+
+// Library notices (bulk progress, export paths, server requests) go to stderr
+// through tracing, leaving stdout to the results
+fn init_logging(verbosity: i8) {
+    let level = match verbosity {
+        i8::MIN..=-1 => tracing::Level::WARN,
+        0 => tracing::Level::INFO,
+        1 => tracing::Level::DEBUG,
+        _ => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .with_target(verbosity > 0)
+        .with_level(verbosity != 0)
+        .init();
+}
+
+// "5000,3000,2000" into numbers
+fn parse_number_list(text: &str) -> Result<Vec<f64>, String> {
+    text.split(',')
+        .map(|part| part.trim().parse::<f64>().map_err(|_| format!("Invalid number '{}' in '{}'", part.trim(), text)))
+        .collect()
+}
+
+// A positive number following `flag`
+fn parse_amount(value: Option<&String>, flag: &str) -> Result<f64, String> {
+    match value.map(|v| v.parse::<f64>()) {
+        Some(Ok(amount)) if amount > 0.0 && amount.is_finite() => Ok(amount),
+        _ => Err(format!("{} requires a positive number", flag)),
+    }
+}
+
+// Hero's equity (0-1) from a hand and optional board given as card arguments,
+// against a villain range or a random hand, printing what was simulated
+fn simulated_equity(card_args: &[Vec<Card>], range_text: Option<&str>, num_sims: usize, rng_choice: rng::RngChoice) -> Result<f64, String> {
+    let hand: [Card; 2] = card_args.first()
+        .and_then(|cards| cards.clone().try_into().ok())
+        .ok_or("Enter exactly two hole cards")?;
+    let board: Vec<Card> = card_args.iter().skip(1).flatten().cloned().collect();
+    if board.len() > 5 {
+        return Err("The board has at most five cards".to_string());
+    }
+    card::check_distinct(hand.iter().chain(board.iter()))?;
+    let villain_range = range_text.map(range::Range::parse).transpose()?;
+    
+    println!("Your hand: {}", format_cards(&hand));
+    if !board.is_empty() {
+        println!("Board: {}", format_cards(&board));
+    }
+    let mut rng = rng_choice.build();
+    let results = match (&villain_range, range_text) {
+        (Some(villain_range), Some(text)) => {
+            println!("Villain range: {}", text);
+            range::monte_carlo_vs_range_with_rng(&hand, villain_range, &board, num_sims, &mut rng)
+        },
+        _ => {
+            println!("Villain: random hand");
+            monte_carlo_with_community_with_rng(&hand, &board, num_sims, &mut rng)
+        },
+    };
+    if results.total_games == 0 {
+        return Err("No showdowns could be simulated; every villain combo is blocked by known cards".to_string());
+    }
+    Ok((results.win_rate + results.tie_rate / 2.0) / 100.0)
+}
+
+// mdf and alpha share their input and table; each leads with its own figure
+fn run_bet_math(args: &[String], command: &str) {
+    let usage = format!("Usage: pokershit {} <pot> [bet], e.g. {} 100 75 (without a bet, common sizes are listed)", command, command);
+    let amounts: Result<Vec<f64>, String> = args.iter()
+        .map(|arg| arg.parse::<f64>().map_err(|_| format!("Invalid amount '{}'", arg)))
+        .collect();
+    let (pot, bet) = match amounts.as_deref() {
+        Ok([pot]) => (*pot, None),
+        Ok([pot, bet]) => (*pot, Some(*bet)),
+        Ok(_) => {
+            println!("Enter the pot and optionally the bet\n{}", usage);
+            return;
+        },
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    let bets: Vec<f64> = match bet {
+        Some(bet) => vec![bet],
+        None => strategy::COMMON_BET_SIZES.iter().map(|fraction| pot * fraction).collect(),
+    };
+    let analyses: Result<Vec<strategy::BetMath>, String> = bets.iter().map(|&bet| strategy::analyze_bet(pot, bet)).collect();
+    let analyses = match analyses {
+        Ok(analyses) => analyses,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    
+    if let [analysis] = analyses[..] {
+        println!("\n=== Bet of {} into {} ({:.0}% pot) ===", analysis.bet, analysis.pot, analysis.bet / analysis.pot * 100.0);
+        let mdf = format!("Minimum defense frequency: {:.1}% (the caller must continue this often)", analysis.minimum_defense * 100.0);
+        let alpha = format!("Bluff break-even (alpha): {:.1}% (a bluff must get this many folds)", analysis.bluff_break_even * 100.0);
+        if command == "alpha" {
+            println!("{}\n{}", alpha, mdf);
+        } else {
+            println!("{}\n{}", mdf, alpha);
+        }
+        println!("Caller's required equity: {:.1}%", analysis.call_equity * 100.0);
+        return;
+    }
+    
+    println!("\n=== Bet Sizes into a Pot of {} ===", pot);
+    println!("{:>10} {:>8} {:>8} {:>8} {:>12}", "Bet", "% pot", "MDF", "Alpha", "Call equity");
+    for analysis in &analyses {
+        println!("{:>10.1} {:>7.0}% {:>7.1}% {:>7.1}% {:>11.1}%",
+                 analysis.bet, analysis.bet / pot * 100.0, analysis.minimum_defense * 100.0,
+                 analysis.bluff_break_even * 100.0, analysis.call_equity * 100.0);
+    }
+}
+
+// "35" or "35%" as a fraction from 0 to 1
+fn parse_percent(value: Option<&String>) -> Result<f64, String> {
+    let text = value.ok_or("Expected a percentage, e.g. 35%".to_string())?;
+    match text.trim().trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent / 100.0),
+        _ => Err(format!("Invalid percentage '{}', expected 0-100", text)),
+    }
+}
+
+fn run_view(args: &[String]) {
+    let usage = "Usage: pokershit view <results.csv|results.json> [--sort <key>] [--filter <condition>]..., e.g. view poker_results.json --filter suited";
+    let mut input = None;
+    let mut sort = None;
+    let mut filters = Vec::new();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--sort" => iter.next().ok_or("--sort requires win, lose, tie, hand or chen".to_string())
+                .and_then(|key| key.parse()).map(|key| sort = Some(key)),
+            "--filter" => iter.next().ok_or("--filter requires a condition".to_string())
+                .and_then(|filter| filter.parse()).map(|filter| filters.push(filter)),
+            _ if input.is_none() => {
+                input = Some(arg.clone());
+                Ok(())
+            },
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let input = match input {
+        Some(input) => input,
+        None => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let mut results = match import_results(&input) {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Error reading {}: {}", input, e);
+            return;
+        }
+    };
+    if results.is_empty() {
+        println!("No results in {}", input);
+        return;
+    }
+    results.sort_by(card::by_win_rate);
+    println!("Loaded {} hands from {}", results.len(), input);
+    
+    print_bulk_results(&bulk_view(&results, sort, &filters), Some(50));
+    results_menu(&results, sort, filters);
+}
+
+fn run_diff(args: &[String]) {
+    let usage = "Usage: pokershit diff <before.csv> <after.csv> [--top n] [-o output.csv], e.g. diff heads_up.csv vs4.csv";
+    let mut inputs = Vec::new();
+    let mut top = 20;
+    let mut output = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--top" => parse_simulations(iter.next()).map(|n| top = n),
+            "-o" | "--output" => iter.next().ok_or("-o requires a filename".to_string()).map(|f| output = Some(f.clone())),
+            _ => {
+                inputs.push(arg.clone());
+                Ok(())
+            },
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    if inputs.len() != 2 {
+        println!("{}", usage);
+        return;
+    }
+    
+    let mut runs = Vec::new();
+    for input in &inputs {
+        match import_results(input) {
+            Ok(results) => {
+                println!("Loaded {} hands from {}", results.len(), input);
+                runs.push(results);
+            },
+            Err(e) => {
+                println!("Error reading {}: {}", input, e);
+                return;
+            }
+        }
+    }
+    
+    let diffs = diff::diff_results(&runs[0], &runs[1]);
+    if diffs.is_empty() {
+        println!("The two files have no hands in common");
+        return;
+    }
+    
+    println!("\n=== Biggest Equity Changes ({} of {} hands) ===", top.min(diffs.len()), diffs.len());
+    println!("{:<10} {:>8} {:>8} {:>8} {:>12} {:>6}", "Hand", "Before%", "After%", "Delta", "Rank", "Moved");
+    println!("{}", "-".repeat(57));
+    for diff in diffs.iter().take(top) {
+        println!("{:<10} {:>8.2} {:>8.2} {:>+8.2} {:>12} {:>+6}",
+                 diff.hand,
+                 diff.before_equity,
+                 diff.after_equity,
+                 diff.equity_delta(),
+                 format!("{} -> {}", diff.before_rank, diff.after_rank),
+                 diff.rank_change());
+    }
+    
+    let mut by_rank = diffs.clone();
+    by_rank.sort_by_key(|diff| std::cmp::Reverse(diff.rank_change().abs()));
+    println!("\n=== Biggest Rank Changes ===");
+    for diff in by_rank.iter().take(top) {
+        let direction = if diff.rank_change() > 0 { "up" } else { "down" };
+        println!("{:<10} {} -> {} ({} {})", diff.hand, diff.before_rank, diff.after_rank, direction, diff.rank_change().abs());
+    }
+    
+    let average = diffs.iter().map(|diff| diff.equity_delta()).sum::<f64>() / diffs.len() as f64;
+    println!("\nAverage equity change: {:+.2}%", average);
+    
+    if let Some(output) = output {
+        if let Err(e) = diff::export_diff_csv(&diffs, &output) {
+            println!("Error exporting to CSV: {}", e);
+        }
+    }
+}
+
+fn run_bomb_pot(args: &[String]) {
+    let usage = "Usage: pokershit bombpot <hand> <hand>... [--board1 <cards>] [--board2 <cards>] [-n simulations] [--rng <small|chacha|os>[:seed]], e.g. bombpot AhKh QsQd 9c8c --board1 Th7h2c";
+    let mut hands: Vec<[Card; 2]> = Vec::new();
+    let mut boards = vec![Vec::new(), Vec::new()];
+    let mut num_sims = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--board1" | "--board2" => {
+                let index = if arg == "--board1" { 0 } else { 1 };
+                iter.next().ok_or(format!("{} requires cards", arg))
+                    .and_then(|v| parse_cards(v)).map(|cards| boards[index] = cards)
+            },
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => parse_cards(arg).and_then(|cards| {
+                let hand: [Card; 2] = cards.try_into().map_err(|_| format!("'{}' is not a two-card hand", arg))?;
+                hands.push(hand);
+                Ok(())
+            }),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    if hands.len() < 2 || boards.iter().any(|board| board.len() > 5) {
+        println!("Enter at least two hands and at most five cards per board\n{}", usage);
+        return;
+    }
+    
+    println!("\n=== Double-Board Bomb Pot ===");
+    for (i, board) in boards.iter().enumerate() {
+        if !board.is_empty() {
+            println!("Board {}: {}{}", i + 1, format_cards(board), texture_label(board));
+        }
+    }
+    
+    println!("\nRunning {} simulations...", num_sims);
+    let results = monte_carlo_multi_board_with_rng(&hands, &boards, num_sims, &mut rng_choice.build());
+    
+    println!("\n{:<10} {:<10} {:<10} {:<10} {:<10}", "Hand", "Equity%", "Scoop%", "Chop%", "Lose%");
+    println!("{}", "-".repeat(50));
+    for (hand, result) in hands.iter().zip(results.iter()) {
+        println!("{:<10} {:<10.2} {:<10.2} {:<10.2} {:<10.2}",
+                 format_cards(hand),
+                 result.equity,
+                 result.scoop_rate(),
+                 result.chop_rate(),
+                 result.lose_rate());
+    }
+}
+
+fn run_it(args: &[String]) {
+    let usage = "Usage: pokershit runit <your hand> <opponent hand> [board] [--times n] [-n trials] [--rng <small|chacha|os>[:seed]], e.g. runit AhAd KsKc 7h8h2c --times 2";
+    let mut card_args = Vec::new();
+    let mut runs = 2;
+    let mut num_trials = 10000;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--times" => match iter.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => {
+                    runs = n;
+                    Ok(())
+                },
+                _ => Err("--times requires a positive number".to_string()),
+            },
+            "-n" | "--trials" => parse_simulations(iter.next()).map(|n| num_trials = n),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => parse_cards(arg).map(|cards| card_args.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let hands: Vec<[Card; 2]> = card_args.iter().take(2).filter_map(|cards| cards.clone().try_into().ok()).collect();
+    if hands.len() != 2 {
+        println!("Enter two two-card hands\n{}", usage);
+        return;
+    }
+    let board: Vec<Card> = card_args.iter().skip(2).flatten().cloned().collect();
+    
+    println!("\n=== Run It {} Times ===", runs);
+    println!("Your hand: {}", format_cards(&hands[0]));
+    println!("Opponent's hand: {}", format_cards(&hands[1]));
+    if !board.is_empty() {
+        println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    }
+    
+    match run_it_n_times_with_rng(&hands[0], &hands[1], &board, runs, num_trials, &mut rng_choice.build()) {
+        Ok(results) => {
+            println!("\n{:<14} {:<10}", "Pot share", "Probability");
+            println!("{}", "-".repeat(26));
+            for (share, probability) in results.distribution().iter().rev() {
+                println!("{:<14} {:<10.2}", format!("{:.1}%", share), probability);
+            }
+            println!("\nEquity: {:.2}%", results.equity);
+        },
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn run_pineapple(args: &[String]) {
+    let usage = "Usage: pokershit pineapple <3 cards> [--crazy] [--flop <cards>] [-n simulations], e.g. pineapple AhKh7c --crazy --flop Qh9h2c";
+    let mut hand = None;
+    let mut flop = Vec::new();
+    let mut timing = pineapple::DiscardTiming::Preflop;
+    let mut num_sims = 5000;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--crazy" => {
+                timing = pineapple::DiscardTiming::AfterFlop;
+                Ok(())
+            },
+            "--flop" => iter.next().ok_or("--flop requires cards".to_string())
+                .and_then(|v| parse_cards(v)).map(|cards| flop = cards),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            _ => parse_cards(arg).map(|cards| hand = Some(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let hand: [Card; 3] = match hand.map(|cards| cards.try_into()) {
+        Some(Ok(hand)) => hand,
+        _ => {
+            println!("Enter exactly three hole cards\n{}", usage);
+            return;
+        }
+    };
+    if !flop.is_empty() && flop.len() != 3 {
+        println!("The flop has exactly three cards");
+        return;
+    }
     
-    // Ask user for number of simulations per hand
-    println!("This will run Monte Carlo simulations for all 1,326 possible starting hands.");
+    let variant = if timing == pineapple::DiscardTiming::Preflop { "Pineapple" } else { "Crazy Pineapple" };
+    println!("\n=== {} ===", variant);
+    println!("Your hand: {}", format_cards(&hand));
+    if !flop.is_empty() {
+        println!("Flop: {}", format_cards(&flop));
+    }
     
-    let simulations_per_hand = loop {
-        println!("Enter simulations per hand (recommended: 10-100): ");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read line");
-        
-        match input.trim().parse::<usize>() {
-            Ok(num) if num > 0 => break num,
-            _ => println!("Please enter a positive number"),
+    println!("\nRunning {} simulations per discard...", num_sims);
+    let discards = pineapple::enumerate_discards(&hand, timing, &flop, num_sims);
+    
+    println!("\n{:<10} {:<10} {:<8} {:<8} {:<8}", "Discard", "Keep", "Win%", "Lose%", "Tie%");
+    println!("{}", "-".repeat(46));
+    for discard in &discards {
+        println!("{:<10} {:<10} {:<8.2} {:<8.2} {:<8.2}",
+                 discard.discard.to_string(),
+                 format_cards(&discard.kept),
+                 discard.results.win_rate,
+                 100.0 - discard.results.win_rate - discard.results.tie_rate,
+                 discard.results.tie_rate);
+    }
+    
+    if flop.is_empty() {
+        let results = pineapple::monte_carlo_pineapple(&hand, timing, num_sims);
+        println!("\nEquity with the default discard policy: {:.2}% win, {:.2}% tie", results.win_rate, results.tie_rate);
+    }
+}
+
+fn run_lowball(args: &[String]) {
+    let usage = "Usage: pokershit lowball <your kept cards> <opponent kept cards> [--dead <cards>] [-n simulations], e.g. lowball 7h5d3c2s 8c6d4h3s2d --dead Kh";
+    let mut hands = Vec::new();
+    let mut dead_cards = Vec::new();
+    let mut num_sims = 10000;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--dead" => iter.next().ok_or("--dead requires cards".to_string())
+                .and_then(|v| parse_cards(v)).map(|cards| dead_cards.extend(cards)),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            _ => parse_cards(arg).map(|cards| hands.push(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    if hands.len() != 2 || hands.iter().any(|hand| hand.len() > 5) {
+        println!("Enter the cards each player keeps (at most five)\n{}", usage);
+        return;
+    }
+    
+    println!("\n=== Deuce-to-Seven Single Draw ===");
+    for (name, hand) in ["You", "Opponent"].iter().zip(hands.iter()) {
+        let draws = 5 - hand.len();
+        let kept = if hand.is_empty() { "nothing".to_string() } else { format_cards(hand) };
+        if draws == 0 {
+            println!("{}: {} (pat, {})", name, kept, lowball::evaluate_deuce_to_seven(hand).hand.rank);
+        } else {
+            println!("{}: {} drawing {}", name, kept, draws);
+        }
+    }
+    
+    println!("\nRunning {} simulations...", num_sims);
+    let start_time = Instant::now();
+    let results = lowball::monte_carlo_deuce_to_seven(&hands[0], &hands[1], &dead_cards, num_sims);
+    print_simulation_results(&results, start_time.elapsed());
+}
+
+fn run_five_card_draw(args: &[String]) {
+    let usage = "Usage: pokershit draw <5 cards> [--opponents n] [-n simulations], e.g. draw AhKh7h2h9c";
+    let mut hand = None;
+    let mut num_opponents = 1;
+    let mut num_sims = 5000;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--opponents" => match iter.next().and_then(|n| n.parse().ok()) {
+                Some(n) if (1..=5).contains(&n) => {
+                    num_opponents = n;
+                    Ok(())
+                },
+                _ => Err("--opponents must be between 1 and 5".to_string()),
+            },
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            _ => parse_cards(arg).map(|cards| hand = Some(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let hand = match hand {
+        Some(cards) if cards.len() == 5 => cards,
+        _ => {
+            println!("Enter exactly five cards\n{}", usage);
+            return;
+        }
+    };
+    
+    println!("\n=== Five-Card Draw Analysis ===");
+    println!("Your hand: {} ({})", format_cards(&hand), five_card_draw::evaluate_five(&hand).rank);
+    println!("Opponents: {}", num_opponents);
+    println!("\nRunning {} simulations per option...", num_sims);
+    
+    let analyses = five_card_draw::analyze_draws(&hand, num_opponents, num_sims);
+    
+    println!("\n{:<22} {:<18} {:<10} {:<8} {:<8}", "Option", "Keep", "Improve%", "Win%", "Tie%");
+    println!("{}", "-".repeat(70));
+    for analysis in &analyses {
+        let keep = if analysis.option.keep.is_empty() { "-".to_string() } else { format_cards(&analysis.option.keep) };
+        println!("{:<22} {:<18} {:<10.2} {:<8.2} {:<8.2}",
+                 analysis.option.label,
+                 keep,
+                 analysis.improvement_rate,
+                 analysis.results.win_rate,
+                 analysis.results.tie_rate);
+    }
+}
+
+fn parse_simulations(value: Option<&String>) -> Result<usize, String> {
+    match value.and_then(|n| n.parse().ok()) {
+        Some(n) if n > 0 => Ok(n),
+        _ => Err("-n requires a positive number".to_string()),
+    }
+}
+
+// A ± margin in percentage points: "0.25", "0.25%" or "±0.25%"
+fn parse_precision(value: Option<&String>) -> Result<f64, String> {
+    let text = value.ok_or("--precision requires a margin, e.g. 0.5%".to_string())?;
+    match text.trim().trim_start_matches('±').trim_end_matches('%').parse::<f64>() {
+        Ok(margin) if margin > 0.0 && margin < 50.0 => Ok(margin),
+        _ => Err(format!("Invalid precision '{}', expected a margin between 0 and 50%, e.g. 0.5%", text)),
+    }
+}
+
+// "30s", "2m", "1h", "500ms" or plain seconds
+fn parse_duration(value: Option<&String>) -> Result<std::time::Duration, String> {
+    let text = value.ok_or("--duration requires a time like 30s, 2m or 500ms".to_string())?.trim();
+    let split = text.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(text.len());
+    let seconds = match (text[..split].parse::<f64>(), &text[split..]) {
+        (Ok(ms), "ms") => ms / 1000.0,
+        (Ok(seconds), "" | "s") => seconds,
+        (Ok(minutes), "m") => minutes * 60.0,
+        (Ok(hours), "h") => hours * 3600.0,
+        _ => f64::NAN,
+    };
+    if !(seconds > 0.0 && seconds.is_finite()) {
+        return Err(format!("Invalid duration '{}', expected a time like 30s, 2m or 500ms", text));
+    }
+    Ok(std::time::Duration::from_secs_f64(seconds))
+}
+
+fn parse_output(value: Option<&String>) -> Result<output::OutputFormat, String> {
+    value.ok_or("--output requires json, csv, plain or markdown".to_string())?.parse()
+}
+
+// A write error here is almost always the reader going away, e.g. piping into head
+fn print_record(format: output::OutputFormat, fields: &[(String, json::JsonValue)]) {
+    if let Err(e) = output::write_record(&mut io::stdout().lock(), format, fields) {
+        eprintln!("Error writing results: {}", e);
+    }
+}
+
+fn parse_rng(value: Option<&String>) -> Result<rng::RngChoice, String> {
+    value.ok_or("--rng requires small, chacha or os".to_string())?.parse()
+}
+
+// Board texture in parentheses, or nothing before the flop
+fn texture_label(board: &[Card]) -> String {
+    match texture::classify_board(board) {
+        Ok(texture) => format!(" ({})", texture),
+        Err(_) => String::new(),
+    }
+}
+
+// Board cards in filename-safe form, e.g. "AsKd7h"
+fn board_file_tag(board: &[Card]) -> String {
+    board.iter()
+        .map(|card| {
+            let suit = match card.suit {
+                Suit::Spades => 's',
+                Suit::Hearts => 'h',
+                Suit::Diamonds => 'd',
+                Suit::Clubs => 'c',
+            };
+            format!("{}{}", card.rank, suit)
+        })
+        .collect()
+}
+
+fn format_cards(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn run_stud(args: &[String]) {
+    let usage = "Usage: pokershit stud <your cards> [--opp <upcards>]... [--dead <cards>] [-n simulations], e.g. stud AsAhKd --opp 9c --opp Qs --dead 2h5d";
+    let mut player_cards = None;
+    let mut opponents = Vec::new();
+    let mut dead_cards = Vec::new();
+    let mut num_sims = 10000;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--opp" => iter.next().ok_or("--opp requires cards".to_string())
+                .and_then(|v| parse_cards(v)).map(|cards| opponents.push(cards)),
+            "--dead" => iter.next().ok_or("--dead requires cards".to_string())
+                .and_then(|v| parse_cards(v)).map(|cards| dead_cards.extend(cards)),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| num_sims = n),
+            _ => parse_cards(arg).map(|cards| player_cards = Some(cards)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    
+    let player_cards = match player_cards {
+        Some(cards) if (2..=7).contains(&cards.len()) => cards,
+        _ => {
+            println!("Enter between two and seven of your cards\n{}", usage);
+            return;
         }
     };
+    if opponents.is_empty() {
+        opponents.push(Vec::new());
+    }
+    if opponents.iter().any(|upcards| upcards.len() > 4) {
+        println!("Opponents show at most four up-cards");
+        return;
+    }
     
-    let total_simulations = 1326 * simulations_per_hand;
-    println!("Total simulations to run: {}", total_simulations);
-    println!("This may take a while...\n");
+    println!("\n=== Seven-Card Stud Monte Carlo Simulation ===");
+    println!("Your cards: {}", format_cards(&player_cards));
+    for (i, upcards) in opponents.iter().enumerate() {
+        println!("Opponent {} up-cards: {}", i + 1, if upcards.is_empty() { "(none)".to_string() } else { format_cards(upcards) });
+    }
+    if !dead_cards.is_empty() {
+        println!("Dead cards: {}", format_cards(&dead_cards));
+    }
     
+    println!("\nRunning {} simulations...", num_sims);
     let start_time = Instant::now();
-    let results = bulk_monte_carlo_simulation(simulations_per_hand);
-    let duration = start_time.elapsed();
+    let results = stud::monte_carlo_stud(&player_cards, &opponents, &dead_cards, num_sims);
+    print_simulation_results(&results, start_time.elapsed());
+}
+
+fn run_omaha(args: &[String]) {
+    let mut card_args = Vec::new();
+    let mut num_sims = 10000;
+    let mut hi_lo = false;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--hilo" => hi_lo = true,
+            "-n" | "--simulations" => match parse_simulations(iter.next()) {
+                Ok(n) => num_sims = n,
+                Err(e) => {
+                    println!("{}", e);
+                    return;
+                }
+            },
+            _ => card_args.push(arg.clone()),
+        }
+    }
     
-    // Print top 50 results by default
-    print_bulk_results(&results, Some(50));
+    let usage = "Usage: pokershit omaha <4 hole cards> [board cards] [-n simulations] [--hilo], e.g. omaha AsAhKsKh 2c7d9h";
+    let hand: [Card; 4] = match card_args.first().map(|s| parse_cards(s)) {
+        Some(Ok(cards)) => match cards.try_into() {
+            Ok(hand) => hand,
+            Err(_) => {
+                println!("Omaha hands have exactly four hole cards\n{}", usage);
+                return;
+            }
+        },
+        Some(Err(e)) => {
+            println!("{}\n{}", e, usage);
+            return;
+        },
+        None => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let board = match parse_cards(&card_args[1..].join(" ")) {
+        Ok(board) if board.len() <= 5 => board,
+        Ok(_) => {
+            println!("The board has at most five cards");
+            return;
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
     
-    println!("\n=== Performance ===");
-    println!("Total time: {:.2}s", duration.as_secs_f64());
-    println!("Simulations per second: {:.0}", total_simulations as f64 / duration.as_secs_f64());
+    println!("\n=== Omaha{} Monte Carlo Simulation ===", if hi_lo { " Hi/Lo" } else { "" });
+    println!("Your hand: {} {} {} {}", hand[0], hand[1], hand[2], hand[3]);
+    if !board.is_empty() {
+        println!("Board: {}{}", format_cards(&board), texture_label(&board));
+    }
     
-    // Automatically export to CSV files
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let csv_filename = format!("poker_results_{}.csv", timestamp);
+    println!("\nRunning {} simulations...", num_sims);
+    let start_time = Instant::now();
+    let results = if hi_lo {
+        omaha::monte_carlo_omaha_hi_lo(&hand, &board, num_sims)
+    } else {
+        omaha::monte_carlo_omaha_with_community(&hand, &board, num_sims)
+    };
+    print_simulation_results(&results, start_time.elapsed());
+}
+
+fn run_merge(args: &[String]) {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = iter.next().cloned(),
+            _ => inputs.push(arg.clone()),
+        }
+    }
+    
+    if inputs.is_empty() {
+        println!("Usage: pokershit merge <shard.csv>... [-o output.csv]");
+        return;
+    }
+    
+    let mut shards = Vec::new();
+    for input in &inputs {
+        match import_results(input) {
+            Ok(results) => {
+                println!("Loaded {} hands from {}", results.len(), input);
+                shards.push(results);
+            },
+            Err(e) => {
+                println!("Error reading {}: {}", input, e);
+                return;
+            }
+        }
+    }
+    
+    let merged = merge_results(&shards);
+    print_bulk_results(&merged, Some(50));
+    
+    let output = output.unwrap_or_else(|| {
+        format!("poker_results_merged_{}.csv", chrono::Local::now().format("%Y%m%d_%H%M%S"))
+    });
+    if let Err(e) = export_to_csv(&merged, &output) {
+        println!("Error exporting to CSV: {}", e);
+    }
+}
+
+fn run_bulk(options: BulkOptions) {
+    // With --output, stdout carries nothing but the table
+    let report = options.output.is_none();
+    if report {
+        println!("=== Texas Hold'em Monte Carlo Analysis ===");
+    }
+    
+    // --canonical simulates one combo per suit-isomorphic class (169 instead of 1,326)
+    let mut all_hands = if options.canonical {
+        generate_canonical_starting_hands()
+    } else {
+        generate_all_starting_hands()
+    };
+    all_hands.retain(|hand| !hand.iter().any(|card| options.board.contains(card)));
+    if let Some(range) = &options.only {
+        all_hands.retain(|hand| range.contains(hand));
+        if all_hands.is_empty() {
+            println!("No starting hands match the --only range.");
+            return;
+        }
+    }
+    let hands = match options.shard {
+        Some(shard) => {
+            let hands = shard.select(&all_hands);
+            if report {
+                println!("Running shard {} ({} of {} starting hands).", shard, hands.len(), all_hands.len());
+            }
+            hands
+        },
+        None => all_hands,
+    };
+    if hands.is_empty() {
+        println!("No starting hands fall in this shard.");
+        return;
+    }
+    
+    if report {
+        println!("This will run Monte Carlo simulations for {} possible starting hands.", hands.len());
+        if let Some(percent) = options.villain_top {
+            println!("Opponents fold everything but the top {}% of hands preflop; folded hands are re-dealt.", percent);
+        } else if options.opponents > 1 {
+            println!("Each hand plays against {} random opponents.", options.opponents);
+        }
+        if !options.board.is_empty() {
+            println!("Board: {}{}", format_cards(&options.board), texture_label(&options.board));
+            println!("Hands holding a board card are skipped.");
+        }
+        if options.rng != rng::RngChoice::default() {
+            println!("Random number generator: {}", options.rng);
+        }
+        if options.sampling != card::Sampling::Independent {
+            println!("Every hand plays the same deals: {}.", options.sampling);
+        }
+    }
+    
+    // Ask user for number of simulations per hand
+    let budget = match (options.duration, options.simulations) {
+        (Some(time), _) => TrialBudget::Time(time),
+        (None, Some(simulations)) => TrialBudget::Count(simulations),
+        (None, None) => TrialBudget::Count({
+            let guide: Vec<String> = [100, 1000, 10000].iter()
+                .map(|&trials| format!("±{:.1}% at {}", card::margin_for_trials(trials, card::PLANNING_EQUITY), trials))
+                .collect();
+            println!("Win rates land within about {} simulations (95% confidence)", guide.join(", "));
+            loop {
+                println!("Enter simulations per hand (recommended: 10-100), or a precision like 0.5%: ");
+                let mut input = String::new();
+                io::stdin().read_line(&mut input).expect("Failed to read line");
+                
+                let input = input.trim();
+                if input.ends_with('%') {
+                    match parse_precision(Some(&input.to_string())) {
+                        Ok(margin) => {
+                            let trials = card::trials_for_margin(margin, card::PLANNING_EQUITY);
+                            println!("±{}% needs {} simulations per hand", margin, trials);
+                            break trials;
+                        },
+                        Err(e) => println!("{}", e),
+                    }
+                    continue;
+                }
+                match input.parse::<usize>() {
+                    Ok(num) if num > 0 => break num,
+                    _ => println!("Please enter a positive number"),
+                }
+            }
+        }),
+    };
+    
+    if report {
+        match budget {
+            TrialBudget::Count(simulations_per_hand) => {
+                if let Some(margin) = options.precision {
+                    println!("{} simulations per hand keep each win rate within ±{}% (95% confidence).", simulations_per_hand, margin);
+                }
+                println!("Total simulations to run: {}", hands.len() * simulations_per_hand)
+            },
+            TrialBudget::Time(time) => println!("Each hand runs for {:.1}s, about {:.0}s in all.", time.as_secs_f64(), time.as_secs_f64() * hands.len() as f64),
+        }
+        println!("This may take a while...\n");
+    }
+    
+    // Results are streamed to the CSV as each hand finishes, then rewritten ranked below
+    let mut timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    if options.opponents > 1 {
+        timestamp = format!("{}_vs{}", timestamp, options.opponents);
+    }
+    if let Some(percent) = options.villain_top {
+        timestamp = format!("{}_top{}", timestamp, percent);
+    }
+    if !options.board.is_empty() {
+        timestamp = format!("{}_{}", timestamp, board_file_tag(&options.board));
+    }
+    if let Some(shard) = options.shard {
+        timestamp = format!("{}_shard{}of{}", timestamp, shard.index, shard.count);
+    }
+    let compressed = options.compress.map_or("", |compression| compression.extension());
+    let csv_filename = format!("poker_results_{}.csv{}", timestamp, compressed);
     let summary_filename = format!("poker_summary_{}.csv", timestamp);
     
+    let start_time = Instant::now();
+    let mut rng = options.rng.build();
+    let on_progress = progress_bar();
+    let model = options.villain_top.map(|percent| opponent::RangeModel::top_percent(percent).expect("--villain-top was checked when parsed"));
+    let streamed = card::CsvStreamWriter::new(&csv_filename, STREAM_FLUSH_INTERVAL).and_then(|writer| match (&model, budget) {
+        (None, TrialBudget::Count(simulations_per_hand)) if options.sampling != card::Sampling::Independent => {
+            let deals = card::CommonDeals::new(simulations_per_hand, options.opponents, &options.board, options.sampling, &mut rng)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            card::bulk_simulation_streaming_with(&hands, budget, options.opponents, &options.board, writer, |hand| deals.play(hand), on_progress)
+        },
+        (Some(model), _) => {
+            let simulate = |hand: &[Card; 2]| budget.run(|trials| opponent::monte_carlo_vs_model_with_rng(hand, options.opponents, &options.board, trials, model, &mut rng).results);
+            card::bulk_simulation_streaming_with(&hands, budget, options.opponents, &options.board, writer, simulate, on_progress)
+        },
+        (None, _) => bulk_monte_carlo_simulation_streaming(&hands, budget, options.opponents, &options.board, writer, &mut rng, on_progress),
+    });
+    let mut results = match streamed {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Error streaming results to {}: {}", csv_filename, e);
+            return;
+        }
+    };
+    // The streamed file keeps the first pass; the ranked export below has the refined counts
+    let refinement = (options.adaptive > 0).then(|| match &model {
+        Some(model) => card::refine_ranking_with(&mut results, options.adaptive, |hand| budget.run(|trials| opponent::monte_carlo_vs_model_with_rng(hand, options.opponents, &options.board, trials, model, &mut rng).results)),
+        None => card::refine_ranking(&mut results, budget, options.opponents, &options.board, options.adaptive, &mut rng),
+    });
+    let duration = start_time.elapsed();
+    let total_simulations: usize = results.iter().map(|result| result.results.total_games).sum();
+    // With a time budget or adaptive rounds each hand has a different number; the exports record the average
+    let simulations_per_hand = match budget {
+        TrialBudget::Count(simulations_per_hand) if refinement.is_none() => simulations_per_hand,
+        _ => total_simulations / results.len().max(1),
+    };
+    
+    // The table and custom exports show the sorted, filtered view; the automatic exports keep every hand
+    if report {
+        print_bulk_results(&bulk_view(&results, options.sort, &options.filters), Some(50));
+        
+        println!("\n=== Performance ===");
+        println!("Total time: {:.2}s", duration.as_secs_f64());
+        println!("Simulations per second: {:.0}", total_simulations as f64 / duration.as_secs_f64());
+        if matches!(budget, TrialBudget::Time(_)) {
+            print_budget_confidence(&results);
+        }
+        if let Some(refinement) = &refinement {
+            println!("Adaptive rounds: {} ({} extra simulations, {:.0}% of the first pass)", refinement.rounds, refinement.extra_simulations,
+                     refinement.extra_simulations as f64 / (total_simulations - refinement.extra_simulations).max(1) as f64 * 100.0);
+            println!("Hands still overlapping a neighbour: {} of {}", refinement.unsettled, results.len());
+        }
+    }
+    
+    // Automatically export to CSV files
     if let Err(e) = export_to_csv(&results, &csv_filename) {
         println!("Error exporting to CSV: {}", e);
     }
     
-    if let Err(e) = export_summary_to_csv(&results, &summary_filename, simulations_per_hand, duration) {
+    if let Err(e) = export_summary_to_csv(&results, &summary_filename, simulations_per_hand, &options.board, duration) {
         println!("Error exporting summary: {}", e);
     }
     
+    if options.json {
+        if let Err(e) = card::export_to_json(&results, &format!("poker_results_{}.json{}", timestamp, compressed)) {
+            println!("Error exporting to JSON: {}", e);
+        }
+    }
+    
+    if options.markdown {
+        if let Err(e) = card::export_markdown_report(&results, &format!("poker_results_{}.md", timestamp), simulations_per_hand, &options.board, duration) {
+            println!("Error exporting to Markdown: {}", e);
+        }
+    }
+    
+    if options.xlsx {
+        #[cfg(feature = "xlsx")]
+        if let Err(e) = spreadsheet::export_workbook(&results, &format!("poker_results_{}.xlsx", timestamp), simulations_per_hand, &options.board, duration) {
+            println!("Error exporting to Excel: {}", e);
+        }
+        #[cfg(not(feature = "xlsx"))]
+        println!("Excel export requires building with the xlsx feature");
+    }
+    
+    #[cfg(feature = "parquet")]
+    {
+        let parquet_filename = format!("poker_results_{}.parquet", timestamp);
+        if let Err(e) = card::export_to_parquet(&results, &parquet_filename, simulations_per_hand) {
+            println!("Error exporting to Parquet: {}", e);
+        }
+    }
+    
+    if options.report {
+        let report_filename = format!("poker_report_{}.html", timestamp);
+        if let Err(e) = report::export_html_report(&results, &report_filename, simulations_per_hand, duration) {
+            println!("Error exporting report: {}", e);
+        }
+    }
+    
+    if options.images {
+        #[cfg(feature = "plotters")]
+        if let Err(e) = chart::export_images(&results, &format!("poker_{}", timestamp)) {
+            println!("Error exporting images: {}", e);
+        }
+        #[cfg(not(feature = "plotters"))]
+        println!("Image export requires building with the plotters feature");
+    }
+    
+    if let Some(format) = options.output {
+        if let Err(e) = output::write_results(&mut io::stdout().lock(), format, &bulk_view(&results, options.sort, &options.filters)) {
+            eprintln!("Error writing results: {}", e);
+        }
+        return;
+    }
+    
+    results_menu(&results, options.sort, options.filters);
+}
+
+// How evenly a time budget was spent across the hands, and the least precise hand
+fn print_budget_confidence(results: &[card::HandResult]) {
+    let margin = |result: &card::HandResult| {
+        let (low, high) = result.results.equity_ci();
+        (high - low) / 2.0
+    };
+    let counts = results.iter().map(|result| result.results.total_games);
+    if let (Some(fewest), Some(most), Some(widest)) = (counts.clone().min(), counts.max(), results.iter().max_by(|a, b| margin(a).total_cmp(&margin(b)))) {
+        let average = results.iter().map(|result| result.results.total_games).sum::<usize>() / results.len();
+        let (low, high) = widest.results.equity_ci();
+        println!("Simulations per hand: {}-{} (average {})", fewest, most, average);
+        println!("Widest 95% confidence interval: {} equity {:.2}-{:.2}% (±{:.2}%)", widest.hand_description, low, high, margin(widest));
+    }
+}
+
+// Redraws at most this often so a fast run doesn't spend its time on the terminal
+const PROGRESS_REDRAW: std::time::Duration = std::time::Duration::from_millis(100);
+const PROGRESS_WIDTH: usize = 30;
+
+// The bulk run's progress callback: a bar redrawn in place on stderr, or a
+// line logged every 100 hands when stderr is a file. Silent under -q and --output.
+fn progress_bar() -> impl FnMut(&card::BulkProgress) {
+    use std::io::{IsTerminal, Write};
+    
+    let shown = tracing::enabled!(tracing::Level::INFO);
+    let terminal = io::stderr().is_terminal();
+    let mut last_draw: Option<Instant> = None;
+    move |progress| {
+        if !shown {
+            return;
+        }
+        let finished = progress.completed == progress.total;
+        if !terminal {
+            if progress.completed % 100 == 0 || finished {
+                tracing::info!("Progress: {}", progress_stats(progress));
+            }
+            return;
+        }
+        if !finished && last_draw.is_some_and(|time| time.elapsed() < PROGRESS_REDRAW) {
+            return;
+        }
+        let filled = ((progress.percent() / 100.0 * PROGRESS_WIDTH as f64) as usize).min(PROGRESS_WIDTH);
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r[{}{}] {}", "#".repeat(filled), "-".repeat(PROGRESS_WIDTH - filled), progress_stats(progress));
+        if finished {
+            let _ = writeln!(stderr);
+        }
+        let _ = stderr.flush();
+        last_draw = Some(Instant::now());
+    }
+}
+
+// "45.2% 600/1326 hands, 120.5 hands/s, 12050 sims/s, ETA 0:06"
+fn progress_stats(progress: &card::BulkProgress) -> String {
+    let eta = match progress.remaining() {
+        Some(remaining) => {
+            let seconds = remaining.as_secs_f64().round() as u64;
+            if seconds >= 3600 {
+                format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+            } else {
+                format!("{}:{:02}", seconds / 60, seconds % 60)
+            }
+        },
+        None => "-".to_string(),
+    };
+    format!("{:5.1}% {}/{} hands, {:.1} hands/s, {:.0} sims/s, ETA {}",
+            progress.percent(), progress.completed, progress.total, progress.hands_per_second(), progress.simulations_per_second(), eta)
+}
+
+// With the tui feature the dashboard takes the place of the numbered menu
+#[cfg(feature = "tui")]
+fn results_menu(results: &[card::HandResult], sort: Option<SortKey>, filters: Vec<ResultFilter>) {
+    if let Err(e) = tui::run(results.to_vec(), sort, filters, rng::RngChoice::default()) {
+        println!("Error running the dashboard: {}", e);
+    }
+}
+
+// Interactive viewer over a finished (or re-imported) bulk run
+#[cfg(not(feature = "tui"))]
+fn results_menu(results: &[card::HandResult], mut sort: Option<SortKey>, mut filters: Vec<ResultFilter>) {
+    let mut view = bulk_view(results, sort, &filters);
+    
     // Ask if user wants to see more results
     loop {
         println!("\nOptions:");
         println!("1. Show top 100 hands");
         println!("2. Show all hands");
         println!("3. Show bottom 50 hands");
-        println!("4. Export custom CSV (choose filename)");
-        println!("5. Exit");
-        print!("Enter choice (1-5): ");
+        println!("4. Export custom CSV, JSON or Markdown (choose filename)");
+        println!("5. Show 169 canonical hands");
+        println!("6. Show 13x13 hand grid");
+        println!("7. Sort and filter results");
+        println!("8. Exit");
+        print!("Enter choice (1-8): ");
         
         let mut input = String::new();
         io::stdin().read_line(&mut input).expect("Failed to read line");
         
         match input.trim() {
-            "1" => print_bulk_results(&results, Some(100)),
-            "2" => print_bulk_results(&results, None),
+            "1" => print_bulk_results(&view, Some(100)),
+            "2" => print_bulk_results(&view, None),
             "3" => {
-                let start_idx = results.len().saturating_sub(50);
-                let bottom_results = &results[start_idx..];
+                let start_idx = view.len().saturating_sub(50);
+                let bottom_results = &view[start_idx..];
                 println!("\n=== Bottom 50 Hands ===");
                 print_bulk_results(bottom_results, None);
             },
             "4" => {
-                println!("Enter filename (without .csv extension): ");
+                println!("Enter filename (ending in .json for JSON, .md for Markdown, otherwise .csv is added; add .gz or .zst to compress): ");
                 let mut filename_input = String::new();
                 io::stdin().read_line(&mut filename_input).expect("Failed to read line");
-                let filename = format!("{}.csv", filename_input.trim());
+                let filename = filename_input.trim();
                 
-                if let Err(e) = export_to_csv(&results, &filename) {
-                    println!("Error exporting to CSV: {}", e);
+                let exported = if compress::base_name(filename).to_ascii_lowercase().ends_with(".json") {
+                    card::export_to_json(&view, filename)
+                } else if filename.to_ascii_lowercase().ends_with(".md") {
+                    card::export_to_markdown(&view, filename)
+                } else if compress::Compression::from_filename(filename).is_some() {
+                    export_to_csv(&view, filename)
+                } else {
+                    export_to_csv(&view, &format!("{}.csv", filename))
+                };
+                if let Err(e) = exported {
+                    println!("Error exporting results: {}", e);
                 }
             },
             "5" => {
+                let canonical = bulk_view(&card::aggregate_canonical(results), sort, &filters);
+                println!("\n=== Canonical Starting Hands ===");
+                print_bulk_results(&canonical, None);
+            },
+            "6" => card::print_hand_grid(results),
+            "7" => {
+                println!("Sort by (win, lose, tie, hand, chen; blank keeps current): ");
+                let mut sort_input = String::new();
+                io::stdin().read_line(&mut sort_input).expect("Failed to read line");
+                if !sort_input.trim().is_empty() {
+                    match sort_input.trim().parse() {
+                        Ok(key) => sort = Some(key),
+                        Err(e) => println!("{}", e),
+                    }
+                }
+                
+                println!("Filters separated by commas, e.g. win > 55%, suited (blank keeps current, 'none' clears): ");
+                let mut filter_input = String::new();
+                io::stdin().read_line(&mut filter_input).expect("Failed to read line");
+                match filter_input.trim() {
+                    "" => {},
+                    "none" => filters.clear(),
+                    text => match text.split(',').map(|f| f.parse()).collect::<Result<Vec<ResultFilter>, String>>() {
+                        Ok(parsed) => filters = parsed,
+                        Err(e) => println!("{}", e),
+                    },
+                }
+                
+                view = bulk_view(results, sort, &filters);
+                println!("{} of {} hands shown", view.len(), results.len());
+                print_bulk_results(&view, Some(50));
+            },
+            "8" => {
                 println!("Goodbye!");
                 break;
             },
-            _ => println!("Invalid choice, please enter 1-5"),
+            _ => println!("Invalid choice, please enter 1-8"),
         }
     }
 }
 
-fn play_single_hand() {
+// Bulk results after the row filters, reordered when a sort key is set
+fn bulk_view(results: &[card::HandResult], sort: Option<SortKey>, filters: &[ResultFilter]) -> Vec<card::HandResult> {
+    let mut view = filter_results(results, filters);
+    if let Some(key) = sort {
+        sort_results(&mut view, key);
+    }
+    view
+}
+
+fn play_single_hand(args: &[String]) {
+    let usage = "Usage: pokershit play [--stack chips] [--blinds small/big] [-n bot simulations] [--history <hand.txt|hand.phh>] [--rng <small|chacha|os>[:seed]], e.g. play --stack 2000 --blinds 10/20";
+    let mut stack = 1000;
+    let mut blinds = (5, 10);
+    let mut bot_sims = 2000;
+    let mut history_file = None;
+    let mut rng_choice = rng::RngChoice::default();
+    let mut iter = args.iter();
+    
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--stack" => parse_simulations(iter.next()).and_then(|n| u32::try_from(n).map_err(|_| "Stack is too large".to_string())).map(|n| stack = n),
+            "--blinds" => parse_blinds(iter.next()).map(|b| blinds = b),
+            "-n" | "--simulations" => parse_simulations(iter.next()).map(|n| bot_sims = n),
+            "--history" => iter.next().ok_or("--history requires a file name".to_string())
+                .map(|f| history_file = Some(f.clone())),
+            "--rng" => parse_rng(iter.next()).map(|choice| rng_choice = choice),
+            _ => Err(format!("Unknown argument: {}", arg)),
+        };
+        if let Err(e) = parsed {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    }
+    let mut rng = rng_choice.build();
+    
     println!("\n=== Single Hand Mode ===");
+    println!("You have the button against the bot, {} chips each, blinds {}/{}", stack, blinds.0, blinds.1);
     println!("Enter your two cards using numbers:");
     println!("Suits: 1=Spades, 2=Hearts, 3=Diamonds, 4=Clubs");
     println!("Ranks: 1=Ace, 2-10=Number cards, 11=Jack, 12=Queen, 13=King");
@@ -106,33 +4119,113 @@ fn play_single_hand() {
 
     println!("\nYour hand: {} {}", user_hand[0], user_hand[1]);
 
-    // Generate opponent's hand from remaining cards
-    let opp_card1 = deck.draw().expect("Failed to draw opponent card 1");
-    let opp_card2 = deck.draw().expect("Failed to draw opponent card 2");
-    let opponent_hand = [opp_card1, opp_card2];
+    let mut game = match game::Game::new(&[stack, stack], game::Stakes::new(blinds.0, blinds.1), play::HERO) {
+        Ok(game) => game,
+        Err(e) => {
+            println!("{}\n{}", e, usage);
+            return;
+        }
+    };
+    // The bot's hand stays face down until showdown
+    game.set_hole_cards(play::HERO, user_hand.clone()).expect("a fresh deck has the user's cards");
+    game.deal_hole_cards_with(&mut rng);
+    let bot_hand = game.players[play::BOT].hole_cards.clone().expect("the bot was dealt in");
+    
+    println!("\n--- {} ---", game.street);
+    while !game.is_over() {
+        let Some(seat) = game.to_act else {
+            // Once someone is all-in the board just runs out
+            game.next_street_with(&mut rng).expect("the street's betting is over");
+            if game.street != game::Street::Showdown {
+                println!("\n--- {} ---", game.street);
+                println!("Board: {}", game.board.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" "));
+            }
+            continue;
+        };
+        
+        println!("Pot: {}  Your stack: {}  Bot stack: {}", game.pot(), game.players[play::HERO].stack, game.players[play::BOT].stack);
+        let facing_bet = game.current_bet() > 0;
+        let action = if seat == play::HERO {
+            get_play_action(&game)
+        } else {
+            let equity = play::bot_equity(&bot_hand, &game.board, bot_sims, &mut rng);
+            play::bot_action(&game, equity, &mut rng)
+        };
+        if let Err(e) = game.apply(action) {
+            println!("{}", e);
+            continue;
+        }
+        
+        let record = game.history.last().expect("the action was recorded");
+        let (name, s) = if seat == play::HERO { ("You", "") } else { ("Bot", "s") };
+        let all_in = if record.all_in { " (all-in)" } else { "" };
+        match record.action {
+            game::Action::Fold => println!("{} fold{}", name, s),
+            game::Action::Check => println!("{} check{}", name, s),
+            game::Action::Call => println!("{} call{}{}", name, s, all_in),
+            game::Action::Bet(total) if facing_bet => println!("{} raise{} to {}{}", name, s, total, all_in),
+            game::Action::Bet(total) => println!("{} bet{} {}{}", name, s, total, all_in),
+            game::Action::AllIn => println!("{} {} all-in", name, if seat == play::HERO { "are" } else { "is" }),
+        }
+    }
 
-    println!("Opponent's hand: {} {}", opponent_hand[0], opponent_hand[1]);
+    println!("\n=== Result ===");
+    if game.street == game::Street::Showdown {
+        let user_eval = card::evaluate_hand(&user_hand, &game.board);
+        let bot_eval = card::evaluate_hand(&bot_hand, &game.board);
+        println!("Your hand: {} {} ({})", user_hand[0], user_hand[1], user_eval.rank);
+        println!("Bot's hand: {} {} ({})", bot_hand[0], bot_hand[1], bot_eval.rank);
+    }
+    
+    let pot = game.pot();
+    let won = game.finish().expect("the hand is over");
+    match (won[play::HERO], won[play::BOT]) {
+        (hero, 0) => println!("You win {} chips", hero),
+        (0, bot) => println!("Bot wins {} chips", bot),
+        (hero, bot) => println!("Split pot of {}: you get {}, the bot {}", pot, hero, bot),
+    }
+    let net = game.players[play::HERO].stack as i64 - stack as i64;
+    println!("Your stack: {} ({:+})", game.players[play::HERO].stack, net);
 
-    // Generate community cards (flop, turn, river)
-    let mut community_cards = Vec::new();
-    for _ in 0..5 {
-        if let Some(card) = deck.draw() {
-            community_cards.push(card);
+    if let Some(file) = history_file {
+        let names = ["Hero".to_string(), "Bot".to_string()];
+        let id = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+        let mut hand = hand_history::HandHistory::from_game(&game, &[stack, stack], &won, &names, &id, "pokershit");
+        hand.hero = Some(play::HERO);
+        if let Err(e) = hand_history::export_hands(&[hand], &file) {
+            println!("Error exporting hand history: {}", e);
         }
     }
+}
 
-    println!("\nCommunity cards:");
-    println!("Flop: {} {} {}", community_cards[0], community_cards[1], community_cards[2]);
-    println!("Turn: {}", community_cards[3]);
-    println!("River: {}", community_cards[4]);
-
-    // Evaluate hands and determine winner
-    let (winner, user_eval, opp_eval) = verify(&user_hand, &opponent_hand, &community_cards);
+// "5/10" into (small blind, big blind)
+fn parse_blinds(value: Option<&String>) -> Result<(u32, u32), String> {
+    let text = value.ok_or("--blinds requires small/big, e.g. 5/10".to_string())?;
+    let parsed = text.split_once('/').and_then(|(small, big)| Some((small.trim().parse::<u32>().ok()?, big.trim().parse::<u32>().ok()?)));
+    match parsed {
+        Some((small, big)) if small > 0 && small <= big => Ok((small, big)),
+        _ => Err(format!("Invalid blinds '{}', expected small/big, e.g. 5/10", text)),
+    }
+}
 
-    println!("\n=== Hand Evaluation ===");
-    println!("Your hand: {}", user_eval.rank);
-    println!("Opponent's hand: {}", opp_eval.rank);
-    println!("Winner: {}", winner);
+// Reads the player's action, re-prompting until one parses; end of input
+// checks or folds
+fn get_play_action(game: &game::Game) -> game::Action {
+    let legal = game.legal_actions();
+    let mut options: Vec<String> = legal.iter().map(|action| action.to_string()).collect();
+    options.push("all-in".to_string());
+    
+    loop {
+        println!("Your action ({}): ", options.join(", "));
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            return if legal.contains(&game::LegalAction::Check) { game::Action::Check } else { game::Action::Fold };
+        }
+        match input.parse() {
+            Ok(action) => return action,
+            Err(e) => println!("{}", e),
+        }
+    }
 }
 
 fn run_preflop_simulation() {
@@ -239,6 +4332,41 @@ fn print_simulation_results(results: &SimulationResults, duration: std::time::Du
     println!("Ties: {} ({:.2}%)", results.ties, results.tie_rate);
     println!("Simulation time: {:.2}s", duration.as_secs_f64());
     println!("Games per second: {:.0}", results.total_games as f64 / duration.as_secs_f64());
+    
+    if let Some(split) = &results.split_pot {
+        println!("\n=== Split Pot ===");
+        println!("Pot equity: {:.2}%", split.pot_equity);
+        println!("High equity: {:.2}%", split.high_equity);
+        println!("Low equity: {:.2}% (low qualified {:.2}%)", split.low_equity, split.low_qualified_rate);
+        println!("Scoop: {:.2}%", split.scoop_rate);
+    }
+    
+    if !results.made_hands.is_empty() {
+        println!("\n=== Made Hands ===");
+        for rank in card::HAND_RANKS.iter().rev() {
+            if let Some(count) = results.made_hands.get(rank) {
+                println!("{:<16} {:>8} ({:.2}%)", rank.to_string(), count, results.made_hand_rate(rank));
+            }
+        }
+    }
+    
+    if let Some(showdown) = &results.showdown {
+        let describe = |average: Option<f64>| match average {
+            Some(average) => format!("{:.2} (~{})", average, card::HAND_RANKS[(average.round() as usize).clamp(1, 10) - 1]),
+            None => "-".to_string(),
+        };
+        println!("\n=== Showdown Strength ===");
+        println!("Average winning hand: {}", describe(showdown.average_winning_rank()));
+        println!("Average hand lost to: {}", describe(showdown.average_losing_to_rank()));
+        println!("Coolers (lost with {} or better): {} ({:.2}%)", card::COOLER_RANK, showdown.coolers, showdown.cooler_rate());
+    }
+    
+    if let Some(streets) = &results.street_equity {
+        println!("\n=== Equity by Street ===");
+        println!("Ahead on the flop: {:.2}%", streets.flop);
+        println!("Ahead on the turn: {:.2}%", streets.turn);
+        println!("Ahead on the river: {:.2}%", streets.river);
+    }
 }
 
 fn get_card_input(prompt: &str) -> Card {
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::card::{Card, generate_canonical_starting_hands, monte_carlo_heads_up};
+use crate::range::{Range, hand_notation};
+
+// Preflop equity of every canonical starting hand against every other
+#[derive(Debug, Clone)]
+pub struct EquityMatrix {
+    // Canonical notation ("AA", "AKs", ...) in row/column order
+    pub hands: Vec<String>,
+    // equity[i][j] is hands[i]'s all-in equity against hands[j], in percent
+    pub equity: Vec<Vec<f64>>,
+}
+
+const SUIT_PERMUTATIONS: [[u8; 4]; 24] = [
+    [0, 1, 2, 3], [0, 1, 3, 2], [0, 2, 1, 3], [0, 2, 3, 1], [0, 3, 1, 2], [0, 3, 2, 1],
+    [1, 0, 2, 3], [1, 0, 3, 2], [1, 2, 0, 3], [1, 2, 3, 0], [1, 3, 0, 2], [1, 3, 2, 0],
+    [2, 0, 1, 3], [2, 0, 3, 1], [2, 1, 0, 3], [2, 1, 3, 0], [2, 3, 0, 1], [2, 3, 1, 0],
+    [3, 0, 1, 2], [3, 0, 2, 1], [3, 1, 0, 2], [3, 1, 2, 0], [3, 2, 0, 1], [3, 2, 1, 0],
+];
+
+// Matchups that only differ by relabeling suits have the same equity, so each is
+// keyed by its smallest relabeling
+fn isomorphism_key(hero: &[Card; 2], villain: &[Card; 2]) -> [u8; 4] {
+    SUIT_PERMUTATIONS.iter()
+        .map(|permutation| {
+            let relabel = |card: &Card| {
+                let index = card.index();
+                permutation[(index / 13) as usize] * 13 + index % 13
+            };
+            let (a, b) = (relabel(&hero[0]), relabel(&hero[1]));
+            let (c, d) = (relabel(&villain[0]), relabel(&villain[1]));
+            [a.min(b), a.max(b), c.min(d), c.max(d)]
+        })
+        .min()
+        .unwrap()
+}
+
+// Equity of one canonical hand against another: a fixed combo of the first
+// against every non-conflicting combo of the second, simulating each distinct
+// (suit-isomorphic) matchup once and weighting it by how many combos share it
+fn class_equity(hero: &[Card; 2], villain_class: &Range, simulations_per_matchup: usize) -> f64 {
+    let mut matchups: HashMap<[u8; 4], ([Card; 2], usize)> = HashMap::new();
+    for villain in villain_class.combos() {
+        if villain.iter().any(|card| hero.contains(card)) {
+            continue;
+        }
+        matchups.entry(isomorphism_key(hero, villain)).or_insert((villain.clone(), 0)).1 += 1;
+    }
+
+    let mut total = 0.0;
+    let mut combos = 0;
+    for (villain, count) in matchups.values() {
+        let results = monte_carlo_heads_up(hero, villain, &[], simulations_per_matchup);
+        total += (results.win_rate + results.tie_rate / 2.0) * *count as f64;
+        combos += count;
+    }
+    total / combos as f64
+}
+
+// Fills the full 169x169 matrix. Only the upper triangle is simulated since
+// equity(B vs A) = 100 - equity(A vs B), and the work is split across `threads`.
+pub fn compute_equity_matrix(simulations_per_matchup: usize, threads: usize) -> EquityMatrix {
+    let representatives = generate_canonical_starting_hands();
+    let hands: Vec<String> = representatives.iter().map(hand_notation).collect();
+    let classes: Vec<Range> = hands.iter().map(|notation| Range::parse(notation).expect("canonical hands parse")).collect();
+
+    let pairs: Vec<(usize, usize)> = (0..hands.len())
+        .flat_map(|i| (i..hands.len()).map(move |j| (i, j)))
+        .collect();
+    let total = pairs.len();
+    let completed = AtomicUsize::new(0);
+    let threads = threads.max(1);
+    let chunk_size = pairs.len().div_ceil(threads);
+
+    let results: Vec<(usize, usize, f64)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = pairs.chunks(chunk_size)
+            .map(|chunk| {
+                let (representatives, classes, completed) = (&representatives, &classes, &completed);
+                scope.spawn(move || {
+                    chunk.iter()
+                        .map(|&(i, j)| {
+                            let equity = class_equity(&representatives[i], &classes[j], simulations_per_matchup);
+                            let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                            if done % 1000 == 0 {
+                                tracing::info!("Completed {}/{} matchups", done, total);
+                            }
+                            (i, j, equity)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("matrix worker panicked")).collect()
+    });
+
+    let mut equity = vec![vec![0.0; hands.len()]; hands.len()];
+    for (i, j, value) in results {
+        equity[i][j] = value;
+        if i != j {
+            equity[j][i] = 100.0 - value;
+        }
+    }
+
+    EquityMatrix { hands, equity }
+}
+
+// One row per hand, one column per opponent hand; gzip or zstd when the name
+// ends in .gz or .zst
+pub fn export_matrix_csv(matrix: &EquityMatrix, filename: &str) -> Result<(), std::io::Error> {
+    use std::io::Write;
+
+    let mut file = crate::compress::create(filename)?;
+    writeln!(file, "Hand,{}", matrix.hands.join(","))?;
+    for (hand, row) in matrix.hands.iter().zip(matrix.equity.iter()) {
+        let values: Vec<String> = row.iter().map(|value| format!("{:.4}", value)).collect();
+        writeln!(file, "{},{}", hand, values.join(","))?;
+    }
+    file.finish()?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
+
+// Reads a matrix written by export_matrix_csv, so it only has to be simulated once
+pub fn import_matrix_csv(filename: &str) -> Result<EquityMatrix, std::io::Error> {
+    use std::io::{BufRead, Error, ErrorKind};
+
+    let invalid = |msg: String| Error::new(ErrorKind::InvalidData, format!("{}: {}", filename, msg));
+    let mut lines = crate::compress::open(filename)?.lines();
+    let header = lines.next().transpose()?.ok_or_else(|| invalid("empty file".to_string()))?;
+    let hands: Vec<String> = header.split(',').skip(1).map(|hand| hand.trim().to_string()).collect();
+    if hands.len() != 169 {
+        return Err(invalid(format!("expected 169 hand columns, found {}", hands.len())));
+    }
+
+    let mut equity = Vec::with_capacity(hands.len());
+    for (line_number, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |msg: String| invalid(format!("line {}: {}", line_number + 2, msg));
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields[0].trim() != hands.get(equity.len()).map_or("", |hand| hand.as_str()) {
+            return Err(invalid(format!("row '{}' doesn't match the header order", fields[0])));
+        }
+        let row = fields[1..].iter()
+            .map(|field| field.trim().parse::<f64>().map_err(|_| invalid(format!("invalid equity '{}'", field))))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        if row.len() != hands.len() {
+            return Err(invalid(format!("expected {} equities, found {}", hands.len(), row.len())));
+        }
+        equity.push(row);
+    }
+    if equity.len() != hands.len() {
+        return Err(invalid(format!("expected {} rows, found {}", hands.len(), equity.len())));
+    }
+
+    Ok(EquityMatrix { hands, equity })
+}
+
+// Long format: one row per (hand, opponent) pair
+#[cfg(feature = "parquet")]
+pub fn export_matrix_parquet(matrix: &EquityMatrix, filename: &str, simulations_per_matchup: usize) -> Result<(), std::io::Error> {
+    use std::fs::File;
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Float64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let to_io = |e: Box<dyn std::error::Error + Send + Sync>| std::io::Error::other(e);
+
+    let mut metadata = HashMap::new();
+    metadata.insert("timestamp".to_string(), chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string());
+    metadata.insert("simulations_per_matchup".to_string(), simulations_per_matchup.to_string());
+
+    let schema = Arc::new(Schema::new_with_metadata(vec![
+        Field::new("hand", DataType::Utf8, false),
+        Field::new("opponent", DataType::Utf8, false),
+        Field::new("equity", DataType::Float64, false),
+    ], metadata));
+
+    let n = matrix.hands.len();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values((0..n * n).map(|k| matrix.hands[k / n].clone()))),
+        Arc::new(StringArray::from_iter_values((0..n * n).map(|k| matrix.hands[k % n].clone()))),
+        Arc::new(Float64Array::from_iter_values((0..n * n).map(|k| matrix.equity[k / n][k % n]))),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| to_io(Box::new(e)))?;
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+
+    let file = File::create(filename)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props)).map_err(|e| to_io(Box::new(e)))?;
+    writer.write(&batch).map_err(|e| to_io(Box::new(e)))?;
+    writer.close().map_err(|e| to_io(Box::new(e)))?;
+
+    tracing::info!("Results exported to: {}", filename);
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use crate::card::{Card, Deck, HandEvaluation, SimulationResults, SplitPotResults, Variant, evaluate_variant};
+use crate::lowball::{AceToFiveLow, evaluate_ace_to_five_qualified};
+
+pub fn evaluate_omaha(hole_cards: &[Card; 4], community_cards: &[Card]) -> HandEvaluation {
+    evaluate_variant(Variant::Omaha, hole_cards, community_cards)
+}
+
+pub fn verify_omaha(hand_a: &[Card; 4], hand_b: &[Card; 4], community_cards: &[Card]) -> (String, HandEvaluation, HandEvaluation) {
+    let eval_a = evaluate_omaha(hand_a, community_cards);
+    let eval_b = evaluate_omaha(hand_b, community_cards);
+
+    let winner = match eval_a.cmp(&eval_b) {
+        std::cmp::Ordering::Greater => "Hand A",
+        std::cmp::Ordering::Less => "Hand B",
+        std::cmp::Ordering::Equal => "Tie",
+    };
+
+    (winner.to_string(), eval_a, eval_b)
+}
+
+pub fn monte_carlo_omaha_simulation(player_hand: &[Card; 4], num_simulations: usize) -> SimulationResults {
+    monte_carlo_omaha_with_community(player_hand, &[], num_simulations)
+}
+
+pub fn monte_carlo_omaha_with_community(
+    player_hand: &[Card; 4],
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+
+    // Remove player's cards and known community cards from deck once, recycling the rest per deal
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+
+    for _ in 0..num_simulations {
+        deck.recycle();
+
+        // Deal opponent's four hole cards
+        let mut opponent_cards = Vec::new();
+        for _ in 0..4 {
+            if let Some(card) = deck.draw() {
+                opponent_cards.push(card);
+            }
+        }
+        let opponent_hand: [Card; 4] = match opponent_cards.try_into() {
+            Ok(hand) => hand,
+            Err(_) => continue,
+        };
+
+        // Complete community cards
+        let mut community_cards = known_community.to_vec();
+        while community_cards.len() < 5 {
+            match deck.draw() {
+                Some(card) => community_cards.push(card),
+                None => break,
+            }
+        }
+
+        if community_cards.len() < 5 {
+            continue;
+        }
+
+        let (winner, _player_eval, _opp_eval) = verify_omaha(player_hand, &opponent_hand, &community_cards);
+
+        match winner.as_str() {
+            "Hand A" => wins += 1,
+            "Hand B" => losses += 1,
+            "Tie" => ties += 1,
+            _ => {}
+        }
+    }
+
+    SimulationResults::new(num_simulations, wins, losses, ties)
+}
+
+// Best eight-or-better low using exactly two hole cards and three board cards
+pub fn evaluate_omaha_low(hole_cards: &[Card; 4], community_cards: &[Card]) -> Option<AceToFiveLow> {
+    let mut best: Option<AceToFiveLow> = None;
+    
+    for i in 0..hole_cards.len() {
+        for j in (i + 1)..hole_cards.len() {
+            for a in 0..community_cards.len() {
+                for b in (a + 1)..community_cards.len() {
+                    for c in (b + 1)..community_cards.len() {
+                        let five = [
+                            hole_cards[i].clone(), hole_cards[j].clone(),
+                            community_cards[a].clone(), community_cards[b].clone(), community_cards[c].clone(),
+                        ];
+                        if let Some(low) = evaluate_ace_to_five_qualified(&five, 8) {
+                            if best.as_ref().is_none_or(|current| low > *current) {
+                                best = Some(low);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+// Hero's share of a hi/lo pot (0.0 - 1.0), plus the high share and the low share
+// (None when neither hand qualified for low)
+pub fn hi_lo_pot_share(hand_a: &[Card; 4], hand_b: &[Card; 4], community_cards: &[Card]) -> (f64, f64, Option<f64>) {
+    let high_share = match evaluate_omaha(hand_a, community_cards).cmp(&evaluate_omaha(hand_b, community_cards)) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Less => 0.0,
+    };
+    
+    let low_share = match (evaluate_omaha_low(hand_a, community_cards), evaluate_omaha_low(hand_b, community_cards)) {
+        (None, None) => None,
+        (Some(_), None) => Some(1.0),
+        (None, Some(_)) => Some(0.0),
+        (Some(low_a), Some(low_b)) => Some(match low_a.cmp(&low_b) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        }),
+    };
+    
+    let pot_share = match low_share {
+        Some(low) => (high_share + low) / 2.0,
+        None => high_share,
+    };
+    (pot_share, high_share, low_share)
+}
+
+// Omaha Hi/Lo (eight or better). Wins/losses/ties count whether hero took more,
+// less or exactly half of the pot; the split_pot breakdown carries the details
+pub fn monte_carlo_omaha_hi_lo(
+    player_hand: &[Card; 4],
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut pot_total = 0.0;
+    let mut high_total = 0.0;
+    let mut low_total = 0.0;
+    let mut low_deals = 0;
+    let mut scoops = 0;
+    let mut games = 0;
+    
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    
+    for _ in 0..num_simulations {
+        deck.recycle();
+        
+        let mut opponent_cards = Vec::new();
+        for _ in 0..4 {
+            if let Some(card) = deck.draw() {
+                opponent_cards.push(card);
+            }
+        }
+        let opponent_hand: [Card; 4] = match opponent_cards.try_into() {
+            Ok(hand) => hand,
+            Err(_) => continue,
+        };
+        
+        let mut community_cards = known_community.to_vec();
+        while community_cards.len() < 5 {
+            match deck.draw() {
+                Some(card) => community_cards.push(card),
+                None => break,
+            }
+        }
+        
+        if community_cards.len() < 5 {
+            continue;
+        }
+        
+        let (pot_share, high_share, low_share) = hi_lo_pot_share(player_hand, &opponent_hand, &community_cards);
+        games += 1;
+        pot_total += pot_share;
+        high_total += high_share;
+        if let Some(low) = low_share {
+            low_total += low;
+            low_deals += 1;
+        }
+        if pot_share == 1.0 {
+            scoops += 1;
+        }
+        
+        if pot_share > 0.5 {
+            wins += 1;
+        } else if pot_share < 0.5 {
+            losses += 1;
+        } else {
+            ties += 1;
+        }
+    }
+    
+    let mut results = SimulationResults::new(num_simulations, wins, losses, ties);
+    let percent = |value: f64, count: usize| if count == 0 { 0.0 } else { value / count as f64 * 100.0 };
+    results.split_pot = Some(SplitPotResults {
+        pot_equity: percent(pot_total, games),
+        high_equity: percent(high_total, games),
+        low_equity: percent(low_total, low_deals),
+        low_qualified_rate: percent(low_deals as f64, games),
+        scoop_rate: percent(scoops as f64, games),
+    });
+    results
+}
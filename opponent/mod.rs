@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::bucket::{BucketMetric, hand_values_with_rng, river_strength};
+use crate::card::{Card, Deck, HandRank, ShowdownStats, SimulationResults, evaluate_hand};
+use crate::range::{Range, hand_notation};
+
+// Opponents that don't play every hand to showdown. Equity against a random
+// hand assumes the opponent never folds, which flatters hands that are
+// usually dominated by the hands people actually play; a model decides which
+// hole cards an opponent plays preflop and whether it calls down once the
+// board is out.
+
+pub trait OpponentModel {
+    fn name(&self) -> String;
+
+    // Whether the opponent plays these hole cards; folded hands never reach the flop
+    fn plays_preflop(&self, hand: &[Card; 2]) -> bool;
+
+    // Whether the opponent calls down to showdown on the complete board
+    fn goes_to_showdown(&self, hand: &[Card; 2], board: &[Card]) -> bool;
+}
+
+// Plays any two cards and always shows down: the usual random-hand villain
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysCall;
+
+impl OpponentModel for AlwaysCall {
+    fn name(&self) -> String {
+        "random (always calls)".to_string()
+    }
+
+    fn plays_preflop(&self, _hand: &[Card; 2]) -> bool {
+        true
+    }
+
+    fn goes_to_showdown(&self, _hand: &[Card; 2], _board: &[Card]) -> bool {
+        true
+    }
+}
+
+// Plays the hands in a range and calls down with at least a given hand category
+#[derive(Debug, Clone)]
+pub struct RangeModel {
+    pub name: String,
+    pub range: Range,
+    // HighCard calls down with anything
+    pub min_showdown: HandRank,
+}
+
+impl RangeModel {
+    // Top 15% of hands, gives up without a pair
+    pub fn tight() -> Self {
+        RangeModel { name: "tight".to_string(), range: Range::top_percent(15.0), min_showdown: HandRank::Pair }
+    }
+
+    // Plays only the strongest `percent` of hands and always calls down, so
+    // equity is measured against the hands that actually see a showdown
+    pub fn top_percent(percent: f64) -> Result<Self, String> {
+        if !(percent > 0.0 && percent <= 100.0) {
+            return Err(format!("The playing range is between 0% and 100% of hands, not {}%", percent));
+        }
+        Ok(RangeModel { name: format!("top {}%", percent), range: Range::top_percent(percent), min_showdown: HandRank::HighCard })
+    }
+
+    // Top 60% of hands, calls down with anything
+    pub fn loose_passive() -> Self {
+        RangeModel { name: "loose-passive".to_string(), range: Range::top_percent(60.0), min_showdown: HandRank::HighCard }
+    }
+}
+
+impl OpponentModel for RangeModel {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn plays_preflop(&self, hand: &[Card; 2]) -> bool {
+        self.range.contains(hand)
+    }
+
+    fn goes_to_showdown(&self, hand: &[Card; 2], board: &[Card]) -> bool {
+        evaluate_hand(hand, board).rank >= self.min_showdown
+    }
+}
+
+// Preflop equity against a random hand for each canonical hand, from this many deals
+const PREFLOP_SAMPLES: usize = 2000;
+
+// Plays and calls down only with hands whose equity against a random hand
+// (preflop all-in equity, then exact river strength) reaches a threshold
+#[derive(Debug, Clone)]
+pub struct EquityThreshold {
+    // Between 0 and 1
+    pub threshold: f64,
+    preflop_equity: HashMap<String, f64>,
+}
+
+impl EquityThreshold {
+    pub fn new(threshold: f64) -> Result<Self, String> {
+        Self::new_with_rng(threshold, &mut rand::thread_rng())
+    }
+
+    pub fn new_with_rng<R: Rng>(threshold: f64, rng: &mut R) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!("An equity threshold is between 0 and 1, not {}", threshold));
+        }
+        let preflop_equity = hand_values_with_rng(&[], BucketMetric::Equity, PREFLOP_SAMPLES, rng)?
+            .into_iter()
+            .map(|hand| (hand.hand, hand.value))
+            .collect();
+        Ok(EquityThreshold { threshold, preflop_equity })
+    }
+}
+
+impl OpponentModel for EquityThreshold {
+    fn name(&self) -> String {
+        format!("equity threshold {:.0}%", self.threshold * 100.0)
+    }
+
+    fn plays_preflop(&self, hand: &[Card; 2]) -> bool {
+        self.preflop_equity.get(&hand_notation(hand)).is_some_and(|equity| *equity >= self.threshold)
+    }
+
+    fn goes_to_showdown(&self, hand: &[Card; 2], board: &[Card]) -> bool {
+        river_strength(hand, board) >= self.threshold
+    }
+}
+
+// "random", "tight", "loose-passive", "top:<percent>" or "equity:<percent>", e.g. "top:20"
+pub fn parse_model(text: &str) -> Result<Box<dyn OpponentModel>, String> {
+    let lower = text.trim().to_ascii_lowercase();
+    let percent = |value: &str| value.trim().trim_end_matches('%').parse::<f64>()
+        .map_err(|_| format!("Invalid percent '{}' in opponent model '{}'", value.trim(), text));
+    match lower.split_once(':') {
+        Some(("equity", value)) => Ok(Box::new(EquityThreshold::new(percent(value)? / 100.0)?)),
+        Some(("top", value)) => Ok(Box::new(RangeModel::top_percent(percent(value)?)?)),
+        _ => match lower.as_str() {
+            "random" | "call" | "always-call" => Ok(Box::new(AlwaysCall)),
+            "tight" => Ok(Box::new(RangeModel::tight())),
+            "loose-passive" | "loose" => Ok(Box::new(RangeModel::loose_passive())),
+            _ => Err(format!("Unknown opponent model '{}', expected random, tight, loose-passive, top:<percent> or equity:<percent>", text)),
+        },
+    }
+}
+
+// Draws before giving up on finding hole cards the model plays
+const MAX_PREFLOP_DRAWS: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct ModelResults {
+    // Wins include deals where every opponent gave up before showdown
+    pub results: SimulationResults,
+    // Deals where no opponent called down
+    pub uncontested: usize,
+}
+
+impl ModelResults {
+    // Percent of deals won without a showdown
+    pub fn uncontested_rate(&self) -> f64 {
+        self.uncontested as f64 / self.results.total_games.max(1) as f64 * 100.0
+    }
+}
+
+// Hero's results against `num_opponents` who each hold hands `model` plays
+// preflop, running the board out. Opponents that won't call down on the
+// final board give up the pot; hero wins by beating every opponent left.
+pub fn monte_carlo_vs_model(
+    player_hand: &[Card; 2],
+    num_opponents: usize,
+    known_community: &[Card],
+    num_simulations: usize,
+    model: &dyn OpponentModel,
+) -> ModelResults {
+    monte_carlo_vs_model_with_rng(player_hand, num_opponents, known_community, num_simulations, model, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_vs_model_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    num_opponents: usize,
+    known_community: &[Card],
+    num_simulations: usize,
+    model: &dyn OpponentModel,
+    rng: &mut R,
+) -> ModelResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut uncontested = 0;
+    let mut made_hands = HashMap::new();
+    let mut showdown = ShowdownStats::default();
+
+    let known: Vec<Card> = player_hand.iter().chain(known_community.iter()).cloned().collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return ModelResults { results: SimulationResults::new(0, 0, 0, 0), uncontested: 0 },
+    };
+
+    let mut opponents = Vec::with_capacity(num_opponents);
+    let mut board = known_community.to_vec();
+
+    'deals: for _ in 0..num_simulations {
+        deck.recycle();
+
+        // Folded hands go back in the deck, so each opponent holds a hand from its playing range
+        opponents.clear();
+        for _ in 0..num_opponents {
+            let mut draws = 0;
+            loop {
+                let hand = match (deck.draw_with(rng), deck.draw_with(rng)) {
+                    (Some(a), Some(b)) => [a, b],
+                    _ => continue 'deals,
+                };
+                if model.plays_preflop(&hand) {
+                    opponents.push(hand);
+                    break;
+                }
+                let [a, b] = hand;
+                if deck.add(a).is_err() || deck.add(b).is_err() {
+                    continue 'deals;
+                }
+                draws += 1;
+                if draws == MAX_PREFLOP_DRAWS {
+                    continue 'deals;
+                }
+            }
+        }
+        board.truncate(known_community.len());
+        match deck.deal_n_with(5 - known_community.len(), rng) {
+            Some(cards) => board.extend_from_slice(cards),
+            None => continue,
+        }
+
+        let player_eval = evaluate_hand(player_hand, &board);
+        *made_hands.entry(player_eval.rank.clone()).or_insert(0) += 1;
+        let best_caller = opponents.iter()
+            .filter(|hand| model.goes_to_showdown(hand, &board))
+            .map(|hand| evaluate_hand(hand, &board))
+            .max();
+        let Some(best_caller) = best_caller else {
+            wins += 1;
+            uncontested += 1;
+            continue;
+        };
+        showdown.record(&player_eval, &best_caller);
+        match player_eval.cmp(&best_caller) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+    }
+
+    let mut results = SimulationResults::new(wins + losses + ties, wins, losses, ties);
+    results.made_hands = made_hands;
+    results.showdown = Some(showdown);
+    ModelResults { results, uncontested }
+}
@@ -0,0 +1,109 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::api::{cards_text, result_fields};
+use crate::card::{HandResult, markdown_cell, write_csv, write_json, write_markdown};
+use crate::json::JsonValue;
+
+// Bare results on stdout for jq or a script, chosen with --output. Nothing
+// else is printed: no banners, progress or timings, and the notices that
+// normally go to stderr are quieted unless -v asks for them.
+//
+// json and csv follow the files the bulk run exports (and the server's JSON
+// answers for a single result); plain is one "name value" line per field, or
+// a tab-separated table for many hands; markdown is a table to paste into a
+// post or an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Plain,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "plain" | "text" => Ok(OutputFormat::Plain),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!("Unknown output format '{}', expected json, csv, plain or markdown", text)),
+        }
+    }
+}
+
+// One result, e.g. a single equity run, as named fields
+pub fn write_record<W: Write>(writer: &mut W, format: OutputFormat, fields: &[(String, JsonValue)]) -> Result<(), std::io::Error> {
+    match format {
+        OutputFormat::Json => writeln!(writer, "{}", JsonValue::Object(fields.to_vec())),
+        OutputFormat::Csv => {
+            writeln!(writer, "{}", fields.iter().map(|(name, _)| csv_field(name)).collect::<Vec<_>>().join(","))?;
+            writeln!(writer, "{}", fields.iter().map(|(_, value)| csv_field(&plain_value(value))).collect::<Vec<_>>().join(","))
+        },
+        OutputFormat::Plain => {
+            for (name, value) in fields {
+                writeln!(writer, "{} {}", name, plain_value(value))?;
+            }
+            Ok(())
+        },
+        OutputFormat::Markdown => {
+            writeln!(writer, "| Field | Value |")?;
+            writeln!(writer, "| :--- | ---: |")?;
+            for (name, value) in fields {
+                writeln!(writer, "| {} | {} |", markdown_cell(name), markdown_cell(&plain_value(value)))?;
+            }
+            Ok(())
+        },
+    }
+}
+
+// A bulk table, best hand first
+pub fn write_results<W: Write>(writer: &mut W, format: OutputFormat, results: &[HandResult]) -> Result<(), std::io::Error> {
+    match format {
+        OutputFormat::Json => write_json(results, writer),
+        OutputFormat::Csv => write_csv(results, writer),
+        OutputFormat::Markdown => write_markdown(results, writer),
+        OutputFormat::Plain => {
+            let rows: Vec<Vec<(String, JsonValue)>> = results.iter().enumerate()
+                .map(|(i, result)| {
+                    let mut fields = vec![
+                        ("rank".to_string(), JsonValue::Number((i + 1) as f64)),
+                        ("hand".to_string(), JsonValue::String(result.hand_description.clone())),
+                        ("cards".to_string(), JsonValue::String(cards_text(&result.hand))),
+                    ];
+                    fields.extend(result_fields(&result.results));
+                    fields
+                })
+                .collect();
+            if let Some(first) = rows.first() {
+                writeln!(writer, "{}", first.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join("\t"))?;
+            }
+            for row in &rows {
+                writeln!(writer, "{}", row.iter().map(|(_, value)| plain_value(value)).collect::<Vec<_>>().join("\t"))?;
+            }
+            Ok(())
+        },
+    }
+}
+
+// Strings unquoted and numbers with at most four decimals, as in the exports
+fn plain_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Number(number) if number.fract() == 0.0 => format!("{}", number),
+        JsonValue::Number(number) => format!("{:.4}", number),
+        value => value.to_string(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,118 @@
+use crate::card::{Card, Deck, HandEvaluation, SimulationResults, evaluate_hand};
+
+// Pineapple discards before the flop; Crazy Pineapple discards after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardTiming {
+    Preflop,
+    AfterFlop,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscardResult {
+    pub discard: Card,
+    pub kept: [Card; 2],
+    pub results: SimulationResults,
+}
+
+// Default discard policy: keep the two cards making the best hand with the
+// visible board (pairs, then high cards), preferring suited cards on a tie
+pub fn keep_two(cards: &[Card; 3], board: &[Card]) -> ([Card; 2], Card) {
+    let choices = [(0, 1, 2), (0, 2, 1), (1, 2, 0)];
+    let score = |i: usize, j: usize| -> (HandEvaluation, bool) {
+        let pair = [cards[i].clone(), cards[j].clone()];
+        (evaluate_hand(&pair, board), cards[i].suit == cards[j].suit)
+    };
+
+    let (i, j, discard) = choices.iter()
+        .max_by(|a, b| score(a.0, a.1).cmp(&score(b.0, b.1)))
+        .copied()
+        .unwrap();
+    ([cards[i].clone(), cards[j].clone()], cards[discard].clone())
+}
+
+fn draw_into(deck: &mut Deck, cards: &mut Vec<Card>, count: usize) -> bool {
+    while cards.len() < count {
+        match deck.draw() {
+            Some(card) => cards.push(card),
+            None => return false,
+        }
+    }
+    true
+}
+
+// Plays one heads-up deal. The hero discards `hero_discard` (an index into
+// `hero`) or follows keep_two when None; the opponent always follows keep_two.
+fn play_deal(hero: &[Card; 3], hero_discard: Option<usize>, known_flop: &[Card], timing: DiscardTiming) -> Option<std::cmp::Ordering> {
+    let known: Vec<Card> = hero.iter().chain(known_flop.iter()).cloned().collect();
+    let mut deck = Deck::without(&known).ok()?;
+
+    let mut opponent_cards = Vec::new();
+    let mut board = known_flop.to_vec();
+    if !draw_into(&mut deck, &mut opponent_cards, 3) || !draw_into(&mut deck, &mut board, 3) {
+        return None;
+    }
+    let opponent: [Card; 3] = opponent_cards.try_into().ok()?;
+
+    let visible = match timing {
+        DiscardTiming::Preflop => &board[..0],
+        DiscardTiming::AfterFlop => &board[..],
+    };
+    let hero_hand = match hero_discard {
+        Some(discard) => {
+            let kept: Vec<Card> = hero.iter().enumerate().filter(|(i, _)| *i != discard).map(|(_, c)| c.clone()).collect();
+            [kept[0].clone(), kept[1].clone()]
+        }
+        None => keep_two(hero, visible).0,
+    };
+    let (opponent_hand, _) = keep_two(&opponent, visible);
+
+    // Discards are dead, so the turn and river come from the remaining deck
+    if !draw_into(&mut deck, &mut board, 5) {
+        return None;
+    }
+
+    Some(evaluate_hand(&hero_hand, &board).cmp(&evaluate_hand(&opponent_hand, &board)))
+}
+
+fn tally<F: FnMut() -> Option<std::cmp::Ordering>>(num_simulations: usize, mut deal: F) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+
+    for _ in 0..num_simulations {
+        match deal() {
+            Some(std::cmp::Ordering::Greater) => wins += 1,
+            Some(std::cmp::Ordering::Less) => losses += 1,
+            Some(std::cmp::Ordering::Equal) => ties += 1,
+            None => {}
+        }
+    }
+
+    SimulationResults::new(num_simulations, wins, losses, ties)
+}
+
+// Heads-up equity of a three-card start when both players discard with keep_two
+pub fn monte_carlo_pineapple(hero: &[Card; 3], timing: DiscardTiming, num_simulations: usize) -> SimulationResults {
+    tally(num_simulations, || play_deal(hero, None, &[], timing))
+}
+
+// Simulates each of the three possible discards, best equity first. For Crazy
+// Pineapple pass the flop the decision is made on; Pineapple decides preflop.
+pub fn enumerate_discards(hero: &[Card; 3], timing: DiscardTiming, flop: &[Card], num_simulations: usize) -> Vec<DiscardResult> {
+    let mut discards: Vec<DiscardResult> = (0..3)
+        .map(|discard| {
+            let kept: Vec<Card> = hero.iter().enumerate().filter(|(i, _)| *i != discard).map(|(_, c)| c.clone()).collect();
+            DiscardResult {
+                discard: hero[discard].clone(),
+                kept: [kept[0].clone(), kept[1].clone()],
+                results: tally(num_simulations, || play_deal(hero, Some(discard), flop, timing)),
+            }
+        })
+        .collect();
+
+    discards.sort_by(|a, b| {
+        let equity = |d: &DiscardResult| d.results.win_rate + d.results.tie_rate / 2.0;
+        equity(b).partial_cmp(&equity(a)).unwrap()
+    });
+    discards
+}
@@ -0,0 +1,41 @@
+use crate::card::{Card, monte_carlo_with_community_with_rng};
+use crate::game::{Action, Game, LegalAction};
+use rand::Rng;
+
+// Seats in a heads-up hand against the bot
+pub const HERO: usize = 0;
+pub const BOT: usize = 1;
+
+// The bot's equity against a random hand, from `simulations` run-outs of the board
+pub fn bot_equity<R: Rng>(hand: &[Card; 2], board: &[Card], simulations: usize, rng: &mut R) -> f64 {
+    let results = monte_carlo_with_community_with_rng(hand, board, simulations, rng);
+    (results.win_rate + results.tie_rate / 2.0) / 100.0
+}
+
+// A simple equity-driven bot for the player to act: raises pot with strong
+// hands, calls when its equity beats the pot odds, and bets two thirds of the
+// pot for value (or as an occasional bluff) when checked to
+pub fn bot_action<R: Rng>(game: &Game, equity: f64, rng: &mut R) -> Action {
+    let legal = game.legal_actions();
+    let to_call = legal.iter().find_map(|action| match action {
+        LegalAction::Call(amount) => Some(*amount),
+        _ => None,
+    }).unwrap_or(0);
+    let raise_range = legal.iter().find_map(|action| match action {
+        LegalAction::Bet { min, max } | LegalAction::Raise { min, max } => Some((*min, *max)),
+        _ => None,
+    });
+    let pot = game.pot();
+    let sized = |(min, max): (u32, u32), fraction: f64| {
+        let total = (game.current_bet() + ((pot + to_call) as f64 * fraction) as u32).max(min);
+        if total >= max { Action::AllIn } else { Action::Bet(total) }
+    };
+
+    match raise_range {
+        Some(range) if to_call == 0 && (equity > 0.65 || rng.gen_bool(0.1)) => sized(range, 2.0 / 3.0),
+        _ if to_call == 0 => Action::Check,
+        Some(range) if equity > 0.8 => sized(range, 1.0),
+        _ if equity >= to_call as f64 / (pot + to_call) as f64 => Action::Call,
+        _ => Action::Fold,
+    }
+}
@@ -0,0 +1,361 @@
+use crate::card::{Card, Deck, HandEvaluation, HandRank, Rank, evaluate_hand};
+use crate::range::{Range, monte_carlo_vs_range_with_rng};
+use crate::texture::classify_board;
+
+// What a card has to do to count as an out
+#[derive(Debug, Clone)]
+pub enum OutsTarget {
+    // Put hero strictly ahead of this known hand
+    BeatHand([Card; 2]),
+    // Make hero's hand this category or better
+    Reach(HandRank),
+}
+
+#[derive(Debug, Clone)]
+pub struct Outs {
+    pub clean: Vec<Card>,
+    // Outs that also help the opponent: they improve villain's known hand, or
+    // (for a hand-category target) pair the board, bring a third card of a suit
+    // or make a straight possible below the target category
+    pub tainted: Vec<Card>,
+    // Cards not in hero's hand, on the board or in villain's known hand
+    pub unseen: usize,
+}
+
+impl Outs {
+    pub fn count(&self) -> usize {
+        self.clean.len() + self.tainted.len()
+    }
+
+    // Chance of hitting an out on the next card, in percent
+    pub fn next_card_probability(&self) -> f64 {
+        self.count() as f64 / self.unseen as f64 * 100.0
+    }
+
+    // Chance of hitting at least one out by the river, in percent. On the flop this
+    // counts both the turn and the river.
+    pub fn by_river_probability(&self, board_size: usize) -> f64 {
+        if board_size >= 4 {
+            return self.next_card_probability();
+        }
+        let misses = (self.unseen - self.count()) as f64;
+        let unseen = self.unseen as f64;
+        (1.0 - misses / unseen * (misses - 1.0) / (unseen - 1.0)) * 100.0
+    }
+}
+
+// Whether adding `card` to the board opens up a category below `target` that
+// wasn't possible before
+fn helps_board(board: &[Card], card: &Card, target: &HandRank) -> bool {
+    let mut next_board = board.to_vec();
+    next_board.push(card.clone());
+    let (before, after) = match (classify_board(board), classify_board(&next_board)) {
+        (Ok(before), Ok(after)) => (before, after),
+        _ => return false,
+    };
+
+    let pairs_board = board.iter().any(|c| c.rank == card.rank);
+    (pairs_board && *target < HandRank::FullHouse)
+        || (after.flush_possible && !before.flush_possible && *target < HandRank::Flush)
+        || (after.connected && !before.connected && *target < HandRank::Straight)
+}
+
+// Enumerates every unseen card that gets hero to the target on a flop or turn
+pub fn find_outs(hero: &[Card; 2], board: &[Card], target: &OutsTarget) -> Result<Outs, String> {
+    if board.len() != 3 && board.len() != 4 {
+        return Err("Outs are counted on the flop or turn".to_string());
+    }
+
+    let already = match target {
+        OutsTarget::BeatHand(villain) => evaluate_hand(hero, board) > evaluate_hand(villain, board),
+        OutsTarget::Reach(rank) => evaluate_hand(hero, board).rank >= *rank,
+    };
+    if already {
+        return Err("Hero already meets the target".to_string());
+    }
+
+    let mut known: Vec<Card> = hero.iter().chain(board.iter()).cloned().collect();
+    if let OutsTarget::BeatHand(villain) = target {
+        known.extend_from_slice(villain);
+    }
+    let unseen: Vec<Card> = Deck::new().cards().iter().filter(|card| !known.contains(card)).cloned().collect();
+
+    let mut outs = Outs { clean: Vec::new(), tainted: Vec::new(), unseen: unseen.len() };
+    for card in unseen {
+        let mut next_board = board.to_vec();
+        next_board.push(card.clone());
+        let hero_eval = evaluate_hand(hero, &next_board);
+
+        let (is_out, tainted) = match target {
+            OutsTarget::BeatHand(villain) => {
+                let villain_now = evaluate_hand(villain, board).rank;
+                let villain_eval = evaluate_hand(villain, &next_board);
+                (hero_eval > villain_eval, villain_eval.rank > villain_now)
+            }
+            OutsTarget::Reach(rank) => (hero_eval.rank >= *rank, helps_board(board, &card, rank)),
+        };
+
+        if is_out && tainted {
+            outs.tainted.push(card);
+        } else if is_out {
+            outs.clean.push(card);
+        }
+    }
+
+    Ok(outs)
+}
+
+// Current draws of a flop or turn hand, reported alongside the made hand
+#[derive(Debug, Clone)]
+pub struct DrawAnalysis {
+    pub made_hand: HandEvaluation,
+    // Four to a flush using at least one hole card
+    pub flush_draw: bool,
+    // Three to a flush on the flop using at least one hole card
+    pub backdoor_flush_draw: bool,
+    // Two ranks complete a straight (open-ended or double gutshot)
+    pub open_ended: bool,
+    // Exactly one rank completes a straight
+    pub gutshot: bool,
+}
+
+impl DrawAnalysis {
+    pub fn has_straight_draw(&self) -> bool {
+        self.open_ended || self.gutshot
+    }
+
+    // A flush draw together with a straight draw
+    pub fn is_combo_draw(&self) -> bool {
+        self.flush_draw && self.has_straight_draw()
+    }
+}
+
+impl std::fmt::Display for DrawAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.made_hand.rank)?;
+        let straight_draw = if self.open_ended { "open-ended straight draw" } else { "gutshot" };
+        if self.is_combo_draw() {
+            write!(f, " + combo draw (flush draw, {})", straight_draw)?;
+        } else if self.flush_draw {
+            write!(f, " + flush draw")?;
+        } else if self.has_straight_draw() {
+            write!(f, " + {}", straight_draw)?;
+        }
+        if self.backdoor_flush_draw {
+            write!(f, " + backdoor flush draw")?;
+        }
+        Ok(())
+    }
+}
+
+// Rank values present, with aces at both 1 and 14
+fn rank_presence<'a>(cards: impl Iterator<Item = &'a Card>) -> [bool; 15] {
+    let mut present = [false; 15];
+    for card in cards {
+        present[card.rank.value() as usize] = true;
+        if card.rank == Rank::Ace {
+            present[1] = true;
+        }
+    }
+    present
+}
+
+fn has_straight(present: &[bool; 15]) -> bool {
+    (1..=10).any(|low| (low..low + 5).all(|v| present[v]))
+}
+
+// Ranks that would give hero a straight the board alone doesn't make
+fn straight_completions(hole_cards: &[Card; 2], board: &[Card]) -> Vec<u8> {
+    let all = rank_presence(hole_cards.iter().chain(board.iter()));
+    let board_only = rank_presence(board.iter());
+    (2..=14u8)
+        .filter(|&value| {
+            let add = |mut present: [bool; 15]| {
+                present[value as usize] = true;
+                if value == 14 {
+                    present[1] = true;
+                }
+                present
+            };
+            !all[value as usize] && has_straight(&add(all)) && !has_straight(&add(board_only))
+        })
+        .collect()
+}
+
+pub fn analyze_draws(hole_cards: &[Card; 2], board: &[Card]) -> DrawAnalysis {
+    let made_hand = evaluate_hand(hole_cards, board);
+    let mut analysis = DrawAnalysis {
+        made_hand,
+        flush_draw: false,
+        backdoor_flush_draw: false,
+        open_ended: false,
+        gutshot: false,
+    };
+    // Nothing left to draw to on the river (or before the flop)
+    if board.len() < 3 || board.len() > 4 {
+        return analysis;
+    }
+
+    if analysis.made_hand.rank < HandRank::Flush {
+        for hole in hole_cards.iter() {
+            let suited = hole_cards.iter().chain(board.iter()).filter(|c| c.suit == hole.suit).count();
+            analysis.flush_draw |= suited == 4;
+            analysis.backdoor_flush_draw |= suited == 3 && board.len() == 3;
+        }
+        if analysis.flush_draw {
+            analysis.backdoor_flush_draw = false;
+        }
+    }
+
+    if analysis.made_hand.rank < HandRank::Straight {
+        match straight_completions(hole_cards, board).len() {
+            0 => {}
+            1 => analysis.gutshot = true,
+            _ => analysis.open_ended = true,
+        }
+    }
+
+    analysis
+}
+
+// Billings-style hand potential against a range, as fractions between 0 and 1
+#[derive(Debug, Clone)]
+pub struct HandPotential {
+    // Chance hero is ahead right now (ties count half)
+    pub hand_strength: f64,
+    // Chance a hand that is currently behind (or tied) ends up ahead by the river
+    pub positive: f64,
+    // Chance a hand that is currently ahead (or tied) ends up behind by the river
+    pub negative: f64,
+    pub samples: usize,
+}
+
+const AHEAD: usize = 0;
+const TIED: usize = 1;
+const BEHIND: usize = 2;
+
+fn standing(hero: &HandEvaluation, villain: &HandEvaluation) -> usize {
+    match hero.cmp(villain) {
+        std::cmp::Ordering::Greater => AHEAD,
+        std::cmp::Ordering::Equal => TIED,
+        std::cmp::Ordering::Less => BEHIND,
+    }
+}
+
+// Samples villain combos (by weight) and runouts to compare where hero stands now
+// with where hero stands on the river
+pub fn hand_potential(hero: &[Card; 2], villain_range: &Range, board: &[Card], num_samples: usize) -> Result<HandPotential, String> {
+    hand_potential_with_rng(hero, villain_range, board, num_samples, &mut rand::thread_rng())
+}
+
+pub fn hand_potential_with_rng<R: rand::Rng>(hero: &[Card; 2], villain_range: &Range, board: &[Card], num_samples: usize, rng: &mut R) -> Result<HandPotential, String> {
+    if board.len() != 3 && board.len() != 4 {
+        return Err("Hand potential is measured on the flop or turn".to_string());
+    }
+
+    let mut dead_cards = hero.to_vec();
+    dead_cards.extend_from_slice(board);
+
+    // transitions[now][river] counts samples moving between standings
+    let mut transitions = [[0usize; 3]; 3];
+    let mut samples = 0;
+    for _ in 0..num_samples {
+        let villain = match villain_range.sample(&dead_cards, rng) {
+            Some(villain) => villain,
+            None => return Err("Every combo in the villain range is blocked by known cards".to_string()),
+        };
+
+        let known: Vec<Card> = dead_cards.iter().chain(villain.iter()).cloned().collect();
+        let mut deck = match Deck::without(&known) {
+            Ok(deck) => deck,
+            Err(_) => continue,
+        };
+        let mut river_board = board.to_vec();
+        match deck.deal_n_with(5 - board.len(), rng) {
+            Some(cards) => river_board.extend_from_slice(cards),
+            None => continue,
+        }
+
+        let now = standing(&evaluate_hand(hero, board), &evaluate_hand(&villain, board));
+        let river = standing(&evaluate_hand(hero, &river_board), &evaluate_hand(&villain, &river_board));
+        transitions[now][river] += 1;
+        samples += 1;
+    }
+
+    let total = |now: usize| transitions[now].iter().sum::<usize>() as f64;
+    let ratio = |numerator: f64, denominator: f64| if denominator > 0.0 { numerator / denominator } else { 0.0 };
+    let t = |now: usize, river: usize| transitions[now][river] as f64;
+
+    Ok(HandPotential {
+        hand_strength: ratio(total(AHEAD) + total(TIED) / 2.0, samples as f64),
+        positive: ratio(t(BEHIND, AHEAD) + t(BEHIND, TIED) / 2.0 + t(TIED, AHEAD) / 2.0, total(BEHIND) + total(TIED) / 2.0),
+        negative: ratio(t(AHEAD, BEHIND) + t(AHEAD, TIED) / 2.0 + t(TIED, BEHIND) / 2.0, total(AHEAD) + total(TIED) / 2.0),
+        samples,
+    })
+}
+
+impl HandPotential {
+    // Effective hand strength: HS + (1 - HS) * PPOT - HS * NPOT
+    pub fn effective_strength(&self) -> f64 {
+        let hs = self.hand_strength;
+        hs + (1.0 - hs) * self.positive - hs * self.negative
+    }
+}
+
+// Everything known about a flop or turn hand against a villain range
+#[derive(Debug, Clone)]
+pub struct PostflopAnalysis {
+    pub draws: DrawAnalysis,
+    pub potential: HandPotential,
+    pub effective_strength: f64,
+}
+
+pub fn analyze_postflop(hero: &[Card; 2], villain_range: &Range, board: &[Card], num_samples: usize) -> Result<PostflopAnalysis, String> {
+    analyze_postflop_with_rng(hero, villain_range, board, num_samples, &mut rand::thread_rng())
+}
+
+pub fn analyze_postflop_with_rng<R: rand::Rng>(hero: &[Card; 2], villain_range: &Range, board: &[Card], num_samples: usize, rng: &mut R) -> Result<PostflopAnalysis, String> {
+    let potential = hand_potential_with_rng(hero, villain_range, board, num_samples, rng)?;
+    Ok(PostflopAnalysis {
+        draws: analyze_draws(hero, board),
+        effective_strength: potential.effective_strength(),
+        potential,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct TurnCardEquity {
+    pub card: Card,
+    // Hero's equity (wins plus half of ties) with this turn card, in percent
+    pub equity: f64,
+}
+
+// Hero's equity for every possible turn card on a flop, best first. Turn cards that
+// leave the villain range no unblocked combos are skipped.
+pub fn turn_card_equities(hero: &[Card; 2], villain_range: &Range, flop: &[Card], simulations_per_card: usize) -> Result<Vec<TurnCardEquity>, String> {
+    turn_card_equities_with_rng(hero, villain_range, flop, simulations_per_card, &mut rand::thread_rng())
+}
+
+pub fn turn_card_equities_with_rng<R: rand::Rng>(hero: &[Card; 2], villain_range: &Range, flop: &[Card], simulations_per_card: usize, rng: &mut R) -> Result<Vec<TurnCardEquity>, String> {
+    if flop.len() != 3 {
+        return Err("The turn card breakdown needs exactly three board cards".to_string());
+    }
+
+    let known: Vec<Card> = hero.iter().chain(flop.iter()).cloned().collect();
+    let mut equities = Vec::new();
+    for card in Deck::new().cards().iter().filter(|card| !known.contains(card)) {
+        let mut board = flop.to_vec();
+        board.push(card.clone());
+        let mut dead_cards = known.clone();
+        dead_cards.push(card.clone());
+        if villain_range.available(&dead_cards).is_empty() {
+            continue;
+        }
+
+        let results = monte_carlo_vs_range_with_rng(hero, villain_range, &board, simulations_per_card, rng);
+        equities.push(TurnCardEquity { card: card.clone(), equity: results.win_rate + results.tie_rate / 2.0 });
+    }
+
+    equities.sort_by(|a, b| b.equity.partial_cmp(&a.equity).unwrap());
+    Ok(equities)
+}
@@ -0,0 +1,105 @@
+// Splitting the chips of a finished hand into the main pot and side pots and
+// paying each to the best hand that can win it
+
+// Who gets the chips a split pot can't divide evenly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OddChipRule {
+    // Hold'em and Omaha: the winners closest to the left of this button seat
+    LeftOfButton(usize),
+    // An explicit priority, first seat first (e.g. stud's highest card by suit)
+    Order(Vec<usize>),
+}
+
+impl OddChipRule {
+    // Every seat, highest priority first
+    fn seat_order(&self, seats: usize) -> Vec<usize> {
+        match self {
+            OddChipRule::LeftOfButton(button) => (1..=seats).map(|offset| (button + offset) % seats).collect(),
+            OddChipRule::Order(order) => {
+                // Seats the order leaves out come last, lowest seat first
+                let mut full: Vec<usize> = order.iter().copied().filter(|&seat| seat < seats).collect();
+                full.extend((0..seats).filter(|seat| !order.contains(seat)));
+                full
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u32,
+    // Seats still in the hand that put in enough to win this pot
+    pub eligible: Vec<usize>,
+    // Who was paid from it: the best eligible hands, or the folded players
+    // who put the chips in when nobody eligible is left
+    pub winners: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PotDistribution {
+    // The main pot first, then each side pot
+    pub pots: Vec<Pot>,
+    // Chips paid to each seat
+    pub payouts: Vec<u32>,
+}
+
+// Pays out a hand from each seat's total contribution and its showdown
+// strength (None for a seat that folded). Strength is anything ordered, so
+// it works for HandEvaluation as well as the lowball and hi/lo strengths, or
+// `()` when the last player standing takes everything without a showdown.
+// Each pot holds the chips every seat put in up to one all-in amount; pots
+// with the same eligible seats are merged, and odd chips go one at a time to
+// the winners in `odd_chip` priority.
+pub fn distribute_pots<T: Ord>(contributions: &[u32], strengths: &[Option<T>], odd_chip: &OddChipRule) -> PotDistribution {
+    assert_eq!(contributions.len(), strengths.len(), "one strength per contribution");
+    let order = odd_chip.seat_order(contributions.len());
+
+    let mut levels: Vec<u32> = contributions.iter().copied().filter(|&c| c > 0).collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    // Each pot with the contribution range (above, up to) its chips came from
+    let mut pots: Vec<(Pot, u32, u32)> = Vec::new();
+    let mut previous = 0;
+    for level in levels {
+        let amount: u32 = contributions.iter().map(|&c| c.min(level) - c.min(previous)).sum();
+        let eligible: Vec<usize> = (0..contributions.len())
+            .filter(|&seat| contributions[seat] >= level && strengths[seat].is_some())
+            .collect();
+        match pots.last_mut() {
+            Some((pot, _, up_to)) if pot.eligible == eligible => {
+                pot.amount += amount;
+                *up_to = level;
+            },
+            _ => pots.push((Pot { amount, eligible, winners: Vec::new() }, previous, level)),
+        }
+        previous = level;
+    }
+
+    let mut payouts = vec![0; contributions.len()];
+    for (pot, above, up_to) in &mut pots {
+        // Chips only folded players reached go back to whoever put them in
+        if pot.eligible.is_empty() {
+            for (seat, &c) in contributions.iter().enumerate() {
+                let refund = c.min(*up_to) - c.min(*above);
+                if refund > 0 {
+                    payouts[seat] += refund;
+                    pot.winners.push(seat);
+                }
+            }
+            continue;
+        }
+        let best = pot.eligible.iter().filter_map(|&seat| strengths[seat].as_ref()).max();
+        pot.winners = order.iter().copied()
+            .filter(|seat| pot.eligible.contains(seat) && strengths[*seat].as_ref() == best)
+            .collect();
+        let share = pot.amount / pot.winners.len() as u32;
+        let odd_chips = pot.amount as usize % pot.winners.len();
+        for (i, &seat) in pot.winners.iter().enumerate() {
+            payouts[seat] += share + u32::from(i < odd_chips);
+        }
+    }
+
+    let pots = pots.into_iter().map(|(pot, _, _)| pot).collect();
+    PotDistribution { pots, payouts }
+}
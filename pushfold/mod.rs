@@ -0,0 +1,275 @@
+use crate::card::Card;
+use crate::icm::icm_equities;
+use crate::matrix::EquityMatrix;
+use crate::range::{Range, compact_notation};
+
+// Push/fold play between the blinds once everyone else has folded: the small
+// blind jams or folds and the big blind calls or folds. Stacks are in big
+// blinds. Both strategies are solved together with fictitious play, where
+// each round both players best-respond to the other's average strategy so
+// far; the averages converge to an equilibrium neither side can exploit.
+
+pub const SMALL_BLIND: f64 = 0.5;
+pub const BIG_BLIND: f64 = 1.0;
+
+// Each player's result for the four ways the hand can end, as [small blind,
+// big blind]: chips won or lost for chip EV, or prize equity under ICM
+#[derive(Debug, Clone, Copy)]
+pub struct Payoffs {
+    pub sb_folds: [f64; 2],
+    pub bb_folds: [f64; 2],
+    pub sb_wins: [f64; 2],
+    pub bb_wins: [f64; 2],
+}
+
+// Stacks after each ending, in Payoffs order. The small blind is stacks[0],
+// the big blind stacks[1], and everyone else has folded after posting `ante`.
+fn final_stacks(stacks: &[f64], ante: f64) -> Result<[Vec<f64>; 4], String> {
+    if stacks.len() < 2 {
+        return Err("Push/fold needs a small blind and a big blind stack".to_string());
+    }
+    if !(ante >= 0.0 && ante.is_finite()) {
+        return Err(format!("Invalid ante {} bb", ante));
+    }
+    if let Some(stack) = stacks.iter().find(|s| !(**s > ante && s.is_finite())) {
+        return Err(format!("Invalid stack {} bb, every stack must cover the ante", stack));
+    }
+    if stacks[0] <= SMALL_BLIND + ante || stacks[1] <= BIG_BLIND + ante {
+        return Err("The blinds' stacks must cover their blind and ante".to_string());
+    }
+
+    let dead = ante * stacks.len() as f64;
+    let after_ante: Vec<f64> = stacks.iter().map(|stack| stack - ante).collect();
+    let all_in = after_ante[0].min(after_ante[1]);
+    let ending = |sb_change: f64, bb_change: f64| {
+        let mut stacks = after_ante.clone();
+        stacks[0] += sb_change;
+        stacks[1] += bb_change;
+        stacks
+    };
+    Ok([
+        ending(-SMALL_BLIND, SMALL_BLIND + dead),
+        ending(BIG_BLIND + dead, -BIG_BLIND),
+        ending(all_in + dead, -all_in),
+        ending(-all_in, all_in + dead),
+    ])
+}
+
+fn payoffs_from(endings: [Vec<f64>; 4], value: impl Fn(&[f64]) -> Result<[f64; 2], String>) -> Result<Payoffs, String> {
+    let [sb_folds, bb_folds, sb_wins, bb_wins] = endings;
+    Ok(Payoffs {
+        sb_folds: value(&sb_folds)?,
+        bb_folds: value(&bb_folds)?,
+        sb_wins: value(&sb_wins)?,
+        bb_wins: value(&bb_wins)?,
+    })
+}
+
+// Chips won or lost from the start of the hand, in big blinds
+pub fn chip_payoffs(stacks: &[f64], ante: f64) -> Result<Payoffs, String> {
+    payoffs_from(final_stacks(stacks, ante)?, |after| Ok([after[0] - stacks[0], after[1] - stacks[1]]))
+}
+
+// Each blind's ICM prize equity after the hand. A chopped pot is valued as
+// half a win and half a loss, which is slightly off under ICM but keeps the
+// solver on a single equity per matchup.
+pub fn icm_payoffs(stacks: &[f64], ante: f64, payouts: &[f64]) -> Result<Payoffs, String> {
+    payoffs_from(final_stacks(stacks, ante)?, |after| {
+        let equities = icm_equities(after, payouts)?;
+        // icm_equities pays nothing to an empty stack; a blind who busts
+        // here takes the place just below everyone left
+        let left = after.iter().filter(|&&stack| stack > 0.0).count();
+        let busted = payouts.get(left).copied().unwrap_or(0.0);
+        Ok([0, 1].map(|seat| if after[seat] > 0.0 { equities[seat] } else { busted }))
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct PushFoldSolution {
+    // Canonical hands in the equity matrix's order
+    pub hands: Vec<String>,
+    // How often the small blind jams each hand, and the big blind calls a jam with it
+    pub jam: Vec<f64>,
+    pub call: Vec<f64>,
+    // Each player's average payoff when both play the solution, overall and
+    // for each hand they can be dealt
+    pub sb_value: f64,
+    pub bb_value: f64,
+    pub sb_hand_values: Vec<f64>,
+    pub bb_hand_values: Vec<f64>,
+    // What the two players together would gain by best-responding to each
+    // other instead; 0 at an exact equilibrium
+    pub exploitability: f64,
+}
+
+impl PushFoldSolution {
+    // Share of all 1,326 combos the small blind jams
+    pub fn jam_percent(&self) -> f64 {
+        self.combo_percent(&self.jam)
+    }
+
+    // Share of all 1,326 combos the big blind calls a jam with
+    pub fn call_percent(&self) -> f64 {
+        self.combo_percent(&self.call)
+    }
+
+    fn combo_percent(&self, frequencies: &[f64]) -> f64 {
+        let combos: f64 = self.hands.iter().zip(frequencies).map(|(hand, f)| class_size(hand) * f).sum();
+        combos / 1326.0 * 100.0
+    }
+
+    // The jamming range in range notation, frequencies rounded to `precision` (e.g. 0.05)
+    pub fn jam_range(&self, precision: f64) -> String {
+        self.notation(&self.jam, precision)
+    }
+
+    pub fn call_range(&self, precision: f64) -> String {
+        self.notation(&self.call, precision)
+    }
+
+    fn notation(&self, frequencies: &[f64], precision: f64) -> String {
+        let rounded: Vec<(&String, f64)> = self.hands.iter()
+            .zip(frequencies)
+            .map(|(hand, f)| (hand, ((f / precision).round() * precision * 100.0).round() / 100.0))
+            .collect();
+        compact_notation(&rounded).expect("matrix hands are canonical")
+    }
+}
+
+fn class_size(hand: &str) -> f64 {
+    match hand.len() {
+        2 => 6.0,
+        _ if hand.ends_with('s') => 4.0,
+        _ => 12.0,
+    }
+}
+
+// Matchups between every pair of canonical hands: how many combo pairs don't
+// share a card (so blockers count) and the first hand's equity as a fraction
+struct Matchups {
+    weights: Vec<Vec<f64>>,
+    equity: Vec<Vec<f64>>,
+    // Combo pairs each hand takes part in, and all of them together
+    hand_weights: Vec<f64>,
+    total: f64,
+}
+
+impl Matchups {
+    fn new(matrix: &EquityMatrix) -> Result<Self, String> {
+        let classes: Vec<Vec<[Card; 2]>> = matrix.hands.iter()
+            .map(|hand| Range::parse(hand).map(|range| range.combos().to_vec()))
+            .collect::<Result<_, _>>()?;
+        if classes.iter().map(|combos| combos.len()).sum::<usize>() != 1326 {
+            return Err("The equity matrix must cover each of the 169 starting hands once".to_string());
+        }
+
+        let weights: Vec<Vec<f64>> = classes.iter()
+            .map(|hero| classes.iter()
+                .map(|villain| {
+                    hero.iter()
+                        .flat_map(|a| villain.iter().filter(move |b| !b.iter().any(|card| a.contains(card))))
+                        .count() as f64
+                })
+                .collect())
+            .collect();
+        let equity = matrix.equity.iter().map(|row| row.iter().map(|e| e / 100.0).collect()).collect();
+        let hand_weights: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+        let total = hand_weights.iter().sum();
+        Ok(Matchups { weights, equity, hand_weights, total })
+    }
+
+    // Small blind's payoff for a jam called by the big blind
+    fn showdown(&self, payoffs: &Payoffs, sb: usize, bb: usize, seat: usize) -> f64 {
+        let equity = self.equity[sb][bb];
+        equity * payoffs.sb_wins[seat] + (1.0 - equity) * payoffs.bb_wins[seat]
+    }
+
+    // Gain in total payoff (summed over the hand's combo pairs) from jamming
+    // each hand instead of folding, against a calling strategy
+    fn jam_gains(&self, payoffs: &Payoffs, call: &[f64]) -> Vec<f64> {
+        (0..self.weights.len())
+            .map(|sb| {
+                (0..self.weights.len())
+                    .map(|bb| {
+                        let jam = (1.0 - call[bb]) * payoffs.bb_folds[0] + call[bb] * self.showdown(payoffs, sb, bb, 0);
+                        self.weights[sb][bb] * (jam - payoffs.sb_folds[0])
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    // Gain from calling each hand instead of folding, against a jamming strategy
+    fn call_gains(&self, payoffs: &Payoffs, jam: &[f64]) -> Vec<f64> {
+        (0..self.weights.len())
+            .map(|bb| {
+                (0..self.weights.len())
+                    .map(|sb| self.weights[sb][bb] * jam[sb] * (self.showdown(payoffs, sb, bb, 1) - payoffs.bb_folds[1]))
+                    .sum()
+            })
+            .collect()
+    }
+
+    // Average payoff of each hand when the small blind plays `jam` and the
+    // big blind `call`, as [small blind holding it, big blind holding it]
+    fn hand_values(&self, payoffs: &Payoffs, jam: &[f64], call: &[f64]) -> [Vec<f64>; 2] {
+        let hands = self.weights.len();
+        let mut values = [vec![0.0; hands], vec![0.0; hands]];
+        for (sb, &jam) in jam.iter().enumerate() {
+            for (bb, &call) in call.iter().enumerate() {
+                for (seat, hand) in [(0, sb), (1, bb)] {
+                    let jammed = (1.0 - call) * payoffs.bb_folds[seat] + call * self.showdown(payoffs, sb, bb, seat);
+                    values[seat][hand] += self.weights[sb][bb] * ((1.0 - jam) * payoffs.sb_folds[seat] + jam * jammed);
+                }
+            }
+        }
+        // Combo pairs are symmetric, so a hand's row and column weigh the same
+        values.map(|seat| seat.iter().zip(&self.hand_weights).map(|(value, weight)| value / weight).collect())
+    }
+
+    // Both players' average payoffs when they play `jam` and `call`
+    fn values(&self, payoffs: &Payoffs, jam: &[f64], call: &[f64]) -> [f64; 2] {
+        self.hand_values(payoffs, jam, call)
+            .map(|seat| seat.iter().zip(&self.hand_weights).map(|(value, weight)| value * weight).sum::<f64>() / self.total)
+    }
+}
+
+fn best_response(gains: &[f64]) -> Vec<f64> {
+    gains.iter().map(|&gain| if gain > 0.0 { 1.0 } else { 0.0 }).collect()
+}
+
+// Solves the jam and call ranges with `iterations` rounds of fictitious play
+pub fn solve_push_fold(matrix: &EquityMatrix, payoffs: &Payoffs, iterations: usize) -> Result<PushFoldSolution, String> {
+    if iterations == 0 {
+        return Err("Run at least one iteration".to_string());
+    }
+    let matchups = Matchups::new(matrix)?;
+    let hands = matrix.hands.len();
+
+    let mut jam = vec![1.0; hands];
+    let mut call = vec![1.0; hands];
+    for iteration in 1..=iterations {
+        let jam_response = best_response(&matchups.jam_gains(payoffs, &call));
+        let call_response = best_response(&matchups.call_gains(payoffs, &jam));
+        let step = 1.0 / (iteration + 1) as f64;
+        for hand in 0..hands {
+            jam[hand] += step * (jam_response[hand] - jam[hand]);
+            call[hand] += step * (call_response[hand] - call[hand]);
+        }
+    }
+
+    let [sb_value, bb_value] = matchups.values(payoffs, &jam, &call);
+    let [sb_hand_values, bb_hand_values] = matchups.hand_values(payoffs, &jam, &call);
+    let sb_best = matchups.values(payoffs, &best_response(&matchups.jam_gains(payoffs, &call)), &call)[0];
+    let bb_best = matchups.values(payoffs, &jam, &best_response(&matchups.call_gains(payoffs, &jam)))[1];
+    Ok(PushFoldSolution {
+        hands: matrix.hands.clone(),
+        jam,
+        call,
+        sb_value,
+        bb_value,
+        sb_hand_values,
+        bb_hand_values,
+        exploitability: (sb_best - sb_value) + (bb_best - bb_value),
+    })
+}
@@ -0,0 +1,1009 @@
+use rand::Rng;
+
+use crate::card::{Card, Deck, HandEvaluation, HandRank, HandResult, ShowdownStats, Rank, SimulationResults, Suit, aggregate_canonical, evaluate_hand, evaluate_many,
+                  exact_river_showdown};
+
+// The 169 canonical starting hands ordered by all-in equity against a random
+// hand (100,000 heads-up simulations each), used for "top X%" ranges
+pub const PREFLOP_RANKING: [&str; 169] = [
+    "AA", "KK", "QQ", "JJ", "TT", "99", "88", "AKs", "77", "AQs", "AJs", "AKo",
+    "ATs", "AQo", "KQs", "66", "AJo", "A9s", "ATo", "KJs", "KTs", "A8s", "KQo", "A9o",
+    "A7s", "KJo", "55", "QJs", "A8o", "A6s", "K9s", "A5s", "KTo", "QTs", "A4s", "A7o",
+    "K8s", "A3s", "QJo", "K9o", "A5o", "Q9s", "JTs", "A6o", "K7s", "A2s", "QTo", "44",
+    "A4o", "K6s", "K8o", "Q8s", "K5s", "J9s", "Q9o", "A3o", "K7o", "JTo", "A2o", "K4s",
+    "Q7s", "T9s", "K6o", "K3s", "J8s", "Q6s", "J9o", "Q8o", "33", "K2s", "K5o", "Q5s",
+    "K4o", "T8s", "J7s", "Q4s", "Q7o", "T9o", "J8o", "K3o", "Q6o", "Q3s", "J6s", "98s",
+    "K2o", "T7s", "Q5o", "Q2s", "22", "J5s", "T8o", "J7o", "Q4o", "97s", "J4s", "T6s",
+    "J3s", "Q3o", "98o", "J6o", "T7o", "87s", "T5s", "J2s", "96s", "Q2o", "J5o", "T4s",
+    "97o", "86s", "J4o", "T6o", "T3s", "95s", "76s", "87o", "J3o", "T2s", "85s", "96o",
+    "J2o", "T5o", "94s", "75s", "T4o", "93s", "86o", "65s", "95o", "T3o", "84s", "76o",
+    "92s", "T2o", "74s", "85o", "64s", "54s", "83s", "94o", "75o", "82s", "73s", "65o",
+    "93o", "53s", "63s", "84o", "92o", "43s", "74o", "72s", "54o", "52s", "64o", "62s",
+    "83o", "42s", "73o", "82o", "53o", "32s", "63o", "43o", "72o", "52o", "62o", "42o",
+    "32o"
+];
+
+// Named opening and defending ranges for a 6-max, 100bb game. Any of these
+// names can be used as an entry in range notation, e.g. "btn-open" or "co-open,22+".
+pub const PRESETS: [(&str, &str); 9] = [
+    ("utg-open", "55+,A9s+,KTs+,QTs+,JTs,T9s,98s,AJo+,KQo"),
+    ("mp-open", "33+,A7s+,A5s,K9s+,Q9s+,J9s+,T9s,98s,87s,ATo+,KJo+,QJo"),
+    ("co-open", "22+,A2s+,K7s+,Q8s+,J8s+,T8s+,97s+,86s+,76s,65s,54s,A9o+,KTo+,QTo+,JTo"),
+    ("btn-open", "22+,A2s+,K2s+,Q4s+,J6s+,T6s+,96s+,85s+,75s+,64s+,54s,43s,A2o+,K8o+,Q9o+,J9o+,T8o+,98o,87o"),
+    ("sb-open", "22+,A2s+,K5s+,Q7s+,J7s+,T7s+,97s+,86s+,75s+,65s,54s,A4o+,K9o+,QTo+,JTo,T9o"),
+    ("sb-3bet", "88+,ATs+,A5s-A4s,KTs+,QJs,JTs,AJo+,KQo"),
+    ("bb-defend", "22+,A2s+,K2s+,Q2s+,J4s+,T6s+,96s+,85s+,74s+,64s+,53s+,43s,A2o+,K7o+,Q8o+,J8o+,T8o+,97o+,87o,76o"),
+    ("bb-3bet", "99+,ATs+,A5s-A3s,KJs+,QJs,76s,65s,AQo+"),
+    ("4bet", "QQ+,AKs,A5s,AKo"),
+];
+
+const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+// A set of two-card combos an opponent might hold, each with a weight between
+// 0 and 1 for how often the combo is actually played this way
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    combos: Vec<[Card; 2]>,
+    weights: Vec<f64>,
+}
+
+fn rank_from_char(c: char) -> Option<Rank> {
+    match c.to_ascii_uppercase() {
+        'A' => Some(Rank::Ace),
+        'K' => Some(Rank::King),
+        'Q' => Some(Rank::Queen),
+        'J' => Some(Rank::Jack),
+        'T' => Some(Rank::Ten),
+        d @ '2'..='9' => Rank::from_number(d as u8 - b'0'),
+        _ => None,
+    }
+}
+
+fn rank_char(rank: Rank) -> char {
+    match rank {
+        Rank::Ace => 'A',
+        Rank::King => 'K',
+        Rank::Queen => 'Q',
+        Rank::Jack => 'J',
+        Rank::Ten => 'T',
+        other => (b'0' + other.value()) as char,
+    }
+}
+
+// Range-notation name of a starting hand: "AA", "AKs", "T9o"
+pub fn hand_notation(hand: &[Card; 2]) -> String {
+    let (high, low) = if hand[0].rank >= hand[1].rank { (&hand[0], &hand[1]) } else { (&hand[1], &hand[0]) };
+    if high.rank == low.rank {
+        format!("{}{}", rank_char(high.rank), rank_char(low.rank))
+    } else if high.suit == low.suit {
+        format!("{}{}s", rank_char(high.rank), rank_char(low.rank))
+    } else {
+        format!("{}{}o", rank_char(high.rank), rank_char(low.rank))
+    }
+}
+
+// Canonical hands ordered by simulated equity (wins plus half of ties), strongest
+// first, from bulk simulation results
+pub fn ranking_from_results(results: &[HandResult]) -> Vec<String> {
+    let mut canonical = aggregate_canonical(results);
+    let equity = |r: &HandResult| r.results.win_rate + r.results.tie_rate / 2.0;
+    canonical.sort_by(|a, b| equity(b).partial_cmp(&equity(a)).unwrap());
+    canonical.iter().map(|r| hand_notation(&r.hand)).collect()
+}
+
+// Every combo of a canonical hand; `suited` is ignored for pairs and None means both
+fn hand_combos(high: Rank, low: Rank, suited: Option<bool>) -> Vec<[Card; 2]> {
+    let mut combos = Vec::new();
+    for (i, s1) in SUITS.iter().enumerate() {
+        for (j, s2) in SUITS.iter().enumerate() {
+            let keep = if high == low {
+                j > i
+            } else {
+                match suited {
+                    Some(true) => i == j,
+                    Some(false) => i != j,
+                    None => true,
+                }
+            };
+            if keep {
+                combos.push([Card::new(high, s1.clone()), Card::new(low, s2.clone())]);
+            }
+        }
+    }
+    combos
+}
+
+// Splits "AKs" into (A, K, Some(true)); "AK" gives None for either suitedness
+fn parse_hand_class(text: &str) -> Result<(Rank, Rank, Option<bool>), String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err(format!("Invalid hand '{}'", text));
+    }
+    let r1 = rank_from_char(chars[0]).ok_or(format!("Invalid rank in '{}'", text))?;
+    let r2 = rank_from_char(chars[1]).ok_or(format!("Invalid rank in '{}'", text))?;
+    let suited = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+        None => None,
+        Some('s') => Some(true),
+        Some('o') => Some(false),
+        Some(_) => return Err(format!("Invalid suitedness in '{}'", text)),
+    };
+    if r1 == r2 && suited.is_some() {
+        return Err(format!("Pairs can't be suited or offsuit: '{}'", text));
+    }
+    Ok((r1.max(r2), r1.min(r2), suited))
+}
+
+// Writes canonical hands with weights back as range notation parse() reads,
+// e.g. "77+,A2s+,K9s-K6s,Q9s:0.5". Neighbouring hands with the same weight
+// share an entry and hands with weight 0 are left out; "AK" counts as both
+// "AKs" and "AKo".
+pub fn compact_notation<S: AsRef<str>>(hands: &[(S, f64)]) -> Result<String, String> {
+    // weights[suited][high][low] by rank value; pairs sit on the diagonal of the suited table
+    let mut weights = [[[0.0; 15]; 15]; 2];
+    for (hand, weight) in hands {
+        let (high, low, suited) = parse_hand_class(hand.as_ref())?;
+        for (table, cells) in weights.iter_mut().enumerate() {
+            if high == low || suited.is_none_or(|suited| suited == (table == 0)) {
+                cells[high.value() as usize][low.value() as usize] = weight.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    // Runs of equal weight walking from the top value of a row down to `lowest`
+    let mut add_runs = |row: &[f64; 15], top: u8, lowest: u8, name: &dyn Fn(u8) -> String| {
+        let mut value = top;
+        while value >= lowest {
+            let weight = row[value as usize];
+            let start = value;
+            while value >= lowest && row[value as usize] == weight {
+                value -= 1;
+            }
+            if weight == 0.0 {
+                continue;
+            }
+            let end = value + 1;
+            let mut entry = match (start == top, start == end) {
+                (_, true) => name(start),
+                (true, false) => format!("{}+", name(end)),
+                (false, false) => format!("{}-{}", name(start), name(end)),
+            };
+            if weight < 1.0 {
+                entry = format!("{}:{}", entry, weight);
+            }
+            entries.push(entry);
+        }
+    };
+
+    let pairs: [f64; 15] = std::array::from_fn(|value| weights[0][value][value]);
+    add_runs(&pairs, 14, 2, &|value| {
+        let rank = rank_char(rank_from_value(value));
+        format!("{}{}", rank, rank)
+    });
+    for (table, suffix) in [(0, 's'), (1, 'o')] {
+        for high in (3..=14).rev() {
+            let high_char = rank_char(rank_from_value(high));
+            add_runs(&weights[table][high as usize], high - 1, 2, &|low| {
+                format!("{}{}{}", high_char, rank_char(rank_from_value(low)), suffix)
+            });
+        }
+    }
+    Ok(entries.join(","))
+}
+
+impl Range {
+    pub fn from_combos(combos: Vec<[Card; 2]>) -> Self {
+        let mut range = Range::default();
+        for combo in combos {
+            range.set_weight(combo, 1.0);
+        }
+        range
+    }
+
+    // A built-in range from PRESETS, looked up by name ignoring case
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS.iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .map(|(_, notation)| Range::parse(notation).expect("preset ranges are valid"))
+    }
+
+    // All 1,326 combos
+    pub fn random() -> Self {
+        Range::from_combos(crate::card::generate_all_starting_hands())
+    }
+
+    // Parses standard range notation, comma separated: pairs ("QQ", "77+", "22-55"),
+    // suited/offsuit hands ("AKs", "AKo", "AK", "ATs+", "K9o-KJo"), connector
+    // spans ("T9s-65s"), exact combos ("AhKh") or combos with any suit as "x"
+    // ("AxKx", "AsKx"), "15%" for the top 15% of hands, "random", and preset
+    // names such as "btn-open" (see PRESETS). Any entry can take a weight suffix
+    // such as "AQo:0.3", or a percentage as ProPokerTools writes it ("AQo@30").
+    // Equilab and PokerStove weight blocks ("[30]AQo,KQs[/30]") weight every
+    // entry inside. Later entries override earlier weights.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Range::parse_with_ranking(text, &PREFLOP_RANKING)
+    }
+
+    // Like parse, but "X%" entries take the top hands from `ranking` (e.g. from
+    // ranking_from_results) instead of PREFLOP_RANKING
+    pub fn parse_with_ranking<S: AsRef<str>>(text: &str, ranking: &[S]) -> Result<Self, String> {
+        let mut range = Range::default();
+        // Weight of the open "[w]...[/w]" block, if any
+        let mut block_weight = None;
+        
+        for token in text.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let mut entry = token;
+            if let Some(rest) = entry.strip_prefix('[') {
+                let (weight, rest) = rest.split_once(']').ok_or(format!("Unclosed weight block in '{}'", token))?;
+                block_weight = Some(percent_weight(weight, token)?);
+                entry = rest.trim();
+            }
+            let closes_block = match entry.find("[/") {
+                Some(end) if entry.ends_with(']') => {
+                    entry = entry[..end].trim();
+                    true
+                }
+                Some(_) => return Err(format!("Unclosed weight block in '{}'", token)),
+                None => false,
+            };
+
+            let (hands, weight) = if let Some((hands, weight)) = entry.split_once(':') {
+                let weight: f64 = weight.trim().parse().map_err(|_| format!("Invalid weight in '{}'", token))?;
+                if !(0.0..=1.0).contains(&weight) {
+                    return Err(format!("Weights must be between 0 and 1: '{}'", token));
+                }
+                (hands.trim(), weight)
+            } else if let Some((hands, weight)) = entry.split_once('@') {
+                (hands.trim(), percent_weight(weight, token)?)
+            } else {
+                (entry, block_weight.unwrap_or(1.0))
+            };
+            
+            if !hands.is_empty() {
+                for combo in parse_entry(hands, ranking)? {
+                    range.set_weight(combo, weight);
+                }
+            }
+            if closes_block {
+                block_weight = None;
+            }
+        }
+        
+        Ok(range)
+    }
+
+    // The strongest hands by PREFLOP_RANKING until `percent` of all combos are covered
+    pub fn top_percent(percent: f64) -> Self {
+        Range::top_percent_of(percent, &PREFLOP_RANKING)
+    }
+
+    // The first hands of `ranking` (canonical notation, strongest first) until
+    // `percent` of all combos are covered
+    pub fn top_percent_of<S: AsRef<str>>(percent: f64, ranking: &[S]) -> Self {
+        let target = (percent.clamp(0.0, 100.0) / 100.0 * 1326.0).round() as usize;
+        let mut range = Range::default();
+        for notation in ranking.iter() {
+            if range.len() >= target {
+                break;
+            }
+            if let Ok((high, low, suited)) = parse_hand_class(notation.as_ref()) {
+                for combo in hand_combos(high, low, suited) {
+                    range.set_weight(combo, 1.0);
+                }
+            }
+        }
+        range
+    }
+
+    pub fn combos(&self) -> &[[Card; 2]] {
+        &self.combos
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    // Combos paired with their weights
+    pub fn weighted_combos(&self) -> impl Iterator<Item = (&[Card; 2], f64)> {
+        self.combos.iter().zip(self.weights.iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    // Number of combos counting each by its weight
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    fn position(&self, combo: &[Card; 2]) -> Option<usize> {
+        self.combos.iter().position(|c| same_combo(c, combo))
+    }
+
+    pub fn contains(&self, combo: &[Card; 2]) -> bool {
+        self.position(combo).is_some()
+    }
+
+    // Weight of a combo, 0 if it isn't in the range
+    pub fn weight(&self, combo: &[Card; 2]) -> f64 {
+        self.position(combo).map_or(0.0, |i| self.weights[i])
+    }
+
+    // Adds the combo or updates its weight; a weight of 0 removes it
+    pub fn set_weight(&mut self, combo: [Card; 2], weight: f64) {
+        let weight = weight.clamp(0.0, 1.0);
+        match self.position(&combo) {
+            Some(i) if weight == 0.0 => {
+                self.combos.remove(i);
+                self.weights.remove(i);
+            }
+            Some(i) => self.weights[i] = weight,
+            None if weight > 0.0 => {
+                self.combos.push(combo);
+                self.weights.push(weight);
+            }
+            None => {}
+        }
+    }
+
+    pub fn extend(&mut self, other: &Range) {
+        for (combo, weight) in other.weighted_combos() {
+            self.set_weight(combo.clone(), weight);
+        }
+    }
+
+    // Combos (with weights) that don't share a card with `dead_cards`
+    pub fn available(&self, dead_cards: &[Card]) -> Vec<(&[Card; 2], f64)> {
+        self.weighted_combos()
+            .filter(|(combo, _)| !combo.iter().any(|c| dead_cards.contains(c)))
+            .collect()
+    }
+
+    // Picks a combo not blocked by `dead_cards`, with probability proportional to its weight
+    pub fn sample<R: Rng>(&self, dead_cards: &[Card], rng: &mut R) -> Option<[Card; 2]> {
+        sample_weighted(&self.available(dead_cards), rng).cloned()
+    }
+}
+
+// Picks one of `combos` with probability proportional to its weight. Simulations
+// with fixed dead cards call available() once and sample from it every deal.
+fn sample_weighted<'a, R: Rng>(combos: &[(&'a [Card; 2], f64)], rng: &mut R) -> Option<&'a [Card; 2]> {
+    let total: f64 = combos.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    
+    let mut target = rng.gen_range(0.0..total);
+    for (combo, weight) in combos {
+        if target < *weight {
+            return Some(combo);
+        }
+        target -= weight;
+    }
+    combos.last().map(|(combo, _)| *combo)
+}
+
+// "50" or "50%" as a weight of 0.5
+fn percent_weight(text: &str, token: &str) -> Result<f64, String> {
+    let percent: f64 = text.trim().trim_end_matches('%').parse().map_err(|_| format!("Invalid weight in '{}'", token))?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(format!("Percentage weights must be between 0 and 100: '{}'", token));
+    }
+    Ok(percent / 100.0)
+}
+
+// Combos of a two-card pattern where "x" stands for any suit: "AxKx", "AsKx", "QxQx"
+fn suit_pattern_combos(token: &str) -> Option<Vec<[Card; 2]>> {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() != 4 || (!chars[1].eq_ignore_ascii_case(&'x') && !chars[3].eq_ignore_ascii_case(&'x')) {
+        return None;
+    }
+    let suits = |c: char| -> Option<Vec<Suit>> {
+        match c.to_ascii_lowercase() {
+            'x' => Some(SUITS.to_vec()),
+            's' => Some(vec![Suit::Spades]),
+            'h' => Some(vec![Suit::Hearts]),
+            'd' => Some(vec![Suit::Diamonds]),
+            'c' => Some(vec![Suit::Clubs]),
+            _ => None,
+        }
+    };
+    let (rank1, rank2) = (rank_from_char(chars[0])?, rank_from_char(chars[2])?);
+    let (suits1, suits2) = (suits(chars[1])?, suits(chars[3])?);
+    let mut combos: Vec<[Card; 2]> = Vec::new();
+    for suit1 in &suits1 {
+        for suit2 in &suits2 {
+            let combo = [Card::new(rank1, suit1.clone()), Card::new(rank2, suit2.clone())];
+            if combo[0] != combo[1] && !combos.iter().any(|other| same_combo(other, &combo)) {
+                combos.push(combo);
+            }
+        }
+    }
+    Some(combos)
+}
+
+// Expands one range entry (without its weight) into combos
+fn parse_entry<S: AsRef<str>>(token: &str, ranking: &[S]) -> Result<Vec<[Card; 2]>, String> {
+    let mut combos = Vec::new();
+    
+    if token.eq_ignore_ascii_case("random") || token.eq_ignore_ascii_case("any") {
+        combos = crate::card::generate_all_starting_hands();
+    } else if let Some(in_class) = named_class(token) {
+        combos = crate::card::generate_all_starting_hands().into_iter().filter(in_class).collect();
+    } else if let Some(preset) = Range::preset(token) {
+        combos = preset.combos;
+    } else if let Some(percent) = token.strip_suffix('%') {
+        let percent: f64 = percent.trim().parse().map_err(|_| format!("Invalid percentage '{}'", token))?;
+        combos = Range::top_percent_of(percent, ranking).combos;
+    } else if let Some((from, to)) = token.split_once('-') {
+        let (h1, l1, s1) = parse_hand_class(from.trim())?;
+        let (h2, l2, s2) = parse_hand_class(to.trim())?;
+        if s1 != s2 {
+            return Err(format!("Mismatched suitedness in '{}'", token));
+        }
+        if h1 == l1 && h2 == l2 {
+            // Pair span: 22-55
+            for value in h1.min(h2).value()..=h1.max(h2).value() {
+                let rank = rank_from_value(value);
+                combos.extend(hand_combos(rank, rank, None));
+            }
+        } else if h1 == h2 {
+            // Kicker span: K9o-KJo
+            for value in l1.min(l2).value()..=l1.max(l2).value() {
+                combos.extend(hand_combos(h1, rank_from_value(value), s1));
+            }
+        } else if h1.value() - l1.value() == h2.value() - l2.value() {
+            // Connector span with a fixed gap: T9s-65s, J9s-75s
+            let gap = h1.value() - l1.value();
+            for value in h1.min(h2).value()..=h1.max(h2).value() {
+                combos.extend(hand_combos(rank_from_value(value), rank_from_value(value - gap), s1));
+            }
+        } else {
+            return Err(format!("Invalid span '{}'", token));
+        }
+    } else if let Some(base) = token.strip_suffix('+') {
+        let (high, low, suited) = parse_hand_class(base)?;
+        if high == low {
+            // 77+ is every pair from sevens up
+            for value in high.value()..=Rank::Ace.value() {
+                let rank = rank_from_value(value);
+                combos.extend(hand_combos(rank, rank, None));
+            }
+        } else {
+            // ATs+ raises the kicker up to just below the high card
+            for value in low.value()..high.value() {
+                combos.extend(hand_combos(high, rank_from_value(value), suited));
+            }
+        }
+    } else if let Ok((high, low, suited)) = parse_hand_class(token) {
+        combos = hand_combos(high, low, suited);
+    } else if let Some(pattern) = suit_pattern_combos(token) {
+        combos = pattern;
+    } else {
+        let cards = crate::card::parse_cards(token).map_err(|_| format!("Invalid range entry '{}'", token))?;
+        let combo: [Card; 2] = cards.try_into().map_err(|_| format!("Invalid range entry '{}'", token))?;
+        if combo[0] == combo[1] {
+            return Err(format!("Invalid combo '{}'", token));
+        }
+        combos.push(combo);
+    }
+    
+    Ok(combos)
+}
+
+// Broad hand classes usable as range entries: "pairs", "suited", "offsuit", "broadway"
+fn named_class(token: &str) -> Option<fn(&[Card; 2]) -> bool> {
+    match token.to_ascii_lowercase().as_str() {
+        "pairs" => Some(|hand| hand[0].rank == hand[1].rank),
+        "suited" => Some(|hand| hand[0].suit == hand[1].suit),
+        "offsuit" => Some(|hand| hand[0].rank != hand[1].rank && hand[0].suit != hand[1].suit),
+        "broadway" => Some(|hand| hand.iter().all(|card| card.rank >= Rank::Ten)),
+        _ => None,
+    }
+}
+
+fn rank_from_value(value: u8) -> Rank {
+    Rank::from_number(if value == 14 { 1 } else { value }).expect("rank values are 2-14")
+}
+
+fn same_combo(a: &[Card; 2], b: &[Card; 2]) -> bool {
+    (a[0] == b[0] && a[1] == b[1]) || (a[0] == b[1] && a[1] == b[0])
+}
+
+// Deals out the rest of the board into `board` for one heads-up showdown and
+// evaluates both hands; None if the deck runs out. `deck` holds every card but
+// the known board's (and usually hero's), so cards in either hand are passed
+// over instead of building a new deck for each combo.
+fn play_out<R: Rng>(
+    deck: &mut Deck,
+    player_hand: &[Card; 2],
+    opponent_hand: &[Card; 2],
+    known_community: &[Card],
+    board: &mut Vec<Card>,
+    rng: &mut R
+) -> Option<(HandEvaluation, HandEvaluation)> {
+    deck.recycle();
+    board.clear();
+    board.extend_from_slice(known_community);
+    while board.len() < 5 {
+        let card = deck.draw_with(rng)?;
+        if !opponent_hand.contains(&card) && !player_hand.contains(&card) {
+            board.push(card);
+        }
+    }
+    
+    Some((evaluate_hand(player_hand, board), evaluate_hand(opponent_hand, board)))
+}
+
+// Like monte_carlo_with_community, but the opponent's hand is drawn from
+// `villain_range` (skipping combos blocked by known cards) instead of at random
+pub fn monte_carlo_vs_range(
+    player_hand: &[Card; 2],
+    villain_range: &Range,
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    monte_carlo_vs_range_with_rng(player_hand, villain_range, known_community, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn monte_carlo_vs_range_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    villain_range: &Range,
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut made_hands = std::collections::HashMap::new();
+    let mut showdown = ShowdownStats::default();
+    
+    let mut dead_cards = player_hand.to_vec();
+    dead_cards.extend_from_slice(known_community);
+    
+    // On the river every combo can be counted exactly instead of sampled
+    let available = villain_range.available(&dead_cards);
+    if known_community.len() == 5 {
+        return exact_river_showdown(player_hand, known_community, available);
+    }
+    
+    let mut deck = match Deck::without(&dead_cards) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    let mut board = Vec::with_capacity(5);
+    
+    for _ in 0..num_simulations {
+        let opponent_hand = match sample_weighted(&available, rng) {
+            Some(hand) => hand,
+            None => continue,
+        };
+        
+        let (player_eval, opp_eval) = match play_out(&mut deck, player_hand, opponent_hand, known_community, &mut board, rng) {
+            Some(result) => result,
+            None => continue,
+        };
+        showdown.record(&player_eval, &opp_eval);
+        match player_eval.cmp(&opp_eval) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+        *made_hands.entry(player_eval.rank).or_insert(0) += 1;
+    }
+    
+    let mut results = SimulationResults::new(wins + losses + ties, wins, losses, ties);
+    results.made_hands = made_hands;
+    results.showdown = Some(showdown);
+    results
+}
+
+// Fewer villain combos than this (after card removal) leave too little spread
+// between them for stratifying to pay for its pilot
+const STRATIFIED_MIN_COMBOS: usize = 20;
+// Deals per combo the pilot needs, out of a quarter of the budget
+const STRATIFIED_MIN_PILOT: usize = 4;
+// Share of the deals after the pilot still spread by the range's own weights,
+// so a combo the pilot misjudged is never starved
+const STRATIFIED_MIX: f64 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct StratifiedResults {
+    // Rates are reweighted to the villain range; the counts are raw deals
+    pub results: SimulationResults,
+    // False when the range or budget was too small and plain sampling ran instead
+    pub weighted: bool,
+    pub pilot_trials: usize,
+    // Plain deals that would have given the same precision
+    pub equivalent_trials: f64,
+    // 95% interval for equity, in percent
+    pub equity_ci: (f64, f64),
+}
+
+// Deals played against one villain combo
+#[derive(Debug, Clone, Copy, Default)]
+struct ComboTally {
+    deals: usize,
+    wins: usize,
+    ties: usize,
+    // Hero's pot shares (1 for a win, 0.5 for a tie) and their squares
+    share: f64,
+    share_squared: f64,
+}
+
+impl ComboTally {
+    fn record(&mut self, outcome: std::cmp::Ordering) {
+        let share = match outcome {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Less => 0.0,
+            std::cmp::Ordering::Equal => 0.5,
+        };
+        self.deals += 1;
+        self.wins += (outcome == std::cmp::Ordering::Greater) as usize;
+        self.ties += (outcome == std::cmp::Ordering::Equal) as usize;
+        self.share += share;
+        self.share_squared += share * share;
+    }
+    
+    fn equity(&self) -> f64 {
+        self.share / self.deals.max(1) as f64
+    }
+    
+    fn variance(&self) -> f64 {
+        (self.share_squared / self.deals.max(1) as f64 - self.equity() * self.equity()).max(0.0)
+    }
+}
+
+// Like monte_carlo_vs_range, but stratified by villain combo: every combo is
+// scored on its own and the combos' results are weighted back to the range, so
+// which combos happened to be dealt adds no noise. A pilot first plays a few
+// deals against every combo, however rare (a lone set in a wide range gets as
+// many as a common broadway hand), then the rest of the budget is shared out in
+// proportion to weight times the spread of each combo's results (Neyman
+// allocation).
+pub fn stratified_vs_range_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    villain_range: &Range,
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> StratifiedResults {
+    let mut dead_cards = player_hand.to_vec();
+    dead_cards.extend_from_slice(known_community);
+    let available = villain_range.available(&dead_cards);
+    let pilot_per_combo = num_simulations / 4 / available.len().max(1);
+    let plain = |results: SimulationResults| StratifiedResults {
+        weighted: false,
+        pilot_trials: 0,
+        equivalent_trials: results.total_games as f64,
+        equity_ci: results.equity_ci(),
+        results,
+    };
+    if known_community.len() == 5 || available.len() < STRATIFIED_MIN_COMBOS || pilot_per_combo < STRATIFIED_MIN_PILOT {
+        return plain(monte_carlo_vs_range_with_rng(player_hand, villain_range, known_community, num_simulations, rng));
+    }
+    
+    let mut deck = match Deck::without(&dead_cards) {
+        Ok(deck) => deck,
+        Err(_) => return plain(SimulationResults::new(0, 0, 0, 0)),
+    };
+    let mut board = Vec::with_capacity(5);
+    let mut deal = |i: usize, tallies: &mut [ComboTally], rng: &mut R| {
+        if let Some((player_eval, opp_eval)) = play_out(&mut deck, player_hand, available[i].0, known_community, &mut board, rng) {
+            tallies[i].record(player_eval.cmp(&opp_eval));
+        }
+    };
+    
+    let mut pilot = vec![ComboTally::default(); available.len()];
+    for i in 0..available.len() {
+        for _ in 0..pilot_per_combo {
+            deal(i, &mut pilot, rng);
+        }
+    }
+    let pilot_trials = pilot_per_combo * available.len();
+    
+    // The pilot plans the rest of the deals, so its tallies can't simply be
+    // added to theirs without biasing the result towards whatever it happened
+    // to see; the two estimates are kept apart and blended by their deal
+    // counts instead. A combo the pilot never saw hero beat can still be lost
+    // to only most of the time, so spreads come from a lightly smoothed equity.
+    let total_weight: f64 = available.iter().map(|(_, weight)| weight).sum();
+    let variances: Vec<f64> = pilot.iter()
+        .map(|tally| {
+            let equity = (tally.share + 0.5) / (tally.deals as f64 + 1.0);
+            equity * (1.0 - equity)
+        })
+        .collect();
+    let total_spread: f64 = available.iter().zip(&variances).map(|((_, weight), variance)| weight * variance.sqrt()).sum();
+    let main_trials = num_simulations - pilot_trials;
+    let mut tallies = vec![ComboTally::default(); available.len()];
+    for (i, ((_, weight), variance)) in available.iter().zip(&variances).enumerate() {
+        let share = (1.0 - STRATIFIED_MIX) * weight * variance.sqrt() / total_spread + STRATIFIED_MIX * weight / total_weight;
+        let deals = ((share * main_trials as f64 + rng.gen_range(0.0..1.0)) as usize).max(1);
+        for _ in 0..deals {
+            deal(i, &mut tallies, rng);
+        }
+    }
+    
+    // Each combo counts for its weight, however many deals it got
+    // Deals play_out couldn't complete aren't counted
+    let pilot_deals: usize = pilot.iter().map(|t| t.deals).sum();
+    let total = pilot_deals + tallies.iter().map(|t| t.deals).sum::<usize>();
+    let pilot_share = pilot_deals as f64 / total.max(1) as f64;
+    let mut rates = [0.0; 2];
+    let (mut equity, mut variance) = (0.0, 0.0);
+    for (tallies, blend) in [(&pilot, pilot_share), (&tallies, 1.0 - pilot_share)] {
+        for (((_, weight), tally), combo_variance) in available.iter().zip(tallies).zip(&variances) {
+            let (share, deals) = (blend * weight / total_weight, tally.deals.max(1) as f64);
+            rates[0] += share * tally.wins as f64 / deals;
+            rates[1] += share * tally.ties as f64 / deals;
+            equity += share * tally.equity();
+            variance += share * share * combo_variance / deals;
+        }
+    }
+    // A plain deal's variance: within each combo plus between them
+    let plain_variance: f64 = available.iter().zip(&pilot)
+        .map(|((_, weight), tally)| weight / total_weight * (tally.variance() + (tally.equity() - equity).powi(2)))
+        .sum();
+    
+    let wins = pilot.iter().chain(&tallies).map(|t| t.wins).sum();
+    let ties = pilot.iter().chain(&tallies).map(|t| t.ties).sum();
+    let mut results = SimulationResults::new(total, wins, total - wins - ties, ties);
+    results.win_rate = rates[0] * 100.0;
+    results.tie_rate = rates[1] * 100.0;
+    let margin = 1.96 * variance.sqrt() * 100.0;
+    StratifiedResults {
+        weighted: true,
+        pilot_trials: pilot_deals,
+        equivalent_trials: if variance > 0.0 { plain_variance / variance } else { total as f64 },
+        equity_ci: ((equity * 100.0 - margin).max(0.0), (equity * 100.0 + margin).min(100.0)),
+        results,
+    }
+}
+
+// Deals tried before giving up on finding a hero and villain combo that don't
+// share a card, when the two ranges mostly block each other
+const MAX_REJECTIONS: usize = 100;
+
+// Hero's results when both players' hands are drawn from ranges. Each deal picks
+// a pair of combos with probability proportional to the product of their weights,
+// skipping pairs that share a card, so combos that block much of the other range
+// are dealt less often.
+pub fn range_vs_range(
+    hero_range: &Range,
+    villain_range: &Range,
+    known_community: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    range_vs_range_with_rng(hero_range, villain_range, known_community, num_simulations, &mut rand::thread_rng())
+}
+
+pub fn range_vs_range_with_rng<R: Rng>(
+    hero_range: &Range,
+    villain_range: &Range,
+    known_community: &[Card],
+    num_simulations: usize,
+    rng: &mut R
+) -> SimulationResults {
+    let hero_combos = hero_range.available(known_community);
+    let villain_combos = villain_range.available(known_community);
+    if known_community.len() == 5 {
+        return exact_range_vs_range(&hero_combos, &villain_combos, known_community);
+    }
+    
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+    let mut made_hands = std::collections::HashMap::new();
+    let mut showdown = ShowdownStats::default();
+    
+    let mut deck = match Deck::without(known_community) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+    let mut board = Vec::with_capacity(5);
+    
+    for _ in 0..num_simulations {
+        let pair = (0..MAX_REJECTIONS).find_map(|_| {
+            let hero = sample_weighted(&hero_combos, rng)?;
+            let villain = sample_weighted(&villain_combos, rng)?;
+            (!villain.iter().any(|card| hero.contains(card))).then_some((hero, villain))
+        });
+        let Some((hero, villain)) = pair else { continue };
+        
+        let (player_eval, opp_eval) = match play_out(&mut deck, hero, villain, known_community, &mut board, rng) {
+            Some(result) => result,
+            None => continue,
+        };
+        showdown.record(&player_eval, &opp_eval);
+        match player_eval.cmp(&opp_eval) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Less => losses += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+        }
+        *made_hands.entry(player_eval.rank).or_insert(0) += 1;
+    }
+    
+    let mut results = SimulationResults::new(wins + losses + ties, wins, losses, ties);
+    results.made_hands = made_hands;
+    results.showdown = Some(showdown);
+    results
+}
+
+// Every pair of combos on a full board, weighted like range_vs_range's deals.
+// Games count the pairs; the rates use their weights.
+fn exact_range_vs_range(hero_combos: &[(&[Card; 2], f64)], villain_combos: &[(&[Card; 2], f64)], board: &[Card]) -> SimulationResults {
+    let hands = |combos: &[(&[Card; 2], f64)]| combos.iter().map(|(combo, _)| (*combo).clone()).collect::<Vec<[Card; 2]>>();
+    let hero_evals = evaluate_many(&hands(hero_combos), board);
+    let villain_evals = evaluate_many(&hands(villain_combos), board);
+    
+    let mut counts = [0usize; 3];
+    let mut weights = [0.0; 3];
+    let mut made_hands = std::collections::HashMap::new();
+    for ((hero, hero_weight), hero_eval) in hero_combos.iter().zip(&hero_evals) {
+        for ((villain, villain_weight), villain_eval) in villain_combos.iter().zip(&villain_evals) {
+            if villain.iter().any(|card| hero.contains(card)) {
+                continue;
+            }
+            let outcome = match hero_eval.cmp(villain_eval) {
+                std::cmp::Ordering::Greater => 0,
+                std::cmp::Ordering::Less => 1,
+                std::cmp::Ordering::Equal => 2,
+            };
+            counts[outcome] += 1;
+            weights[outcome] += hero_weight * villain_weight;
+            *made_hands.entry(hero_eval.rank.clone()).or_insert(0) += 1;
+        }
+    }
+    
+    let total = counts.iter().sum();
+    let mut results = SimulationResults::new(total, counts[0], counts[1], counts[2]);
+    results.made_hands = made_hands;
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight > 0.0 {
+        results.win_rate = weights[0] / total_weight * 100.0;
+        results.tie_rate = weights[2] / total_weight * 100.0;
+    }
+    results
+}
+
+#[derive(Debug, Clone)]
+pub struct ComboEquity {
+    pub combo: [Card; 2],
+    pub weight: f64,
+    // Hero's equity against this combo (wins plus half of ties), in percent
+    pub equity: f64,
+}
+
+// Hero's equity against each villain combo, bucketed into 10% bands
+#[derive(Debug, Clone)]
+pub struct EquityDistribution {
+    pub combos: Vec<ComboEquity>,
+    // Percent of the (weighted) villain range in each band: 0-10%, 10-20%, ... 90-100%
+    pub buckets: [f64; 10],
+}
+
+impl EquityDistribution {
+    pub fn average_equity(&self) -> f64 {
+        let total: f64 = self.combos.iter().map(|c| c.weight).sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.combos.iter().map(|c| c.equity * c.weight).sum::<f64>() / total
+    }
+}
+
+// Runs `simulations_per_combo` showdowns against every villain combo not blocked by
+// hero's hand or the board. A polarized range puts its weight in the outer buckets,
+// a linear one spreads it through the middle.
+pub fn equity_distribution(
+    player_hand: &[Card; 2],
+    villain_range: &Range,
+    known_community: &[Card],
+    simulations_per_combo: usize
+) -> EquityDistribution {
+    equity_distribution_with_rng(player_hand, villain_range, known_community, simulations_per_combo, &mut rand::thread_rng())
+}
+
+pub fn equity_distribution_with_rng<R: Rng>(
+    player_hand: &[Card; 2],
+    villain_range: &Range,
+    known_community: &[Card],
+    simulations_per_combo: usize,
+    rng: &mut R
+) -> EquityDistribution {
+    let mut dead_cards = player_hand.to_vec();
+    dead_cards.extend_from_slice(known_community);
+    
+    let mut combos = Vec::new();
+    let mut deck = match Deck::without(&dead_cards) {
+        Ok(deck) => deck,
+        Err(_) => return EquityDistribution { combos, buckets: [0.0; 10] },
+    };
+    let mut board = Vec::with_capacity(5);
+    for (combo, weight) in villain_range.available(&dead_cards) {
+        let mut points = 0.0;
+        let mut played = 0;
+        for _ in 0..simulations_per_combo {
+            match play_out(&mut deck, player_hand, combo, known_community, &mut board, rng) {
+                Some((player_eval, opp_eval)) => match player_eval.cmp(&opp_eval) {
+                    std::cmp::Ordering::Greater => points += 1.0,
+                    std::cmp::Ordering::Equal => points += 0.5,
+                    std::cmp::Ordering::Less => {}
+                },
+                None => continue,
+            }
+            played += 1;
+        }
+        if played > 0 {
+            combos.push(ComboEquity { combo: combo.clone(), weight, equity: points / played as f64 * 100.0 });
+        }
+    }
+    
+    let total_weight: f64 = combos.iter().map(|c| c.weight).sum();
+    let mut buckets = [0.0; 10];
+    for combo in &combos {
+        let bucket = ((combo.equity / 10.0) as usize).min(9);
+        buckets[bucket] += combo.weight / total_weight * 100.0;
+    }
+    
+    combos.sort_by(|a, b| a.equity.partial_cmp(&b.equity).unwrap());
+    EquityDistribution { combos, buckets }
+}
+
+#[derive(Debug, Clone)]
+pub struct Blocker {
+    pub card: Card,
+    // Weighted villain combos in the category that contain this card
+    pub removed: f64,
+    pub removed_pct: f64,
+}
+
+// The single cards that block the most villain combos of one made-hand category
+#[derive(Debug, Clone)]
+pub struct BlockerReport {
+    pub category: HandRank,
+    pub total_weight: f64,
+    pub blockers: Vec<Blocker>,
+}
+
+// For each hand category at or above `min_rank` that the villain range makes on this
+// board, ranks every unseen card by how much of that category holding it removes,
+// e.g. the A♠ removing 37% of villain's flushes on a three-spade board
+pub fn blocker_report(villain_range: &Range, board: &[Card], min_rank: HandRank) -> Result<Vec<BlockerReport>, String> {
+    if board.len() < 3 || board.len() > 5 {
+        return Err("Blocker analysis needs a flop, turn or river".to_string());
+    }
+    
+    let mut categories: std::collections::BTreeMap<HandRank, Vec<(&[Card; 2], f64)>> = std::collections::BTreeMap::new();
+    for (combo, weight) in villain_range.available(board) {
+        let rank = evaluate_hand(combo, board).rank;
+        if rank >= min_rank {
+            categories.entry(rank).or_default().push((combo, weight));
+        }
+    }
+    
+    let unseen: Vec<Card> = Deck::new().cards().iter().filter(|card| !board.contains(card)).cloned().collect();
+    let mut reports = Vec::new();
+    for (category, combos) in categories.into_iter().rev() {
+        let total_weight: f64 = combos.iter().map(|(_, weight)| weight).sum();
+        let mut blockers: Vec<Blocker> = unseen.iter()
+            .map(|card| {
+                let removed: f64 = combos.iter().filter(|(combo, _)| combo.contains(card)).map(|(_, weight)| weight).sum();
+                Blocker { card: card.clone(), removed, removed_pct: removed / total_weight * 100.0 }
+            })
+            .filter(|blocker| blocker.removed > 0.0)
+            .collect();
+        blockers.sort_by(|a, b| b.removed.partial_cmp(&a.removed).unwrap());
+        reports.push(BlockerReport { category, total_weight, blockers });
+    }
+    
+    Ok(reports)
+}
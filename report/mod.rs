@@ -0,0 +1,245 @@
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::card::{grid_hand, grid_win_rates, describe_hand, chen_score, sklansky_group, HandResult, RANKS_DESCENDING};
+use crate::luck::{HandLuck, SessionLuck};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1, h2 { font-weight: 600; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+td, th { padding: 4px 10px; border: 1px solid #ddd; text-align: right; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+td.hand, th.hand { text-align: left; }
+table.grid td { width: 48px; height: 36px; text-align: center; font-size: 12px; border: 1px solid #fff; }
+table.grid td.label { background: #f0f0f0; font-weight: 600; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 4px 16px; }
+dt { font-weight: 600; }
+svg text { font-size: 12px; fill: #555; }
+";
+
+// Clicking a header sorts by that column, toggling direction on repeat clicks
+const SCRIPT: &str = "
+document.querySelectorAll('table.sortable th').forEach(function (th, col) {
+  th.addEventListener('click', function () {
+    var body = th.closest('table').tBodies[0];
+    var asc = th.dataset.asc !== 'true';
+    th.dataset.asc = asc;
+    var rows = Array.prototype.slice.call(body.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[col].textContent, y = b.cells[col].textContent;
+      var nx = parseFloat(x), ny = parseFloat(y);
+      var cmp = (isNaN(nx) || isNaN(ny)) ? x.localeCompare(y) : nx - ny;
+      return asc ? cmp : -cmp;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Hue runs from red (0) to green (120) across the observed win-rate range
+fn heat_color(scale: f64) -> String {
+    format!("hsl({:.0}, 70%, 60%)", scale.clamp(0.0, 1.0) * 120.0)
+}
+
+fn write_grid(file: &mut File, results: &[HandResult]) -> Result<(), std::io::Error> {
+    let grid = grid_win_rates(results);
+    let rates: Vec<f64> = grid.iter().flatten().filter_map(|r| *r).collect();
+    let min = rates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = rates.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    writeln!(file, "<table class=\"grid\">")?;
+    write!(file, "<tr><td class=\"label\"></td>")?;
+    for rank in RANKS_DESCENDING.iter() {
+        write!(file, "<td class=\"label\">{}</td>", rank)?;
+    }
+    writeln!(file, "</tr>")?;
+
+    for (row, cells) in grid.iter().enumerate() {
+        write!(file, "<tr><td class=\"label\">{}</td>", RANKS_DESCENDING[row])?;
+        for (col, cell) in cells.iter().enumerate() {
+            let description = describe_hand(&grid_hand(row, col)).replace("(pair)", "");
+            match cell {
+                Some(rate) => {
+                    let scale = if max > min { (rate - min) / (max - min) } else { 1.0 };
+                    write!(file, "<td style=\"background: {}\" title=\"{}\">{}<br>{:.1}</td>",
+                           heat_color(scale), escape(&description), escape(&description), rate)?;
+                }
+                None => write!(file, "<td>{}</td>", escape(&description))?,
+            }
+        }
+        writeln!(file, "</tr>")?;
+    }
+    writeln!(file, "</table>")
+}
+
+// Writes a single self-contained HTML file with run metadata, summary statistics,
+// the 13x13 heatmap and a sortable table of every result
+pub fn export_html_report(results: &[HandResult], filename: &str, simulations_per_hand: usize, duration: Duration) -> Result<(), std::io::Error> {
+    if results.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "No results to report"));
+    }
+
+    let mut file = File::create(filename)?;
+
+    let avg_win_rate = results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64;
+    let best_hand = &results[0];
+    let worst_hand = &results[results.len() - 1];
+    let total_simulations = results.len() * simulations_per_hand;
+
+    writeln!(file, "<!DOCTYPE html>")?;
+    writeln!(file, "<html lang=\"en\"><head><meta charset=\"utf-8\">")?;
+    writeln!(file, "<title>Texas Hold'em Monte Carlo Analysis</title>")?;
+    writeln!(file, "<style>{}</style></head><body>", STYLE)?;
+    writeln!(file, "<h1>Texas Hold'em Monte Carlo Analysis</h1>")?;
+
+    writeln!(file, "<h2>Run</h2><dl>")?;
+    writeln!(file, "<dt>Timestamp</dt><dd>{}</dd>", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
+    writeln!(file, "<dt>Total hands</dt><dd>{}</dd>", results.len())?;
+    writeln!(file, "<dt>Simulations per hand</dt><dd>{}</dd>", simulations_per_hand)?;
+    writeln!(file, "<dt>Total simulations</dt><dd>{}</dd>", total_simulations)?;
+    writeln!(file, "<dt>Execution time</dt><dd>{:.2}s</dd>", duration.as_secs_f64())?;
+    writeln!(file, "<dt>Simulations per second</dt><dd>{:.0}</dd>", total_simulations as f64 / duration.as_secs_f64())?;
+    writeln!(file, "</dl>")?;
+
+    writeln!(file, "<h2>Summary</h2><dl>")?;
+    writeln!(file, "<dt>Average win rate</dt><dd>{:.2}%</dd>", avg_win_rate)?;
+    writeln!(file, "<dt>Best hand</dt><dd>{} ({:.2}%)</dd>", escape(&best_hand.hand_description), best_hand.results.win_rate)?;
+    writeln!(file, "<dt>Worst hand</dt><dd>{} ({:.2}%)</dd>", escape(&worst_hand.hand_description), worst_hand.results.win_rate)?;
+    writeln!(file, "</dl>")?;
+
+    writeln!(file, "<h2>Starting hand grid</h2>")?;
+    writeln!(file, "<p>Suited hands above the diagonal, offsuit below. Values are win rates in percent.</p>")?;
+    write_grid(&mut file, results)?;
+
+    writeln!(file, "<h2>Results</h2>")?;
+    writeln!(file, "<table class=\"sortable\"><thead><tr>")?;
+    writeln!(file, "<th>Rank</th><th class=\"hand\">Hand</th><th class=\"hand\">Cards</th><th>Win %</th><th>Lose %</th><th>Tie %</th><th>Wins</th><th>Losses</th><th>Ties</th><th>Total</th><th>Group</th><th>Chen</th>")?;
+    writeln!(file, "</tr></thead><tbody>")?;
+    for (rank, result) in results.iter().enumerate() {
+        let lose_rate = 100.0 - result.results.win_rate - result.results.tie_rate;
+        let losses = result.results.total_games - result.results.wins - result.results.ties;
+        writeln!(file, "<tr><td>{}</td><td class=\"hand\">{}</td><td class=\"hand\">{} {}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                 rank + 1,
+                 escape(&result.hand_description),
+                 result.hand[0],
+                 result.hand[1],
+                 result.results.win_rate,
+                 lose_rate,
+                 result.results.tie_rate,
+                 result.results.wins,
+                 losses,
+                 result.results.ties,
+                 result.results.total_games,
+                 sklansky_group(&result.hand),
+                 chen_score(&result.hand))?;
+    }
+    writeln!(file, "</tbody></table>")?;
+
+    writeln!(file, "<script>{}</script>", SCRIPT)?;
+    writeln!(file, "</body></html>")?;
+
+    tracing::info!("Report exported to: {}", filename);
+    Ok(())
+}
+
+// Cumulative actual and all-in EV results in big blinds, one point per hand
+fn write_luck_chart(file: &mut File, hands: &[HandLuck]) -> Result<(), std::io::Error> {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 300.0;
+    let mut net = vec![0.0];
+    let mut ev = vec![0.0];
+    for hand in hands {
+        net.push(net[net.len() - 1] + hand.net_bb);
+        ev.push(ev[ev.len() - 1] + hand.ev_bb);
+    }
+    let min = net.iter().chain(&ev).cloned().fold(0.0, f64::min);
+    let max = net.iter().chain(&ev).cloned().fold(0.0, f64::max);
+    let span = if max > min { max - min } else { 1.0 };
+    let y = |value: f64| HEIGHT - (value - min) / span * HEIGHT;
+    let points = |line: &[f64]| line.iter().enumerate()
+        .map(|(i, value)| format!("{:.1},{:.1}", i as f64 / hands.len() as f64 * WIDTH, y(*value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writeln!(file, "<svg width=\"{}\" height=\"{}\" viewBox=\"-60 -10 {} {}\">", WIDTH + 70.0, HEIGHT + 20.0, WIDTH + 70.0, HEIGHT + 20.0)?;
+    writeln!(file, "<line x1=\"0\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#ccc\"/>", y(0.0), WIDTH, y(0.0))?;
+    writeln!(file, "<text x=\"-8\" y=\"4\" text-anchor=\"end\">{:+.0}</text>", max)?;
+    writeln!(file, "<text x=\"-8\" y=\"{}\" text-anchor=\"end\">{:+.0}</text>", HEIGHT + 4.0, min)?;
+    writeln!(file, "<polyline points=\"{}\" fill=\"none\" stroke=\"#2a7\" stroke-width=\"1.5\"/>", points(&net))?;
+    writeln!(file, "<polyline points=\"{}\" fill=\"none\" stroke=\"#d72\" stroke-width=\"1.5\"/>", points(&ev))?;
+    writeln!(file, "</svg>")?;
+    writeln!(file, "<p><span style=\"color: #2a7\">&#9632;</span> Actual &nbsp; <span style=\"color: #d72\">&#9632;</span> All-in EV (big blinds)</p>")
+}
+
+// Writes a single self-contained HTML file comparing actual results with
+// all-in EV: totals, the cumulative graph, each session and every all-in
+pub fn export_luck_report(hands: &[HandLuck], sessions: &[SessionLuck], filename: &str) -> Result<(), std::io::Error> {
+    if hands.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "No hands to report"));
+    }
+
+    let mut file = File::create(filename)?;
+
+    let net: f64 = hands.iter().map(|hand| hand.net_bb).sum();
+    let ev: f64 = hands.iter().map(|hand| hand.ev_bb).sum();
+    let all_ins: Vec<&HandLuck> = hands.iter().filter(|hand| hand.all_in.is_some()).collect();
+    let average_equity = all_ins.iter().filter_map(|hand| hand.all_in.as_ref()).map(|all_in| all_in.equity).sum::<f64>() / all_ins.len().max(1) as f64;
+
+    writeln!(file, "<!DOCTYPE html>")?;
+    writeln!(file, "<html lang=\"en\"><head><meta charset=\"utf-8\">")?;
+    writeln!(file, "<title>All-in EV Report</title>")?;
+    writeln!(file, "<style>{}</style></head><body>", STYLE)?;
+    writeln!(file, "<h1>All-in EV Report: {}</h1>", escape(&hands[0].player))?;
+
+    writeln!(file, "<h2>Summary</h2><dl>")?;
+    writeln!(file, "<dt>Timestamp</dt><dd>{}</dd>", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
+    writeln!(file, "<dt>Hands</dt><dd>{}</dd>", hands.len())?;
+    writeln!(file, "<dt>All-ins</dt><dd>{}</dd>", all_ins.len())?;
+    writeln!(file, "<dt>Average all-in equity</dt><dd>{:.1}%</dd>", average_equity * 100.0)?;
+    writeln!(file, "<dt>Actual result</dt><dd>{:+.1} bb ({:+.2} bb/100)</dd>", net, net / hands.len() as f64 * 100.0)?;
+    writeln!(file, "<dt>All-in EV result</dt><dd>{:+.1} bb ({:+.2} bb/100)</dd>", ev, ev / hands.len() as f64 * 100.0)?;
+    writeln!(file, "<dt>Luck</dt><dd>{:+.1} bb</dd>", net - ev)?;
+    writeln!(file, "</dl>")?;
+
+    writeln!(file, "<h2>Results over time</h2>")?;
+    write_luck_chart(&mut file, hands)?;
+
+    writeln!(file, "<h2>Sessions</h2>")?;
+    writeln!(file, "<table class=\"sortable\"><thead><tr>")?;
+    writeln!(file, "<th class=\"hand\">Session</th><th>Hands</th><th>All-ins</th><th>Avg equity %</th><th>Net bb</th><th>EV bb</th><th>Luck bb</th>")?;
+    writeln!(file, "</tr></thead><tbody>")?;
+    for session in sessions {
+        writeln!(file, "<tr><td class=\"hand\">{}</td><td>{}</td><td>{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td></tr>",
+                 escape(&session.session), session.hands, session.all_ins, session.average_equity * 100.0,
+                 session.net_bb, session.ev_bb, session.luck_bb())?;
+    }
+    writeln!(file, "</tbody></table>")?;
+
+    writeln!(file, "<h2>All-ins</h2>")?;
+    writeln!(file, "<table class=\"sortable\"><thead><tr>")?;
+    writeln!(file, "<th class=\"hand\">Hand</th><th class=\"hand\">Street</th><th class=\"hand\">Cards</th><th class=\"hand\">Villain</th><th class=\"hand\">Board</th><th>Equity %</th><th>Net bb</th><th>EV bb</th><th>Luck bb</th>")?;
+    writeln!(file, "</tr></thead><tbody>")?;
+    for hand in &all_ins {
+        let Some(all_in) = &hand.all_in else {
+            continue;
+        };
+        let board: Vec<String> = all_in.board.iter().map(|card| card.to_string()).collect();
+        writeln!(file, "<tr><td class=\"hand\">{}</td><td class=\"hand\">{}</td><td class=\"hand\">{} {}</td><td class=\"hand\">{} ({} {})</td><td class=\"hand\">{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td></tr>",
+                 escape(&hand.id), all_in.street, all_in.hero_cards[0], all_in.hero_cards[1],
+                 escape(&all_in.villain), all_in.villain_cards[0], all_in.villain_cards[1], board.join(" "),
+                 all_in.equity * 100.0, hand.net_bb, hand.ev_bb, hand.luck_bb())?;
+    }
+    writeln!(file, "</tbody></table>")?;
+
+    writeln!(file, "<script>{}</script>", SCRIPT)?;
+    writeln!(file, "</body></html>")?;
+
+    tracing::info!("Report exported to: {}", filename);
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use rand::rngs::{OsRng, SmallRng, StdRng};
+use rand::{RngCore, SeedableRng};
+
+// Random number generators the simulators can draw from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngKind {
+    // Fastest, but not cryptographically strong and not stable across rand releases
+    Small,
+    // ChaCha12 (rand's StdRng): slower than Small with high-quality streams
+    ChaCha,
+    // The operating system's generator: slowest, and cannot be seeded
+    Os,
+}
+
+impl RngKind {
+    // Every generator, fastest first
+    pub const ALL: [RngKind; 3] = [RngKind::Small, RngKind::ChaCha, RngKind::Os];
+}
+
+// Generator selection as given to --rng: "small", "chacha" or "os", optionally
+// followed by a seed ("chacha:42") to replay the same stream on the next run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RngChoice {
+    pub kind: RngKind,
+    pub seed: Option<u64>,
+}
+
+impl Default for RngChoice {
+    fn default() -> Self {
+        RngChoice { kind: RngKind::ChaCha, seed: None }
+    }
+}
+
+impl std::str::FromStr for RngChoice {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (name, seed) = match text.split_once(':') {
+            Some((name, seed)) => {
+                let seed = seed.trim().parse().map_err(|_| format!("Invalid RNG seed '{}', expected a whole number", seed))?;
+                (name, Some(seed))
+            },
+            None => (text, None),
+        };
+        let kind = match name.trim().to_lowercase().as_str() {
+            "small" | "smallrng" => RngKind::Small,
+            "chacha" | "std" => RngKind::ChaCha,
+            "os" => RngKind::Os,
+            _ => return Err(format!("Unknown RNG '{}', expected small, chacha or os", name)),
+        };
+        if kind == RngKind::Os && seed.is_some() {
+            return Err("The OS RNG cannot be seeded; use small or chacha".to_string());
+        }
+        Ok(RngChoice { kind, seed })
+    }
+}
+
+impl std::fmt::Display for RngChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self.kind {
+            RngKind::Small => "small",
+            RngKind::ChaCha => "chacha",
+            RngKind::Os => "os",
+        };
+        match self.seed {
+            Some(seed) => write!(f, "{} (seed {})", name, seed),
+            None => write!(f, "{}", name),
+        }
+    }
+}
+
+impl RngChoice {
+    pub fn build(&self) -> SimRng {
+        match (self.kind, self.seed) {
+            (RngKind::Small, Some(seed)) => SimRng::Small(SmallRng::seed_from_u64(seed)),
+            (RngKind::Small, None) => SimRng::Small(SmallRng::from_entropy()),
+            (RngKind::ChaCha, Some(seed)) => SimRng::ChaCha(StdRng::seed_from_u64(seed)),
+            (RngKind::ChaCha, None) => SimRng::ChaCha(StdRng::from_entropy()),
+            (RngKind::Os, _) => SimRng::Os(OsRng),
+        }
+    }
+}
+
+// One concrete type for whichever generator was chosen at run time, so callers
+// can pass it to any of the *_with_rng simulators. ChaCha's state dwarfs the
+// others, but a run makes one generator and rarely moves it, so it isn't boxed.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum SimRng {
+    Small(SmallRng),
+    ChaCha(StdRng),
+    Os(OsRng),
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            SimRng::Small(rng) => rng.next_u32(),
+            SimRng::ChaCha(rng) => rng.next_u32(),
+            SimRng::Os(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            SimRng::Small(rng) => rng.next_u64(),
+            SimRng::ChaCha(rng) => rng.next_u64(),
+            SimRng::Os(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            SimRng::Small(rng) => rng.fill_bytes(dest),
+            SimRng::ChaCha(rng) => rng.fill_bytes(dest),
+            SimRng::Os(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            SimRng::Small(rng) => rng.try_fill_bytes(dest),
+            SimRng::ChaCha(rng) => rng.try_fill_bytes(dest),
+            SimRng::Os(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::{BufRead, Write};
+
+use rand::Rng;
+
+use crate::api;
+use crate::json::{self, JsonValue};
+
+// JSON-RPC 2.0 over standard input and output, one message per line, so an
+// editor, bot or other program can keep the engine running as a subprocess
+// instead of starting the CLI per question or talking to the HTTP server.
+// Each request line gets one response line, in order:
+//
+//   {"jsonrpc": "2.0", "id": 1, "method": "equity", "params": {"hero": "AhKh", "board": "Qh7c2d"}}
+//   {"jsonrpc":"2.0","id":1,"result":{"hero":"AhKh","board":"Qh7c2d","opponents":1,...}}
+//
+// Methods take their params as an object, as in api:
+//   evaluate       api::evaluate
+//   equity         api::equity against "opponents" random hands or a "villain" range
+//   range-equity   api::equity, with "villain" required
+//   preflop        api::preflop
+// Requests without an id are notifications and get no response. A batch (an
+// array of requests on one line) gets an array of responses.
+
+// Error codes fixed by the JSON-RPC specification
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+// Answers requests from `input` on `output` until input ends
+pub fn run<I, O, R>(input: I, mut output: O, max_trials: usize, rng: &mut R) -> Result<(), std::io::Error>
+where
+    I: BufRead,
+    O: Write,
+    R: Rng,
+{
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match json::parse(&line) {
+            Ok(JsonValue::Array(batch)) if !batch.is_empty() => {
+                let responses: Vec<JsonValue> = batch.iter().filter_map(|request| answer(request, max_trials, rng)).collect();
+                if responses.is_empty() { None } else { Some(JsonValue::Array(responses)) }
+            },
+            Ok(request) => answer(&request, max_trials, rng),
+            Err(e) => Some(error(JsonValue::Null, PARSE_ERROR, e)),
+        };
+        if let Some(response) = response {
+            writeln!(output, "{}", response)?;
+            // The caller is waiting on this line before it sends the next request
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+// The response to one request, or None for a notification
+fn answer<R: Rng>(request: &JsonValue, max_trials: usize, rng: &mut R) -> Option<JsonValue> {
+    let id = request.get("id").cloned();
+    let method = match (request.get("jsonrpc").and_then(|version| version.as_str()), request.get("method").and_then(|method| method.as_str())) {
+        (Some("2.0"), Some(method)) => method,
+        _ => return Some(error(id.unwrap_or(JsonValue::Null), INVALID_REQUEST, "Expected a JSON-RPC 2.0 request object with a method")),
+    };
+    let empty = JsonValue::Object(Vec::new());
+    let params = match request.get("params") {
+        None => &empty,
+        Some(params @ JsonValue::Object(_)) => params,
+        Some(_) => return id.map(|id| error(id, INVALID_PARAMS, "params must be an object")),
+    };
+
+    let result = match method {
+        "evaluate" => api::evaluate(params),
+        "equity" => api::equity(params, max_trials, rng),
+        "range-equity" if params.get("villain").is_none() => Err("Missing \"villain\"".to_string()),
+        "range-equity" => api::equity(params, max_trials, rng),
+        "preflop" => api::preflop(params, max_trials, rng),
+        _ => return id.map(|id| error(id, METHOD_NOT_FOUND, format!("Unknown method '{}', expected evaluate, equity, range-equity or preflop", method))),
+    };
+    let id = id?;
+    Some(match result {
+        Ok(result) => JsonValue::Object(vec![
+            ("jsonrpc".to_string(), JsonValue::String("2.0".to_string())),
+            ("id".to_string(), id),
+            ("result".to_string(), result),
+        ]),
+        Err(message) => error(id, INVALID_PARAMS, message),
+    })
+}
+
+fn error(id: JsonValue, code: i32, message: impl Into<String>) -> JsonValue {
+    JsonValue::Object(vec![
+        ("jsonrpc".to_string(), JsonValue::String("2.0".to_string())),
+        ("id".to_string(), id),
+        ("error".to_string(), JsonValue::Object(vec![
+            ("code".to_string(), JsonValue::Number(code as f64)),
+            ("message".to_string(), JsonValue::String(message.into())),
+        ])),
+    ])
+}
@@ -0,0 +1,359 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::api;
+use crate::json::{self, JsonValue};
+use crate::rng::RngChoice;
+
+// A small HTTP/1.1 server for the JSON API, so web pages and scripts can call
+// the simulator without starting the CLI for every question. Each connection
+// gets its own thread and carries one request; answers are JSON with CORS
+// headers so a page served from anywhere can call it.
+//
+//   GET  /health           {"status": "ok"}
+//   POST /equity           see api::equity
+//   GET  /preflop/{hand}   see api::preflop; "opponents" and "trials" go in the query string
+//   GET  /bulk             WebSocket: the events of api::bulk as text messages, e.g.
+//                          ws://localhost:8080/bulk?trials=1000&canonical=true
+
+#[derive(Debug, Clone, Copy)]
+pub struct ServerConfig {
+    // Upper limit on the trials one request can ask for
+    pub max_trials: usize,
+    // Every request gets a fresh generator from this, so a seed replays answers
+    pub rng: RngChoice,
+}
+
+// Request bodies larger than this are refused
+const MAX_BODY: usize = 1 << 20;
+
+// A client that stops sending is dropped after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    // Names in lower case
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+struct Response {
+    status: u16,
+    body: JsonValue,
+}
+
+impl Response {
+    fn ok(body: JsonValue) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response { status, body: JsonValue::Object(vec![("error".to_string(), JsonValue::String(message.into()))]) }
+    }
+}
+
+// Serves until the process is stopped; only fails if `address` can't be bound
+pub fn serve(address: &str, config: ServerConfig) -> Result<(), std::io::Error> {
+    let listener = TcpListener::bind(address)?;
+    tracing::info!("Listening on http://{} (max {} trials per request, {} rng)", listener.local_addr()?, config.max_trials, config.rng);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || handle_connection(stream, config));
+            },
+            Err(e) => tracing::warn!("Connection failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, config: ServerConfig) {
+    let start_time = Instant::now();
+    if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+        return;
+    }
+    let mut reader = BufReader::new(&stream);
+    let request = match read_request(&mut reader) {
+        Ok(request) => request,
+        Err(response) => {
+            tracing::info!("- {} ({:.2}s)", response.status, start_time.elapsed().as_secs_f64());
+            let _ = write_response(&stream, &response);
+            return;
+        },
+    };
+    if let Some(key) = websocket_key(&request).filter(|_| request.method == "GET" && request.path.trim_matches('/') == "bulk") {
+        let events = stream_bulk(&stream, &request, key, config);
+        tracing::info!("GET {} 101, {} events ({:.2}s)", request.path, events, start_time.elapsed().as_secs_f64());
+        return;
+    }
+    let response = route(&request, config);
+    tracing::info!("{} {} {} ({:.2}s)", request.method, request.path, response.status, start_time.elapsed().as_secs_f64());
+    // The client may already have gone; there is nobody left to tell
+    let _ = write_response(&stream, &response);
+}
+
+fn route(request: &Request, config: ServerConfig) -> Response {
+    let mut rng = config.rng.build();
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let answer = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => Ok(JsonValue::Object(vec![("status".to_string(), JsonValue::String("ok".to_string()))])),
+        ("POST", ["equity"]) => match body_json(request) {
+            Ok(body) => api::equity(&body, config.max_trials, &mut rng),
+            Err(response) => return response,
+        },
+        ("GET", ["preflop", hand]) => {
+            let mut fields = query_fields(&request.query);
+            fields.push(("hand".to_string(), JsonValue::String(percent_decode(hand, false))));
+            api::preflop(&JsonValue::Object(fields), config.max_trials, &mut rng)
+        },
+        // Browsers ask before sending a JSON POST from another origin
+        ("OPTIONS", _) => return Response { status: 204, body: JsonValue::Null },
+        ("GET", ["bulk"]) => return Response::error(426, "/bulk streams its results over a WebSocket"),
+        (_, ["health"] | ["equity"] | ["preflop", _] | ["bulk"]) => return Response::error(405, format!("{} is not allowed on {}", request.method, request.path)),
+        _ => return Response::error(404, format!("No endpoint at {}", request.path)),
+    };
+    match answer {
+        Ok(body) => Response::ok(body),
+        Err(message) => Response::error(400, message),
+    }
+}
+
+fn body_json(request: &Request) -> Result<JsonValue, Response> {
+    let text = std::str::from_utf8(&request.body).map_err(|_| Response::error(400, "The request body is not UTF-8"))?;
+    json::parse(text).map_err(|e| Response::error(400, e))
+}
+
+// Query parameters as request fields: numbers and true/false where they parse as one, text otherwise
+fn query_fields(query: &[(String, String)]) -> Vec<(String, JsonValue)> {
+    query.iter()
+        .map(|(key, value)| {
+            let value = match (value.parse::<f64>(), value.parse::<bool>()) {
+                (Ok(number), _) => JsonValue::Number(number),
+                (_, Ok(flag)) => JsonValue::Bool(flag),
+                _ => JsonValue::String(value.clone()),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+// Reads the request line, headers and body; a malformed request comes back as the error to send
+fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, Response> {
+    let bad_request = |message: &str| Response::error(400, message);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|_| bad_request("Could not read the request"))?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => (method.to_string(), target.to_string()),
+        _ => return Err(bad_request("Malformed request line")),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(|_| bad_request("Could not read the request headers"))? == 0 {
+            return Err(bad_request("The request ended inside its headers"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').ok_or_else(|| bad_request("Malformed header"))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let content_length = match headers.iter().find(|(name, _)| name == "content-length") {
+        Some((_, value)) => value.parse().map_err(|_| bad_request("Invalid Content-Length"))?,
+        None => 0,
+    };
+    if content_length > MAX_BODY {
+        return Err(Response::error(413, format!("Request bodies are limited to {} bytes", MAX_BODY)));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|_| bad_request("The request body is shorter than its Content-Length"))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key, true), percent_decode(value, true))
+        })
+        .collect();
+    Ok(Request { method, path: path.to_string(), query, headers, body })
+}
+
+// Undoes URL escapes ("%2B" is "+"); in query strings "+" also stands for a space
+fn percent_decode(text: &str, query: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            },
+            (b'+', _) if query => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn write_response(mut stream: &TcpStream, response: &Response) -> Result<(), std::io::Error> {
+    let reason = match response.status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        426 => "Upgrade Required",
+        _ => "Error",
+    };
+    let body = if response.status == 204 { String::new() } else { response.body.to_string() };
+    write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                    Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\n\
+                    Connection: close\r\n\r\n{}",
+           response.status, reason, body.len(), body)?;
+    stream.flush()
+}
+
+// The client's handshake key, if the request asks to switch to a WebSocket
+fn websocket_key(request: &Request) -> Option<&str> {
+    let upgrade = request.header("upgrade")?;
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        return None;
+    }
+    request.header("sec-websocket-key")
+}
+
+// Runs the bulk simulation the query asks for and sends each event as a text
+// message. Nothing is read from the client after the handshake: a client that
+// goes away is noticed when the next message can't be sent, which stops the
+// run. Returns the number of events sent.
+fn stream_bulk(stream: &TcpStream, request: &Request, key: &str, config: ServerConfig) -> usize {
+    // The accept value proves the server read this handshake (RFC 6455, section 4.2.2)
+    let accept = base64(&sha1(format!("{}258EAFA5-E914-47DA-95CA-C5AB0DC85B11", key).as_bytes()));
+    let handshake = format!("HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n", accept);
+    if (&*stream).write_all(handshake.as_bytes()).is_err() {
+        return 0;
+    }
+
+    let mut sent = 0;
+    let mut rng = config.rng.build();
+    let fields = JsonValue::Object(query_fields(&request.query));
+    let mut send = |event: JsonValue| {
+        let delivered = write_frame(stream, TEXT_FRAME, event.to_string().as_bytes()).is_ok();
+        sent += delivered as usize;
+        delivered
+    };
+    if let Err(message) = api::bulk(&fields, config.max_trials, &mut rng, &mut send) {
+        send(JsonValue::Object(vec![
+            ("event".to_string(), JsonValue::String("error".to_string())),
+            ("error".to_string(), JsonValue::String(message)),
+        ]));
+    }
+    // Status 1000: closed normally
+    let _ = write_frame(stream, CLOSE_FRAME, &1000u16.to_be_bytes());
+    sent
+}
+
+const TEXT_FRAME: u8 = 0x1;
+const CLOSE_FRAME: u8 = 0x8;
+
+// One unfragmented, unmasked frame, as a server sends them
+fn write_frame(mut stream: &TcpStream, opcode: u8, payload: &[u8]) -> Result<(), std::io::Error> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        length @ 0..=125 => frame.push(length as u8),
+        length @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        },
+        length => {
+            frame.push(127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        },
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)?;
+    stream.flush()
+}
+
+// SHA-1 digest; only the WebSocket handshake needs it, so it is kept here
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut words = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let next = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = next;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+// Standard padded base64
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| bits | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::game::{Action, Game, Stakes, Street};
+use crate::matrix::EquityMatrix;
+use crate::pushfold::{PushFoldSolution, icm_payoffs, solve_push_fold};
+use crate::range::hand_notation;
+use crate::tournament::BotStrategy;
+
+// Heads-up sit-and-gos: push/fold equilibria at each stack depth turned into
+// prize equity, and whole matches played on the game engine to measure what
+// a strategy is worth against another. With two players ICM is linear in
+// chips, so a player's prize equity is second place plus their chip share of
+// the difference between first and second.
+
+// Chips in a big blind when matches are played out
+const BIG_BLIND_CHIPS: u32 = 100;
+
+// Push/fold equilibria for even stacks of each depth from 2 bb up
+#[derive(Debug, Clone)]
+pub struct NashChart {
+    pub solutions: Vec<(u32, PushFoldSolution)>,
+    hand_index: HashMap<String, usize>,
+}
+
+impl NashChart {
+    // Solves every depth from 2 bb to `max_depth` for these payouts
+    pub fn solve(matrix: &EquityMatrix, max_depth: u32, payouts: &[f64], iterations: usize) -> Result<Self, String> {
+        if max_depth < 2 {
+            return Err(format!("Solve at least 2 bb deep, not {} bb", max_depth));
+        }
+        let solutions = (2..=max_depth)
+            .map(|depth| {
+                let stacks = [depth as f64; 2];
+                let payoffs = icm_payoffs(&stacks, 0.0, payouts)?;
+                Ok((depth, solve_push_fold(matrix, &payoffs, iterations)?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let hand_index = matrix.hands.iter().enumerate().map(|(i, hand)| (hand.clone(), i)).collect();
+        Ok(NashChart { solutions, hand_index })
+    }
+
+    // The solution for the depth nearest `effective_bb`
+    pub fn solution(&self, effective_bb: f64) -> &PushFoldSolution {
+        let depth = effective_bb.round().max(2.0) as u32;
+        let index = (depth as usize - 2).min(self.solutions.len() - 1);
+        &self.solutions[index].1
+    }
+
+    pub fn depth(&self, depth: u32) -> Option<&PushFoldSolution> {
+        self.solutions.iter().find(|(d, _)| *d == depth).map(|(_, solution)| solution)
+    }
+
+    fn index(&self, hand: &str) -> usize {
+        self.hand_index[hand]
+    }
+}
+
+// A heads-up player: the push/fold equilibrium for the current effective
+// stack, or one of the tournament bots
+#[derive(Debug, Clone)]
+pub enum SngStrategy {
+    Nash,
+    Bot(BotStrategy),
+}
+
+impl SngStrategy {
+    // "nash", or anything BotStrategy::parse accepts
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.eq_ignore_ascii_case("nash") {
+            Ok(SngStrategy::Nash)
+        } else {
+            BotStrategy::parse(text).map(SngStrategy::Bot)
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SngStrategy::Nash => "nash",
+            SngStrategy::Bot(bot) => &bot.name,
+        }
+    }
+
+    fn decide<R: Rng>(&self, game: &Game, chart: &NashChart, rng: &mut R) -> Action {
+        match self {
+            SngStrategy::Bot(bot) => bot.decide(game),
+            SngStrategy::Nash if game.street == Street::Preflop => nash_preflop(game, chart, rng),
+            // Only reached after a limp or a raise short of all-in; plays on like tag
+            SngStrategy::Nash => BotStrategy::preset("tag").expect("tag is a preset").decide(game),
+        }
+    }
+}
+
+// Jams or calls with the equilibrium frequency for the hand and the stacks at
+// the start of the hand; a raise that isn't all-in is treated as a jam
+fn nash_preflop<R: Rng>(game: &Game, chart: &NashChart, rng: &mut R) -> Action {
+    let Some(seat) = game.to_act else {
+        return Action::Check;
+    };
+    let Some(cards) = &game.players[seat].hole_cards else {
+        return Action::Fold;
+    };
+    let big_blind = game.stakes.big_blind;
+    let effective = game.players.iter().map(|p| p.stack + p.contributed).min().unwrap_or(0);
+    let solution = chart.solution(effective as f64 / big_blind as f64);
+    let hand = chart.index(&hand_notation(cards));
+    let to_call = game.to_call(seat);
+
+    let frequency = match (to_call, game.current_bet() > big_blind) {
+        (0, _) => return Action::Check,
+        // First in from the small blind
+        (_, false) => solution.jam[hand],
+        (_, true) => solution.call[hand],
+    };
+    if rng.gen_bool(frequency.clamp(0.0, 1.0)) { Action::AllIn } else { Action::Fold }
+}
+
+// Plays one match from even stacks of `depth_bb` with the blinds doubling
+// every `hands_per_level` hands and returns the winner's seat
+pub fn play_sng<R: Rng>(depth_bb: u32, strategies: &[SngStrategy; 2], chart: &NashChart, hands_per_level: usize, rng: &mut R) -> Result<usize, String> {
+    if depth_bb == 0 || hands_per_level == 0 {
+        return Err("The starting depth and hands per level must be positive".to_string());
+    }
+    let mut stacks = [depth_bb.saturating_mul(BIG_BLIND_CHIPS); 2];
+    let mut button = rng.gen_range(0..2);
+    let mut hand = 0;
+    loop {
+        let doublings = (hand / hands_per_level).min(31) as u32;
+        let big_blind = BIG_BLIND_CHIPS.saturating_mul(1 << doublings);
+        let mut game = Game::new(&stacks, Stakes::new(big_blind / 2, big_blind), button)?;
+        game.deal_hole_cards_with(rng);
+        while !game.is_over() {
+            match game.to_act {
+                Some(seat) => game.apply(strategies[seat].decide(&game, chart, rng))?,
+                None => game.next_street_with(rng)?,
+            }
+        }
+        game.finish()?;
+        stacks = [game.players[0].stack, game.players[1].stack];
+        if let Some(loser) = stacks.iter().position(|&stack| stack == 0) {
+            return Ok(1 - loser);
+        }
+        button = 1 - button;
+        hand += 1;
+    }
+}
+
+// Share of `trials` matches `hero` wins against `villain` from even stacks of `depth_bb`
+pub fn simulate_sng(
+    depth_bb: u32,
+    hero: &SngStrategy,
+    villain: &SngStrategy,
+    chart: &NashChart,
+    hands_per_level: usize,
+    trials: usize,
+) -> Result<f64, String> {
+    simulate_sng_with_rng(depth_bb, hero, villain, chart, hands_per_level, trials, &mut rand::thread_rng())
+}
+
+pub fn simulate_sng_with_rng<R: Rng>(
+    depth_bb: u32,
+    hero: &SngStrategy,
+    villain: &SngStrategy,
+    chart: &NashChart,
+    hands_per_level: usize,
+    trials: usize,
+    rng: &mut R,
+) -> Result<f64, String> {
+    if trials == 0 {
+        return Err("Simulate at least one match".to_string());
+    }
+    let strategies = [hero.clone(), villain.clone()];
+    let mut wins = 0;
+    for _ in 0..trials {
+        if play_sng(depth_bb, &strategies, chart, hands_per_level, rng)? == 0 {
+            wins += 1;
+        }
+    }
+    Ok(wins as f64 / trials as f64)
+}
+
+// Prize equity, as a share of the prize pool, for winning a match `win_rate` of the time
+pub fn finish_equity(win_rate: f64, payouts: &[f64]) -> f64 {
+    let first = payouts.first().copied().unwrap_or(0.0);
+    let second = payouts.get(1).copied().unwrap_or(0.0);
+    let pool = first + second;
+    if pool > 0.0 { (second + (first - second) * win_rate) / pool } else { 0.0 }
+}
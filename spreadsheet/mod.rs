@@ -0,0 +1,134 @@
+use rust_xlsxwriter::{ConditionalFormat3ColorScale, Format, FormatAlign, Workbook, Worksheet, XlsxError};
+
+use crate::card::{chen_score, grid_win_rates, sklansky_group, Card, HandResult, HAND_RANKS, RANKS_DESCENDING};
+
+fn to_io_error(e: XlsxError) -> std::io::Error {
+    std::io::Error::other(e.to_string())
+}
+
+// One workbook per bulk run: the ranked results, the run's summary and the
+// 13x13 grid. Rates are stored as plain numbers (percent), so sorting, filters
+// and conditional formatting work on them without re-importing anything.
+pub fn export_workbook(results: &[HandResult], filename: &str, simulations_per_hand: usize, board: &[Card], duration: std::time::Duration) -> Result<(), std::io::Error> {
+    let mut workbook = Workbook::new();
+    write_results_sheet(workbook.add_worksheet(), results).map_err(to_io_error)?;
+    write_summary_sheet(workbook.add_worksheet(), results, simulations_per_hand, board, duration).map_err(to_io_error)?;
+    write_grid_sheet(workbook.add_worksheet(), results).map_err(to_io_error)?;
+    workbook.save(filename).map_err(to_io_error)?;
+
+    tracing::info!("Workbook exported to: {}", filename);
+    Ok(())
+}
+
+// The columns of the CSV export, with the win rate's 95% interval
+fn write_results_sheet(sheet: &mut Worksheet, results: &[HandResult]) -> Result<(), XlsxError> {
+    let bold = Format::new().set_bold();
+    let rate = Format::new().set_num_format("0.00");
+    sheet.set_name("Results")?;
+
+    let mut headers: Vec<String> = ["Rank", "Hand", "Card 1", "Card 2", "Win %", "Lose %", "Tie %", "Equity %", "Win % CI low", "Win % CI high",
+                                    "Wins", "Losses", "Ties", "Games", "Sklansky group", "Chen score"]
+        .iter().map(|header| header.to_string()).collect();
+    headers.extend(HAND_RANKS.iter().map(|hand_rank| format!("{} %", hand_rank)));
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, header, &bold)?;
+    }
+
+    for (i, result) in results.iter().enumerate() {
+        let row = i as u32 + 1;
+        let games = &result.results;
+        let (ci_low, ci_high) = games.win_rate_ci();
+        sheet.write_number(row, 0, (i + 1) as f64)?;
+        sheet.write_string(row, 1, &result.hand_description)?;
+        sheet.write_string(row, 2, result.hand[0].to_string())?;
+        sheet.write_string(row, 3, result.hand[1].to_string())?;
+        let rates = [games.win_rate, 100.0 - games.win_rate - games.tie_rate, games.tie_rate, games.win_rate + games.tie_rate / 2.0, ci_low, ci_high];
+        for (offset, value) in rates.iter().enumerate() {
+            sheet.write_number_with_format(row, 4 + offset as u16, *value, &rate)?;
+        }
+        let counts = [games.wins, games.total_games - games.wins - games.ties, games.ties, games.total_games];
+        for (offset, value) in counts.iter().enumerate() {
+            sheet.write_number(row, 10 + offset as u16, *value as f64)?;
+        }
+        sheet.write_number(row, 14, sklansky_group(&result.hand) as f64)?;
+        sheet.write_number(row, 15, chen_score(&result.hand) as f64)?;
+        for (offset, hand_rank) in HAND_RANKS.iter().enumerate() {
+            sheet.write_number_with_format(row, 16 + offset as u16, games.made_hand_rate(hand_rank), &rate)?;
+        }
+    }
+
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofilter(0, 0, results.len() as u32, headers.len() as u16 - 1)?;
+    sheet.set_column_width(1, 12)?;
+    Ok(())
+}
+
+fn write_summary_sheet(sheet: &mut Worksheet, results: &[HandResult], simulations_per_hand: usize, board: &[Card], duration: std::time::Duration) -> Result<(), XlsxError> {
+    let bold = Format::new().set_bold();
+    sheet.set_name("Summary")?;
+
+    let by_win_rate = |a: &&HandResult, b: &&HandResult| a.results.win_rate.partial_cmp(&b.results.win_rate).unwrap();
+    let total_simulations = results.len() * simulations_per_hand;
+    let mut numbers = vec![
+        ("Hands", results.len() as f64),
+        ("Simulations per hand", simulations_per_hand as f64),
+        ("Total simulations", total_simulations as f64),
+        ("Time (s)", duration.as_secs_f64()),
+        ("Simulations per second", (total_simulations as f64 / duration.as_secs_f64()).round()),
+    ];
+    if !results.is_empty() {
+        numbers.push(("Average win %", results.iter().map(|r| r.results.win_rate).sum::<f64>() / results.len() as f64));
+    }
+
+    sheet.write_string_with_format(0, 0, "Statistic", &bold)?;
+    sheet.write_string_with_format(0, 1, "Value", &bold)?;
+    let mut row = 1;
+    sheet.write_string(row, 0, "Exported")?;
+    sheet.write_string(row, 1, chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string())?;
+    row += 1;
+    if !board.is_empty() {
+        sheet.write_string(row, 0, "Board")?;
+        sheet.write_string(row, 1, board.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" "))?;
+        row += 1;
+    }
+    for (label, value) in numbers {
+        sheet.write_string(row, 0, label)?;
+        sheet.write_number(row, 1, value)?;
+        row += 1;
+    }
+    for (label, hand) in [("Best hand", results.iter().max_by(by_win_rate)), ("Worst hand", results.iter().min_by(by_win_rate))] {
+        if let Some(hand) = hand {
+            sheet.write_string(row, 0, label)?;
+            sheet.write_string(row, 1, &hand.hand_description)?;
+            sheet.write_number(row, 2, hand.results.win_rate)?;
+            row += 1;
+        }
+    }
+
+    sheet.set_column_width(0, 24)?;
+    sheet.set_column_width(1, 24)?;
+    Ok(())
+}
+
+// Win rates laid out like the terminal grid, suited above the diagonal and
+// offsuit below, with a red-to-green scale over the 169 cells
+fn write_grid_sheet(sheet: &mut Worksheet, results: &[HandResult]) -> Result<(), XlsxError> {
+    let label = Format::new().set_bold().set_align(FormatAlign::Center);
+    let rate = Format::new().set_num_format("0.0").set_align(FormatAlign::Center);
+    sheet.set_name("Grid")?;
+
+    for (i, rank) in RANKS_DESCENDING.iter().enumerate() {
+        sheet.write_string_with_format(0, i as u16 + 1, rank.to_string(), &label)?;
+        sheet.write_string_with_format(i as u32 + 1, 0, rank.to_string(), &label)?;
+    }
+    for (row, cells) in grid_win_rates(results).iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if let Some(win_rate) = cell {
+                sheet.write_number_with_format(row as u32 + 1, col as u16 + 1, *win_rate, &rate)?;
+            }
+        }
+    }
+    sheet.add_conditional_format(1, 1, 13, 13, &ConditionalFormat3ColorScale::new())?;
+    sheet.write_string(15, 0, "Win % by starting hand: suited above the diagonal, offsuit below, pairs on it")?;
+    Ok(())
+}
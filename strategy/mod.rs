@@ -0,0 +1,175 @@
+// The arithmetic that turns an equity into a decision. Amounts are in any
+// unit (chips or big blinds) as long as they all use the same one, and
+// equities are fractions from 0 to 1.
+
+// Equity a call needs to break even: the call over the pot after calling.
+// `pot` already includes the bet being called.
+pub fn required_equity(pot: f64, to_call: f64) -> f64 {
+    if pot + to_call > 0.0 { to_call / (pot + to_call) } else { 0.0 }
+}
+
+// Average chips won by calling instead of folding: the final pot times the
+// equity, less the call
+pub fn call_ev(pot: f64, to_call: f64, equity: f64) -> f64 {
+    equity * (pot + to_call) - to_call
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Call,
+    Fold,
+    // Calling and folding are worth the same
+    Indifferent,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = match self {
+            Verdict::Call => "Call",
+            Verdict::Fold => "Fold",
+            Verdict::Indifferent => "Indifferent (call and fold break even)",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CallAnalysis {
+    pub pot: f64,
+    pub to_call: f64,
+    pub equity: f64,
+    pub required_equity: f64,
+    // Pot to call, as in "3 to 1"
+    pub odds_ratio: f64,
+    pub ev: f64,
+    pub verdict: Verdict,
+}
+
+// Pot odds, EV and a call/fold verdict for calling `to_call` into `pot` with `equity`
+pub fn analyze_call(pot: f64, to_call: f64, equity: f64) -> Result<CallAnalysis, String> {
+    if !(pot >= 0.0 && pot.is_finite()) {
+        return Err(format!("Invalid pot size {}", pot));
+    }
+    if !(to_call > 0.0 && to_call.is_finite()) {
+        return Err(format!("Invalid amount to call {}, expected a positive number", to_call));
+    }
+    if !(0.0..=1.0).contains(&equity) {
+        return Err(format!("Invalid equity {:.1}%, expected 0-100%", equity * 100.0));
+    }
+
+    let ev = call_ev(pot, to_call, equity);
+    // EVs within a millionth of the pot are rounding noise
+    let verdict = match ev {
+        ev if ev.abs() <= (pot + to_call) * 1e-6 => Verdict::Indifferent,
+        ev if ev > 0.0 => Verdict::Call,
+        _ => Verdict::Fold,
+    };
+    Ok(CallAnalysis {
+        pot,
+        to_call,
+        equity,
+        required_equity: required_equity(pot, to_call),
+        odds_ratio: pot / to_call,
+        ev,
+        verdict,
+    })
+}
+
+// Minimum defense frequency: how much of their range the player facing a
+// bet of `bet` into `pot` (before the bet) must continue with so a bluff of
+// any two cards can't profit
+pub fn minimum_defense_frequency(pot: f64, bet: f64) -> f64 {
+    if pot + bet > 0.0 { pot / (pot + bet) } else { 0.0 }
+}
+
+// Alpha: how often a bluff of `bet` into `pot` (before the bet) has to work
+// to break even; always 1 - MDF
+pub fn bluff_break_even(pot: f64, bet: f64) -> f64 {
+    if pot + bet > 0.0 { bet / (pot + bet) } else { 0.0 }
+}
+
+// Bet sizes, as fractions of the pot, that the mdf and alpha commands list
+// when no bet is given
+pub const COMMON_BET_SIZES: [f64; 8] = [0.25, 0.33, 0.5, 0.66, 0.75, 1.0, 1.5, 2.0];
+
+#[derive(Debug, Clone, Copy)]
+pub struct BetMath {
+    pub pot: f64,
+    pub bet: f64,
+    pub minimum_defense: f64,
+    pub bluff_break_even: f64,
+    // Equity the caller needs against the bettor's range
+    pub call_equity: f64,
+}
+
+// MDF, alpha and the caller's pot odds for a bet of `bet` into `pot` (before the bet)
+pub fn analyze_bet(pot: f64, bet: f64) -> Result<BetMath, String> {
+    if !(pot > 0.0 && pot.is_finite()) {
+        return Err(format!("Invalid pot size {}, expected a positive number", pot));
+    }
+    if !(bet > 0.0 && bet.is_finite()) {
+        return Err(format!("Invalid bet {}, expected a positive number", bet));
+    }
+    Ok(BetMath {
+        pot,
+        bet,
+        minimum_defense: minimum_defense_frequency(pot, bet),
+        bluff_break_even: bluff_break_even(pot, bet),
+        call_equity: required_equity(pot + bet, bet),
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShoveAnalysis {
+    pub pot: f64,
+    pub effective_stack: f64,
+    pub fold_frequency: f64,
+    pub equity_when_called: f64,
+    // Chips won or lost on average, measured from hero's stack before acting
+    pub ev_when_called: f64,
+    pub ev_shove: f64,
+    pub ev_check: f64,
+    // Fold frequency at which jamming and checking are worth the same (0 if
+    // jamming is better even when always called)
+    pub break_even_fold: f64,
+}
+
+// Semi-bluff jam of `effective_stack` into `pot` against a villain who folds
+// `fold_frequency` of the time and otherwise calls with a range hero has
+// `equity_when_called` against. Checking is valued as hero's equity share of
+// the current pot, i.e. as if the hand checks down; fold equity is what the
+// jam adds on top of that.
+pub fn analyze_shove(pot: f64, effective_stack: f64, fold_frequency: f64, equity_when_called: f64) -> Result<ShoveAnalysis, String> {
+    if !(pot > 0.0 && pot.is_finite()) {
+        return Err(format!("Invalid pot size {}, expected a positive number", pot));
+    }
+    if !(effective_stack > 0.0 && effective_stack.is_finite()) {
+        return Err(format!("Invalid effective stack {}, expected a positive number", effective_stack));
+    }
+    if !(0.0..=1.0).contains(&fold_frequency) {
+        return Err(format!("Invalid fold frequency {:.1}%, expected 0-100%", fold_frequency * 100.0));
+    }
+    if !(0.0..=1.0).contains(&equity_when_called) {
+        return Err(format!("Invalid equity {:.1}%, expected 0-100%", equity_when_called * 100.0));
+    }
+
+    let ev_when_called = call_ev(pot + effective_stack, effective_stack, equity_when_called);
+    let ev_shove = fold_frequency * pot + (1.0 - fold_frequency) * ev_when_called;
+    let ev_check = equity_when_called * pot;
+    // f * pot + (1 - f) * called = check, solved for f
+    let break_even_fold = if ev_when_called >= ev_check {
+        0.0
+    } else {
+        ((ev_check - ev_when_called) / (pot - ev_when_called)).clamp(0.0, 1.0)
+    };
+    Ok(ShoveAnalysis {
+        pot,
+        effective_stack,
+        fold_frequency,
+        equity_when_called,
+        ev_when_called,
+        ev_shove,
+        ev_check,
+        break_even_fold,
+    })
+}
@@ -0,0 +1,66 @@
+use crate::card::{Card, Deck, HandEvaluation, SimulationResults, evaluate_hand};
+
+// Best five-card hand out of a stud player's (up to) seven cards
+pub fn evaluate_stud(cards: &[Card]) -> HandEvaluation {
+    assert!(cards.len() >= 2, "stud hands need at least two cards");
+    evaluate_hand(&[cards[0].clone(), cards[1].clone()], &cards[2..])
+}
+
+// Seven-card stud equity. `player_cards` are all of hero's known cards (two to seven),
+// each entry of `opponent_upcards` holds one opponent's visible cards, and `dead_cards`
+// are folded up-cards that can no longer be dealt. Everyone is dealt out to seven cards.
+pub fn monte_carlo_stud(
+    player_cards: &[Card],
+    opponent_upcards: &[Vec<Card>],
+    dead_cards: &[Card],
+    num_simulations: usize
+) -> SimulationResults {
+    let mut wins = 0;
+    let mut losses = 0;
+    let mut ties = 0;
+
+    // Remove every known card from the deck once, recycling the rest per deal
+    let known: Vec<Card> = player_cards.iter()
+        .chain(opponent_upcards.iter().flatten())
+        .chain(dead_cards.iter())
+        .cloned()
+        .collect();
+    let mut deck = match Deck::without(&known) {
+        Ok(deck) => deck,
+        Err(_) => return SimulationResults::new(0, 0, 0, 0),
+    };
+
+    for _ in 0..num_simulations {
+        deck.recycle();
+
+        let mut player_hand = player_cards.to_vec();
+        let mut opponent_hands: Vec<Vec<Card>> = opponent_upcards.to_vec();
+
+        for hand in std::iter::once(&mut player_hand).chain(opponent_hands.iter_mut()) {
+            while hand.len() < 7 {
+                match deck.draw() {
+                    Some(card) => hand.push(card),
+                    None => break,
+                }
+            }
+        }
+
+        // Skip if the deck ran out before everyone had seven cards
+        if player_hand.len() < 7 || opponent_hands.iter().any(|hand| hand.len() < 7) {
+            continue;
+        }
+
+        let player_eval = evaluate_stud(&player_hand);
+        let best_opponent = opponent_hands.iter()
+            .map(|hand| evaluate_stud(hand))
+            .max();
+
+        match best_opponent.map(|best| player_eval.cmp(&best)) {
+            Some(std::cmp::Ordering::Greater) | None => wins += 1,
+            Some(std::cmp::Ordering::Less) => losses += 1,
+            Some(std::cmp::Ordering::Equal) => ties += 1,
+        }
+    }
+
+    SimulationResults::new(num_simulations, wins, losses, ties)
+}
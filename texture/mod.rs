@@ -0,0 +1,181 @@
+use std::fmt;
+
+use crate::card::{Card, Rank};
+
+// By the most cards of any one suit, so turns and rivers are classified too
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Suitedness {
+    // Three or more of one suit: the whole flop, or a flush draw's worth on a later street
+    Monotone,
+    // Two of some suit but no more
+    TwoTone,
+    // No two cards share a suit
+    Rainbow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    TwoPaired,
+    // Trips, a full house or quads on board
+    Trips,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Height {
+    // Two or more cards ten or higher
+    High,
+    Middle,
+    // Nothing above an eight
+    Low,
+}
+
+// Labels describing a flop, turn or river board, for grouping results across boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoardTexture {
+    pub suitedness: Suitedness,
+    pub pairing: Pairing,
+    // Some two hole cards make a straight (or the board already has one)
+    pub connected: bool,
+    // Three or more cards of one suit
+    pub flush_possible: bool,
+    pub height: Height,
+}
+
+impl BoardTexture {
+    pub const CSV_HEADER: &'static str = "Suitedness,Pairing,Connected,Flush_Possible,Height";
+
+    pub fn csv_fields(&self) -> String {
+        format!("{},{},{},{},{}", self.suitedness, self.pairing, self.connected, self.flush_possible, self.height)
+    }
+}
+
+impl fmt::Display for Suitedness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Suitedness::Monotone => "monotone",
+            Suitedness::TwoTone => "two-tone",
+            Suitedness::Rainbow => "rainbow",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Pairing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Pairing::Unpaired => "unpaired",
+            Pairing::Paired => "paired",
+            Pairing::TwoPaired => "two-paired",
+            Pairing::Trips => "trips",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Height::High => "high",
+            Height::Middle => "middle",
+            Height::Low => "low",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for BoardTexture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}, {}, {}",
+               self.suitedness,
+               self.pairing,
+               if self.connected { "connected" } else { "disconnected" },
+               self.height)?;
+        if self.flush_possible && self.suitedness != Suitedness::Monotone {
+            write!(f, ", flush possible")?;
+        }
+        Ok(())
+    }
+}
+
+// Whether three board ranks fit inside one five-rank straight window (aces play high and low)
+fn straight_possible(cards: &[Card]) -> bool {
+    let mut present = [false; 15];
+    for card in cards {
+        present[card.rank.value() as usize] = true;
+        if card.rank == Rank::Ace {
+            present[1] = true;
+        }
+    }
+    (1..=10).any(|low| (low..low + 5).filter(|&v| present[v]).count() >= 3)
+}
+
+// Classifies a board of three to five cards
+pub fn classify_board(board: &[Card]) -> Result<BoardTexture, String> {
+    if board.len() < 3 || board.len() > 5 {
+        return Err(format!("A board has three to five cards, found {}", board.len()));
+    }
+
+    let mut suit_counts = std::collections::HashMap::new();
+    let mut rank_counts = std::collections::HashMap::new();
+    for card in board {
+        *suit_counts.entry(card.suit.clone()).or_insert(0) += 1;
+        *rank_counts.entry(card.rank).or_insert(0) += 1;
+    }
+
+    let suitedness = match suit_counts.values().max() {
+        Some(1) => Suitedness::Rainbow,
+        Some(2) => Suitedness::TwoTone,
+        _ => Suitedness::Monotone,
+    };
+
+    let pairs = rank_counts.values().filter(|&&count| count == 2).count();
+    let pairing = if rank_counts.values().any(|&count| count >= 3) {
+        Pairing::Trips
+    } else if pairs >= 2 {
+        Pairing::TwoPaired
+    } else if pairs == 1 {
+        Pairing::Paired
+    } else {
+        Pairing::Unpaired
+    };
+
+    let high_cards = board.iter().filter(|card| card.rank >= Rank::Ten).count();
+    let height = if high_cards >= 2 {
+        Height::High
+    } else if board.iter().all(|card| card.rank <= Rank::Eight) {
+        Height::Low
+    } else {
+        Height::Middle
+    };
+
+    Ok(BoardTexture {
+        suitedness,
+        pairing,
+        connected: straight_possible(board),
+        flush_possible: suit_counts.values().any(|&count| count >= 3),
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    fn suitedness(board: &str) -> Suitedness {
+        classify_board(&parse_cards(board).unwrap()).unwrap().suitedness
+    }
+
+    #[test]
+    fn suitedness_goes_by_the_largest_suit() {
+        assert_eq!(suitedness("As7d2c"), Suitedness::Rainbow);
+        assert_eq!(suitedness("As7d2c9h"), Suitedness::Rainbow);
+        assert_eq!(suitedness("As7s2c"), Suitedness::TwoTone);
+        assert_eq!(suitedness("As7s2c9c"), Suitedness::TwoTone);
+        assert_eq!(suitedness("As7d2c9hKs"), Suitedness::TwoTone);
+        assert_eq!(suitedness("As7s2s"), Suitedness::Monotone);
+        assert_eq!(suitedness("As7s2s9hKd"), Suitedness::Monotone);
+    }
+}
@@ -0,0 +1,509 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::card::{Card, HandRank, evaluate_hand};
+use crate::game::{Action, Game, LegalAction, Stakes, Street};
+use crate::hand_history::HandHistory;
+use crate::range::{PREFLOP_RANKING, hand_notation};
+
+// Whole multi-table tournaments played hand by hand on the game engine:
+// blinds and antes rise on a schedule, busted players are ranked by when
+// they went out, and players move between tables to keep them balanced.
+// Every entrant is a bot, so a strategy can be measured against a field.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindLevel {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
+}
+
+impl BlindLevel {
+    pub fn stakes(&self) -> Stakes {
+        Stakes { small_blind: self.small_blind, big_blind: self.big_blind, ante: self.ante }
+    }
+}
+
+impl std::str::FromStr for BlindLevel {
+    type Err = String;
+
+    // "25/50" or "100/200/25" with the ante last
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let amounts: Vec<u32> = text.split('/')
+            .map(|part| part.trim().parse::<u32>().map_err(|_| format!("Invalid blind level '{}', expected sb/bb or sb/bb/ante", text)))
+            .collect::<Result<_, _>>()?;
+        let level = match amounts[..] {
+            [small_blind, big_blind] => BlindLevel { small_blind, big_blind, ante: 0 },
+            [small_blind, big_blind, ante] => BlindLevel { small_blind, big_blind, ante },
+            _ => return Err(format!("Invalid blind level '{}', expected sb/bb or sb/bb/ante", text)),
+        };
+        if level.big_blind == 0 || level.small_blind > level.big_blind {
+            return Err(format!("Invalid blinds {}/{}", level.small_blind, level.big_blind));
+        }
+        Ok(level)
+    }
+}
+
+impl std::fmt::Display for BlindLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.small_blind, self.big_blind)?;
+        if self.ante > 0 {
+            write!(f, "/{}", self.ante)?;
+        }
+        Ok(())
+    }
+}
+
+// A turbo-style structure for 1,500 chip stacks
+pub const DEFAULT_LEVELS: &str = "10/20,15/30,25/50,50/100,75/150,100/200/25,150/300/25,200/400/50,300/600/75,400/800/100";
+
+// Parses a comma separated schedule such as DEFAULT_LEVELS
+pub fn parse_levels(text: &str) -> Result<Vec<BlindLevel>, String> {
+    text.split(',').map(|level| level.trim().parse()).collect()
+}
+
+// A rule-based bot. Preflop it plays the strongest share of hands (by
+// PREFLOP_RANKING, counting combos); after the flop it bets two pair or
+// better, calls with a pair and gives up with less.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotStrategy {
+    pub name: String,
+    // Share of hands (0-1) it raises when nobody has raised yet
+    pub open: f64,
+    // Share of hands it continues with against a raise; the strongest third re-raises
+    pub defend: f64,
+    // At or below this many big blinds it only jams or folds preflop
+    pub push_fold_below: f64,
+    // Calls any postflop bet with any hand
+    pub calling_station: bool,
+}
+
+// Named strategies: (name, open %, defend %, push/fold below bb, calling station)
+pub const STRATEGY_PRESETS: [(&str, f64, f64, f64, bool); 5] = [
+    ("nit", 12.0, 6.0, 8.0, false),
+    ("tag", 22.0, 12.0, 12.0, false),
+    ("lag", 40.0, 25.0, 15.0, false),
+    ("station", 30.0, 50.0, 5.0, true),
+    ("pushfold", 35.0, 18.0, f64::INFINITY, false),
+];
+
+impl BotStrategy {
+    pub fn preset(name: &str) -> Option<Self> {
+        STRATEGY_PRESETS.iter()
+            .find(|(preset, ..)| preset.eq_ignore_ascii_case(name))
+            .map(|&(name, open, defend, push_fold_below, calling_station)| BotStrategy {
+                name: name.to_string(),
+                open: open / 100.0,
+                defend: defend / 100.0,
+                push_fold_below,
+                calling_station,
+            })
+    }
+
+    // A preset name, or "open%/defend%/push-fold bb" such as "25/12/10"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if let Some(strategy) = BotStrategy::preset(text) {
+            return Ok(strategy);
+        }
+        let invalid = || format!("Unknown strategy '{}', expected one of {} or open%/defend%/push-fold bb such as 25/12/10",
+                                 text, STRATEGY_PRESETS.iter().map(|(name, ..)| *name).collect::<Vec<_>>().join(", "));
+        let numbers: Vec<f64> = text.split('/').map(|part| part.trim().parse::<f64>()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+        let [open, defend, push_fold_below] = numbers[..] else {
+            return Err(invalid());
+        };
+        if !(0.0..=100.0).contains(&open) || !(0.0..=100.0).contains(&defend) || push_fold_below.is_nan() || push_fold_below < 0.0 {
+            return Err(invalid());
+        }
+        Ok(BotStrategy {
+            name: text.to_string(),
+            open: open / 100.0,
+            defend: defend / 100.0,
+            push_fold_below,
+            calling_station: false,
+        })
+    }
+
+    // The action for the player to act in `game`
+    pub fn decide(&self, game: &Game) -> Action {
+        let Some(seat) = game.to_act else {
+            return Action::Check;
+        };
+        let player = &game.players[seat];
+        let Some(cards) = &player.hole_cards else {
+            return check_or_fold(game, seat);
+        };
+        if game.street == Street::Preflop {
+            self.decide_preflop(game, seat, cards)
+        } else {
+            self.decide_postflop(game, seat, cards)
+        }
+    }
+
+    fn decide_preflop(&self, game: &Game, seat: usize, cards: &[Card; 2]) -> Action {
+        let big_blind = game.stakes.big_blind;
+        let player = &game.players[seat];
+        let stack_bb = (player.stack + player.street_bet) as f64 / big_blind as f64;
+        let strength = preflop_percentile(cards);
+        let raised = game.current_bet() > big_blind;
+
+        if stack_bb <= self.push_fold_below {
+            let share = if raised { self.defend } else { self.open };
+            return if strength <= share { raise_to(game, seat, u32::MAX) } else { check_or_fold(game, seat) };
+        }
+        match raised {
+            false if strength <= self.open => raise_to(game, seat, big_blind * 5 / 2),
+            false => check_or_fold(game, seat),
+            true if strength <= self.defend / 3.0 => raise_to(game, seat, game.current_bet() * 3),
+            true if strength <= self.defend => Action::Call,
+            true => check_or_fold(game, seat),
+        }
+    }
+
+    fn decide_postflop(&self, game: &Game, seat: usize, cards: &[Card; 2]) -> Action {
+        let rank = evaluate_hand(cards, &game.board).rank;
+        let to_call = game.to_call(seat);
+        let pot = game.pot();
+        match to_call {
+            0 if rank >= HandRank::TwoPair => raise_to(game, seat, pot * 2 / 3),
+            0 => Action::Check,
+            _ if rank >= HandRank::TwoPair || self.calling_station => Action::Call,
+            // A pair calls bets up to the size of the pot
+            _ if rank == HandRank::Pair && to_call * 2 <= pot => Action::Call,
+            _ => Action::Fold,
+        }
+    }
+}
+
+fn check_or_fold(game: &Game, seat: usize) -> Action {
+    if game.to_call(seat) > 0 { Action::Fold } else { Action::Check }
+}
+
+// Bets or raises to a street total of `total`, going all-in when that is at
+// least the stack, and calls or checks when raising isn't allowed
+fn raise_to(game: &Game, seat: usize, total: u32) -> Action {
+    let range = game.legal_actions().into_iter().find_map(|action| match action {
+        LegalAction::Bet { min, max } | LegalAction::Raise { min, max } => Some((min, max)),
+        _ => None,
+    });
+    match range {
+        Some((min, max)) if total.max(min) >= max => Action::AllIn,
+        Some((min, _)) => Action::Bet(total.max(min)),
+        None if game.to_call(seat) > 0 => Action::Call,
+        None => Action::Check,
+    }
+}
+
+// Share of all combos at least as strong as `cards` by PREFLOP_RANKING, so
+// AA is 0.5% and 32o is 100%
+pub fn preflop_percentile(cards: &[Card; 2]) -> f64 {
+    let notation = hand_notation(cards);
+    let mut combos = 0;
+    for hand in PREFLOP_RANKING.iter() {
+        combos += match hand.len() {
+            2 => 6,
+            _ if hand.ends_with('s') => 4,
+            _ => 12,
+        };
+        if *hand == notation {
+            break;
+        }
+    }
+    combos as f64 / 1326.0
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    pub players: usize,
+    // Most players at one table (2-10)
+    pub table_size: usize,
+    pub starting_stack: u32,
+    // After the last level the blinds and ante keep doubling
+    pub levels: Vec<BlindLevel>,
+    // Hands each table plays per level
+    pub hands_per_level: usize,
+    // Percent of the prize pool for each place, first place first
+    pub payouts: Vec<f64>,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig {
+            players: 9,
+            table_size: 9,
+            starting_stack: 1500,
+            levels: parse_levels(DEFAULT_LEVELS).expect("default levels are valid"),
+            hands_per_level: 10,
+            payouts: vec![50.0, 30.0, 20.0],
+        }
+    }
+}
+
+impl TournamentConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.players < 2 {
+            return Err(format!("A tournament needs at least 2 players, found {}", self.players));
+        }
+        if !(2..=10).contains(&self.table_size) {
+            return Err(format!("Tables seat 2 to 10 players, not {}", self.table_size));
+        }
+        if self.starting_stack == 0 || self.hands_per_level == 0 || self.levels.is_empty() {
+            return Err("The starting stack, hands per level and blind levels can't be empty".to_string());
+        }
+        if (self.players as u64) * (self.starting_stack as u64) > u32::MAX as u64 {
+            return Err(format!("{} players with {} chips each is more chips than a table can count", self.players, self.starting_stack));
+        }
+        if self.payouts.len() > self.players {
+            return Err(format!("{} payouts for only {} players", self.payouts.len(), self.players));
+        }
+        let total: f64 = self.payouts.iter().sum();
+        if self.payouts.iter().any(|p| !(*p >= 0.0 && p.is_finite())) || total > 100.0 + 1e-9 {
+            return Err(format!("Payouts must be percentages of the prize pool adding up to at most 100, found {}", total));
+        }
+        Ok(())
+    }
+
+    // Blinds for level `index` (from 0)
+    pub fn level(&self, index: usize) -> BlindLevel {
+        let last = self.levels[self.levels.len() - 1];
+        let Some(doublings) = index.checked_sub(self.levels.len() - 1) else {
+            return self.levels[index];
+        };
+        let factor = 1u32.checked_shl(doublings.min(31) as u32).unwrap_or(u32::MAX);
+        BlindLevel {
+            small_blind: last.small_blind.saturating_mul(factor),
+            big_blind: last.big_blind.saturating_mul(factor),
+            ante: last.ante.saturating_mul(factor),
+        }
+    }
+
+    // Prize for finishing in `place` (from 1), in buy-ins
+    pub fn prize(&self, place: usize) -> f64 {
+        self.payouts.get(place - 1).map_or(0.0, |percent| percent / 100.0 * self.players as f64)
+    }
+}
+
+// Entrants at one table in seat order, and the index of the button among them
+#[derive(Debug, Clone)]
+struct Table {
+    seats: Vec<usize>,
+    button: usize,
+}
+
+// Plays one hand at `table` and updates the stacks, adding it to `history` if given
+fn play_hand<R: Rng>(
+    table: &Table,
+    table_number: usize,
+    stacks: &mut [u32],
+    strategies: &[BotStrategy],
+    level: BlindLevel,
+    history: Option<&mut Vec<HandHistory>>,
+    rng: &mut R,
+) -> Result<(), String> {
+    let table_stacks: Vec<u32> = table.seats.iter().map(|&entrant| stacks[entrant]).collect();
+    let mut game = Game::new(&table_stacks, level.stakes(), table.button)?;
+    game.deal_hole_cards_with(rng);
+    while !game.is_over() {
+        match game.to_act {
+            Some(seat) => game.apply(strategies[table.seats[seat]].decide(&game))?,
+            None => game.next_street_with(rng)?,
+        }
+    }
+    let won = game.finish()?;
+    if let Some(hands) = history {
+        let names: Vec<String> = table.seats.iter()
+            .map(|&entrant| format!("{}_{}", strategies[entrant].name.replace(' ', "_"), entrant + 1))
+            .collect();
+        let mut hand = HandHistory::from_game(&game, &table_stacks, &won, &names, &(hands.len() + 1).to_string(), &format!("1 {}", table_number));
+        hand.tournament = Some("1".to_string());
+        hands.push(hand);
+    }
+    for (player, &entrant) in game.players.iter().zip(&table.seats) {
+        stacks[entrant] = player.stack;
+    }
+    Ok(())
+}
+
+// Drops busted entrants and moves the button to the next player still seated
+fn remove_busted(table: &mut Table, stacks: &[u32]) {
+    let count = table.seats.len();
+    let next_button = (1..=count)
+        .map(|offset| table.seats[(table.button + offset) % count])
+        .find(|&entrant| stacks[entrant] > 0);
+    table.seats.retain(|&entrant| stacks[entrant] > 0);
+    table.button = next_button.and_then(|entrant| table.seats.iter().position(|&e| e == entrant)).unwrap_or(0);
+}
+
+// Breaks tables while the others have room for their players, then moves
+// players from the fullest table to the shortest until they differ by at most one
+fn balance_tables(tables: &mut Vec<Table>, table_size: usize) {
+    loop {
+        let players: usize = tables.iter().map(|table| table.seats.len()).sum();
+        if tables.len() > 1 && players <= (tables.len() - 1) * table_size {
+            let smallest = (0..tables.len()).min_by_key(|&i| tables[i].seats.len()).expect("at least one table");
+            for entrant in tables.remove(smallest).seats {
+                let shortest = (0..tables.len()).min_by_key(|&i| tables[i].seats.len()).expect("at least one table");
+                tables[shortest].seats.push(entrant);
+            }
+            continue;
+        }
+
+        let fullest = (0..tables.len()).max_by_key(|&i| tables[i].seats.len()).expect("at least one table");
+        let shortest = (0..tables.len()).min_by_key(|&i| tables[i].seats.len()).expect("at least one table");
+        if tables[fullest].seats.len() <= tables[shortest].seats.len() + 1 {
+            return;
+        }
+        // The player in the seat before the button moves, so nobody skips the blinds twice
+        let table = &mut tables[fullest];
+        let seat = (table.button + table.seats.len() - 1) % table.seats.len();
+        let entrant = table.seats.remove(seat);
+        if seat < table.button {
+            table.button -= 1;
+        }
+        tables[shortest].seats.push(entrant);
+    }
+}
+
+// Plays one tournament with `strategies[i]` for entrant i and returns each
+// entrant's finishing place (1 is the winner). Players who bust on the same
+// round of hands are ranked by the chips they started it with. Every hand
+// played is added to `history` if given.
+pub fn play_tournament<R: Rng>(
+    config: &TournamentConfig,
+    strategies: &[BotStrategy],
+    mut history: Option<&mut Vec<HandHistory>>,
+    rng: &mut R,
+) -> Result<Vec<usize>, String> {
+    config.validate()?;
+    if strategies.len() != config.players {
+        return Err(format!("{} strategies for {} players", strategies.len(), config.players));
+    }
+
+    let mut entrants: Vec<usize> = (0..config.players).collect();
+    entrants.shuffle(rng);
+    let table_count = config.players.div_ceil(config.table_size);
+    let mut tables: Vec<Table> = (0..table_count)
+        .map(|t| Table { seats: entrants.iter().copied().skip(t).step_by(table_count).collect(), button: 0 })
+        .collect();
+    for table in &mut tables {
+        table.button = rng.gen_range(0..table.seats.len());
+    }
+
+    let mut stacks = vec![config.starting_stack; config.players];
+    let mut places = vec![0; config.players];
+    let mut remaining = config.players;
+    let mut round = 0;
+    while remaining > 1 {
+        let level = config.level(round / config.hands_per_level);
+        let before = stacks.clone();
+        for (number, table) in tables.iter().enumerate().filter(|(_, table)| table.seats.len() > 1) {
+            play_hand(table, number + 1, &mut stacks, strategies, level, history.as_deref_mut(), rng)?;
+        }
+
+        let mut busted: Vec<usize> = (0..config.players).filter(|&e| places[e] == 0 && stacks[e] == 0).collect();
+        busted.sort_by_key(|&entrant| before[entrant]);
+        for entrant in busted {
+            places[entrant] = remaining;
+            remaining -= 1;
+        }
+        for table in &mut tables {
+            remove_busted(table, &stacks);
+        }
+        tables.retain(|table| !table.seats.is_empty());
+        balance_tables(&mut tables, config.table_size);
+        round += 1;
+    }
+    if let Some(winner) = places.iter().position(|&place| place == 0) {
+        places[winner] = 1;
+    }
+    Ok(places)
+}
+
+// How one strategy did over many tournaments
+#[derive(Debug, Clone)]
+pub struct StrategyReport {
+    pub name: String,
+    pub entries: usize,
+    // finishes[p] counts finishes in place p + 1
+    pub finishes: Vec<usize>,
+    // Prizes won, in buy-ins
+    pub winnings: f64,
+}
+
+impl StrategyReport {
+    fn new(name: String, players: usize) -> Self {
+        StrategyReport { name, entries: 0, finishes: vec![0; players], winnings: 0.0 }
+    }
+
+    // Return on investment: average prize over the buy-in, less the buy-in
+    pub fn roi(&self) -> f64 {
+        self.winnings / self.entries.max(1) as f64 - 1.0
+    }
+
+    pub fn average_finish(&self) -> f64 {
+        let total: usize = self.finishes.iter().enumerate().map(|(p, count)| (p + 1) * count).sum();
+        total as f64 / self.entries.max(1) as f64
+    }
+
+    // Share of entries that finished in the first `paid` places
+    pub fn in_the_money(&self, paid: usize) -> f64 {
+        self.finishes.iter().take(paid).sum::<usize>() as f64 / self.entries.max(1) as f64
+    }
+}
+
+// Plays `trials` tournaments with `hero` in one seat and the rest filled from
+// `field` in turn. The first report is the strategy under test, then one for
+// each distinct field strategy. The hands of the first tournament are added
+// to `history` if given.
+pub fn simulate_tournaments(
+    config: &TournamentConfig,
+    hero: &BotStrategy,
+    field: &[BotStrategy],
+    trials: usize,
+    history: Option<&mut Vec<HandHistory>>,
+) -> Result<Vec<StrategyReport>, String> {
+    simulate_tournaments_with_rng(config, hero, field, trials, history, &mut rand::thread_rng())
+}
+
+pub fn simulate_tournaments_with_rng<R: Rng>(
+    config: &TournamentConfig,
+    hero: &BotStrategy,
+    field: &[BotStrategy],
+    trials: usize,
+    mut history: Option<&mut Vec<HandHistory>>,
+    rng: &mut R,
+) -> Result<Vec<StrategyReport>, String> {
+    if field.is_empty() {
+        return Err("The field needs at least one strategy".to_string());
+    }
+    if trials == 0 {
+        return Err("Simulate at least one tournament".to_string());
+    }
+
+    let strategies: Vec<BotStrategy> = std::iter::once(hero.clone())
+        .chain(field.iter().cycle().take(config.players.saturating_sub(1)).cloned())
+        .collect();
+    // Report index for each entrant
+    let mut reports = vec![StrategyReport::new(format!("{} (under test)", hero.name), config.players)];
+    let mut report_of = vec![0];
+    for strategy in &strategies[1..] {
+        let index = match reports.iter().skip(1).position(|report| report.name == strategy.name) {
+            Some(index) => index + 1,
+            None => {
+                reports.push(StrategyReport::new(strategy.name.clone(), config.players));
+                reports.len() - 1
+            },
+        };
+        report_of.push(index);
+    }
+
+    for _ in 0..trials {
+        let places = play_tournament(config, &strategies, history.take(), rng)?;
+        for (entrant, &place) in places.iter().enumerate() {
+            let report = &mut reports[report_of[entrant]];
+            report.entries += 1;
+            report.finishes[place - 1] += 1;
+            report.winnings += config.prize(place);
+        }
+    }
+    Ok(reports)
+}
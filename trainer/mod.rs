@@ -0,0 +1,439 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::card::{Card, Deck, HandResult, aggregate_canonical, combo_count, monte_carlo_simulation_with_rng};
+use crate::game::Street;
+use crate::hand_history::card_code;
+use crate::range::{PRESETS, PREFLOP_RANKING, Range, hand_notation, monte_carlo_vs_range_with_rng};
+use crate::strategy::{CallAnalysis, analyze_call};
+
+// Drills that ask for an estimate, check it against the engine and append
+// every answer to a CSV log, so errors can be followed across sessions.
+// Each line of the log is one answer:
+//
+//   Time,Quiz,Category,Spot,Estimate,Answer
+//   2025-09-20T01:15:33+00:00,equity,flop,AhKh vs btn-open on Qh7c2d,55,61.3
+//
+// Quiz names the drill and Category the kind of question within it (the
+// street for equity spots), which is what the error summary groups by.
+
+pub const DEFAULT_LOG: &str = "pokershit_training.csv";
+
+const LOG_HEADER: &str = "Time,Quiz,Category,Spot,Estimate,Answer";
+
+// Answers counted as the recent form in the summary
+const RECENT: usize = 20;
+
+// Flashcard weights: a miss multiplies a hand's weight by MISS_FACTOR and a
+// hit by HIT_FACTOR, within these bounds, so missed hands keep coming back
+// until they're answered right a few times
+const MISS_FACTOR: f64 = 3.0;
+const HIT_FACTOR: f64 = 0.5;
+const MIN_WEIGHT: f64 = 0.125;
+const MAX_WEIGHT: f64 = 27.0;
+
+// Who hero's equity is against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opposition {
+    Hand,
+    Range,
+    // Either, at random for each spot
+    Mixed,
+}
+
+impl std::str::FromStr for Opposition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hand" => Ok(Opposition::Hand),
+            "range" => Ok(Opposition::Range),
+            "mixed" | "any" => Ok(Opposition::Mixed),
+            _ => Err(format!("Invalid villain '{}', expected hand, range or mixed", s)),
+        }
+    }
+}
+
+pub fn parse_street(text: &str) -> Result<Street, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "preflop" => Ok(Street::Preflop),
+        "flop" => Ok(Street::Flop),
+        "turn" => Ok(Street::Turn),
+        "river" => Ok(Street::River),
+        _ => Err(format!("Invalid street '{}', expected preflop, flop, turn or river", text)),
+    }
+}
+
+fn board_size(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River | Street::Showdown => 5,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Villain {
+    Hand([Card; 2]),
+    // One of the named preset ranges
+    Range(&'static str, Range),
+}
+
+#[derive(Debug, Clone)]
+pub struct EquitySpot {
+    pub hero: [Card; 2],
+    pub villain: Villain,
+    pub board: Vec<Card>,
+    pub street: Street,
+}
+
+impl EquitySpot {
+    // One line without commas for the log, e.g. "AhKh vs QsQd on Qh7c2d"
+    pub fn describe(&self) -> String {
+        let hero: String = self.hero.iter().map(card_code).collect();
+        let villain = match &self.villain {
+            Villain::Hand(hand) => hand.iter().map(card_code).collect(),
+            Villain::Range(name, _) => name.to_string(),
+        };
+        if self.board.is_empty() {
+            format!("{} vs {}", hero, villain)
+        } else {
+            format!("{} vs {} on {}", hero, villain, self.board.iter().map(card_code).collect::<String>())
+        }
+    }
+
+    // Hero's equity in percent (wins plus half of ties); a range on the river
+    // is counted exactly
+    pub fn equity<R: Rng>(&self, simulations: usize, rng: &mut R) -> f64 {
+        let results = match &self.villain {
+            Villain::Hand(hand) => monte_carlo_vs_range_with_rng(&self.hero, &Range::from_combos(vec![hand.clone()]), &self.board, simulations, rng),
+            Villain::Range(_, range) => monte_carlo_vs_range_with_rng(&self.hero, range, &self.board, simulations, rng),
+        };
+        results.win_rate + results.tie_rate / 2.0
+    }
+}
+
+// Deals a random spot on `street` (any street when None). A range spot deals
+// hero's cards again if they leave none of the range's combos.
+pub fn deal_equity_spot<R: Rng>(opposition: Opposition, street: Option<Street>, rng: &mut R) -> EquitySpot {
+    let street = street.unwrap_or_else(|| *[Street::Preflop, Street::Flop, Street::Turn, Street::River].choose(rng).expect("streets are listed"));
+    let against_range = match opposition {
+        Opposition::Hand => false,
+        Opposition::Range => true,
+        Opposition::Mixed => rng.gen_bool(0.5),
+    };
+    loop {
+        let mut deck = Deck::new();
+        let mut draw = || deck.draw_with(rng).expect("a fresh deck has enough cards");
+        let hero = [draw(), draw()];
+        let villain_hand = [draw(), draw()];
+        let board: Vec<Card> = (0..board_size(street)).map(|_| draw()).collect();
+
+        let villain = if against_range {
+            let (name, notation) = *PRESETS.choose(rng).expect("presets are listed");
+            let range = Range::parse(notation).expect("presets parse");
+            let mut dead_cards = hero.to_vec();
+            dead_cards.extend_from_slice(&board);
+            if range.available(&dead_cards).is_empty() {
+                continue;
+            }
+            Villain::Range(name, range)
+        } else {
+            Villain::Hand(villain_hand)
+        };
+        return EquitySpot { hero, villain, board, street };
+    }
+}
+
+// One answer in the log
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub time: String,
+    pub quiz: String,
+    pub category: String,
+    pub spot: String,
+    pub estimate: f64,
+    pub answer: f64,
+}
+
+impl Attempt {
+    pub fn new(quiz: &str, category: &str, spot: &str, estimate: f64, answer: f64) -> Self {
+        Attempt {
+            time: chrono::Utc::now().to_rfc3339(),
+            quiz: quiz.to_string(),
+            category: category.to_string(),
+            spot: spot.to_string(),
+            estimate,
+            answer,
+        }
+    }
+
+    // Positive when the estimate was too high
+    pub fn error(&self) -> f64 {
+        self.estimate - self.answer
+    }
+}
+
+// Adds attempts to the end of the log, writing the header when the file is new
+pub fn append_attempts(attempts: &[Attempt], filename: &str) -> Result<(), std::io::Error> {
+    use std::io::Write;
+
+    let is_new = std::fs::metadata(filename).map(|meta| meta.len() == 0).unwrap_or(true);
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(filename)?;
+    if is_new {
+        writeln!(file, "{}", LOG_HEADER)?;
+    }
+    for attempt in attempts {
+        writeln!(file, "{},{},{},{},{:.2},{:.2}", attempt.time, attempt.quiz, attempt.category, attempt.spot, attempt.estimate, attempt.answer)?;
+    }
+    Ok(())
+}
+
+// Every attempt in the log, oldest first; a missing log has none and lines
+// that don't parse are skipped
+pub fn load_attempts(filename: &str) -> Result<Vec<Attempt>, std::io::Error> {
+    let text = match std::fs::read_to_string(filename) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text.lines()
+        .filter(|line| *line != LOG_HEADER)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 6 {
+                return None;
+            }
+            Some(Attempt {
+                time: fields[0].to_string(),
+                quiz: fields[1].to_string(),
+                category: fields[2].to_string(),
+                spot: fields[3].to_string(),
+                estimate: fields[4].parse().ok()?,
+                answer: fields[5].parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+// How far off a set of estimates were, in the answers' units
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorStats {
+    pub attempts: usize,
+    pub mean_absolute_error: f64,
+    // Average signed error: positive means estimates run high
+    pub bias: f64,
+    pub worst_error: f64,
+    // Percent of estimates within `close` of the answer
+    pub close_rate: f64,
+}
+
+pub fn error_stats<'a, I: IntoIterator<Item = &'a Attempt>>(attempts: I, close: f64) -> Option<ErrorStats> {
+    let errors: Vec<f64> = attempts.into_iter().map(Attempt::error).collect();
+    if errors.is_empty() {
+        return None;
+    }
+    let count = errors.len() as f64;
+    Some(ErrorStats {
+        attempts: errors.len(),
+        mean_absolute_error: errors.iter().map(|e| e.abs()).sum::<f64>() / count,
+        bias: errors.iter().sum::<f64>() / count,
+        worst_error: errors.iter().map(|e| e.abs()).fold(0.0, f64::max),
+        close_rate: errors.iter().filter(|e| e.abs() <= close).count() as f64 / count * 100.0,
+    })
+}
+
+// Error stats of one quiz's attempts: overall, the most recent ones and each
+// category in the order they first appear
+#[derive(Debug, Clone)]
+pub struct QuizSummary {
+    pub overall: ErrorStats,
+    pub recent: ErrorStats,
+    pub by_category: Vec<(String, ErrorStats)>,
+}
+
+pub fn summarize(attempts: &[Attempt], quiz: &str, close: f64) -> Option<QuizSummary> {
+    let attempts: Vec<&Attempt> = attempts.iter().filter(|attempt| attempt.quiz == quiz).collect();
+    let overall = error_stats(attempts.iter().copied(), close)?;
+    let recent = error_stats(attempts[attempts.len().saturating_sub(RECENT)..].iter().copied(), close)?;
+
+    let mut categories: Vec<&str> = Vec::new();
+    for attempt in &attempts {
+        if !categories.contains(&attempt.category.as_str()) {
+            categories.push(&attempt.category);
+        }
+    }
+    let by_category = categories.into_iter()
+        .filter_map(|category| {
+            let stats = error_stats(attempts.iter().copied().filter(|attempt| attempt.category == category), close)?;
+            Some((category.to_string(), stats))
+        })
+        .collect();
+    Some(QuizSummary { overall, recent, by_category })
+}
+
+// A starting hand's place in the preflop ranking
+#[derive(Debug, Clone)]
+pub struct Flashcard {
+    pub hand: [Card; 2],
+    // "AKs"
+    pub notation: String,
+    // 1 for the strongest of the 169 hands
+    pub rank: usize,
+    // Percent of all 1,326 combos in this hand and the hands ranked above it
+    pub top_percent: f64,
+    // Equity against a random hand in percent, when the ranking came from results
+    pub equity: Option<f64>,
+}
+
+impl Flashcard {
+    // The hand's equity against a random hand: from the results it was ranked
+    // by, or else simulated now
+    pub fn equity<R: Rng>(&self, simulations: usize, rng: &mut R) -> f64 {
+        self.equity.unwrap_or_else(|| {
+            let results = monte_carlo_simulation_with_rng(&self.hand, simulations, rng);
+            results.win_rate + results.tie_rate / 2.0
+        })
+    }
+
+    // "pair", "suited" or "offsuit"
+    pub fn category(&self) -> &'static str {
+        match combo_count(&self.hand) {
+            6 => "pair",
+            4 => "suited",
+            _ => "offsuit",
+        }
+    }
+}
+
+fn flashcards(ranked: Vec<([Card; 2], Option<f64>)>) -> Vec<Flashcard> {
+    let mut combos = 0;
+    ranked.into_iter()
+        .enumerate()
+        .map(|(i, (hand, equity))| {
+            combos += combo_count(&hand);
+            Flashcard {
+                notation: hand_notation(&hand),
+                hand,
+                rank: i + 1,
+                top_percent: combos as f64 / 1326.0 * 100.0,
+                equity,
+            }
+        })
+        .collect()
+}
+
+// The built-in ranking (PREFLOP_RANKING), without equities
+pub fn ranking_flashcards() -> Vec<Flashcard> {
+    flashcards(PREFLOP_RANKING.iter()
+        .map(|notation| {
+            let hand = Range::parse(notation).expect("ranked hands parse").combos()[0].clone();
+            (hand, None)
+        })
+        .collect())
+}
+
+// The canonical hands of bulk results ranked by equity (wins plus half of ties)
+pub fn results_flashcards(results: &[HandResult]) -> Vec<Flashcard> {
+    let equity = |result: &HandResult| result.results.win_rate + result.results.tie_rate / 2.0;
+    let mut canonical = aggregate_canonical(results);
+    canonical.sort_by(|a, b| equity(b).total_cmp(&equity(a)));
+    flashcards(canonical.iter().map(|result| (result.hand.clone(), Some(equity(result)))).collect())
+}
+
+// How often each card should come up in `quiz`, replaying the logged answers
+// (whose Spot is the card's name) from oldest to newest; an answer within
+// `tolerance` counts as a hit
+pub fn card_weights(attempts: &[Attempt], quiz: &str, names: &[String], tolerance: f64) -> Vec<f64> {
+    let mut weights = vec![1.0; names.len()];
+    for attempt in attempts.iter().filter(|attempt| attempt.quiz == quiz) {
+        if let Some(i) = names.iter().position(|name| *name == attempt.spot) {
+            update_weight(&mut weights[i], attempt.error(), tolerance);
+        }
+    }
+    weights
+}
+
+// A card index with probability proportional to its weight, other than `last`
+// so the same card isn't asked twice in a row
+pub fn pick_card<R: Rng>(weights: &[f64], last: Option<usize>, rng: &mut R) -> Option<usize> {
+    let weight = |i: usize| if Some(i) == last && weights.len() > 1 { 0.0 } else { weights[i] };
+    let total: f64 = (0..weights.len()).map(weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.gen_range(0.0..total);
+    for i in 0..weights.len() {
+        if target < weight(i) {
+            return Some(i);
+        }
+        target -= weight(i);
+    }
+    (0..weights.len()).rev().find(|&i| weight(i) > 0.0)
+}
+
+// Scales a card's weight after an answer `error` away from the right one
+pub fn update_weight(weight: &mut f64, error: f64, tolerance: f64) {
+    let factor = if error.abs() <= tolerance { HIT_FACTOR } else { MISS_FACTOR };
+    *weight = (*weight * factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
+}
+
+// Draws the pot-odds quiz deals out, with their outs
+const DRAWS: [(&str, usize); 6] = [
+    ("gutshot", 4),
+    ("two overcards", 6),
+    ("open-ended straight draw", 8),
+    ("flush draw", 9),
+    ("flush draw and gutshot", 12),
+    ("open-ended straight and flush draw", 15),
+];
+
+// Bets faced, as fractions of the pot before the bet
+const BET_SIZES: [f64; 7] = [0.33, 0.5, 0.67, 0.75, 1.0, 1.5, 2.0];
+
+// A draw facing a bet: on the flop the bet is all-in so both cards come, on
+// the turn there is one card to come
+#[derive(Debug, Clone)]
+pub struct PotOddsSpot {
+    pub draw: &'static str,
+    pub outs: usize,
+    pub street: Street,
+    // Before the bet
+    pub pot: f64,
+    pub bet: f64,
+}
+
+impl PotOddsSpot {
+    pub fn cards_to_come(&self) -> usize {
+        if self.street == Street::Flop { 2 } else { 1 }
+    }
+
+    // Chance of hitting, as a fraction: 47 unseen cards on the flop, 46 on the turn
+    pub fn equity(&self) -> f64 {
+        let outs = self.outs as f64;
+        match self.street {
+            Street::Flop => 1.0 - (47.0 - outs) / 47.0 * (46.0 - outs) / 46.0,
+            _ => outs / 46.0,
+        }
+    }
+
+    // Calling the bet into the pot with it in
+    pub fn analysis(&self) -> CallAnalysis {
+        analyze_call(self.pot + self.bet, self.bet, self.equity()).expect("quiz spots have a positive bet and an equity between 0 and 1")
+    }
+
+    // One line without commas for the log, e.g. "9 outs on the turn: 60 into 100"
+    pub fn describe(&self) -> String {
+        format!("{} outs on the {}: {} into {}", self.outs, self.street.to_string().to_lowercase(), self.bet, self.pot)
+    }
+}
+
+// A random draw and bet; pots are multiples of 10 chips from 20 to 300
+pub fn deal_pot_odds_spot<R: Rng>(rng: &mut R) -> PotOddsSpot {
+    let (draw, outs) = *DRAWS.choose(rng).expect("draws are listed");
+    let pot = rng.gen_range(2..=30) as f64 * 10.0;
+    let bet = (pot * BET_SIZES.choose(rng).expect("bet sizes are listed")).round();
+    let street = if rng.gen_bool(0.5) { Street::Flop } else { Street::Turn };
+    PotOddsSpot { draw, outs, street, pot, bet }
+}
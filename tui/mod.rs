@@ -0,0 +1,806 @@
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Gauge, Paragraph, Row, Table, TableState, Tabs, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::card::{Card, HandResult, ResultFilter, SimulationResults, SortKey, aggregate_canonical, check_distinct, chen_score, export_to_csv,
+                  export_to_json, export_to_markdown, filter_results, grid_hand, monte_carlo_with_community_with_rng, parse_cards, sklansky_group, sort_results};
+use crate::compress;
+use crate::range::{Range, compact_notation, hand_notation, monte_carlo_vs_range_with_rng, range_vs_range_with_rng};
+use crate::rng::{RngChoice, SimRng};
+
+// A terminal dashboard in place of the numbered menus, built with the tui
+// feature. The Equity tab reruns hero's equity while the cards and villain
+// range are typed, refining it batch by batch between keystrokes; the Ranges
+// tab does the same for two ranges picked on 13x13 grids; the Results tab
+// browses bulk results, sorted and filtered from the keyboard.
+
+// Deals simulated between two redraws of the equity tab
+const BATCH: usize = 2000;
+
+// Live equity stops refining after this many deals
+const MAX_LIVE_DEALS: usize = 1_000_000;
+
+// How long to wait for a key when nothing is being simulated
+const IDLE_POLL: Duration = Duration::from_millis(250);
+
+// Rows moved by PageUp and PageDown
+const PAGE: usize = 20;
+
+// Weight added or taken away by + and - on the range grid
+const WEIGHT_STEP: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Equity,
+    Ranges,
+    Results,
+}
+
+// Deals simulated so far for one spot, or its exact rates on the river
+#[derive(Debug, Clone, Default)]
+struct Tally {
+    deals: usize,
+    wins: usize,
+    ties: usize,
+    exact: Option<(f64, f64)>,
+}
+
+impl Tally {
+    fn wants_deals(&self) -> bool {
+        self.exact.is_none() && self.deals < MAX_LIVE_DEALS
+    }
+
+    fn add(&mut self, results: &SimulationResults, exact: bool) {
+        if exact {
+            self.exact = Some((results.win_rate, results.tie_rate));
+            return;
+        }
+        self.deals += results.total_games;
+        self.wins += results.wins;
+        self.ties += results.ties;
+    }
+
+    // Win and tie rates in percent so far
+    fn rates(&self) -> Option<(f64, f64)> {
+        match self.exact {
+            Some(rates) => Some(rates),
+            None if self.deals > 0 => Some((self.wins as f64 / self.deals as f64 * 100.0, self.ties as f64 / self.deals as f64 * 100.0)),
+            None => None,
+        }
+    }
+
+    // The equity gauge and the rates under it, or why there is nothing to
+    // simulate; `counted` says what an exact river count went through
+    fn draw(tally: Result<&Tally, &String>, counted: &str, frame: &mut Frame, gauge: Rect, summary: Rect) {
+        let (equity, text) = match tally.map(|tally| (tally, tally.rates())) {
+            Err(message) => (0.0, vec![Line::from(message.clone())]),
+            Ok((_, None)) => (0.0, vec![Line::from("Simulating...")]),
+            Ok((tally, Some((win, tie)))) => {
+                let equity = win + tie / 2.0;
+                let precision = match tally.exact {
+                    Some(_) => format!("exact, {}", counted),
+                    // 95% interval of the equity estimate
+                    None => format!("±{:.2}% after {} deals{}", 196.0 * (equity / 100.0 * (1.0 - equity / 100.0) / tally.deals as f64).sqrt(),
+                                    tally.deals, if tally.deals >= MAX_LIVE_DEALS { " (done)" } else { "" }),
+                };
+                (equity, vec![
+                    Line::from(format!("Equity {:.2}%   Win {:.2}%   Tie {:.2}%   Lose {:.2}%", equity, win, tie, 100.0 - win - tie)),
+                    Line::from(precision),
+                ])
+            },
+        };
+        frame.render_widget(Gauge::default()
+                                .block(Block::bordered().title(" Equity "))
+                                .gauge_style(Style::new().fg(Color::Green))
+                                .ratio((equity / 100.0).clamp(0.0, 1.0))
+                                .label(format!("{:.2}%", equity)), gauge);
+        frame.render_widget(Paragraph::new(text).block(Block::bordered()), summary);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Hero,
+    Board,
+    Villain,
+}
+
+// A spot the equity tab can simulate
+struct Spot {
+    hero: [Card; 2],
+    board: Vec<Card>,
+    // None plays a random hand
+    villain: Option<Range>,
+}
+
+struct EquityTab {
+    hero: String,
+    board: String,
+    villain: String,
+    field: Field,
+    // The typed spot, or why it can't be simulated yet
+    spot: Result<Spot, String>,
+    // River equity against a range is counted exactly, once
+    tally: Tally,
+}
+
+impl EquityTab {
+    fn new() -> Self {
+        let mut tab = EquityTab {
+            hero: String::new(),
+            board: String::new(),
+            villain: String::new(),
+            field: Field::Hero,
+            spot: Err(String::new()),
+            tally: Tally::default(),
+        };
+        tab.reparse();
+        tab
+    }
+
+    fn focused(&mut self) -> &mut String {
+        match self.field {
+            Field::Hero => &mut self.hero,
+            Field::Board => &mut self.board,
+            Field::Villain => &mut self.villain,
+        }
+    }
+
+    // Any edit starts the count over
+    fn reparse(&mut self) {
+        self.spot = parse_spot(&self.hero, &self.board, &self.villain);
+        self.tally = Tally::default();
+    }
+
+    fn wants_deals(&self) -> bool {
+        self.spot.is_ok() && self.tally.wants_deals()
+    }
+
+    fn simulate(&mut self, rng: &mut SimRng) {
+        let Ok(spot) = &self.spot else { return };
+        let results = match &spot.villain {
+            Some(range) => monte_carlo_vs_range_with_rng(&spot.hero, range, &spot.board, BATCH, rng),
+            None => monte_carlo_with_community_with_rng(&spot.hero, &spot.board, BATCH, rng),
+        };
+        if results.total_games == 0 {
+            self.spot = Err("No villain combo is left once the known cards are removed".to_string());
+            return;
+        }
+        self.tally.add(&results, spot.villain.is_some() && spot.board.len() == 5);
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up => self.field = match self.field {
+                Field::Hero => Field::Villain,
+                Field::Board => Field::Hero,
+                Field::Villain => Field::Board,
+            },
+            KeyCode::Down | KeyCode::Enter => self.field = match self.field {
+                Field::Hero => Field::Board,
+                Field::Board => Field::Villain,
+                Field::Villain => Field::Hero,
+            },
+            KeyCode::Backspace => {
+                self.focused().pop();
+                self.reparse();
+            },
+            KeyCode::Char(c) => {
+                self.focused().push(c);
+                self.reparse();
+            },
+            _ => {},
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect) {
+        let [inputs, gauge, summary] = Layout::vertical([Constraint::Length(5), Constraint::Length(3), Constraint::Min(0)]).areas(area);
+
+        let input = |label: &str, value: &str, field: Field| {
+            let style = if self.field == field { Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { Style::new() };
+            let cursor = if self.field == field { "_" } else { "" };
+            Line::from(vec![Span::styled(format!("{:<9}", label), style), Span::raw(value.to_string()), Span::styled(cursor, style)])
+        };
+        let lines = vec![
+            input("Hero", &self.hero, Field::Hero),
+            input("Board", &self.board, Field::Board),
+            input("Villain", &self.villain, Field::Villain),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Spot (villain: a range, or empty for a random hand) ")), inputs);
+        Tally::draw(self.spot.as_ref().map(|_| &self.tally), "every villain combo counted", frame, gauge, summary);
+    }
+}
+
+fn parse_spot(hero: &str, board: &str, villain: &str) -> Result<Spot, String> {
+    if hero.trim().is_empty() {
+        return Err("Type hero's hole cards, e.g. AhKh".to_string());
+    }
+    let hero = <[Card; 2]>::try_from(parse_cards(hero)?).map_err(|cards| format!("Hero has two hole cards, not {}", cards.len()))?;
+    let board = parse_board(board)?;
+    check_distinct(hero.iter().chain(board.iter()))?;
+    let villain = if villain.trim().is_empty() { None } else { Some(Range::parse(villain)?) };
+    Ok(Spot { hero, board, villain })
+}
+
+fn parse_board(board: &str) -> Result<Vec<Card>, String> {
+    let board = parse_cards(board)?;
+    if ![0, 3, 4, 5].contains(&board.len()) {
+        return Err(format!("A board has three to five cards, found {}", board.len()));
+    }
+    check_distinct(board.iter())?;
+    Ok(board)
+}
+
+// The two ranges on the ranges tab, indexing RangesTab::grids
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Hero,
+    Villain,
+}
+
+// Text being typed at the bottom of the ranges tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangePrompt {
+    Board,
+    Save,
+    Load,
+}
+
+// A weight for each of the 169 hands, laid out as grid_hand: pairs on the
+// diagonal, suited hands above it and offsuit hands below
+type Grid = [[f64; 13]; 13];
+
+struct RangesTab {
+    grids: [Grid; 2],
+    side: Side,
+    // Row and column of the selected hand
+    cursor: (usize, usize),
+    board: String,
+    // Hero's range, villain's range and the board, or why they can't be simulated yet
+    spot: Result<(Range, Range, Vec<Card>), String>,
+    // River equity is counted exactly, once
+    tally: Tally,
+    prompt: Option<(RangePrompt, String)>,
+    message: String,
+}
+
+impl RangesTab {
+    fn new() -> Self {
+        let mut tab = RangesTab {
+            grids: [[[0.0; 13]; 13]; 2],
+            side: Side::Hero,
+            cursor: (0, 0),
+            board: String::new(),
+            spot: Err(String::new()),
+            tally: Tally::default(),
+            prompt: None,
+            message: String::new(),
+        };
+        tab.reparse();
+        tab
+    }
+
+    fn grid(&mut self) -> &mut Grid {
+        &mut self.grids[self.side as usize]
+    }
+
+    fn side_name(&self) -> &'static str {
+        match self.side {
+            Side::Hero => "hero",
+            Side::Villain => "villain",
+        }
+    }
+
+    // Any edit starts the count over
+    fn reparse(&mut self) {
+        let ranges = self.grids.each_ref().map(grid_range);
+        self.spot = parse_board(&self.board).and_then(|board| {
+            let [hero, villain] = ranges;
+            if hero.is_empty() || villain.is_empty() {
+                return Err("Pick hands for both ranges: space adds or removes a hand, v switches between hero and villain".to_string());
+            }
+            Ok((hero, villain, board))
+        });
+        self.tally = Tally::default();
+    }
+
+    fn wants_deals(&self) -> bool {
+        self.spot.is_ok() && self.tally.wants_deals()
+    }
+
+    fn simulate(&mut self, rng: &mut SimRng) {
+        let Ok((hero, villain, board)) = &self.spot else { return };
+        let results = range_vs_range_with_rng(hero, villain, board, BATCH, rng);
+        if results.total_games == 0 {
+            self.spot = Err("No pair of hero and villain combos is left once the board is removed".to_string());
+            return;
+        }
+        self.tally.add(&results, board.len() == 5);
+    }
+
+    // Sets the selected hand's weight, kept between 0 and 1
+    fn set_weight(&mut self, weight: impl FnOnce(f64) -> f64) {
+        let (row, col) = self.cursor;
+        let cell = &mut self.grid()[row][col];
+        *cell = weight(*cell).clamp(0.0, 1.0);
+        self.reparse();
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if let Some((prompt, text)) = &mut self.prompt {
+            match key.code {
+                KeyCode::Esc => self.prompt = None,
+                KeyCode::Backspace => {
+                    text.pop();
+                },
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Enter => {
+                    let (prompt, text) = (*prompt, text.trim().to_string());
+                    self.prompt = None;
+                    self.submit(prompt, &text);
+                },
+                _ => {},
+            }
+            return;
+        }
+        let (row, col) = self.cursor;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = (row.saturating_sub(1), col),
+            KeyCode::Down | KeyCode::Char('j') => self.cursor = ((row + 1).min(12), col),
+            KeyCode::Left | KeyCode::Char('h') => self.cursor = (row, col.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.cursor = (row, (col + 1).min(12)),
+            KeyCode::Char(' ') => self.set_weight(|weight| if weight > 0.0 { 0.0 } else { 1.0 }),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.set_weight(|weight| weight + WEIGHT_STEP),
+            KeyCode::Char('-') => self.set_weight(|weight| weight - WEIGHT_STEP),
+            KeyCode::Char('v') => self.side = match self.side {
+                Side::Hero => Side::Villain,
+                Side::Villain => Side::Hero,
+            },
+            KeyCode::Char('c') => {
+                *self.grid() = [[0.0; 13]; 13];
+                self.reparse();
+            },
+            KeyCode::Char('b') => self.prompt = Some((RangePrompt::Board, self.board.clone())),
+            KeyCode::Char('s') => self.prompt = Some((RangePrompt::Save, String::new())),
+            KeyCode::Char('o') => self.prompt = Some((RangePrompt::Load, String::new())),
+            _ => {},
+        }
+    }
+
+    fn submit(&mut self, prompt: RangePrompt, text: &str) {
+        match prompt {
+            RangePrompt::Board => {
+                self.board = text.to_string();
+                self.reparse();
+            },
+            RangePrompt::Save | RangePrompt::Load if text.is_empty() => {},
+            // Saved as range notation, so the file also works with --range
+            RangePrompt::Save => {
+                let notation = grid_notation(&self.grids[self.side as usize]);
+                self.message = match std::fs::write(text, format!("{}\n", notation)) {
+                    Ok(()) => format!("Saved {}'s range to {}", self.side_name(), text),
+                    Err(e) => format!("Error saving {}: {}", text, e),
+                };
+            },
+            // Entries may be split over several lines
+            RangePrompt::Load => {
+                let loaded = std::fs::read_to_string(text)
+                    .map_err(|e| format!("Error reading {}: {}", text, e))
+                    .and_then(|contents| {
+                        let entries: Vec<&str> = contents.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+                        Range::parse(&entries.join(","))
+                    });
+                match loaded {
+                    Ok(range) => {
+                        *self.grid() = range_grid(&range);
+                        self.reparse();
+                        self.message = format!("Loaded {:.1} combos from {} into {}'s range", range.total_weight(), text, self.side_name());
+                    },
+                    Err(e) => self.message = e,
+                }
+            },
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect) {
+        let [main, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+        // Four columns per hand plus the border
+        let [grid_area, side] = Layout::horizontal([Constraint::Length(13 * 4 + 2), Constraint::Min(0)]).areas(main);
+        let [info, gauge, summary] = Layout::vertical([Constraint::Min(0), Constraint::Length(3), Constraint::Length(4)]).areas(side);
+
+        let grid = &self.grids[self.side as usize];
+        let lines: Vec<Line> = grid.iter().enumerate().map(|(row, weights)| {
+            let cells: Vec<Span> = weights.iter().enumerate().flat_map(|(col, &weight)| {
+                let mut style = if weight >= 1.0 {
+                    Style::new().fg(Color::Black).bg(Color::Green)
+                } else if weight > 0.0 {
+                    Style::new().fg(Color::Black).bg(Color::Yellow)
+                } else {
+                    Style::new().fg(Color::DarkGray)
+                };
+                if (row, col) == self.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                [Span::styled(format!("{:<3}", hand_notation(&grid_hand(row, col))), style), Span::raw(" ")]
+            }).collect();
+            Line::from(cells)
+        }).collect();
+        let combos = grid_combos(grid);
+        let title = format!(" {} range: {:.1} combos, {:.1}% ", if self.side == Side::Hero { "Hero" } else { "Villain" }, combos, combos / 1326.0 * 100.0);
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), grid_area);
+
+        let (row, col) = self.cursor;
+        let notation = |grid: &Grid| match grid_notation(grid) {
+            notation if notation.is_empty() => "(empty)".to_string(),
+            notation => notation,
+        };
+        let text = vec![
+            Line::from(format!("{}: {:.0}%", hand_notation(&grid_hand(row, col)), grid[row][col] * 100.0)),
+            Line::from(format!("Hero: {}", notation(&self.grids[Side::Hero as usize]))),
+            Line::from(format!("Villain: {}", notation(&self.grids[Side::Villain as usize]))),
+            Line::from(format!("Board: {}", if self.board.trim().is_empty() { "(preflop)" } else { &self.board })),
+        ];
+        frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(Block::bordered().title(" Ranges ")), info);
+        Tally::draw(self.spot.as_ref().map(|_| &self.tally), "every pair of combos counted", frame, gauge, summary);
+
+        let status_line = match &self.prompt {
+            Some((RangePrompt::Board, text)) => format!("Board, e.g. Qh7c2d (blank for preflop): {}_", text),
+            Some((RangePrompt::Save, text)) => format!("Save {}'s range to: {}_", self.side_name(), text),
+            Some((RangePrompt::Load, text)) => format!("Load {}'s range from: {}_", self.side_name(), text),
+            None if !self.message.is_empty() => self.message.clone(),
+            None => "space add/remove  +/- weight  v hero/villain  c clear  b board  s save  o open".to_string(),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+}
+
+// Combos in each grid cell: 6 for a pair, 4 suited, 12 offsuit
+fn cell_combos(row: usize, col: usize) -> f64 {
+    match row.cmp(&col) {
+        std::cmp::Ordering::Equal => 6.0,
+        std::cmp::Ordering::Less => 4.0,
+        std::cmp::Ordering::Greater => 12.0,
+    }
+}
+
+fn grid_combos(grid: &Grid) -> f64 {
+    (0..13).flat_map(|row| (0..13).map(move |col| (row, col))).map(|(row, col)| grid[row][col] * cell_combos(row, col)).sum()
+}
+
+// The grid as range notation, e.g. "TT+,AQs+,KQs:0.5"
+fn grid_notation(grid: &Grid) -> String {
+    let hands: Vec<(String, f64)> = (0..13).flat_map(|row| (0..13).map(move |col| (row, col)))
+        .map(|(row, col)| (hand_notation(&grid_hand(row, col)), grid[row][col]))
+        .collect();
+    compact_notation(&hands).expect("grid hands are canonical")
+}
+
+fn grid_range(grid: &Grid) -> Range {
+    match grid_notation(grid) {
+        notation if notation.is_empty() => Range::default(),
+        notation => Range::parse(&notation).expect("compact notation parses"),
+    }
+}
+
+// Each hand's weight is the average over its combos, so a range with only some
+// suits of a hand loads as a partial weight
+fn range_grid(range: &Range) -> Grid {
+    let mut grid = [[0.0; 13]; 13];
+    for (row, weights) in grid.iter_mut().enumerate() {
+        for (col, weight) in weights.iter_mut().enumerate() {
+            let hand = Range::parse(&hand_notation(&grid_hand(row, col))).expect("grid hands are canonical");
+            *weight = hand.combos().iter().map(|combo| range.weight(combo)).sum::<f64>() / hand.len() as f64;
+        }
+    }
+    grid
+}
+
+// Text being typed at the bottom of the results tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    Filter,
+    Export,
+}
+
+struct ResultsTab {
+    results: Vec<HandResult>,
+    view: Vec<HandResult>,
+    sort: Option<SortKey>,
+    filters: Vec<ResultFilter>,
+    // One row per canonical hand instead of per combo
+    canonical: bool,
+    // Rows before filtering
+    rows: usize,
+    prompt: Option<(Prompt, String)>,
+    message: String,
+    // Set when something printed over the dashboard, so the screen is redrawn from scratch
+    stale: bool,
+    table: TableState,
+}
+
+impl ResultsTab {
+    fn new(results: Vec<HandResult>, sort: Option<SortKey>, filters: Vec<ResultFilter>) -> Self {
+        let mut tab = ResultsTab {
+            results,
+            view: Vec::new(),
+            sort,
+            filters,
+            canonical: false,
+            rows: 0,
+            prompt: None,
+            message: String::new(),
+            stale: false,
+            table: TableState::default(),
+        };
+        tab.refresh();
+        tab
+    }
+
+    fn refresh(&mut self) {
+        let rows = if self.canonical { aggregate_canonical(&self.results) } else { self.results.clone() };
+        self.rows = rows.len();
+        self.view = filter_results(&rows, &self.filters);
+        if let Some(key) = self.sort {
+            sort_results(&mut self.view, key);
+        }
+        self.table.select(if self.view.is_empty() { None } else { Some(0) });
+    }
+
+    fn move_selection(&mut self, rows: isize) {
+        if self.view.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        self.table.select(Some((current + rows).clamp(0, self.view.len() as isize - 1) as usize));
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if let Some((prompt, text)) = &mut self.prompt {
+            match key.code {
+                KeyCode::Esc => self.prompt = None,
+                KeyCode::Backspace => {
+                    text.pop();
+                },
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Enter => {
+                    let (prompt, text) = (*prompt, text.trim().to_string());
+                    self.prompt = None;
+                    self.submit(prompt, &text);
+                },
+                _ => {},
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-(PAGE as isize)),
+            KeyCode::PageDown => self.move_selection(PAGE as isize),
+            KeyCode::Home => self.move_selection(isize::MIN / 2),
+            KeyCode::End => self.move_selection(isize::MAX / 2),
+            KeyCode::Char('s') => {
+                self.sort = Some(match self.sort {
+                    None | Some(SortKey::Chen) => SortKey::WinRate,
+                    Some(SortKey::WinRate) => SortKey::LoseRate,
+                    Some(SortKey::LoseRate) => SortKey::TieRate,
+                    Some(SortKey::TieRate) => SortKey::Hand,
+                    Some(SortKey::Hand) => SortKey::Chen,
+                });
+                self.refresh();
+            },
+            KeyCode::Char('c') => {
+                self.canonical = !self.canonical;
+                self.refresh();
+            },
+            KeyCode::Char('f') => self.prompt = Some((Prompt::Filter, String::new())),
+            KeyCode::Char('x') => self.prompt = Some((Prompt::Export, String::new())),
+            _ => {},
+        }
+    }
+
+    fn submit(&mut self, prompt: Prompt, text: &str) {
+        match prompt {
+            // Blank clears the filters, as "none" does in the menu
+            Prompt::Filter => match text.split(',').filter(|f| !f.trim().is_empty()).map(|f| f.parse()).collect::<Result<Vec<ResultFilter>, String>>() {
+                Ok(filters) => {
+                    self.filters = filters;
+                    self.refresh();
+                    self.message = format!("{} of {} hands shown", self.view.len(), self.results.len());
+                },
+                Err(e) => self.message = e,
+            },
+            Prompt::Export if text.is_empty() => {},
+            // The export functions announce themselves on stdout, over the dashboard
+            Prompt::Export => {
+                self.stale = true;
+                let (exported, filename) = if compress::base_name(text).to_ascii_lowercase().ends_with(".json") {
+                    (export_to_json(&self.view, text), text.to_string())
+                } else if text.to_ascii_lowercase().ends_with(".md") {
+                    (export_to_markdown(&self.view, text), text.to_string())
+                } else if compress::Compression::from_filename(text).is_some() {
+                    (export_to_csv(&self.view, text), text.to_string())
+                } else {
+                    let filename = format!("{}.csv", text.trim_end_matches(".csv"));
+                    (export_to_csv(&self.view, &filename), filename)
+                };
+                self.message = match exported {
+                    Ok(()) => format!("{} hands exported to {}", self.view.len(), filename),
+                    Err(e) => format!("Error exporting results: {}", e),
+                };
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let [table_area, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+
+        if self.results.is_empty() {
+            frame.render_widget(Paragraph::new("No results loaded: run a bulk simulation, or open results with `pokershit tui <results.csv>`")
+                                    .block(Block::bordered().title(" Results ")), table_area);
+            return;
+        }
+        let header = Row::new(["#", "Hand", "Win%", "Lose%", "Tie%", "Games", "Group", "Chen"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.view.iter().enumerate().map(|(rank, result)| Row::new([
+            Cell::from((rank + 1).to_string()),
+            Cell::from(result.hand_description.clone()),
+            Cell::from(format!("{:.2}", result.results.win_rate)),
+            Cell::from(format!("{:.2}", 100.0 - result.results.win_rate - result.results.tie_rate)),
+            Cell::from(format!("{:.2}", result.results.tie_rate)),
+            Cell::from(result.results.total_games.to_string()),
+            Cell::from(sklansky_group(&result.hand).to_string()),
+            Cell::from(chen_score(&result.hand).to_string()),
+        ]));
+        let widths = [
+            Constraint::Length(5), Constraint::Length(14), Constraint::Length(7), Constraint::Length(7),
+            Constraint::Length(7), Constraint::Length(9), Constraint::Length(6), Constraint::Length(5),
+        ];
+        let sort = match self.sort {
+            None => "as run",
+            Some(SortKey::WinRate) => "win",
+            Some(SortKey::LoseRate) => "lose",
+            Some(SortKey::TieRate) => "tie",
+            Some(SortKey::Hand) => "hand",
+            Some(SortKey::Chen) => "chen",
+        };
+        let title = format!(" Results: {} of {} {}, sorted by {}, {} filter(s) ",
+                            self.view.len(), self.rows, if self.canonical { "canonical hands" } else { "combos" }, sort, self.filters.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        let status_line = match &self.prompt {
+            Some((Prompt::Filter, text)) => format!("Filters, e.g. win > 55%, suited (blank clears): {}_", text),
+            Some((Prompt::Export, text)) => format!("Export to (ending in .json for JSON, .md for Markdown, otherwise .csv is added; add .gz or .zst to compress): {}_", text),
+            None if !self.message.is_empty() => self.message.clone(),
+            None => "s sort  f filter  c canonical  x export".to_string(),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+}
+
+struct App {
+    tab: Tab,
+    equity: EquityTab,
+    ranges: RangesTab,
+    results: ResultsTab,
+    rng: SimRng,
+    quit: bool,
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), std::io::Error> {
+        while !self.quit {
+            if self.results.stale {
+                terminal.clear()?;
+                self.results.stale = false;
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+            // While equity is still converging, keys are checked between batches instead of waited for
+            let simulating = match self.tab {
+                Tab::Equity => self.equity.wants_deals(),
+                Tab::Ranges => self.ranges.wants_deals(),
+                Tab::Results => false,
+            };
+            if event::poll(if simulating { Duration::ZERO } else { IDLE_POLL })? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.on_key(key);
+                    }
+                }
+            }
+            if simulating {
+                match self.tab {
+                    Tab::Equity => self.equity.simulate(&mut self.rng),
+                    Tab::Ranges => self.ranges.simulate(&mut self.rng),
+                    Tab::Results => {},
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        let prompting = match self.tab {
+            Tab::Equity => false,
+            Tab::Ranges => self.ranges.prompt.is_some(),
+            Tab::Results => self.results.prompt.is_some(),
+        };
+        // Typed text goes to the equity inputs and the prompts, not to the shortcuts
+        let typing = self.tab == Tab::Equity || prompting;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            // Esc first closes an open prompt
+            KeyCode::Esc if !prompting => self.quit = true,
+            KeyCode::Char('q') if !typing => self.quit = true,
+            KeyCode::Tab if !prompting => {
+                self.tab = match self.tab {
+                    Tab::Equity => Tab::Ranges,
+                    Tab::Ranges => Tab::Results,
+                    Tab::Results => Tab::Equity,
+                };
+            },
+            KeyCode::BackTab if !prompting => {
+                self.tab = match self.tab {
+                    Tab::Equity => Tab::Results,
+                    Tab::Ranges => Tab::Equity,
+                    Tab::Results => Tab::Ranges,
+                };
+            },
+            _ => match self.tab {
+                Tab::Equity => self.equity.on_key(key),
+                Tab::Ranges => self.ranges.on_key(key),
+                Tab::Results => self.results.on_key(key),
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs, body, help] = Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let selected = match self.tab {
+            Tab::Equity => 0,
+            Tab::Ranges => 1,
+            Tab::Results => 2,
+        };
+        frame.render_widget(Tabs::new(["Equity", "Ranges", "Results"])
+                                .select(selected)
+                                .block(Block::bordered().title(" pokershit "))
+                                .highlight_style(Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)), tabs);
+        match self.tab {
+            Tab::Equity => self.equity.draw(frame, body),
+            Tab::Ranges => self.ranges.draw(frame, body),
+            Tab::Results => self.results.draw(frame, body),
+        }
+        let keys = match self.tab {
+            Tab::Equity => "Tab ranges  Up/Down/Enter next field  Esc quit",
+            Tab::Ranges => "Tab results  arrows/hjkl move  q quit",
+            Tab::Results => "Tab equity  Up/Down/PgUp/PgDn/Home/End scroll  q quit",
+        };
+        frame.render_widget(Paragraph::new(keys).style(Style::new().fg(Color::DarkGray)), help);
+    }
+}
+
+// Opens the dashboard on `results` (which may be empty) until the user quits,
+// on the Results tab when there are results to browse
+pub fn run(results: Vec<HandResult>, sort: Option<SortKey>, filters: Vec<ResultFilter>, rng: RngChoice) -> Result<(), std::io::Error> {
+    let mut app = App {
+        tab: if results.is_empty() { Tab::Equity } else { Tab::Results },
+        equity: EquityTab::new(),
+        ranges: RangesTab::new(),
+        results: ResultsTab::new(results, sort, filters),
+        rng: rng.build(),
+        quit: false,
+    };
+    let mut terminal = ratatui::try_init()?;
+    let outcome = app.run(&mut terminal);
+    ratatui::restore();
+    outcome
+}
@@ -0,0 +1,74 @@
+use wasm_bindgen::prelude::*;
+
+use crate::api;
+use crate::hand_history::card_code;
+use crate::json::{self, JsonValue};
+use crate::range::Range;
+use crate::rng::RngChoice;
+
+// JavaScript bindings for a calculator running in the browser, built with the
+// wasm feature for wasm32-unknown-unknown. Functions take and return the same
+// objects as the HTTP API (see api), so a page can switch between the two:
+//
+//   equity({hero: "AhKh", villain: "QQ+,AKs", board: "Qh7c2d"}).equity
+//
+// Only pure computation is reachable from here: no files, standard input,
+// threads or clocks. The bulk run isn't exposed because it times itself.
+
+// A browser tab has one thread; larger runs would freeze the page
+const MAX_TRIALS: usize = 1_000_000;
+
+// {hand, board} to the made hand, see api::evaluate
+#[wasm_bindgen(js_name = evaluateHand)]
+pub fn evaluate_hand(request: JsValue) -> Result<JsValue, JsError> {
+    answer(&request, api::evaluate)
+}
+
+// {hero, villain or opponents, board, trials} to hero's equity, see api::equity
+#[wasm_bindgen]
+pub fn equity(request: JsValue) -> Result<JsValue, JsError> {
+    answer(&request, |params| api::equity(params, MAX_TRIALS, &mut RngChoice::default().build()))
+}
+
+// {hand, opponents, trials} to a starting hand's standing, see api::preflop
+#[wasm_bindgen]
+pub fn preflop(request: JsValue) -> Result<JsValue, JsError> {
+    answer(&request, |params| api::preflop(params, MAX_TRIALS, &mut RngChoice::default().build()))
+}
+
+// Range notation to its weighted combos:
+//   {combos: 12, weight: 10.5, percent: 0.79, hands: [{hand: "AhKh", weight: 1}, ...]}
+#[wasm_bindgen(js_name = parseRange)]
+pub fn parse_range(text: &str) -> Result<JsValue, JsError> {
+    let range = Range::parse(text).map_err(|e| JsError::new(&e))?;
+    let hands = range.weighted_combos()
+        .map(|(combo, weight)| JsonValue::Object(vec![
+            ("hand".to_string(), JsonValue::String(combo.iter().map(card_code).collect())),
+            ("weight".to_string(), JsonValue::Number(weight)),
+        ]))
+        .collect();
+    to_js(&JsonValue::Object(vec![
+        ("combos".to_string(), JsonValue::Number(range.len() as f64)),
+        ("weight".to_string(), JsonValue::Number(range.total_weight())),
+        ("percent".to_string(), JsonValue::Number(range.total_weight() / 1326.0 * 100.0)),
+        ("hands".to_string(), JsonValue::Array(hands)),
+    ]))
+}
+
+// Passes a JavaScript object to an api handler as JSON and its answer back;
+// a bad request is thrown as an Error with the handler's message
+fn answer<F>(request: &JsValue, handle: F) -> Result<JsValue, JsError>
+where
+    F: FnOnce(&JsonValue) -> Result<JsonValue, String>,
+{
+    let text: String = js_sys::JSON::stringify(request)
+        .map_err(|_| JsError::new("The request must be an object that JSON can represent"))?
+        .into();
+    let params = json::parse(&text).map_err(|e| JsError::new(&e))?;
+    let answer = handle(&params).map_err(|e| JsError::new(&e))?;
+    to_js(&answer)
+}
+
+fn to_js(value: &JsonValue) -> Result<JsValue, JsError> {
+    js_sys::JSON::parse(&value.to_string()).map_err(|_| JsError::new("The answer could not be read back as JSON"))
+}